@@ -48,7 +48,7 @@ fn main() {
     println!("=== Cocos2d-Rust Game Demo ===\n");
 
     // Initialize the director
-    let mut director = Director::get_instance();
+    let director = Director::get_instance();
     println!("✓ Director initialized");
 
     // Create the main scene
@@ -61,7 +61,7 @@ fn main() {
     println!("✓ Game layer added");
 
     // Run the scene
-    director.run_scene(scene);
+    director.borrow_mut().run_scene(scene);
     println!("✓ Scene running\n");
 
     println!("Game demo structure:");
@@ -0,0 +1,126 @@
+use crate::math::Vec2;
+use super::{DelayTime, FiniteTimeAction, MoveBy, MoveTo, RotateBy, ScaleTo, Sequence, Spawn};
+
+/// ActionBuilder chains steps into a `Sequence` without building the
+/// action tree by hand.
+///
+/// Each `then_*` call appends a step; `spawn` runs an action alongside the
+/// last step instead of after it.
+///
+/// ```ignore
+/// let action = ActionBuilder::new()
+///     .then_move_by(1.0, Vec2::new(100.0, 0.0))
+///     .then_wait(0.5)
+///     .then_scale_to(0.3, 2.0)
+///     .spawn(Box::new(fade_out))
+///     .build();
+/// ```
+#[derive(Debug, Default)]
+pub struct ActionBuilder {
+    steps: Vec<Box<dyn FiniteTimeAction>>,
+}
+
+impl ActionBuilder {
+    /// Creates an empty builder
+    pub fn new() -> ActionBuilder {
+        ActionBuilder { steps: Vec::new() }
+    }
+
+    /// Appends an action as the next step
+    pub fn then(mut self, action: Box<dyn FiniteTimeAction>) -> ActionBuilder {
+        self.steps.push(action);
+        self
+    }
+
+    /// Appends a `MoveBy` step
+    pub fn then_move_by(self, duration: f32, delta: Vec2) -> ActionBuilder {
+        self.then(Box::new(MoveBy::new(duration, delta)))
+    }
+
+    /// Appends a `MoveTo` step
+    pub fn then_move_to(self, duration: f32, position: Vec2) -> ActionBuilder {
+        self.then(Box::new(MoveTo::new(duration, position)))
+    }
+
+    /// Appends a uniform `ScaleTo` step
+    pub fn then_scale_to(self, duration: f32, scale: f32) -> ActionBuilder {
+        self.then(Box::new(ScaleTo::new(duration, scale)))
+    }
+
+    /// Appends a `RotateBy` step
+    pub fn then_rotate_by(self, duration: f32, delta_angle: f32) -> ActionBuilder {
+        self.then(Box::new(RotateBy::new(duration, delta_angle)))
+    }
+
+    /// Appends a pause of `duration` seconds
+    pub fn then_wait(self, duration: f32) -> ActionBuilder {
+        self.then(Box::new(DelayTime::new(duration)))
+    }
+
+    /// Runs an action at the same time as the last step, merging both into
+    /// a `Spawn`. With no previous step the action becomes the first step.
+    pub fn spawn(mut self, action: Box<dyn FiniteTimeAction>) -> ActionBuilder {
+        match self.steps.pop() {
+            Some(last) => self.steps.push(Box::new(Spawn::new(vec![last, action]))),
+            None => self.steps.push(action),
+        }
+        self
+    }
+
+    /// Gets the number of steps added so far
+    pub fn len(&self) -> usize {
+        self.steps.len()
+    }
+
+    /// Checks if no step was added
+    pub fn is_empty(&self) -> bool {
+        self.steps.is_empty()
+    }
+
+    /// Builds the sequence of all steps
+    pub fn build(self) -> Sequence {
+        Sequence::new(self.steps)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::RefCell;
+    use std::rc::Rc;
+    use crate::action::Action;
+    use crate::base::{Node, NodeRef};
+
+    #[test]
+    fn test_sequence_duration_is_sum_of_steps() {
+        let sequence = ActionBuilder::new()
+            .then_move_by(1.0, Vec2::new(100.0, 0.0))
+            .then_wait(0.5)
+            .then_scale_to(0.3, 2.0)
+            .then_rotate_by(0.2, 90.0)
+            .build();
+        assert_eq!(sequence.get_actions().len(), 4);
+        assert!((sequence.get_duration() - 2.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_spawn_runs_with_last_step() {
+        let mut sequence = ActionBuilder::new()
+            .then_move_by(1.0, Vec2::new(100.0, 0.0))
+            .then_scale_to(0.5, 2.0)
+            .spawn(Box::new(MoveBy::new(2.0, Vec2::new(0.0, 50.0))))
+            .build();
+        assert_eq!(sequence.get_actions().len(), 2);
+        assert_eq!(sequence.get_actions()[1].get_duration(), 2.0);
+        assert_eq!(sequence.get_duration(), 3.0);
+
+        let target: NodeRef = Rc::new(RefCell::new(Node::new()));
+        sequence.start_with_target(target.clone());
+        sequence.step(2.0);
+        assert_eq!(target.borrow().get_scale_x(), 2.0);
+        assert_eq!(*target.borrow().get_position(), Vec2::new(100.0, 25.0));
+        sequence.step(1.0);
+        assert_eq!(*target.borrow().get_position(), Vec2::new(100.0, 50.0));
+        assert!(sequence.is_done());
+    }
+}
@@ -0,0 +1,265 @@
+use std::f32::consts::PI;
+use crate::base::NodeRef;
+use super::{Action, ActionInterval, FiniteTimeAction};
+
+/// Easing curve selectable at runtime
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EasingType {
+    Linear,
+    QuadIn,
+    QuadOut,
+    QuadInOut,
+    CubicInOut,
+    SineInOut,
+    BackInOut,
+    BounceOut,
+    ElasticOut,
+}
+
+/// Maps normalized time through an easing curve.
+///
+/// `t` is clamped to [0, 1] and both ends map exactly to 0 and 1; in
+/// between, Back and Elastic overshoot and Bounce dips back down.
+pub fn apply_easing(ty: EasingType, t: f32) -> f32 {
+    if t <= 0.0 {
+        return 0.0;
+    }
+    if t >= 1.0 {
+        return 1.0;
+    }
+
+    match ty {
+        EasingType::Linear => t,
+        EasingType::QuadIn => t * t,
+        EasingType::QuadOut => t * (2.0 - t),
+        EasingType::QuadInOut => {
+            if t < 0.5 {
+                2.0 * t * t
+            } else {
+                -1.0 + (4.0 - 2.0 * t) * t
+            }
+        }
+        EasingType::CubicInOut => {
+            if t < 0.5 {
+                4.0 * t * t * t
+            } else {
+                let f = 2.0 * t - 2.0;
+                0.5 * f * f * f + 1.0
+            }
+        }
+        EasingType::SineInOut => -0.5 * ((PI * t).cos() - 1.0),
+        EasingType::BackInOut => {
+            let overshoot = 1.70158 * 1.525;
+            let t2 = t * 2.0;
+            if t2 < 1.0 {
+                0.5 * t2 * t2 * ((overshoot + 1.0) * t2 - overshoot)
+            } else {
+                let f = t2 - 2.0;
+                0.5 * (f * f * ((overshoot + 1.0) * f + overshoot) + 2.0)
+            }
+        }
+        EasingType::BounceOut => bounce_out(t),
+        EasingType::ElasticOut => {
+            let period = 0.3;
+            2f32.powf(-10.0 * t) * ((t - period / 4.0) * (2.0 * PI) / period).sin() + 1.0
+        }
+    }
+}
+
+/// Samples an easing curve at `samples` evenly spaced times from 0 to 1
+/// inclusive, returning `(t, eased_t)` pairs for plotting
+pub fn sample_curve(ty: EasingType, samples: usize) -> Vec<(f32, f32)> {
+    let last = samples.saturating_sub(1).max(1) as f32;
+    (0..samples)
+        .map(|i| {
+            let t = i as f32 / last;
+            (t, apply_easing(ty, t))
+        })
+        .collect()
+}
+
+/// Checks that sampled eased values never decrease, i.e. the curve does not
+/// dip back like Back, Bounce or Elastic
+pub fn is_monotonic(points: &[(f32, f32)]) -> bool {
+    points.windows(2).all(|pair| pair[1].1 >= pair[0].1)
+}
+
+fn bounce_out(t: f32) -> f32 {
+    if t < 1.0 / 2.75 {
+        7.5625 * t * t
+    } else if t < 2.0 / 2.75 {
+        let t = t - 1.5 / 2.75;
+        7.5625 * t * t + 0.75
+    } else if t < 2.5 / 2.75 {
+        let t = t - 2.25 / 2.75;
+        7.5625 * t * t + 0.9375
+    } else {
+        let t = t - 2.625 / 2.75;
+        7.5625 * t * t + 0.984375
+    }
+}
+
+/// EaseAction runs an inner action with its progress mapped through an
+/// easing curve
+#[derive(Debug)]
+pub struct EaseAction {
+    interval: ActionInterval,
+    inner_action: Box<dyn FiniteTimeAction>,
+    easing: EasingType,
+}
+
+impl EaseAction {
+    /// Creates a new ease action lasting as long as the inner action
+    pub fn new(action: Box<dyn FiniteTimeAction>, easing: EasingType) -> EaseAction {
+        EaseAction {
+            interval: ActionInterval::new(action.get_duration()),
+            inner_action: action,
+            easing,
+        }
+    }
+
+    /// Gets the easing type
+    pub fn get_easing(&self) -> EasingType {
+        self.easing
+    }
+
+    /// Sets the easing type
+    pub fn set_easing(&mut self, easing: EasingType) {
+        self.easing = easing;
+    }
+
+    /// Gets the inner action
+    pub fn get_inner_action(&self) -> &dyn FiniteTimeAction {
+        self.inner_action.as_ref()
+    }
+}
+
+impl Action for EaseAction {
+    fn start_with_target(&mut self, target: NodeRef) {
+        self.inner_action.start_with_target(target.clone());
+        self.interval.start_with_target(target);
+    }
+
+    fn stop(&mut self) {
+        self.inner_action.stop();
+        self.interval.stop();
+    }
+
+    fn step(&mut self, dt: f32) {
+        let time = self.interval.step(dt);
+        self.update(time);
+    }
+
+    fn update(&mut self, time: f32) {
+        self.inner_action.update(apply_easing(self.easing, time));
+    }
+
+    fn is_done(&self) -> bool {
+        self.interval.is_done()
+    }
+
+    fn get_tag(&self) -> i32 {
+        self.interval.get_tag()
+    }
+
+    fn set_tag(&mut self, tag: i32) {
+        self.interval.set_tag(tag);
+    }
+}
+
+impl FiniteTimeAction for EaseAction {
+    fn get_duration(&self) -> f32 {
+        self.interval.get_duration()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::RefCell;
+    use std::rc::Rc;
+    use crate::action::MoveBy;
+    use crate::base::Node;
+    use crate::math::Vec2;
+
+    const ALL: [EasingType; 9] = [
+        EasingType::Linear,
+        EasingType::QuadIn,
+        EasingType::QuadOut,
+        EasingType::QuadInOut,
+        EasingType::CubicInOut,
+        EasingType::SineInOut,
+        EasingType::BackInOut,
+        EasingType::BounceOut,
+        EasingType::ElasticOut,
+    ];
+
+    #[test]
+    fn test_easing_endpoints() {
+        for ty in ALL {
+            assert_eq!(apply_easing(ty, 0.0), 0.0, "{:?}", ty);
+            assert_eq!(apply_easing(ty, 1.0), 1.0, "{:?}", ty);
+        }
+        assert_eq!(apply_easing(EasingType::QuadIn, 0.5), 0.25);
+        assert_eq!(apply_easing(EasingType::QuadInOut, 0.5), 0.5);
+    }
+
+    #[test]
+    fn test_in_out_easings_are_monotonic() {
+        let monotonic = [
+            EasingType::Linear,
+            EasingType::QuadIn,
+            EasingType::QuadOut,
+            EasingType::QuadInOut,
+            EasingType::CubicInOut,
+            EasingType::SineInOut,
+        ];
+        for ty in monotonic {
+            let samples: Vec<f32> = (0..=100).map(|i| apply_easing(ty, i as f32 / 100.0)).collect();
+            assert!(samples.windows(2).all(|pair| pair[1] >= pair[0]), "{:?}", ty);
+        }
+    }
+
+    #[test]
+    fn test_sample_curve_linear() {
+        let points = sample_curve(EasingType::Linear, 11);
+        assert_eq!(points.len(), 11);
+        assert_eq!(points[0], (0.0, 0.0));
+        assert_eq!(points[10], (1.0, 1.0));
+        for (t, eased) in points {
+            assert_eq!(t, eased);
+        }
+        assert_eq!(sample_curve(EasingType::Linear, 0).len(), 0);
+        assert_eq!(sample_curve(EasingType::Linear, 1), vec![(0.0, 0.0)]);
+    }
+
+    #[test]
+    fn test_is_monotonic() {
+        for ty in [EasingType::SineInOut, EasingType::QuadIn, EasingType::QuadOut] {
+            assert!(is_monotonic(&sample_curve(ty, 64)), "{:?}", ty);
+        }
+        assert!(!is_monotonic(&sample_curve(EasingType::BackInOut, 64)));
+        assert!(!is_monotonic(&sample_curve(EasingType::BounceOut, 64)));
+    }
+
+    #[test]
+    fn test_overshooting_easings() {
+        assert!(apply_easing(EasingType::BackInOut, 0.1) < 0.0);
+        assert!(apply_easing(EasingType::ElasticOut, 0.2) > 1.0);
+        assert!((apply_easing(EasingType::BounceOut, 0.5) - 0.765625).abs() < 1e-5);
+    }
+
+    #[test]
+    fn test_ease_action_wraps_inner_action() {
+        let target: NodeRef = Rc::new(RefCell::new(Node::new()));
+        let mut action = EaseAction::new(Box::new(MoveBy::new(2.0, Vec2::new(100.0, 0.0))), EasingType::QuadIn);
+        assert_eq!(action.get_duration(), 2.0);
+        action.start_with_target(target.clone());
+
+        action.step(1.0);
+        assert_eq!(*target.borrow().get_position(), Vec2::new(25.0, 0.0));
+        action.step(1.0);
+        assert_eq!(*target.borrow().get_position(), Vec2::new(100.0, 0.0));
+        assert!(action.is_done());
+    }
+}
@@ -0,0 +1,317 @@
+use std::f32::consts::PI;
+use crate::base::NodeRef;
+use crate::math::{Vec2, Vec3};
+use super::{Action, ActionInterval, FiniteTimeAction};
+
+/// A mesh of (width + 1) x (height + 1) vertices covering a node.
+///
+/// Grid effects displace the vertices; the renderer draws the node's texture
+/// over the displaced mesh.
+#[derive(Debug, Clone)]
+pub struct Grid3D {
+    grid_width: u32,
+    grid_height: u32,
+    step: Vec2,
+    original_vertices: Vec<Vec3>,
+    vertices: Vec<Vec3>,
+}
+
+impl Grid3D {
+    /// Creates a grid of `grid_width` x `grid_height` cells over an area of `size`
+    pub fn new(grid_width: u32, grid_height: u32, size: Vec2) -> Grid3D {
+        let step = Vec2::new(
+            size.x / grid_width.max(1) as f32,
+            size.y / grid_height.max(1) as f32,
+        );
+        let mut original_vertices = Vec::with_capacity(((grid_width + 1) * (grid_height + 1)) as usize);
+        for y in 0..=grid_height {
+            for x in 0..=grid_width {
+                original_vertices.push(Vec3::new(x as f32 * step.x, y as f32 * step.y, 0.0));
+            }
+        }
+        Grid3D {
+            grid_width,
+            grid_height,
+            step,
+            vertices: original_vertices.clone(),
+            original_vertices,
+        }
+    }
+
+    /// Gets the number of cells along each axis
+    pub fn get_grid_size(&self) -> (u32, u32) {
+        (self.grid_width, self.grid_height)
+    }
+
+    /// Gets the size of one cell
+    pub fn get_step(&self) -> Vec2 {
+        self.step
+    }
+
+    /// Gets the number of vertices
+    pub fn get_vertex_count(&self) -> usize {
+        self.vertices.len()
+    }
+
+    /// Gets the displaced vertices, row by row from the bottom
+    pub fn get_vertices(&self) -> &[Vec3] {
+        &self.vertices
+    }
+
+    /// Gets the displaced vertex at grid position (x, y)
+    pub fn get_vertex(&self, x: u32, y: u32) -> Vec3 {
+        self.vertices[self.index(x, y)]
+    }
+
+    /// Gets the undisplaced vertex at grid position (x, y)
+    pub fn get_original_vertex(&self, x: u32, y: u32) -> Vec3 {
+        self.original_vertices[self.index(x, y)]
+    }
+
+    /// Sets the vertex at grid position (x, y)
+    pub fn set_vertex(&mut self, x: u32, y: u32, vertex: Vec3) {
+        let index = self.index(x, y);
+        self.vertices[index] = vertex;
+    }
+
+    /// Restores every vertex to its undisplaced position
+    pub fn reset(&mut self) {
+        self.vertices.clone_from(&self.original_vertices);
+    }
+
+    fn index(&self, x: u32, y: u32) -> usize {
+        (y * (self.grid_width + 1) + x) as usize
+    }
+}
+
+/// Base for actions that distort their target through a `Grid3D`.
+///
+/// The grid is built from the target's content size when the action starts.
+#[derive(Debug)]
+pub struct Grid3DAction {
+    interval: ActionInterval,
+    grid_size: (u32, u32),
+    grid: Option<Grid3D>,
+}
+
+impl Grid3DAction {
+    /// Creates a new grid action
+    pub fn new(duration: f32, grid_size: (u32, u32)) -> Grid3DAction {
+        Grid3DAction {
+            interval: ActionInterval::new(duration),
+            grid_size,
+            grid: None,
+        }
+    }
+
+    /// Gets the grid size
+    pub fn get_grid_size(&self) -> (u32, u32) {
+        self.grid_size
+    }
+
+    /// Gets the grid, available once the action has started
+    pub fn get_grid(&self) -> Option<&Grid3D> {
+        self.grid.as_ref()
+    }
+
+    /// Gets the mutable grid
+    pub fn get_grid_mut(&mut self) -> Option<&mut Grid3D> {
+        self.grid.as_mut()
+    }
+
+    /// Builds the grid over the target and starts the interval
+    pub fn start_with_target(&mut self, target: NodeRef) {
+        let size = target.borrow().get_content_size();
+        self.grid = Some(Grid3D::new(self.grid_size.0, self.grid_size.1, size));
+        self.interval.start_with_target(target);
+    }
+
+    /// Stops the action, restoring the undistorted grid
+    pub fn stop(&mut self) {
+        if let Some(grid) = self.grid.as_mut() {
+            grid.reset();
+        }
+        self.interval.stop();
+    }
+
+    /// Advances the elapsed time and returns the normalized progress
+    pub fn step(&mut self, dt: f32) -> f32 {
+        self.interval.step(dt)
+    }
+
+    /// Checks if the action is done
+    pub fn is_done(&self) -> bool {
+        self.interval.is_done()
+    }
+
+    /// Gets the duration
+    pub fn get_duration(&self) -> f32 {
+        self.interval.get_duration()
+    }
+
+    /// Gets the tag
+    pub fn get_tag(&self) -> i32 {
+        self.interval.get_tag()
+    }
+
+    /// Sets the tag
+    pub fn set_tag(&mut self, tag: i32) {
+        self.interval.set_tag(tag);
+    }
+}
+
+/// Ripple3D sends circular waves out from a center point.
+///
+/// Vertices within `radius` of `center` are lifted along Z, more strongly the
+/// closer they are to the center. The effect fades in and out over the
+/// duration so the node is undistorted when the action starts and ends.
+#[derive(Debug)]
+pub struct Ripple3D {
+    grid_action: Grid3DAction,
+    center: Vec2,
+    radius: f32,
+    waves: u32,
+    amplitude: f32,
+}
+
+impl Ripple3D {
+    /// Creates a new ripple action
+    pub fn new(duration: f32, grid_size: (u32, u32), center: Vec2, radius: f32, waves: u32, amplitude: f32) -> Ripple3D {
+        Ripple3D {
+            grid_action: Grid3DAction::new(duration, grid_size),
+            center,
+            radius,
+            waves,
+            amplitude,
+        }
+    }
+
+    /// Gets the ripple center
+    pub fn get_center(&self) -> Vec2 {
+        self.center
+    }
+
+    /// Sets the ripple center
+    pub fn set_center(&mut self, center: Vec2) {
+        self.center = center;
+    }
+
+    /// Gets the amplitude
+    pub fn get_amplitude(&self) -> f32 {
+        self.amplitude
+    }
+
+    /// Sets the amplitude
+    pub fn set_amplitude(&mut self, amplitude: f32) {
+        self.amplitude = amplitude;
+    }
+
+    /// Gets the grid, available once the action has started
+    pub fn get_grid(&self) -> Option<&Grid3D> {
+        self.grid_action.get_grid()
+    }
+}
+
+impl Action for Ripple3D {
+    fn start_with_target(&mut self, target: NodeRef) {
+        self.grid_action.start_with_target(target);
+    }
+
+    fn stop(&mut self) {
+        self.grid_action.stop();
+    }
+
+    fn step(&mut self, dt: f32) {
+        let time = self.grid_action.step(dt);
+        self.update(time);
+    }
+
+    fn update(&mut self, time: f32) {
+        let envelope = (PI * time).sin();
+        let phase = time * PI * self.waves as f32 * 2.0;
+        let (center, radius, amplitude) = (self.center, self.radius, self.amplitude);
+
+        if let Some(grid) = self.grid_action.get_grid_mut() {
+            let (width, height) = grid.get_grid_size();
+            for y in 0..=height {
+                for x in 0..=width {
+                    let mut vertex = grid.get_original_vertex(x, y);
+                    let distance = (Vec2::new(vertex.x, vertex.y) - center).length();
+                    if distance < radius {
+                        let r = radius - distance;
+                        let rate = (r / radius) * (r / radius);
+                        vertex.z += (phase + r * 0.1).sin() * amplitude * rate * envelope;
+                    }
+                    grid.set_vertex(x, y, vertex);
+                }
+            }
+        }
+    }
+
+    fn is_done(&self) -> bool {
+        self.grid_action.is_done()
+    }
+
+    fn get_tag(&self) -> i32 {
+        self.grid_action.get_tag()
+    }
+
+    fn set_tag(&mut self, tag: i32) {
+        self.grid_action.set_tag(tag);
+    }
+}
+
+impl FiniteTimeAction for Ripple3D {
+    fn get_duration(&self) -> f32 {
+        self.grid_action.get_duration()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::RefCell;
+    use std::rc::Rc;
+    use crate::base::Node;
+
+    fn sized_target() -> NodeRef {
+        let mut node = Node::new();
+        node.set_content_size(Vec2::new(100.0, 100.0));
+        Rc::new(RefCell::new(node))
+    }
+
+    #[test]
+    fn test_grid_vertex_count() {
+        let grid = Grid3D::new(4, 4, Vec2::new(100.0, 100.0));
+        assert_eq!(grid.get_vertex_count(), 25);
+        assert_eq!(grid.get_step(), Vec2::new(25.0, 25.0));
+        assert_eq!(grid.get_vertex(4, 4), Vec3::new(100.0, 100.0, 0.0));
+    }
+
+    #[test]
+    fn test_ripple_displaces_inside_radius() {
+        let mut ripple = Ripple3D::new(1.0, (4, 4), Vec2::new(50.0, 50.0), 40.0, 1, 10.0);
+        ripple.start_with_target(sized_target());
+        assert_eq!(ripple.get_grid().unwrap().get_vertex_count(), 25);
+
+        ripple.update(0.0);
+        let grid = ripple.get_grid().unwrap();
+        assert!(grid.get_vertices().iter().all(|v| v.z == 0.0));
+
+        ripple.update(0.5);
+        let grid = ripple.get_grid().unwrap();
+        assert!(grid.get_vertex(2, 2).z.abs() > 0.1);
+        assert!(grid.get_vertex(1, 2).z.abs() > 0.01);
+        assert_eq!(grid.get_vertex(0, 0).z, 0.0);
+        assert_eq!(grid.get_vertex(4, 4).z, 0.0);
+    }
+
+    #[test]
+    fn test_ripple_stop_restores_grid() {
+        let mut ripple = Ripple3D::new(1.0, (4, 4), Vec2::new(50.0, 50.0), 40.0, 2, 10.0);
+        ripple.start_with_target(sized_target());
+        ripple.step(0.3);
+        ripple.stop();
+        assert!(ripple.get_grid().unwrap().get_vertices().iter().all(|v| v.z == 0.0));
+    }
+}
@@ -1,123 +1,1536 @@
-use crate::base::{Node, Ref, RefPtr};
+use std::cell::RefCell;
+use std::fmt;
+use std::rc::Rc;
+use crate::base::{ColorProtocol, Director, Node, NodeRef};
+use crate::base::types::Color3B;
 use crate::math::Vec2;
 
-/// Action is the base class for all actions
+pub mod grid_action;
+pub mod easing;
+pub mod builder;
+
+pub use grid_action::{Grid3D, Grid3DAction, Ripple3D};
+pub use easing::{EasingType, EaseAction, apply_easing, sample_curve, is_monotonic};
+pub use builder::ActionBuilder;
+
+/// Action is the base trait for all actions
+pub trait Action: fmt::Debug {
+    /// Starts the action with a target
+    fn start_with_target(&mut self, target: NodeRef);
+
+    /// Stops the action
+    fn stop(&mut self) {}
+
+    /// Advances the action by `dt` seconds
+    fn step(&mut self, dt: f32);
+
+    /// Applies the action at normalized `time` in [0, 1]
+    fn update(&mut self, time: f32);
+
+    /// Checks if the action is done
+    fn is_done(&self) -> bool;
+
+    /// Gets the tag
+    fn get_tag(&self) -> i32;
+
+    /// Sets the tag
+    fn set_tag(&mut self, tag: i32);
+}
+
+/// Finite Time Action is an action that takes a finite amount of time
+pub trait FiniteTimeAction: Action {
+    /// Gets the duration
+    fn get_duration(&self) -> f32;
+}
+
+/// Reversible is a finite time action that can be played backwards. The
+/// relative `*By` actions implement it; the absolute `*To` actions do not.
+pub trait Reversible: FiniteTimeAction {
+    /// Creates an action that plays this one backwards from where it ends,
+    /// e.g. for ping-pong sequences
+    fn reverse(&self) -> Box<dyn FiniteTimeAction>;
+}
+
+/// Shared state for all actions: the target and the tag
+#[derive(Debug)]
+pub struct ActionBase {
+    target: Option<NodeRef>,
+    original_target: Option<NodeRef>,
+    tag: i32,
+    flags: u32,
+}
+
+impl ActionBase {
+    /// Creates a new action base
+    pub fn new() -> ActionBase {
+        ActionBase {
+            target: None,
+            original_target: None,
+            tag: 0,
+            flags: 0,
+        }
+    }
+
+    /// Starts the action with a target
+    pub fn start_with_target(&mut self, target: NodeRef) {
+        self.original_target = Some(target.clone());
+        self.target = Some(target);
+    }
+
+    /// Stops the action
+    pub fn stop(&mut self) {
+        self.target = None;
+    }
+
+    /// Gets the target
+    pub fn get_target(&self) -> Option<&NodeRef> {
+        self.target.as_ref()
+    }
+
+    /// Gets the original target
+    pub fn get_original_target(&self) -> Option<&NodeRef> {
+        self.original_target.as_ref()
+    }
+
+    /// Sets the tag
+    pub fn set_tag(&mut self, tag: i32) {
+        self.tag = tag;
+    }
+
+    /// Gets the tag
+    pub fn get_tag(&self) -> i32 {
+        self.tag
+    }
+}
+
+/// Timing state shared by actions that run over a duration
+#[derive(Debug)]
+pub struct ActionInterval {
+    base: ActionBase,
+    duration: f32,
+    elapsed: f32,
+}
+
+impl ActionInterval {
+    /// Creates a new interval
+    pub fn new(duration: f32) -> ActionInterval {
+        ActionInterval {
+            base: ActionBase::new(),
+            duration: duration.max(0.0),
+            elapsed: 0.0,
+        }
+    }
+
+    /// Starts the interval with a target, resetting the elapsed time
+    pub fn start_with_target(&mut self, target: NodeRef) {
+        self.base.start_with_target(target);
+        self.elapsed = 0.0;
+    }
+
+    /// Stops the interval
+    pub fn stop(&mut self) {
+        self.base.stop();
+    }
+
+    /// Advances the elapsed time and returns the normalized progress
+    pub fn step(&mut self, dt: f32) -> f32 {
+        self.elapsed += dt;
+        self.get_progress()
+    }
+
+    /// Gets the normalized progress in [0, 1]
+    pub fn get_progress(&self) -> f32 {
+        if self.duration <= 0.0 {
+            1.0
+        } else {
+            (self.elapsed / self.duration).clamp(0.0, 1.0)
+        }
+    }
+
+    /// Checks if the interval is done
+    pub fn is_done(&self) -> bool {
+        self.elapsed >= self.duration
+    }
+
+    /// Gets the duration
+    pub fn get_duration(&self) -> f32 {
+        self.duration
+    }
+
+    /// Sets the duration
+    pub fn set_duration(&mut self, duration: f32) {
+        self.duration = duration.max(0.0);
+    }
+
+    /// Gets the elapsed time
+    pub fn get_elapsed(&self) -> f32 {
+        self.elapsed
+    }
+
+    /// Gets the target
+    pub fn get_target(&self) -> Option<&NodeRef> {
+        self.base.get_target()
+    }
+
+    /// Gets the tag
+    pub fn get_tag(&self) -> i32 {
+        self.base.get_tag()
+    }
+
+    /// Sets the tag
+    pub fn set_tag(&mut self, tag: i32) {
+        self.base.set_tag(tag);
+    }
+}
+
+/// MoveBy moves the target by a relative offset
+#[derive(Debug)]
+pub struct MoveBy {
+    interval: ActionInterval,
+    delta: Vec2,
+    start_position: Vec2,
+}
+
+impl MoveBy {
+    /// Creates a new move by action
+    pub fn new(duration: f32, delta: Vec2) -> MoveBy {
+        MoveBy {
+            interval: ActionInterval::new(duration),
+            delta,
+            start_position: Vec2::ZERO,
+        }
+    }
+
+    /// Gets the offset
+    pub fn get_delta(&self) -> Vec2 {
+        self.delta
+    }
+}
+
+impl Action for MoveBy {
+    fn start_with_target(&mut self, target: NodeRef) {
+        self.start_position = *target.borrow().get_position();
+        self.interval.start_with_target(target);
+    }
+
+    fn stop(&mut self) {
+        self.interval.stop();
+    }
+
+    fn step(&mut self, dt: f32) {
+        let time = self.interval.step(dt);
+        self.update(time);
+    }
+
+    fn update(&mut self, time: f32) {
+        if let Some(target) = self.interval.get_target() {
+            target.borrow_mut().set_position(self.start_position + self.delta * time);
+        }
+    }
+
+    fn is_done(&self) -> bool {
+        self.interval.is_done()
+    }
+
+    fn get_tag(&self) -> i32 {
+        self.interval.get_tag()
+    }
+
+    fn set_tag(&mut self, tag: i32) {
+        self.interval.set_tag(tag);
+    }
+}
+
+impl FiniteTimeAction for MoveBy {
+    fn get_duration(&self) -> f32 {
+        self.interval.get_duration()
+    }
+}
+
+impl Reversible for MoveBy {
+    fn reverse(&self) -> Box<dyn FiniteTimeAction> {
+        Box::new(MoveBy::new(self.get_duration(), -self.delta))
+    }
+}
+
+/// MoveTo moves the target to an absolute position
+#[derive(Debug)]
+pub struct MoveTo {
+    move_by: MoveBy,
+    end_position: Vec2,
+}
+
+impl MoveTo {
+    /// Creates a new move to action
+    pub fn new(duration: f32, position: Vec2) -> MoveTo {
+        MoveTo {
+            move_by: MoveBy::new(duration, Vec2::ZERO),
+            end_position: position,
+        }
+    }
+}
+
+impl Action for MoveTo {
+    fn start_with_target(&mut self, target: NodeRef) {
+        self.move_by.start_with_target(target);
+        self.move_by.delta = self.end_position - self.move_by.start_position;
+    }
+
+    fn stop(&mut self) {
+        self.move_by.stop();
+    }
+
+    fn step(&mut self, dt: f32) {
+        self.move_by.step(dt);
+    }
+
+    fn update(&mut self, time: f32) {
+        self.move_by.update(time);
+    }
+
+    fn is_done(&self) -> bool {
+        self.move_by.is_done()
+    }
+
+    fn get_tag(&self) -> i32 {
+        self.move_by.get_tag()
+    }
+
+    fn set_tag(&mut self, tag: i32) {
+        self.move_by.set_tag(tag);
+    }
+}
+
+impl FiniteTimeAction for MoveTo {
+    fn get_duration(&self) -> f32 {
+        self.move_by.get_duration()
+    }
+}
+
+/// ScaleTo scales the target to an absolute factor
+#[derive(Debug)]
+pub struct ScaleTo {
+    interval: ActionInterval,
+    end_scale_x: f32,
+    end_scale_y: f32,
+    start_scale_x: f32,
+    start_scale_y: f32,
+    delta_x: f32,
+    delta_y: f32,
+}
+
+impl ScaleTo {
+    /// Creates a new scale to action with a uniform factor
+    pub fn new(duration: f32, scale: f32) -> ScaleTo {
+        ScaleTo::with_xy(duration, scale, scale)
+    }
+
+    /// Creates a new scale to action with separate X and Y factors
+    pub fn with_xy(duration: f32, scale_x: f32, scale_y: f32) -> ScaleTo {
+        ScaleTo {
+            interval: ActionInterval::new(duration),
+            end_scale_x: scale_x,
+            end_scale_y: scale_y,
+            start_scale_x: 1.0,
+            start_scale_y: 1.0,
+            delta_x: 0.0,
+            delta_y: 0.0,
+        }
+    }
+}
+
+impl Action for ScaleTo {
+    fn start_with_target(&mut self, target: NodeRef) {
+        {
+            let node = target.borrow();
+            self.start_scale_x = node.get_scale_x();
+            self.start_scale_y = node.get_scale_y();
+        }
+        self.delta_x = self.end_scale_x - self.start_scale_x;
+        self.delta_y = self.end_scale_y - self.start_scale_y;
+        self.interval.start_with_target(target);
+    }
+
+    fn stop(&mut self) {
+        self.interval.stop();
+    }
+
+    fn step(&mut self, dt: f32) {
+        let time = self.interval.step(dt);
+        self.update(time);
+    }
+
+    fn update(&mut self, time: f32) {
+        if let Some(target) = self.interval.get_target() {
+            target.borrow_mut().set_scale_xy(
+                self.start_scale_x + self.delta_x * time,
+                self.start_scale_y + self.delta_y * time,
+            );
+        }
+    }
+
+    fn is_done(&self) -> bool {
+        self.interval.is_done()
+    }
+
+    fn get_tag(&self) -> i32 {
+        self.interval.get_tag()
+    }
+
+    fn set_tag(&mut self, tag: i32) {
+        self.interval.set_tag(tag);
+    }
+}
+
+impl FiniteTimeAction for ScaleTo {
+    fn get_duration(&self) -> f32 {
+        self.interval.get_duration()
+    }
+}
+
+/// ScaleBy multiplies the target's scale by a factor
+#[derive(Debug)]
+pub struct ScaleBy {
+    scale_to: ScaleTo,
+    scale_x: f32,
+    scale_y: f32,
+}
+
+impl ScaleBy {
+    /// Creates a new scale by action with a uniform factor
+    pub fn new(duration: f32, scale: f32) -> ScaleBy {
+        ScaleBy::with_xy(duration, scale, scale)
+    }
+
+    /// Creates a new scale by action with separate X and Y factors
+    pub fn with_xy(duration: f32, scale_x: f32, scale_y: f32) -> ScaleBy {
+        ScaleBy {
+            scale_to: ScaleTo::with_xy(duration, scale_x, scale_y),
+            scale_x,
+            scale_y,
+        }
+    }
+}
+
+impl Action for ScaleBy {
+    fn start_with_target(&mut self, target: NodeRef) {
+        self.scale_to.start_with_target(target);
+        let scale_to = &mut self.scale_to;
+        scale_to.delta_x = scale_to.start_scale_x * self.scale_x - scale_to.start_scale_x;
+        scale_to.delta_y = scale_to.start_scale_y * self.scale_y - scale_to.start_scale_y;
+    }
+
+    fn stop(&mut self) {
+        self.scale_to.stop();
+    }
+
+    fn step(&mut self, dt: f32) {
+        self.scale_to.step(dt);
+    }
+
+    fn update(&mut self, time: f32) {
+        self.scale_to.update(time);
+    }
+
+    fn is_done(&self) -> bool {
+        self.scale_to.is_done()
+    }
+
+    fn get_tag(&self) -> i32 {
+        self.scale_to.get_tag()
+    }
+
+    fn set_tag(&mut self, tag: i32) {
+        self.scale_to.set_tag(tag);
+    }
+}
+
+impl FiniteTimeAction for ScaleBy {
+    fn get_duration(&self) -> f32 {
+        self.scale_to.get_duration()
+    }
+}
+
+impl Reversible for ScaleBy {
+    /// Scales by the inverse factors. A zero factor cannot be undone, so that
+    /// axis is left as it is.
+    fn reverse(&self) -> Box<dyn FiniteTimeAction> {
+        let inverse = |scale: f32| if scale == 0.0 { 1.0 } else { 1.0 / scale };
+        Box::new(ScaleBy::with_xy(self.get_duration(), inverse(self.scale_x), inverse(self.scale_y)))
+    }
+}
+
+/// RotateBy rotates the target by a relative angle in degrees
+#[derive(Debug)]
+pub struct RotateBy {
+    interval: ActionInterval,
+    delta_angle: f32,
+    start_angle: f32,
+}
+
+impl RotateBy {
+    /// Creates a new rotate by action
+    pub fn new(duration: f32, delta_angle: f32) -> RotateBy {
+        RotateBy {
+            interval: ActionInterval::new(duration),
+            delta_angle,
+            start_angle: 0.0,
+        }
+    }
+
+    /// Gets the rotation offset in degrees
+    pub fn get_delta_angle(&self) -> f32 {
+        self.delta_angle
+    }
+}
+
+impl Action for RotateBy {
+    fn start_with_target(&mut self, target: NodeRef) {
+        self.start_angle = target.borrow().get_rotation();
+        self.interval.start_with_target(target);
+    }
+
+    fn stop(&mut self) {
+        self.interval.stop();
+    }
+
+    fn step(&mut self, dt: f32) {
+        let time = self.interval.step(dt);
+        self.update(time);
+    }
+
+    fn update(&mut self, time: f32) {
+        if let Some(target) = self.interval.get_target() {
+            target.borrow_mut().set_rotation(self.start_angle + self.delta_angle * time);
+        }
+    }
+
+    fn is_done(&self) -> bool {
+        self.interval.is_done()
+    }
+
+    fn get_tag(&self) -> i32 {
+        self.interval.get_tag()
+    }
+
+    fn set_tag(&mut self, tag: i32) {
+        self.interval.set_tag(tag);
+    }
+}
+
+impl FiniteTimeAction for RotateBy {
+    fn get_duration(&self) -> f32 {
+        self.interval.get_duration()
+    }
+}
+
+impl Reversible for RotateBy {
+    fn reverse(&self) -> Box<dyn FiniteTimeAction> {
+        Box::new(RotateBy::new(self.get_duration(), -self.delta_angle))
+    }
+}
+
+/// RotateTo rotates the target to an absolute angle in degrees along the
+/// shortest path
+#[derive(Debug)]
+pub struct RotateTo {
+    rotate_by: RotateBy,
+    end_angle: f32,
+}
+
+impl RotateTo {
+    /// Creates a new rotate to action
+    pub fn new(duration: f32, angle: f32) -> RotateTo {
+        RotateTo {
+            rotate_by: RotateBy::new(duration, 0.0),
+            end_angle: angle,
+        }
+    }
+}
+
+impl Action for RotateTo {
+    fn start_with_target(&mut self, target: NodeRef) {
+        self.rotate_by.start_with_target(target);
+
+        let mut delta = (self.end_angle - self.rotate_by.start_angle) % 360.0;
+        if delta > 180.0 {
+            delta -= 360.0;
+        } else if delta < -180.0 {
+            delta += 360.0;
+        }
+        self.rotate_by.delta_angle = delta;
+    }
+
+    fn stop(&mut self) {
+        self.rotate_by.stop();
+    }
+
+    fn step(&mut self, dt: f32) {
+        self.rotate_by.step(dt);
+    }
+
+    fn update(&mut self, time: f32) {
+        self.rotate_by.update(time);
+    }
+
+    fn is_done(&self) -> bool {
+        self.rotate_by.is_done()
+    }
+
+    fn get_tag(&self) -> i32 {
+        self.rotate_by.get_tag()
+    }
+
+    fn set_tag(&mut self, tag: i32) {
+        self.rotate_by.set_tag(tag);
+    }
+}
+
+impl FiniteTimeAction for RotateTo {
+    fn get_duration(&self) -> f32 {
+        self.rotate_by.get_duration()
+    }
+}
+
+/// Control points and end point of a cubic Bezier path
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BezierConfig {
+    pub control_point_1: Vec2,
+    pub control_point_2: Vec2,
+    pub end_position: Vec2,
+}
+
+impl BezierConfig {
+    /// Creates a new bezier config
+    pub fn new(control_point_1: Vec2, control_point_2: Vec2, end_position: Vec2) -> BezierConfig {
+        BezierConfig {
+            control_point_1,
+            control_point_2,
+            end_position,
+        }
+    }
+}
+
+fn bezier_at(a: f32, b: f32, c: f32, d: f32, t: f32) -> f32 {
+    let u = 1.0 - t;
+    u * u * u * a + 3.0 * t * u * u * b + 3.0 * t * t * u * c + t * t * t * d
+}
+
+/// BezierBy moves the target along a cubic Bezier path relative to its start
+#[derive(Debug)]
+pub struct BezierBy {
+    interval: ActionInterval,
+    config: BezierConfig,
+    start_position: Vec2,
+}
+
+impl BezierBy {
+    /// Creates a new bezier by action
+    pub fn new(duration: f32, config: BezierConfig) -> BezierBy {
+        BezierBy {
+            interval: ActionInterval::new(duration),
+            config,
+            start_position: Vec2::ZERO,
+        }
+    }
+
+    /// Gets the bezier config
+    pub fn get_config(&self) -> &BezierConfig {
+        &self.config
+    }
+}
+
+impl Action for BezierBy {
+    fn start_with_target(&mut self, target: NodeRef) {
+        self.start_position = *target.borrow().get_position();
+        self.interval.start_with_target(target);
+    }
+
+    fn stop(&mut self) {
+        self.interval.stop();
+    }
+
+    fn step(&mut self, dt: f32) {
+        let time = self.interval.step(dt);
+        self.update(time);
+    }
+
+    fn update(&mut self, time: f32) {
+        if let Some(target) = self.interval.get_target() {
+            let c = &self.config;
+            let x = bezier_at(0.0, c.control_point_1.x, c.control_point_2.x, c.end_position.x, time);
+            let y = bezier_at(0.0, c.control_point_1.y, c.control_point_2.y, c.end_position.y, time);
+            target.borrow_mut().set_position(self.start_position + Vec2::new(x, y));
+        }
+    }
+
+    fn is_done(&self) -> bool {
+        self.interval.is_done()
+    }
+
+    fn get_tag(&self) -> i32 {
+        self.interval.get_tag()
+    }
+
+    fn set_tag(&mut self, tag: i32) {
+        self.interval.set_tag(tag);
+    }
+}
+
+impl FiniteTimeAction for BezierBy {
+    fn get_duration(&self) -> f32 {
+        self.interval.get_duration()
+    }
+}
+
+impl Reversible for BezierBy {
+    /// Follows the same path back, with the control points taken relative
+    /// to the end point
+    fn reverse(&self) -> Box<dyn FiniteTimeAction> {
+        let c = &self.config;
+        let config = BezierConfig::new(
+            c.control_point_2 - c.end_position,
+            c.control_point_1 - c.end_position,
+            -c.end_position,
+        );
+        Box::new(BezierBy::new(self.get_duration(), config))
+    }
+}
+
+/// BezierTo moves the target along a cubic Bezier path in absolute coordinates
+#[derive(Debug)]
+pub struct BezierTo {
+    bezier_by: BezierBy,
+    to_config: BezierConfig,
+}
+
+impl BezierTo {
+    /// Creates a new bezier to action
+    pub fn new(duration: f32, config: BezierConfig) -> BezierTo {
+        BezierTo {
+            bezier_by: BezierBy::new(duration, config),
+            to_config: config,
+        }
+    }
+}
+
+impl Action for BezierTo {
+    fn start_with_target(&mut self, target: NodeRef) {
+        self.bezier_by.start_with_target(target);
+        let start = self.bezier_by.start_position;
+        self.bezier_by.config = BezierConfig::new(
+            self.to_config.control_point_1 - start,
+            self.to_config.control_point_2 - start,
+            self.to_config.end_position - start,
+        );
+    }
+
+    fn stop(&mut self) {
+        self.bezier_by.stop();
+    }
+
+    fn step(&mut self, dt: f32) {
+        self.bezier_by.step(dt);
+    }
+
+    fn update(&mut self, time: f32) {
+        self.bezier_by.update(time);
+    }
+
+    fn is_done(&self) -> bool {
+        self.bezier_by.is_done()
+    }
+
+    fn get_tag(&self) -> i32 {
+        self.bezier_by.get_tag()
+    }
+
+    fn set_tag(&mut self, tag: i32) {
+        self.bezier_by.set_tag(tag);
+    }
+}
+
+impl FiniteTimeAction for BezierTo {
+    fn get_duration(&self) -> f32 {
+        self.bezier_by.get_duration()
+    }
+}
+
+/// JumpBy moves the target by an offset while hopping along parabolic arcs
+#[derive(Debug)]
+pub struct JumpBy {
+    interval: ActionInterval,
+    delta: Vec2,
+    height: f32,
+    jumps: u32,
+    start_position: Vec2,
+}
+
+impl JumpBy {
+    /// Creates a new jump by action
+    pub fn new(duration: f32, delta: Vec2, height: f32, jumps: u32) -> JumpBy {
+        JumpBy {
+            interval: ActionInterval::new(duration),
+            delta,
+            height,
+            jumps,
+            start_position: Vec2::ZERO,
+        }
+    }
+
+    /// Gets the offset
+    pub fn get_delta(&self) -> Vec2 {
+        self.delta
+    }
+
+    /// Gets the height of each jump
+    pub fn get_height(&self) -> f32 {
+        self.height
+    }
+
+    /// Gets the number of jumps
+    pub fn get_jumps(&self) -> u32 {
+        self.jumps
+    }
+}
+
+impl Action for JumpBy {
+    fn start_with_target(&mut self, target: NodeRef) {
+        self.start_position = *target.borrow().get_position();
+        self.interval.start_with_target(target);
+    }
+
+    fn stop(&mut self) {
+        self.interval.stop();
+    }
+
+    fn step(&mut self, dt: f32) {
+        let time = self.interval.step(dt);
+        self.update(time);
+    }
+
+    fn update(&mut self, time: f32) {
+        if let Some(target) = self.interval.get_target() {
+            // Each jump is a parabola 4h * f * (1 - f) over its share of the duration
+            let frac = if time >= 1.0 { 0.0 } else { (time * self.jumps as f32).fract() };
+            let y = self.height * 4.0 * frac * (1.0 - frac) + self.delta.y * time;
+            let x = self.delta.x * time;
+            target.borrow_mut().set_position(self.start_position + Vec2::new(x, y));
+        }
+    }
+
+    fn is_done(&self) -> bool {
+        self.interval.is_done()
+    }
+
+    fn get_tag(&self) -> i32 {
+        self.interval.get_tag()
+    }
+
+    fn set_tag(&mut self, tag: i32) {
+        self.interval.set_tag(tag);
+    }
+}
+
+impl FiniteTimeAction for JumpBy {
+    fn get_duration(&self) -> f32 {
+        self.interval.get_duration()
+    }
+}
+
+impl Reversible for JumpBy {
+    fn reverse(&self) -> Box<dyn FiniteTimeAction> {
+        Box::new(JumpBy::new(self.get_duration(), -self.delta, self.height, self.jumps))
+    }
+}
+
+/// JumpTo moves the target to a position while hopping along parabolic arcs
+#[derive(Debug)]
+pub struct JumpTo {
+    jump_by: JumpBy,
+    end_position: Vec2,
+}
+
+impl JumpTo {
+    /// Creates a new jump to action
+    pub fn new(duration: f32, position: Vec2, height: f32, jumps: u32) -> JumpTo {
+        JumpTo {
+            jump_by: JumpBy::new(duration, Vec2::ZERO, height, jumps),
+            end_position: position,
+        }
+    }
+}
+
+impl Action for JumpTo {
+    fn start_with_target(&mut self, target: NodeRef) {
+        self.jump_by.start_with_target(target);
+        self.jump_by.delta = self.end_position - self.jump_by.start_position;
+    }
+
+    fn stop(&mut self) {
+        self.jump_by.stop();
+    }
+
+    fn step(&mut self, dt: f32) {
+        self.jump_by.step(dt);
+    }
+
+    fn update(&mut self, time: f32) {
+        self.jump_by.update(time);
+    }
+
+    fn is_done(&self) -> bool {
+        self.jump_by.is_done()
+    }
+
+    fn get_tag(&self) -> i32 {
+        self.jump_by.get_tag()
+    }
+
+    fn set_tag(&mut self, tag: i32) {
+        self.jump_by.set_tag(tag);
+    }
+}
+
+impl FiniteTimeAction for JumpTo {
+    fn get_duration(&self) -> f32 {
+        self.jump_by.get_duration()
+    }
+}
+
+/// Shared handle to an object whose color and opacity are animated
+pub type ColorTarget = Rc<RefCell<dyn ColorProtocol>>;
+
+fn lerp_u8(from: u8, to: u8, time: f32) -> u8 {
+    (from as f32 + (to as f32 - from as f32) * time) as u8
+}
+
+/// FadeTo changes the opacity of its color target to a given value
+pub struct FadeTo {
+    interval: ActionInterval,
+    color_target: ColorTarget,
+    to_opacity: u8,
+    from_opacity: u8,
+}
+
+impl FadeTo {
+    /// Creates a new fade to action
+    pub fn new(duration: f32, opacity: u8, color_target: ColorTarget) -> FadeTo {
+        FadeTo {
+            interval: ActionInterval::new(duration),
+            color_target,
+            to_opacity: opacity,
+            from_opacity: 255,
+        }
+    }
+
+    /// Gets the destination opacity
+    pub fn get_to_opacity(&self) -> u8 {
+        self.to_opacity
+    }
+}
+
+impl fmt::Debug for FadeTo {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("FadeTo")
+            .field("interval", &self.interval)
+            .field("to_opacity", &self.to_opacity)
+            .field("from_opacity", &self.from_opacity)
+            .finish()
+    }
+}
+
+impl Action for FadeTo {
+    fn start_with_target(&mut self, target: NodeRef) {
+        self.from_opacity = self.color_target.borrow().get_opacity();
+        self.interval.start_with_target(target);
+    }
+
+    fn stop(&mut self) {
+        self.interval.stop();
+    }
+
+    fn step(&mut self, dt: f32) {
+        let time = self.interval.step(dt);
+        self.update(time);
+    }
+
+    fn update(&mut self, time: f32) {
+        let opacity = lerp_u8(self.from_opacity, self.to_opacity, time);
+        self.color_target.borrow_mut().set_opacity(opacity);
+    }
+
+    fn is_done(&self) -> bool {
+        self.interval.is_done()
+    }
+
+    fn get_tag(&self) -> i32 {
+        self.interval.get_tag()
+    }
+
+    fn set_tag(&mut self, tag: i32) {
+        self.interval.set_tag(tag);
+    }
+}
+
+impl FiniteTimeAction for FadeTo {
+    fn get_duration(&self) -> f32 {
+        self.interval.get_duration()
+    }
+}
+
+/// FadeIn fades its color target to full opacity
+#[derive(Debug)]
+pub struct FadeIn {
+    fade_to: FadeTo,
+}
+
+impl FadeIn {
+    /// Creates a new fade in action
+    pub fn new(duration: f32, color_target: ColorTarget) -> FadeIn {
+        FadeIn {
+            fade_to: FadeTo::new(duration, 255, color_target),
+        }
+    }
+}
+
+impl Action for FadeIn {
+    fn start_with_target(&mut self, target: NodeRef) {
+        self.fade_to.start_with_target(target);
+    }
+
+    fn stop(&mut self) {
+        self.fade_to.stop();
+    }
+
+    fn step(&mut self, dt: f32) {
+        self.fade_to.step(dt);
+    }
+
+    fn update(&mut self, time: f32) {
+        self.fade_to.update(time);
+    }
+
+    fn is_done(&self) -> bool {
+        self.fade_to.is_done()
+    }
+
+    fn get_tag(&self) -> i32 {
+        self.fade_to.get_tag()
+    }
+
+    fn set_tag(&mut self, tag: i32) {
+        self.fade_to.set_tag(tag);
+    }
+}
+
+impl FiniteTimeAction for FadeIn {
+    fn get_duration(&self) -> f32 {
+        self.fade_to.get_duration()
+    }
+}
+
+/// FadeOut fades its color target to full transparency
+#[derive(Debug)]
+pub struct FadeOut {
+    fade_to: FadeTo,
+}
+
+impl FadeOut {
+    /// Creates a new fade out action
+    pub fn new(duration: f32, color_target: ColorTarget) -> FadeOut {
+        FadeOut {
+            fade_to: FadeTo::new(duration, 0, color_target),
+        }
+    }
+}
+
+impl Action for FadeOut {
+    fn start_with_target(&mut self, target: NodeRef) {
+        self.fade_to.start_with_target(target);
+    }
+
+    fn stop(&mut self) {
+        self.fade_to.stop();
+    }
+
+    fn step(&mut self, dt: f32) {
+        self.fade_to.step(dt);
+    }
+
+    fn update(&mut self, time: f32) {
+        self.fade_to.update(time);
+    }
+
+    fn is_done(&self) -> bool {
+        self.fade_to.is_done()
+    }
+
+    fn get_tag(&self) -> i32 {
+        self.fade_to.get_tag()
+    }
+
+    fn set_tag(&mut self, tag: i32) {
+        self.fade_to.set_tag(tag);
+    }
+}
+
+impl FiniteTimeAction for FadeOut {
+    fn get_duration(&self) -> f32 {
+        self.fade_to.get_duration()
+    }
+}
+
+/// TintTo changes the color of its color target to a given color
+pub struct TintTo {
+    interval: ActionInterval,
+    color_target: ColorTarget,
+    to_color: Color3B,
+    from_color: Color3B,
+}
+
+impl TintTo {
+    /// Creates a new tint to action
+    pub fn new(duration: f32, color: Color3B, color_target: ColorTarget) -> TintTo {
+        TintTo {
+            interval: ActionInterval::new(duration),
+            color_target,
+            to_color: color,
+            from_color: Color3B::WHITE,
+        }
+    }
+
+    /// Gets the destination color
+    pub fn get_to_color(&self) -> Color3B {
+        self.to_color
+    }
+}
+
+impl fmt::Debug for TintTo {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("TintTo")
+            .field("interval", &self.interval)
+            .field("to_color", &self.to_color)
+            .field("from_color", &self.from_color)
+            .finish()
+    }
+}
+
+impl Action for TintTo {
+    fn start_with_target(&mut self, target: NodeRef) {
+        self.from_color = self.color_target.borrow().get_color();
+        self.interval.start_with_target(target);
+    }
+
+    fn stop(&mut self) {
+        self.interval.stop();
+    }
+
+    fn step(&mut self, dt: f32) {
+        let time = self.interval.step(dt);
+        self.update(time);
+    }
+
+    fn update(&mut self, time: f32) {
+        let color = Color3B::new(
+            lerp_u8(self.from_color.r, self.to_color.r, time),
+            lerp_u8(self.from_color.g, self.to_color.g, time),
+            lerp_u8(self.from_color.b, self.to_color.b, time),
+        );
+        self.color_target.borrow_mut().set_color(color);
+    }
+
+    fn is_done(&self) -> bool {
+        self.interval.is_done()
+    }
+
+    fn get_tag(&self) -> i32 {
+        self.interval.get_tag()
+    }
+
+    fn set_tag(&mut self, tag: i32) {
+        self.interval.set_tag(tag);
+    }
+}
+
+impl FiniteTimeAction for TintTo {
+    fn get_duration(&self) -> f32 {
+        self.interval.get_duration()
+    }
+}
+
+/// Sequence runs its actions one after another. A sequence built with
+/// `Sequence::reversible` holds only reversible actions and is reversible
+/// itself.
+#[derive(Debug)]
+pub struct Sequence<A: ?Sized = dyn FiniteTimeAction> {
+    interval: ActionInterval,
+    actions: Vec<Box<A>>,
+    started: usize,
+    finished: usize,
+}
+
+impl Sequence {
+    /// Creates a new sequence
+    pub fn new(actions: Vec<Box<dyn FiniteTimeAction>>) -> Sequence {
+        Sequence::with_actions(actions)
+    }
+}
+
+impl Sequence<dyn Reversible> {
+    /// Creates a new sequence that can be reversed
+    pub fn reversible(actions: Vec<Box<dyn Reversible>>) -> Sequence<dyn Reversible> {
+        Sequence::with_actions(actions)
+    }
+}
+
+impl<A: FiniteTimeAction + ?Sized> Sequence<A> {
+    fn with_actions(actions: Vec<Box<A>>) -> Sequence<A> {
+        let duration = actions.iter().map(|action| action.get_duration()).sum();
+        Sequence {
+            interval: ActionInterval::new(duration),
+            actions,
+            started: 0,
+            finished: 0,
+        }
+    }
+
+    /// Gets the actions
+    pub fn get_actions(&self) -> &[Box<A>] {
+        &self.actions
+    }
+}
+
+impl<A: FiniteTimeAction + ?Sized> Action for Sequence<A> {
+    fn start_with_target(&mut self, target: NodeRef) {
+        self.interval.start_with_target(target);
+        self.started = 0;
+        self.finished = 0;
+    }
+
+    fn stop(&mut self) {
+        if self.started > self.finished {
+            self.actions[self.started - 1].stop();
+        }
+        self.interval.stop();
+    }
+
+    fn step(&mut self, dt: f32) {
+        let time = self.interval.step(dt);
+        self.update(time);
+    }
+
+    fn update(&mut self, time: f32) {
+        let target = match self.interval.get_target() {
+            Some(target) => target.clone(),
+            None => return,
+        };
+        let target_time = time * self.interval.get_duration();
+
+        let mut start = 0.0;
+        for (index, action) in self.actions.iter_mut().enumerate() {
+            if target_time < start {
+                break;
+            }
+            let duration = action.get_duration();
+            let end = start + duration;
+
+            if index >= self.started {
+                action.start_with_target(target.clone());
+                self.started = index + 1;
+            }
+
+            if target_time >= end {
+                if index >= self.finished {
+                    action.update(1.0);
+                    action.stop();
+                    self.finished = index + 1;
+                }
+            } else {
+                action.update((target_time - start) / duration);
+                break;
+            }
+            start = end;
+        }
+    }
+
+    fn is_done(&self) -> bool {
+        self.interval.is_done()
+    }
+
+    fn get_tag(&self) -> i32 {
+        self.interval.get_tag()
+    }
+
+    fn set_tag(&mut self, tag: i32) {
+        self.interval.set_tag(tag);
+    }
+}
+
+impl<A: FiniteTimeAction + ?Sized> FiniteTimeAction for Sequence<A> {
+    fn get_duration(&self) -> f32 {
+        self.interval.get_duration()
+    }
+}
+
+impl Reversible for Sequence<dyn Reversible> {
+    /// Reverses each action and plays them in the opposite order
+    fn reverse(&self) -> Box<dyn FiniteTimeAction> {
+        let actions = self.actions.iter().rev().map(|action| action.reverse()).collect();
+        Box::new(Sequence::new(actions))
+    }
+}
+
+/// Spawn runs its actions at the same time. It lasts as long as its
+/// longest action; shorter ones hold their end state until it finishes.
+/// A spawn built with `Spawn::reversible` is reversible itself.
+#[derive(Debug)]
+pub struct Spawn<A: ?Sized = dyn FiniteTimeAction> {
+    interval: ActionInterval,
+    actions: Vec<Box<A>>,
+}
+
+impl Spawn {
+    /// Creates a new spawn
+    pub fn new(actions: Vec<Box<dyn FiniteTimeAction>>) -> Spawn {
+        Spawn::with_actions(actions)
+    }
+}
+
+impl Spawn<dyn Reversible> {
+    /// Creates a new spawn that can be reversed
+    pub fn reversible(actions: Vec<Box<dyn Reversible>>) -> Spawn<dyn Reversible> {
+        Spawn::with_actions(actions)
+    }
+}
+
+impl<A: FiniteTimeAction + ?Sized> Spawn<A> {
+    fn with_actions(actions: Vec<Box<A>>) -> Spawn<A> {
+        let duration = actions.iter().map(|action| action.get_duration()).fold(0.0, f32::max);
+        Spawn {
+            interval: ActionInterval::new(duration),
+            actions,
+        }
+    }
+
+    /// Gets the actions
+    pub fn get_actions(&self) -> &[Box<A>] {
+        &self.actions
+    }
+}
+
+impl<A: FiniteTimeAction + ?Sized> Action for Spawn<A> {
+    fn start_with_target(&mut self, target: NodeRef) {
+        for action in self.actions.iter_mut() {
+            action.start_with_target(target.clone());
+        }
+        self.interval.start_with_target(target);
+    }
+
+    fn stop(&mut self) {
+        for action in self.actions.iter_mut() {
+            action.stop();
+        }
+        self.interval.stop();
+    }
+
+    fn step(&mut self, dt: f32) {
+        let time = self.interval.step(dt);
+        self.update(time);
+    }
+
+    fn update(&mut self, time: f32) {
+        let target_time = time * self.interval.get_duration();
+        for action in self.actions.iter_mut() {
+            let duration = action.get_duration();
+            if duration <= 0.0 {
+                action.update(1.0);
+            } else {
+                action.update((target_time / duration).min(1.0));
+            }
+        }
+    }
+
+    fn is_done(&self) -> bool {
+        self.interval.is_done()
+    }
+
+    fn get_tag(&self) -> i32 {
+        self.interval.get_tag()
+    }
+
+    fn set_tag(&mut self, tag: i32) {
+        self.interval.set_tag(tag);
+    }
+}
+
+impl<A: FiniteTimeAction + ?Sized> FiniteTimeAction for Spawn<A> {
+    fn get_duration(&self) -> f32 {
+        self.interval.get_duration()
+    }
+}
+
+impl Reversible for Spawn<dyn Reversible> {
+    /// Reverses each action. Shorter actions finish early in the spawn, so
+    /// their reverses are delayed to finish with it.
+    fn reverse(&self) -> Box<dyn FiniteTimeAction> {
+        let duration = self.get_duration();
+        let actions = self
+            .actions
+            .iter()
+            .map(|action| {
+                let reversed = action.reverse();
+                let gap = duration - action.get_duration();
+                if gap > 0.0 {
+                    Box::new(Sequence::new(vec![Box::new(DelayTime::new(gap)), reversed])) as Box<dyn FiniteTimeAction>
+                } else {
+                    reversed
+                }
+            })
+            .collect();
+        Box::new(Spawn::new(actions))
+    }
+}
+
+/// DelayTime does nothing for its duration; used as a gap in a `Sequence`
 #[derive(Debug)]
-pub struct Action {
-    target: Option<RefPtr<Node>>,
-    original_target: Option<RefPtr<Node>>,
-    tag: i32,
-    flags: u32,
+pub struct DelayTime {
+    interval: ActionInterval,
 }
 
-impl Action {
-    /// Creates a new action
-    pub fn new() -> Action {
-        Action {
-            target: None,
-            original_target: None,
-            tag: 0,
-            flags: 0,
+impl DelayTime {
+    /// Creates a new delay
+    pub fn new(duration: f32) -> DelayTime {
+        DelayTime {
+            interval: ActionInterval::new(duration),
         }
     }
+}
 
-    /// Clones the action
-    pub fn clone(&self) -> Action {
-        Action {
-            target: None,
-            original_target: None,
-            tag: self.tag,
-            flags: self.flags,
-        }
+impl Action for DelayTime {
+    fn start_with_target(&mut self, target: NodeRef) {
+        self.interval.start_with_target(target);
     }
 
-    /// Starts the action with a target
-    pub fn start_with_target(&mut self, target: RefPtr<Node>) {
-        self.original_target = Some(target.clone());
-        self.target = Some(target);
+    fn stop(&mut self) {
+        self.interval.stop();
     }
 
-    /// Stops the action
-    pub fn stop(&mut self) {
-        self.target = None;
+    fn step(&mut self, dt: f32) {
+        self.interval.step(dt);
     }
 
-    /// Steps the action
-    pub fn step(&mut self, _dt: f32) {
-        // Override in subclasses
+    fn update(&mut self, _time: f32) {}
+
+    fn is_done(&self) -> bool {
+        self.interval.is_done()
     }
 
-    /// Updates the action
-    pub fn update(&mut self, _time: f32) {
-        // Override in subclasses
+    fn get_tag(&self) -> i32 {
+        self.interval.get_tag()
     }
 
-    /// Gets the target
-    pub fn get_target(&self) -> Option<&RefPtr<Node>> {
-        self.target.as_ref()
+    fn set_tag(&mut self, tag: i32) {
+        self.interval.set_tag(tag);
     }
+}
 
-    /// Gets the original target
-    pub fn get_original_target(&self) -> Option<&RefPtr<Node>> {
-        self.original_target.as_ref()
+impl FiniteTimeAction for DelayTime {
+    fn get_duration(&self) -> f32 {
+        self.interval.get_duration()
     }
+}
 
-    /// Sets the tag
-    pub fn set_tag(&mut self, tag: i32) {
-        self.tag = tag;
+impl Reversible for DelayTime {
+    fn reverse(&self) -> Box<dyn FiniteTimeAction> {
+        Box::new(DelayTime::new(self.get_duration()))
     }
+}
 
-    /// Gets the tag
-    pub fn get_tag(&self) -> i32 {
-        self.tag
+/// CallFunc invokes a closure once, taking no time
+pub struct CallFunc {
+    base: ActionBase,
+    callback: Box<dyn FnMut()>,
+    done: bool,
+}
+
+impl CallFunc {
+    /// Creates a new call func action
+    pub fn new(callback: Box<dyn FnMut()>) -> CallFunc {
+        CallFunc {
+            base: ActionBase::new(),
+            callback,
+            done: false,
+        }
     }
 
-    /// Checks if the action is done
-    pub fn is_done(&self) -> bool {
-        true
+    /// Invokes the callback unless it already ran
+    pub fn execute(&mut self) {
+        if !self.done {
+            self.done = true;
+            (self.callback)();
+        }
     }
 }
 
-/// Finite Time Action is an action that takes a finite amount of time
-#[derive(Debug)]
-pub struct FiniteTimeAction {
-    base: Action,
-    duration: f32,
+impl fmt::Debug for CallFunc {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("CallFunc")
+            .field("tag", &self.base.get_tag())
+            .field("done", &self.done)
+            .finish()
+    }
 }
 
-impl FiniteTimeAction {
-    /// Creates a new finite time action
-    pub fn new(duration: f32) -> FiniteTimeAction {
-        FiniteTimeAction {
-            base: Action::new(),
-            duration,
-        }
+impl Action for CallFunc {
+    fn start_with_target(&mut self, target: NodeRef) {
+        self.base.start_with_target(target);
     }
 
-    /// Gets the duration
-    pub fn get_duration(&self) -> f32 {
-        self.duration
+    fn stop(&mut self) {
+        self.base.stop();
     }
 
-    /// Sets the duration
-    pub fn set_duration(&mut self, duration: f32) {
-        self.duration = duration;
+    fn step(&mut self, _dt: f32) {
+        self.execute();
+    }
+
+    fn update(&mut self, _time: f32) {
+        self.execute();
+    }
+
+    fn is_done(&self) -> bool {
+        self.done
+    }
+
+    fn get_tag(&self) -> i32 {
+        self.base.get_tag()
+    }
+
+    fn set_tag(&mut self, tag: i32) {
+        self.base.set_tag(tag);
+    }
+}
+
+impl FiniteTimeAction for CallFunc {
+    fn get_duration(&self) -> f32 {
+        0.0
     }
 }
 
 /// Speed controls the speed of an action
 #[derive(Debug)]
 pub struct Speed {
-    base: Action,
-    inner_action: Box<Action>,
+    base: ActionBase,
+    inner_action: Box<dyn Action>,
     speed: f32,
 }
 
 impl Speed {
     /// Creates a new speed action
-    pub fn new(action: Box<Action>, speed: f32) -> Speed {
+    pub fn new(action: Box<dyn Action>, speed: f32) -> Speed {
         Speed {
-            base: Action::new(),
+            base: ActionBase::new(),
             inner_action: action,
             speed,
         }
@@ -134,21 +1547,55 @@ impl Speed {
     }
 
     /// Gets the inner action
-    pub fn get_inner_action(&self) -> &Box<Action> {
+    pub fn get_inner_action(&self) -> &Box<dyn Action> {
         &self.inner_action
     }
 
     /// Gets mutable inner action
-    pub fn get_inner_action_mut(&mut self) -> &mut Box<Action> {
+    pub fn get_inner_action_mut(&mut self) -> &mut Box<dyn Action> {
         &mut self.inner_action
     }
 }
 
+impl Action for Speed {
+    fn start_with_target(&mut self, target: NodeRef) {
+        self.inner_action.start_with_target(target.clone());
+        self.base.start_with_target(target);
+    }
+
+    fn stop(&mut self) {
+        self.inner_action.stop();
+        self.base.stop();
+    }
+
+    /// Advances the inner action by `dt` scaled by the current speed, so a
+    /// speed change applies from the next step on
+    fn step(&mut self, dt: f32) {
+        self.inner_action.step(dt * self.speed);
+    }
+
+    fn update(&mut self, time: f32) {
+        self.inner_action.update(time);
+    }
+
+    fn is_done(&self) -> bool {
+        self.inner_action.is_done()
+    }
+
+    fn get_tag(&self) -> i32 {
+        self.base.get_tag()
+    }
+
+    fn set_tag(&mut self, tag: i32) {
+        self.base.set_tag(tag);
+    }
+}
+
 /// Follow is an action that follows a node
 #[derive(Debug)]
 pub struct Follow {
-    base: Action,
-    target_node: RefPtr<Node>,
+    base: ActionBase,
+    target_node: NodeRef,
     boundary_set: bool,
     left_boundary: f32,
     right_boundary: f32,
@@ -159,9 +1606,9 @@ pub struct Follow {
 
 impl Follow {
     /// Creates a new follow action
-    pub fn new(target: RefPtr<Node>) -> Follow {
+    pub fn new(target: NodeRef) -> Follow {
         Follow {
-            base: Action::new(),
+            base: ActionBase::new(),
             target_node: target,
             boundary_set: false,
             left_boundary: 0.0,
@@ -173,7 +1620,7 @@ impl Follow {
     }
 
     /// Creates a follow action with a boundary
-    pub fn new_with_boundary(target: RefPtr<Node>, left: f32, bottom: f32, right: f32, top: f32) -> Follow {
+    pub fn new_with_boundary(target: NodeRef, left: f32, bottom: f32, right: f32, top: f32) -> Follow {
         let mut follow = Follow::new(target);
         follow.boundary_set = true;
         follow.left_boundary = left;
@@ -184,7 +1631,7 @@ impl Follow {
     }
 
     /// Creates a follow action with a world rect
-    pub fn new_with_world_rect(target: RefPtr<Node>, rect: (f32, f32, f32, f32)) -> Follow {
+    pub fn new_with_world_rect(target: NodeRef, rect: (f32, f32, f32, f32)) -> Follow {
         let mut follow = Follow::new(target);
         follow.boundary_set = true;
         follow.world_rect = rect;
@@ -206,86 +1653,834 @@ impl Follow {
     }
 }
 
+/// Actions running on one target
+#[derive(Debug)]
+struct ActionElement {
+    target: std::rc::Weak<RefCell<Node>>,
+    actions: Vec<Box<dyn Action>>,
+    pending: Vec<Box<dyn Action>>,
+    paused: bool,
+}
+
+impl ActionElement {
+    /// Checks if the element is paused directly or through its target node
+    fn is_paused(&self) -> bool {
+        self.paused
+            || self
+                .target
+                .upgrade()
+                .map_or(false, |target| target.borrow().is_paused())
+    }
+}
+
+/// A change made while the stepped actions are taken out of the manager,
+/// replayed on them when they are merged back
+#[derive(Debug, Clone, Copy)]
+enum DeferredChange {
+    RemoveByTag(usize, i32),
+    RemoveTarget(usize),
+    RemoveAll,
+    SetPaused(usize, bool),
+}
+
 /// ActionManager manages all actions
 #[derive(Debug)]
 pub struct ActionManager {
-    action_hash: std::collections::HashMap<i32, RefPtr<Action>>,
-    current_action: Option<RefPtr<Action>>,
-    current_action_removed: bool,
-    target_map: std::collections::HashMap<usize, Vec<RefPtr<Action>>>,
+    target_map: std::collections::HashMap<usize, ActionElement>,
+    updating: bool,
+    deferred: Vec<DeferredChange>,
 }
 
 impl ActionManager {
     /// Creates a new action manager
     pub fn new() -> ActionManager {
         ActionManager {
-            action_hash: std::collections::HashMap::new(),
-            current_action: None,
-            current_action_removed: false,
             target_map: std::collections::HashMap::new(),
+            updating: false,
+            deferred: Vec::new(),
         }
     }
 
-    /// Gets the singleton instance
-    pub fn get_instance() -> &'static mut ActionManager {
-        static mut ACTION_MANAGER: Option<ActionManager> = None;
-        unsafe {
-            if ACTION_MANAGER.is_none() {
-                ACTION_MANAGER = Some(ActionManager::new());
-            }
-            ACTION_MANAGER.as_mut().unwrap()
+    /// Gets the action manager of the current thread.
+    ///
+    /// Nodes are confined to the thread that created them, so each thread
+    /// has its own manager. Advance it with `update_instance`, which does not
+    /// keep it borrowed while actions step, so action callbacks can run,
+    /// stop and pause actions through this handle.
+    pub fn get_instance() -> Rc<RefCell<ActionManager>> {
+        thread_local! {
+            static ACTION_MANAGER: Rc<RefCell<ActionManager>> = Rc::new(RefCell::new(ActionManager::new()));
         }
+        ACTION_MANAGER.with(Rc::clone)
     }
 
-    /// Adds an action
-    pub fn add_action(&mut self, action: RefPtr<Action>, target: RefPtr<Node>, paused: bool) {
-        let target_id = &target as *const _ as usize;
-
-        if let Some(actions) = self.target_map.get_mut(&target_id) {
-            actions.push(action);
-        } else {
-            self.target_map.insert(target_id, vec![action]);
+    /// Updates the action manager of the current thread.
+    ///
+    /// The actions are taken out of the manager while they step and merged
+    /// back afterwards. Actions added during the update start at the next
+    /// one; removals and pauses also apply to the actions being stepped.
+    /// Nested calls from inside an update do nothing.
+    pub fn update_instance(dt: f32) {
+        let manager = ActionManager::get_instance();
+        if manager.borrow().updating {
+            return;
         }
+        let mut elements = manager.borrow_mut().begin_update();
+        ActionManager::step_elements(&mut elements, dt);
+        manager.borrow_mut().end_update(elements);
+    }
+
+    fn target_id(target: &NodeRef) -> usize {
+        std::rc::Rc::as_ptr(target) as usize
+    }
+
+    fn element_for(&mut self, target: &NodeRef, paused: bool) -> &mut ActionElement {
+        let target_id = ActionManager::target_id(target);
+        self.target_map.entry(target_id).or_insert_with(|| ActionElement {
+            target: Rc::downgrade(target),
+            actions: Vec::new(),
+            pending: Vec::new(),
+            paused: paused || Director::hold_new_action_target(target),
+        })
+    }
+
+    /// Adds an action and starts it on the target. If `paused` is set the
+    /// target's actions do not advance until `resume_target` is called.
+    ///
+    /// As in cocos2d-x, `paused` only applies when the target has no actions
+    /// yet; a target that already has actions keeps its pause state, which
+    /// `pause_target` and `resume_target` change.
+    pub fn add_action(&mut self, mut action: Box<dyn Action>, target: NodeRef, paused: bool) {
+        action.start_with_target(target.clone());
+        self.element_for(&target, paused).actions.push(action);
+    }
+
+    /// Adds an action that is started on the target at the next `update`.
+    ///
+    /// Used when the target is already borrowed, e.g. from `Node::run_action`.
+    pub fn add_action_deferred(&mut self, action: Box<dyn Action>, target: &NodeRef) {
+        self.element_for(target, false).pending.push(action);
     }
 
     /// Removes an action by tag
-    pub fn remove_action_by_tag(&mut self, tag: i32, target: &RefPtr<Node>) {
-        let target_id = target as *const _ as usize;
-        if let Some(actions) = self.target_map.get_mut(&target_id) {
-            actions.retain(|action| action.get_tag() != tag);
+    pub fn remove_action_by_tag(&mut self, tag: i32, target: &NodeRef) {
+        self.apply(DeferredChange::RemoveByTag(ActionManager::target_id(target), tag));
+    }
+
+    /// Stops the actions with the given tag on `root` and all of its
+    /// descendants, e.g. to cancel a blink across a whole panel.
+    ///
+    /// Nodes are matched through their `NodeRef` handle, so the children
+    /// added to a node are targets in their own right.
+    pub fn stop_actions_by_tag_in_subtree(&mut self, root: &NodeRef, tag: i32) {
+        let mut stack = vec![Rc::clone(root)];
+        while let Some(node) = stack.pop() {
+            stack.extend(node.borrow().get_children().iter().cloned());
+            self.remove_action_by_tag(tag, &node);
         }
     }
 
     /// Removes all actions
     pub fn remove_all_actions(&mut self) {
-        self.target_map.clear();
+        self.apply(DeferredChange::RemoveAll);
     }
 
     /// Removes all actions from a target
-    pub fn remove_all_actions_from_target(&mut self, target: &RefPtr<Node>) {
-        let target_id = target as *const _ as usize;
-        self.target_map.remove(&target_id);
+    pub fn remove_all_actions_from_target(&mut self, target: &NodeRef) {
+        self.apply(DeferredChange::RemoveTarget(ActionManager::target_id(target)));
     }
 
     /// Gets an action by tag
-    pub fn get_action_by_tag(&self, tag: i32, target: &RefPtr<Node>) -> Option<&RefPtr<Action>> {
-        let target_id = target as *const _ as usize;
-        if let Some(actions) = self.target_map.get(&target_id) {
-            for action in actions {
-                if action.get_tag() == tag {
-                    return Some(action);
+    pub fn get_action_by_tag(&self, tag: i32, target: &NodeRef) -> Option<&dyn Action> {
+        let target_id = ActionManager::target_id(target);
+        self.target_map
+            .get(&target_id)?
+            .actions
+            .iter()
+            .chain(self.target_map.get(&target_id)?.pending.iter())
+            .find(|action| action.get_tag() == tag)
+            .map(|action| action.as_ref())
+    }
+
+    /// Gets the number of running actions on a target
+    pub fn get_number_of_running_actions_in_target(&self, target: &NodeRef) -> usize {
+        let target_id = ActionManager::target_id(target);
+        self.target_map
+            .get(&target_id)
+            .map_or(0, |element| element.actions.len() + element.pending.len())
+    }
+
+    /// Pauses all actions of a target
+    pub fn pause_target(&mut self, target: &NodeRef) {
+        self.apply(DeferredChange::SetPaused(ActionManager::target_id(target), true));
+    }
+
+    /// Resumes all actions of a target
+    pub fn resume_target(&mut self, target: &NodeRef) {
+        self.apply(DeferredChange::SetPaused(ActionManager::target_id(target), false));
+    }
+
+    /// Applies a change to the actions in the manager and, during
+    /// `update_instance`, records it for the actions being stepped
+    fn apply(&mut self, change: DeferredChange) {
+        ActionManager::apply_change(&mut self.target_map, change);
+        if self.updating {
+            self.deferred.push(change);
+        }
+    }
+
+    fn apply_change(elements: &mut std::collections::HashMap<usize, ActionElement>, change: DeferredChange) {
+        match change {
+            DeferredChange::RemoveByTag(target_id, tag) => {
+                if let Some(element) = elements.get_mut(&target_id) {
+                    element.actions.retain(|action| action.get_tag() != tag);
+                    element.pending.retain(|action| action.get_tag() != tag);
+                }
+            }
+            DeferredChange::RemoveTarget(target_id) => {
+                elements.remove(&target_id);
+            }
+            DeferredChange::RemoveAll => elements.clear(),
+            DeferredChange::SetPaused(target_id, paused) => {
+                if let Some(element) = elements.get_mut(&target_id) {
+                    element.paused = paused;
                 }
             }
         }
-        None
     }
 
-    /// Updates the action manager
+    /// Pauses the actions of every target except `excluded`, returning the
+    /// targets this call paused so they can be passed to `resume_targets`.
+    /// Targets that were already paused are left out.
+    pub fn pause_all_running_actions_except(&mut self, excluded: &[NodeRef]) -> Vec<NodeRef> {
+        let excluded: Vec<usize> = excluded.iter().map(ActionManager::target_id).collect();
+        let mut paused = Vec::new();
+        for (target_id, element) in self.target_map.iter_mut() {
+            if element.paused || excluded.contains(target_id) {
+                continue;
+            }
+            if let Some(target) = element.target.upgrade() {
+                element.paused = true;
+                paused.push(target);
+            }
+        }
+        paused
+    }
+
+    /// Resumes the actions of each target
+    pub fn resume_targets(&mut self, targets: &[NodeRef]) {
+        for target in targets {
+            self.resume_target(target);
+        }
+    }
+
+    /// Checks if the actions of a target are paused, either through
+    /// `pause_target` or because the target node itself is paused
+    pub fn is_target_paused(&self, target: &NodeRef) -> bool {
+        let target_id = ActionManager::target_id(target);
+        self.target_map.get(&target_id).map_or(false, |element| element.is_paused())
+    }
+
+    /// Updates the action manager, dropping finished actions
     pub fn update(&mut self, dt: f32) {
-        for (target_id, actions) in &mut self.target_map {
-            for action in actions {
-                action.borrow_mut().step(dt);
+        let mut elements = self.begin_update();
+        ActionManager::step_elements(&mut elements, dt);
+        self.end_update(elements);
+    }
+
+    /// Takes the actions out of the manager for stepping
+    fn begin_update(&mut self) -> std::collections::HashMap<usize, ActionElement> {
+        self.updating = true;
+        std::mem::take(&mut self.target_map)
+    }
+
+    /// Replays the changes made during the update on the stepped actions,
+    /// then merges them with the actions added meanwhile
+    fn end_update(&mut self, mut elements: std::collections::HashMap<usize, ActionElement>) {
+        self.updating = false;
+        for change in self.deferred.drain(..) {
+            ActionManager::apply_change(&mut elements, change);
+        }
+        for (target_id, added) in std::mem::take(&mut self.target_map) {
+            match elements.get_mut(&target_id) {
+                Some(element) => {
+                    element.actions.extend(added.actions);
+                    element.pending.extend(added.pending);
+                }
+                None => {
+                    elements.insert(target_id, added);
+                }
+            }
+        }
+        elements.retain(|_, element| !element.actions.is_empty() || !element.pending.is_empty());
+        self.target_map = elements;
+    }
+
+    /// Starts the pending actions and steps the running ones, dropping
+    /// finished actions
+    fn step_elements(elements: &mut std::collections::HashMap<usize, ActionElement>, dt: f32) {
+        for element in elements.values_mut() {
+            if element.is_paused() {
+                continue;
+            }
+            if !element.pending.is_empty() {
+                let target = match element.target.upgrade() {
+                    Some(target) => target,
+                    None => {
+                        element.pending.clear();
+                        continue;
+                    }
+                };
+                for mut action in element.pending.drain(..) {
+                    action.start_with_target(target.clone());
+                    element.actions.push(action);
+                }
             }
+            for action in element.actions.iter_mut() {
+                action.step(dt);
+            }
+            element.actions.retain_mut(|action| {
+                if action.is_done() {
+                    action.stop();
+                    false
+                } else {
+                    true
+                }
+            });
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::Cell;
+    use crate::sprite::Sprite;
+
+    fn new_target() -> NodeRef {
+        Rc::new(RefCell::new(Node::new()))
+    }
+
+    fn counter_callback(counter: &Rc<Cell<u32>>) -> Box<dyn FnMut()> {
+        let counter = counter.clone();
+        Box::new(move || counter.set(counter.get() + 1))
+    }
+
+    #[test]
+    fn test_move_by() {
+        let target = new_target();
+        let mut action = MoveBy::new(1.0, Vec2::new(100.0, 50.0));
+        action.start_with_target(target.clone());
+
+        action.step(0.5);
+        assert_eq!(*target.borrow().get_position(), Vec2::new(50.0, 25.0));
+        action.step(0.5);
+        assert_eq!(*target.borrow().get_position(), Vec2::new(100.0, 50.0));
+        assert!(action.is_done());
+    }
+
+    #[test]
+    fn test_scale_to() {
+        let target = new_target();
+        let mut action = ScaleTo::new(1.0, 2.0);
+        action.start_with_target(target.clone());
+
+        action.step(0.5);
+        assert!((target.borrow().get_scale_x() - 1.5).abs() < 1e-5);
+        assert!((target.borrow().get_scale_y() - 1.5).abs() < 1e-5);
+        action.step(0.5);
+        assert_eq!(target.borrow().get_scale_x(), 2.0);
+        assert_eq!(target.borrow().get_scale_y(), 2.0);
+        assert!(action.is_done());
+    }
+
+    #[test]
+    fn test_scale_by() {
+        let target = new_target();
+        target.borrow_mut().set_scale_xy(2.0, 3.0);
+        let mut action = ScaleBy::with_xy(1.0, 2.0, 0.5);
+        action.start_with_target(target.clone());
+
+        action.step(1.0);
+        assert_eq!(target.borrow().get_scale_x(), 4.0);
+        assert_eq!(target.borrow().get_scale_y(), 1.5);
+    }
+
+    #[test]
+    fn test_rotate_by() {
+        let target = new_target();
+        target.borrow_mut().set_rotation(30.0);
+        let mut action = RotateBy::new(2.0, 90.0);
+        action.start_with_target(target.clone());
+
+        action.step(1.0);
+        assert_eq!(target.borrow().get_rotation(), 75.0);
+        action.step(1.0);
+        assert_eq!(target.borrow().get_rotation(), 120.0);
+    }
+
+    #[test]
+    fn test_move_by_reverse_negates_delta() {
+        let target = new_target();
+        let mut reversed = MoveBy::new(1.0, Vec2::new(100.0, 0.0)).reverse();
+        assert_eq!(reversed.get_duration(), 1.0);
+        reversed.start_with_target(target.clone());
+
+        reversed.step(1.0);
+        assert_eq!(*target.borrow().get_position(), Vec2::new(-100.0, 0.0));
+    }
+
+    #[test]
+    fn test_sequence_reverse_returns_to_start() {
+        let target = new_target();
+        target.borrow_mut().set_position(Vec2::new(10.0, 20.0));
+        let sequence = Sequence::reversible(vec![
+            Box::new(MoveBy::new(1.0, Vec2::new(100.0, 0.0))),
+            Box::new(MoveBy::new(0.5, Vec2::new(0.0, 50.0))),
+        ]);
+        let reversed = sequence.reverse();
+        let mut ping_pong = Sequence::new(vec![Box::new(sequence), reversed]);
+        ping_pong.start_with_target(target.clone());
+
+        ping_pong.step(1.5);
+        assert_eq!(*target.borrow().get_position(), Vec2::new(110.0, 70.0));
+        // Undoes the vertical move first
+        ping_pong.step(0.5);
+        let position = *target.borrow().get_position();
+        assert!((position - Vec2::new(110.0, 20.0)).length() < 1e-3);
+        ping_pong.step(1.0);
+        assert_eq!(*target.borrow().get_position(), Vec2::new(10.0, 20.0));
+        assert!(ping_pong.is_done());
+    }
+
+    #[test]
+    fn test_bezier_by_and_jump_by_reverse_mirror_their_paths() {
+        let target = new_target();
+        let config = BezierConfig::new(Vec2::new(0.0, 50.0), Vec2::new(100.0, 50.0), Vec2::new(100.0, 0.0));
+        let bezier = BezierBy::new(1.0, config);
+        let jump = JumpBy::new(1.0, Vec2::new(40.0, 0.0), 20.0, 2);
+        let mut reversed = Sequence::new(vec![bezier.reverse(), jump.reverse()]);
+        reversed.start_with_target(target.clone());
+
+        reversed.step(0.5);
+        // Halfway back along the same arc
+        let position = *target.borrow().get_position();
+        assert!((position - Vec2::new(-50.0, 37.5)).length() < 1e-3);
+        reversed.step(1.5);
+        assert_eq!(*target.borrow().get_position(), Vec2::new(-140.0, 0.0));
+    }
+
+    #[test]
+    fn test_spawn_reverse_delays_shorter_actions() {
+        let target = new_target();
+        let spawn = Spawn::reversible(vec![
+            Box::new(MoveBy::new(1.0, Vec2::new(100.0, 0.0))),
+            Box::new(RotateBy::new(0.5, 90.0)),
+        ]);
+        let mut reversed = spawn.reverse();
+        assert_eq!(reversed.get_duration(), 1.0);
+        reversed.start_with_target(target.clone());
+
+        reversed.step(0.5);
+        assert_eq!(target.borrow().get_rotation(), 0.0);
+        reversed.step(0.5);
+        assert_eq!(*target.borrow().get_position(), Vec2::new(-100.0, 0.0));
+        assert_eq!(target.borrow().get_rotation(), -90.0);
+    }
+
+    #[test]
+    fn test_speed_scales_step() {
+        let target = new_target();
+        let mut action = Speed::new(Box::new(MoveBy::new(1.0, Vec2::new(100.0, 0.0))), 2.0);
+        action.start_with_target(target.clone());
+
+        action.step(0.25);
+        assert_eq!(target.borrow().get_position().x, 50.0);
+        assert!(!action.is_done());
+        action.step(0.25);
+        assert_eq!(target.borrow().get_position().x, 100.0);
+        assert!(action.is_done());
+    }
+
+    #[test]
+    fn test_speed_change_applies_to_remaining_steps() {
+        let target = new_target();
+        let mut action = Speed::new(Box::new(MoveBy::new(1.0, Vec2::new(100.0, 0.0))), 1.0);
+        action.start_with_target(target.clone());
+
+        action.step(0.25);
+        assert_eq!(target.borrow().get_position().x, 25.0);
+        action.set_speed(3.0);
+        action.step(0.25);
+        assert_eq!(target.borrow().get_position().x, 100.0);
+        assert!(action.is_done());
+    }
+
+    #[test]
+    fn test_rotate_to_takes_shortest_path() {
+        let target = new_target();
+        target.borrow_mut().set_rotation(350.0);
+        let mut action = RotateTo::new(1.0, 10.0);
+        action.start_with_target(target.clone());
+
+        action.step(0.5);
+        assert!((target.borrow().get_rotation() - 360.0).abs() < 1e-4);
+        action.step(0.5);
+        assert!((target.borrow().get_rotation() - 370.0).abs() < 1e-4);
+
+        let target = new_target();
+        target.borrow_mut().set_rotation(10.0);
+        let mut action = RotateTo::new(1.0, 350.0);
+        action.start_with_target(target.clone());
+        action.step(1.0);
+        assert!((target.borrow().get_rotation() + 10.0).abs() < 1e-4);
+    }
+
+    #[test]
+    fn test_bezier_by() {
+        let target = new_target();
+        target.borrow_mut().set_position(Vec2::new(10.0, 10.0));
+        let config = BezierConfig::new(Vec2::new(0.0, 100.0), Vec2::new(100.0, 100.0), Vec2::new(100.0, 0.0));
+        let mut action = BezierBy::new(1.0, config);
+        action.start_with_target(target.clone());
+
+        action.update(0.0);
+        assert_eq!(*target.borrow().get_position(), Vec2::new(10.0, 10.0));
+        action.update(0.5);
+        let mid = *target.borrow().get_position();
+        assert!((mid.x - 60.0).abs() < 1e-4);
+        assert!(mid.y > 10.0);
+        action.update(1.0);
+        assert_eq!(*target.borrow().get_position(), Vec2::new(110.0, 10.0));
+    }
+
+    #[test]
+    fn test_bezier_to() {
+        let target = new_target();
+        target.borrow_mut().set_position(Vec2::new(50.0, 0.0));
+        let config = BezierConfig::new(Vec2::new(50.0, 80.0), Vec2::new(150.0, 80.0), Vec2::new(150.0, 0.0));
+        let mut action = BezierTo::new(2.0, config);
+        action.start_with_target(target.clone());
+
+        action.step(0.0);
+        assert_eq!(*target.borrow().get_position(), Vec2::new(50.0, 0.0));
+        action.step(1.0);
+        let mid = *target.borrow().get_position();
+        assert!((mid.x - 100.0).abs() < 1e-4);
+        assert!((mid.y - 60.0).abs() < 1e-4);
+        action.step(1.0);
+        assert_eq!(*target.borrow().get_position(), Vec2::new(150.0, 0.0));
+    }
+
+    #[test]
+    fn test_jump_by_single_jump() {
+        let target = new_target();
+        let mut action = JumpBy::new(1.0, Vec2::new(100.0, 0.0), 50.0, 1);
+        action.start_with_target(target.clone());
+
+        action.step(0.5);
+        let peak = *target.borrow().get_position();
+        assert!((peak.x - 50.0).abs() < 1e-4);
+        assert!((peak.y - 50.0).abs() < 1e-4);
+        action.step(0.5);
+        assert_eq!(*target.borrow().get_position(), Vec2::new(100.0, 0.0));
+    }
+
+    #[test]
+    fn test_jump_by_two_jumps() {
+        let target = new_target();
+        let mut action = JumpBy::new(1.0, Vec2::new(100.0, 0.0), 20.0, 2);
+        action.start_with_target(target.clone());
+
+        let heights: Vec<f32> = [0.25, 0.5, 0.75]
+            .iter()
+            .map(|t| {
+                action.update(*t);
+                target.borrow().get_position().y
+            })
+            .collect();
+        assert!((heights[0] - 20.0).abs() < 1e-4);
+        assert!(heights[1].abs() < 1e-4);
+        assert!((heights[2] - 20.0).abs() < 1e-4);
+    }
+
+    #[test]
+    fn test_jump_to() {
+        let target = new_target();
+        target.borrow_mut().set_position(Vec2::new(10.0, 10.0));
+        let mut action = JumpTo::new(1.0, Vec2::new(50.0, 30.0), 40.0, 1);
+        action.start_with_target(target.clone());
+
+        action.step(1.0);
+        assert_eq!(*target.borrow().get_position(), Vec2::new(50.0, 30.0));
+    }
+
+    #[test]
+    fn test_fade_out() {
+        let sprite = Rc::new(RefCell::new(Sprite::new()));
+        let mut action = FadeOut::new(1.0, sprite.clone());
+        action.start_with_target(new_target());
+
+        action.step(0.5);
+        let opacity = sprite.borrow().get_opacity();
+        assert!((126..=128).contains(&opacity));
+        action.step(0.5);
+        assert_eq!(sprite.borrow().get_opacity(), 0);
+        assert!(action.is_done());
+    }
+
+    #[test]
+    fn test_fade_in_from_current_opacity() {
+        let sprite = Rc::new(RefCell::new(Sprite::new()));
+        sprite.borrow_mut().set_opacity(55);
+        let mut action = FadeIn::new(2.0, sprite.clone());
+        action.start_with_target(new_target());
+
+        action.step(1.0);
+        assert_eq!(sprite.borrow().get_opacity(), 155);
+        action.step(1.0);
+        assert_eq!(sprite.borrow().get_opacity(), 255);
+    }
+
+    #[test]
+    fn test_tint_to() {
+        let sprite = Rc::new(RefCell::new(Sprite::new()));
+        sprite.borrow_mut().set_color(Color3B::new(0, 100, 200));
+        let mut action = TintTo::new(1.0, Color3B::new(200, 100, 0), sprite.clone());
+        action.start_with_target(new_target());
+
+        action.step(0.5);
+        assert_eq!(sprite.borrow().get_color(), Color3B::new(100, 100, 100));
+        action.step(0.5);
+        assert_eq!(sprite.borrow().get_color(), Color3B::new(200, 100, 0));
+    }
+
+    #[test]
+    fn test_call_func_fires_once() {
+        let counter = Rc::new(Cell::new(0));
+        let mut action = CallFunc::new(counter_callback(&counter));
+        action.start_with_target(new_target());
+
+        assert!(!action.is_done());
+        action.step(0.0);
+        action.update(1.0);
+        assert_eq!(counter.get(), 1);
+        assert!(action.is_done());
+        assert_eq!(action.get_duration(), 0.0);
+    }
+
+    #[test]
+    fn test_call_func_in_sequence() {
+        let counter = Rc::new(Cell::new(0));
+        let target = new_target();
+        let mut sequence = Sequence::new(vec![
+            Box::new(MoveBy::new(1.0, Vec2::new(100.0, 0.0))),
+            Box::new(CallFunc::new(counter_callback(&counter))),
+            Box::new(MoveBy::new(1.0, Vec2::new(0.0, 100.0))),
+        ]);
+        assert_eq!(sequence.get_duration(), 2.0);
+        sequence.start_with_target(target.clone());
+
+        sequence.step(0.5);
+        assert_eq!(counter.get(), 0);
+        sequence.step(0.6);
+        assert_eq!(counter.get(), 1);
+        assert!(!sequence.is_done());
+
+        sequence.step(1.0);
+        assert!(sequence.is_done());
+        assert_eq!(counter.get(), 1);
+        assert_eq!(*target.borrow().get_position(), Vec2::new(100.0, 100.0));
+    }
+
+    #[test]
+    fn test_call_func_at_sequence_end() {
+        let counter = Rc::new(Cell::new(0));
+        let mut sequence = Sequence::new(vec![
+            Box::new(MoveBy::new(1.0, Vec2::new(10.0, 0.0))),
+            Box::new(CallFunc::new(counter_callback(&counter))),
+        ]);
+        sequence.start_with_target(new_target());
+
+        sequence.step(0.9);
+        assert_eq!(counter.get(), 0);
+        sequence.step(0.1);
+        assert_eq!(counter.get(), 1);
+        assert!(sequence.is_done());
+
+        sequence.step(0.5);
+        sequence.update(1.0);
+        assert_eq!(counter.get(), 1);
+    }
+
+    #[test]
+    fn test_action_manager_runs_and_drops_actions() {
+        let target = new_target();
+        let mut manager = ActionManager::new();
+        manager.add_action(Box::new(MoveBy::new(1.0, Vec2::new(10.0, 0.0))), target.clone(), false);
+        assert_eq!(manager.get_number_of_running_actions_in_target(&target), 1);
+
+        manager.update(0.5);
+        assert_eq!(*target.borrow().get_position(), Vec2::new(5.0, 0.0));
+        manager.update(0.5);
+        assert_eq!(manager.get_number_of_running_actions_in_target(&target), 0);
+    }
+
+    #[test]
+    fn test_get_instance_shares_manager() {
+        let target = new_target();
+        let first = ActionManager::get_instance();
+        let second = ActionManager::get_instance();
+        assert!(Rc::ptr_eq(&first, &second));
+
+        first.borrow_mut().add_action(Box::new(MoveBy::new(1.0, Vec2::new(10.0, 0.0))), target.clone(), false);
+        assert_eq!(second.borrow().get_number_of_running_actions_in_target(&target), 1);
+        second.borrow_mut().remove_all_actions_from_target(&target);
+        assert_eq!(first.borrow().get_number_of_running_actions_in_target(&target), 0);
+    }
+
+    #[test]
+    fn test_paused_node_freezes_actions() {
+        let target = new_target();
+        let mut manager = ActionManager::new();
+        manager.add_action(Box::new(MoveBy::new(1.0, Vec2::new(100.0, 0.0))), target.clone(), false);
+
+        manager.update(0.25);
+        assert_eq!(target.borrow().get_position().x, 25.0);
+
+        target.borrow_mut().pause();
+        assert!(manager.is_target_paused(&target));
+        manager.update(0.25);
+        manager.update(0.25);
+        assert_eq!(target.borrow().get_position().x, 25.0);
+
+        target.borrow_mut().resume();
+        manager.update(0.25);
+        assert_eq!(target.borrow().get_position().x, 50.0);
+        assert_eq!(manager.get_number_of_running_actions_in_target(&target), 1);
+    }
+
+    #[test]
+    fn test_pause_target_in_manager() {
+        let target = new_target();
+        let mut manager = ActionManager::new();
+        manager.add_action(Box::new(MoveBy::new(1.0, Vec2::new(100.0, 0.0))), target.clone(), true);
+        manager.update(0.5);
+        assert_eq!(target.borrow().get_position().x, 0.0);
+
+        manager.resume_target(&target);
+        manager.update(0.5);
+        assert_eq!(target.borrow().get_position().x, 50.0);
+    }
+
+    #[test]
+    fn test_add_action_paused_flag_applies_to_first_action_only() {
+        let target = new_target();
+        let mut manager = ActionManager::new();
+        manager.add_action(Box::new(MoveBy::new(1.0, Vec2::new(100.0, 0.0))), target.clone(), false);
+        manager.add_action(Box::new(MoveBy::new(1.0, Vec2::new(0.0, 100.0))), target.clone(), true);
+        assert!(!manager.is_target_paused(&target));
+        manager.update(0.5);
+        assert_eq!(*target.borrow().get_position(), Vec2::new(50.0, 50.0));
+
+        manager.pause_target(&target);
+        manager.add_action(Box::new(MoveBy::new(1.0, Vec2::new(100.0, 0.0))), target.clone(), false);
+        assert!(manager.is_target_paused(&target));
+    }
+
+    #[test]
+    fn test_delay_time_is_done_after_duration() {
+        let mut delay = DelayTime::new(0.5);
+        delay.start_with_target(new_target());
+        delay.step(0.25);
+        assert!(!delay.is_done());
+        delay.step(0.25);
+        assert!(delay.is_done());
+    }
+
+    #[test]
+    fn test_delay_time_gaps_sequence() {
+        let target = new_target();
+        let mut sequence = Sequence::new(vec![
+            Box::new(MoveBy::new(1.0, Vec2::new(10.0, 0.0))),
+            Box::new(DelayTime::new(0.5)),
+            Box::new(MoveBy::new(1.0, Vec2::new(0.0, 10.0))),
+        ]);
+        assert_eq!(sequence.get_duration(), 2.5);
+        sequence.start_with_target(target.clone());
+
+        sequence.step(1.0);
+        assert_eq!(*target.borrow().get_position(), Vec2::new(10.0, 0.0));
+        sequence.step(0.25);
+        assert_eq!(*target.borrow().get_position(), Vec2::new(10.0, 0.0));
+        sequence.step(0.25);
+        assert_eq!(*target.borrow().get_position(), Vec2::new(10.0, 0.0));
+        sequence.step(0.5);
+        assert_eq!(*target.borrow().get_position(), Vec2::new(10.0, 5.0));
+        sequence.step(0.5);
+        assert_eq!(*target.borrow().get_position(), Vec2::new(10.0, 10.0));
+        assert!(sequence.is_done());
+    }
+
+    fn tagged_move(tag: i32) -> Box<dyn Action> {
+        let mut action = MoveBy::new(1.0, Vec2::new(10.0, 0.0));
+        action.set_tag(tag);
+        Box::new(action)
+    }
+
+    #[test]
+    fn test_call_func_can_run_and_stop_actions_during_update() {
+        let node = Node::create();
+        let other = Node::create();
+        other.borrow_mut().run_action(Box::new(MoveBy::new(1.0, Vec2::new(100.0, 0.0))));
+
+        let handle = node.clone();
+        let other_handle = other.clone();
+        let sequence = Sequence::new(vec![
+            Box::new(MoveBy::new(0.5, Vec2::new(100.0, 0.0))),
+            Box::new(CallFunc::new(Box::new(move || {
+                handle.borrow_mut().run_action(Box::new(MoveBy::new(0.5, Vec2::new(0.0, 100.0))));
+                other_handle.borrow_mut().stop_all_actions();
+            }))),
+        ]);
+        node.borrow_mut().run_action(Box::new(sequence));
+
+        ActionManager::update_instance(0.5);
+        assert_eq!(*node.borrow().get_position(), Vec2::new(100.0, 0.0));
+        assert_eq!(node.borrow().get_number_of_running_actions(), 1);
+        assert_eq!(other.borrow().get_number_of_running_actions(), 0);
+        assert_eq!(other.borrow().get_position().x, 50.0);
+
+        ActionManager::update_instance(0.25);
+        assert_eq!(*node.borrow().get_position(), Vec2::new(100.0, 50.0));
+        assert_eq!(other.borrow().get_position().x, 50.0);
+        ActionManager::update_instance(0.25);
+        assert_eq!(node.borrow().get_number_of_running_actions(), 0);
+    }
+
+    #[test]
+    fn test_stop_actions_by_tag_in_subtree() {
+        const BLINK: i32 = 1;
+        const MOVE: i32 = 2;
+        let mut manager = ActionManager::new();
+        let targets: Vec<NodeRef> = (0..4).map(|_| new_target()).collect();
+        for target in &targets {
+            manager.add_action(tagged_move(BLINK), target.clone(), false);
+            manager.add_action(tagged_move(MOVE), target.clone(), false);
+        }
+
+        // root -> panel -> button, plus a sibling tree outside the subtree
+        let (root, panel, button) = (&targets[0], &targets[1], &targets[2]);
+        panel.borrow_mut().add_child(button.clone());
+        root.borrow_mut().add_child(panel.clone());
+
+        manager.stop_actions_by_tag_in_subtree(root, BLINK);
+        for target in &targets[..3] {
+            assert!(manager.get_action_by_tag(BLINK, target).is_none());
+            assert!(manager.get_action_by_tag(MOVE, target).is_some());
+            assert_eq!(manager.get_number_of_running_actions_in_target(target), 1);
         }
+        assert!(manager.get_action_by_tag(BLINK, &targets[3]).is_some());
+        assert_eq!(manager.get_number_of_running_actions_in_target(&targets[3]), 2);
     }
 }
@@ -1,8 +1,12 @@
 use super::animation::Animation;
 use super::sprite_frame::SpriteFrame;
+use crate::sprite::Sprite;
 use std::rc::Rc;
 use std::cell::RefCell;
 
+/// 帧事件回调类型，参数为新到达的帧索引
+pub type FrameEventCallback = Box<dyn FnMut(usize)>;
+
 /// Animate 动作
 /// 播放动画序列的动作
 pub struct Animate {
@@ -18,6 +22,10 @@ pub struct Animate {
     executed_loops: u32,
     /// 是否完成
     done: bool,
+    /// 帧事件回调
+    frame_event_callback: Option<FrameEventCallback>,
+    /// 最后触发帧事件的播放步数（循环次数 * 帧数 + 帧索引）
+    last_event_step: Option<usize>,
 }
 
 impl Animate {
@@ -30,6 +38,8 @@ impl Animate {
             current_frame_index: 0,
             executed_loops: 0,
             done: false,
+            frame_event_callback: None,
+            last_event_step: None,
         }
     }
 
@@ -50,15 +60,52 @@ impl Animate {
         self.current_frame_index = 0;
         self.executed_loops = 0;
         self.done = false;
+        self.last_event_step = None;
+    }
+
+    /// 设置帧事件回调
+    /// 播放每到达一个新的帧索引时触发一次，循环播放时每次循环都会重新触发；
+    /// 一次更新跨过多帧时按顺序为每一帧触发
+    pub fn set_frame_event_callback(&mut self, callback: FrameEventCallback) {
+        self.frame_event_callback = Some(callback);
     }
 
     /// 停止播放
     pub fn stop(&mut self) {
         self.done = true;
-        
-        // 恢复原始帧
+        // 原始帧由 stop_sprite 恢复到精灵上
+    }
+
+    /// 以精灵当前显示的帧作为原始帧开始播放
+    pub fn start_with_sprite(&mut self, sprite: &Sprite) {
+        self.start(sprite.get_sprite_frame().cloned());
+    }
+
+    /// 停止播放，并在动画要求时将原始帧恢复到精灵上
+    pub fn stop_sprite(&mut self, sprite: &mut Sprite) {
+        if self.done {
+            return;
+        }
+        self.stop();
         if self.animation.borrow().restore_original_frame() {
-            // 这里需要在实际使用时将 original_frame 应用到精灵上
+            if let Some(frame) = self.original_frame.clone() {
+                sprite.set_sprite_frame(frame);
+            }
+        }
+    }
+
+    /// 更新动画并将当前帧应用到精灵上
+    /// 帧未变化时不会重复设置
+    pub fn update_sprite(&mut self, sprite: &mut Sprite, dt: f32) {
+        let frame = match self.update(dt) {
+            Some(frame) => frame,
+            None => return,
+        };
+        let unchanged = sprite
+            .get_sprite_frame()
+            .map_or(false, |current| Rc::ptr_eq(current, &frame));
+        if !unchanged {
+            sprite.set_sprite_frame(frame);
         }
     }
 
@@ -92,13 +139,17 @@ impl Animate {
         if loops > 0 && self.elapsed >= duration * loops as f32 {
             self.done = true;
             
-            // 恢复原始帧
-            if animation.restore_original_frame() {
-                return self.original_frame.clone();
-            }
-            
-            // 否则返回最后一帧
-            return animation.get_frame(animation.frame_count() - 1);
+            let frame_count = animation.frame_count();
+            let frame = if animation.restore_original_frame() {
+                // 恢复原始帧
+                self.original_frame.clone()
+            } else {
+                // 否则返回最后一帧
+                animation.get_frame(frame_count - 1)
+            };
+            drop(animation);
+            self.fire_frame_events(loops as usize * frame_count - 1, frame_count);
+            return frame;
         }
 
         // 计算当前帧索引
@@ -112,7 +163,25 @@ impl Animate {
         }
 
         self.current_frame_index = new_frame_index;
-        animation.get_frame(new_frame_index)
+        let frame_count = animation.frame_count();
+        let frame = animation.get_frame(new_frame_index);
+        drop(animation);
+        self.fire_frame_events(new_loop as usize * frame_count + new_frame_index, frame_count);
+        frame
+    }
+
+    /// 为上次触发之后直到 `step` 的每一步触发帧事件
+    fn fire_frame_events(&mut self, step: usize, frame_count: usize) {
+        let first = self.last_event_step.map_or(0, |last| last + 1);
+        if first > step {
+            return;
+        }
+        if let Some(callback) = self.frame_event_callback.as_mut() {
+            for event_step in first..=step {
+                callback(event_step % frame_count);
+            }
+        }
+        self.last_event_step = Some(step);
     }
 
     /// 是否完成
@@ -152,9 +221,11 @@ impl Animate {
         self.current_frame_index = 0;
         self.executed_loops = 0;
         self.done = false;
+        self.last_event_step = None;
     }
 
     /// 跳转到指定时间
+    /// 跳转本身不触发帧事件，目标帧视为已到达，之后的播放从下一帧开始触发
     pub fn seek(&mut self, time: f32) {
         self.elapsed = time.max(0.0);
         
@@ -165,6 +236,8 @@ impl Animate {
             let loop_time = self.elapsed % duration;
             self.current_frame_index = animation.get_frame_index_at_time(loop_time);
             self.executed_loops = (self.elapsed / duration) as u32;
+            let step = self.executed_loops as usize * animation.frame_count() + self.current_frame_index;
+            self.last_event_step = Some(step);
         }
     }
 
@@ -177,6 +250,8 @@ impl Animate {
             current_frame_index: 0,
             executed_loops: 0,
             done: false,
+            frame_event_callback: None,
+            last_event_step: None,
         }
     }
 }
@@ -349,4 +424,126 @@ mod tests {
         assert_eq!(cloned.current_frame_index(), 0); // 克隆后重置
         assert!(!cloned.is_done());
     }
+
+    fn frame_at(index: usize) -> Rc<RefCell<SpriteFrame>> {
+        let mut frame = SpriteFrame::new(format!("frame_{}", index));
+        frame.set_rect(crate::math::Rect::new(index as f32 * 16.0, 0.0, 16.0, 16.0));
+        Rc::new(RefCell::new(frame))
+    }
+
+    fn sprite_showing_original() -> Sprite {
+        let mut original = SpriteFrame::new("original");
+        original.set_rect(crate::math::Rect::new(0.0, 64.0, 32.0, 32.0));
+        let mut sprite = Sprite::new();
+        sprite.set_sprite_frame(Rc::new(RefCell::new(original)));
+        sprite
+    }
+
+    fn sprite_rect_x(sprite: &Sprite) -> f32 {
+        sprite.get_texture_rect().origin.x
+    }
+
+    #[test]
+    fn test_update_sprite_steps_through_frames() {
+        let mut anim = Animation::with_frames((0..3).map(frame_at).collect(), 0.1);
+        anim.set_loops(1);
+        anim.set_restore_original_frame(true);
+        let mut animate = Animate::create(anim);
+        let mut sprite = sprite_showing_original();
+        animate.start_with_sprite(&sprite);
+
+        animate.update_sprite(&mut sprite, 0.05);
+        assert_eq!(sprite_rect_x(&sprite), 0.0);
+        animate.update_sprite(&mut sprite, 0.1);
+        assert_eq!(sprite_rect_x(&sprite), 16.0);
+        animate.update_sprite(&mut sprite, 0.1);
+        assert_eq!(sprite_rect_x(&sprite), 32.0);
+
+        animate.update_sprite(&mut sprite, 0.1);
+        assert!(animate.is_done());
+        assert_eq!(sprite.get_texture_rect().origin.y, 64.0);
+        assert_eq!(sprite.get_sprite_frame().unwrap().borrow().name(), "original");
+    }
+
+    #[test]
+    fn test_stop_sprite_restores_original_frame() {
+        let mut anim = Animation::with_frames((0..3).map(frame_at).collect(), 0.1);
+        anim.set_restore_original_frame(true);
+        let mut animate = Animate::create(anim);
+        let mut sprite = sprite_showing_original();
+        animate.start_with_sprite(&sprite);
+
+        animate.update_sprite(&mut sprite, 0.15);
+        assert_eq!(sprite_rect_x(&sprite), 16.0);
+
+        animate.stop_sprite(&mut sprite);
+        assert!(animate.is_done());
+        assert_eq!(sprite.get_sprite_frame().unwrap().borrow().name(), "original");
+    }
+
+    #[test]
+    fn test_completion_keeps_last_frame_without_restore() {
+        let mut anim = Animation::with_frames((0..3).map(frame_at).collect(), 0.1);
+        anim.set_loops(1);
+        let mut animate = Animate::create(anim);
+        let mut sprite = sprite_showing_original();
+        animate.start_with_sprite(&sprite);
+
+        animate.update_sprite(&mut sprite, 0.5);
+        assert!(animate.is_done());
+        assert_eq!(sprite_rect_x(&sprite), 32.0);
+    }
+
+    fn recording_animate(frame_count: usize) -> (Animate, Rc<RefCell<Vec<usize>>>) {
+        let mut animate = Animate::create(create_test_animation(frame_count, 0.1, 0));
+        let events = Rc::new(RefCell::new(Vec::new()));
+        let recorded = events.clone();
+        animate.set_frame_event_callback(Box::new(move |index| recorded.borrow_mut().push(index)));
+        animate.start(None);
+        (animate, events)
+    }
+
+    #[test]
+    fn test_frame_events_fire_once_per_frame_each_loop() {
+        let (mut animate, events) = recording_animate(4);
+
+        animate.update(0.05);
+        assert_eq!(*events.borrow(), vec![0]);
+        // 同一帧内的更新不会重复触发
+        animate.update(0.01);
+        assert_eq!(*events.borrow(), vec![0]);
+
+        for _ in 0..7 {
+            animate.update(0.1);
+        }
+        assert_eq!(*events.borrow(), vec![0, 1, 2, 3, 0, 1, 2, 3]);
+    }
+
+    #[test]
+    fn test_frame_events_cover_skipped_frames() {
+        let (mut animate, events) = recording_animate(4);
+
+        animate.update(0.25);
+        assert_eq!(*events.borrow(), vec![0, 1, 2]);
+    }
+
+    #[test]
+    fn test_frame_events_after_seek() {
+        let (mut animate, events) = recording_animate(4);
+        animate.update(0.35);
+        assert_eq!(*events.borrow(), vec![0, 1, 2, 3]);
+
+        // 向后跳转后，再次前进会重新触发经过的帧
+        events.borrow_mut().clear();
+        animate.seek(0.05);
+        assert!(events.borrow().is_empty());
+        animate.update(0.2);
+        assert_eq!(*events.borrow(), vec![1, 2]);
+
+        // 向前跳转跳过的帧不会触发
+        events.borrow_mut().clear();
+        animate.seek(0.35);
+        animate.update(0.1);
+        assert_eq!(*events.borrow(), vec![0]);
+    }
 }
@@ -1,4 +1,5 @@
 use super::sprite_frame::SpriteFrame;
+use super::sprite_frame_cache::SpriteFrameCache;
 use std::rc::Rc;
 use std::cell::RefCell;
 
@@ -45,6 +46,28 @@ impl Animation {
         }
     }
 
+    /// 从按序号命名的帧创建动画
+    ///
+    /// `pattern` 中的 `{}` 替换为帧序号，`{:02}` 这样的写法会补零到指定宽度，
+    /// 例如 "run_{:02}" 从 1 到 8 取 "run_01".."run_08"。缺少任何一帧都会返回错误。
+    pub fn from_frame_pattern(
+        cache: &SpriteFrameCache,
+        pattern: &str,
+        start: u32,
+        end: u32,
+        delay: f32,
+    ) -> Result<Self, String> {
+        let mut frames = Vec::new();
+        for index in start..=end {
+            let name = format_frame_name(pattern, index)?;
+            let frame = cache
+                .get_frame(&name)
+                .ok_or_else(|| format!("Sprite frame '{}' not found", name))?;
+            frames.push(frame);
+        }
+        Ok(Self::with_frames(frames, delay))
+    }
+
     /// 从帧序列创建动画（带名称）
     pub fn with_sprite_frames(name: String, frames: Vec<Rc<RefCell<SpriteFrame>>>, delay: f32) -> Self {
         let duration = frames.len() as f32 * delay;
@@ -190,6 +213,29 @@ impl Animation {
     }
 }
 
+/// 将帧序号填入名称模式中的 `{}` 或 `{:0N}` 占位符
+fn format_frame_name(pattern: &str, index: u32) -> Result<String, String> {
+    let open = pattern
+        .find('{')
+        .ok_or_else(|| format!("Frame pattern '{}' has no '{{}}' placeholder", pattern))?;
+    let close = pattern[open..]
+        .find('}')
+        .map(|offset| open + offset)
+        .ok_or_else(|| format!("Frame pattern '{}' has an unclosed placeholder", pattern))?;
+
+    let spec = &pattern[open + 1..close];
+    let number = if spec.is_empty() {
+        index.to_string()
+    } else {
+        let width = spec
+            .strip_prefix(":0")
+            .and_then(|width| width.parse::<usize>().ok())
+            .ok_or_else(|| format!("Unsupported placeholder '{{{}}}' in frame pattern", spec))?;
+        format!("{:0width$}", index, width = width)
+    };
+    Ok(format!("{}{}{}", &pattern[..open], number, &pattern[close + 1..]))
+}
+
 impl Default for Animation {
     fn default() -> Self {
         Self::new()
@@ -218,6 +264,35 @@ mod tests {
             .collect()
     }
 
+    fn pattern_cache() -> SpriteFrameCache {
+        let mut cache = SpriteFrameCache::new();
+        for i in 1..=3 {
+            cache.add_frame(SpriteFrame::new(format!("f_{:02}", i)));
+        }
+        cache
+    }
+
+    #[test]
+    fn test_from_frame_pattern() {
+        let cache = pattern_cache();
+        let anim = Animation::from_frame_pattern(&cache, "f_{:02}", 1, 3, 0.1).unwrap();
+
+        assert_eq!(anim.frame_count(), 3);
+        assert!((anim.duration() - 0.3).abs() < 1e-6);
+        assert_eq!(anim.get_frame(2).unwrap().borrow().name(), "f_03");
+    }
+
+    #[test]
+    fn test_from_frame_pattern_missing_frame() {
+        let cache = pattern_cache();
+        let error = Animation::from_frame_pattern(&cache, "f_{:02}", 1, 4, 0.1).unwrap_err();
+        assert!(error.contains("f_04"));
+
+        assert!(Animation::from_frame_pattern(&cache, "f_{}", 1, 3, 0.1).is_err());
+        assert!(Animation::from_frame_pattern(&cache, "f_01", 1, 1, 0.1).is_err());
+        assert_eq!(format_frame_name("walk{}.png", 7).unwrap(), "walk7.png");
+    }
+
     #[test]
     fn test_animation_creation() {
         let anim = Animation::new();
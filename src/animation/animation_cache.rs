@@ -1,4 +1,5 @@
 use super::animation::Animation;
+use super::sprite_frame_cache::SpriteFrameCache;
 use std::collections::HashMap;
 use std::rc::Rc;
 use std::cell::RefCell;
@@ -62,17 +63,7 @@ impl AnimationCache {
 
     /// 从 plist 文件加载动画
     pub fn load_animations_from_plist(&mut self, plist_file: &str) -> Result<(), String> {
-        use std::fs::File;
-        use std::io::BufReader;
-        use super::sprite_frame_cache::SpriteFrameCache;
-        
-        // 读取 plist 文件
-        let file = File::open(plist_file)
-            .map_err(|e| format!("Failed to open plist file '{}': {}", plist_file, e))?;
-        
-        let reader = BufReader::new(file);
-        let plist_data: plist::Value = plist::from_reader(reader)
-            .map_err(|e| format!("Failed to parse plist file: {}", e))?;
+        let plist_data = read_plist(plist_file)?;
         
         // 解析 animations 字典
         if let plist::Value::Dictionary(root) = plist_data {
@@ -81,14 +72,7 @@ impl AnimationCache {
                 
                 for (anim_name, anim_data) in animations.iter() {
                     if let plist::Value::Dictionary(anim_dict) = anim_data {
-                        // 解析延迟时间
-                        let delay = if let Some(plist::Value::Real(d)) = anim_dict.get("delayPerUnit") {
-                            *d as f32
-                        } else if let Some(plist::Value::Integer(d)) = anim_dict.get("delayPerUnit") {
-                            *d as f32
-                        } else {
-                            0.1 // 默认延迟
-                        };
+                        let delay = parse_delay_per_unit(anim_dict);
                         
                         // 解析帧名称数组
                         let mut frames = Vec::new();
@@ -122,6 +106,49 @@ impl AnimationCache {
         }
     }
 
+    /// 从同时包含 frames 和 animations 字典的 plist 文件加载精灵帧和动画
+    ///
+    /// 精灵帧加入 `SpriteFrameCache::shared()`。动画引用的每一帧都必须能找到，
+    /// 否则返回错误且不添加任何动画（已加载的精灵帧保留在缓存中）。
+    pub fn load_with_frames(&mut self, plist_file: &str) -> Result<(), String> {
+        let frame_cache = SpriteFrameCache::shared();
+        frame_cache.borrow_mut().load_frames_from_plist(plist_file)?;
+        
+        let plist_data = read_plist(plist_file)?;
+        let animations = plist_data
+            .as_dictionary()
+            .and_then(|root| root.get("animations"))
+            .and_then(plist::Value::as_dictionary)
+            .ok_or_else(|| "No 'animations' key found in plist".to_string())?;
+        
+        let mut loaded = Vec::new();
+        for (anim_name, anim_data) in animations.iter() {
+            let anim_dict = anim_data
+                .as_dictionary()
+                .ok_or_else(|| format!("Animation '{}' is not a dictionary", anim_name))?;
+            let frame_names = anim_dict
+                .get("frames")
+                .and_then(plist::Value::as_array)
+                .ok_or_else(|| format!("Animation '{}' has no frames", anim_name))?;
+            
+            let mut frames = Vec::new();
+            for frame_name in frame_names {
+                let frame_name = frame_name
+                    .as_string()
+                    .ok_or_else(|| format!("Animation '{}' has a frame name that is not a string", anim_name))?;
+                let frame = frame_cache
+                    .borrow()
+                    .get_frame(frame_name)
+                    .ok_or_else(|| format!("Animation '{}' references missing frame '{}'", anim_name, frame_name))?;
+                frames.push(frame);
+            }
+            loaded.push(Animation::with_sprite_frames(anim_name.clone(), frames, parse_delay_per_unit(anim_dict)));
+        }
+        
+        self.add_animations(loaded);
+        Ok(())
+    }
+
     /// 批量添加动画
     pub fn add_animations(&mut self, animations: Vec<Animation>) {
         for animation in animations {
@@ -165,6 +192,26 @@ impl AnimationCache {
     }
 }
 
+/// 读取 plist 文件
+fn read_plist(plist_file: &str) -> Result<plist::Value, String> {
+    use std::fs::File;
+    use std::io::BufReader;
+    
+    let file = File::open(plist_file)
+        .map_err(|e| format!("Failed to open plist file '{}': {}", plist_file, e))?;
+    plist::from_reader(BufReader::new(file))
+        .map_err(|e| format!("Failed to parse plist file: {}", e))
+}
+
+/// 解析动画的每帧延迟时间，缺省为 0.1 秒
+fn parse_delay_per_unit(anim_dict: &plist::Dictionary) -> f32 {
+    match anim_dict.get("delayPerUnit") {
+        Some(plist::Value::Real(d)) => *d as f32,
+        Some(plist::Value::Integer(d)) => d.as_signed().unwrap_or(0) as f32,
+        _ => 0.1,
+    }
+}
+
 impl Default for AnimationCache {
     fn default() -> Self {
         Self::new()
@@ -295,6 +342,66 @@ mod tests {
         assert_eq!(cloned.frame_count(), 5);
     }
 
+    const COMBINED_PLIST: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<plist version="1.0">
+<dict>
+    <key>frames</key>
+    <dict>
+        <key>combined_walk_0.png</key>
+        <dict><key>frame</key><string>{{0,0},{32,32}}</string></dict>
+        <key>combined_walk_1.png</key>
+        <dict><key>frame</key><string>{{32,0},{32,32}}</string></dict>
+        <key>combined_walk_2.png</key>
+        <dict><key>frame</key><string>{{64,0},{32,32}}</string></dict>
+    </dict>
+    <key>animations</key>
+    <dict>
+        <key>combined_walk</key>
+        <dict>
+            <key>delayPerUnit</key><real>0.2</real>
+            <key>frames</key>
+            <array>
+                <string>combined_walk_0.png</string>
+                <string>combined_walk_1.png</string>
+                <string>combined_walk_2.png</string>
+            </array>
+        </dict>
+        MISSING
+    </dict>
+</dict>
+</plist>"#;
+
+    fn write_plist(name: &str, missing: &str) -> String {
+        let path = std::env::temp_dir().join(format!("cocos2d-rust-{}-{}.plist", name, std::process::id()));
+        std::fs::write(&path, COMBINED_PLIST.replace("MISSING", missing)).unwrap();
+        path.to_string_lossy().into_owned()
+    }
+
+    #[test]
+    fn test_load_with_frames() {
+        let path = write_plist("combined", "");
+        let mut cache = AnimationCache::new();
+        cache.load_with_frames(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        
+        assert!(SpriteFrameCache::shared().borrow().has_frame("combined_walk_1.png"));
+        let walk = cache.get_animation("combined_walk").unwrap();
+        assert_eq!(walk.borrow().frame_count(), 3);
+        assert!((walk.borrow().delay_per_unit() - 0.2).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_load_with_frames_rejects_missing_frame() {
+        let missing = "<key>combined_run</key><dict><key>frames</key><array><string>combined_run_0.png</string></array></dict>";
+        let path = write_plist("combined-missing", missing);
+        let mut cache = AnimationCache::new();
+        let error = cache.load_with_frames(&path).unwrap_err();
+        std::fs::remove_file(&path).unwrap();
+        
+        assert!(error.contains("combined_run_0.png"));
+        assert_eq!(cache.animation_count(), 0);
+    }
+
     #[test]
     fn test_shared_instance() {
         let cache1 = AnimationCache::shared();
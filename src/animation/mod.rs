@@ -8,4 +8,4 @@ pub use animation::Animation;
 pub use animation_cache::AnimationCache;
 pub use sprite_frame::SpriteFrame;
 pub use sprite_frame_cache::SpriteFrameCache;
-pub use animate::Animate;
+pub use animate::{Animate, FrameEventCallback};
@@ -15,6 +15,7 @@ pub struct AudioEngine {
     mute: bool,
     volume: f32,
     listener: AudioListener,
+    paused_by_pause_all: Vec<i32>,
 }
 
 impl AudioEngine {
@@ -28,6 +29,7 @@ impl AudioEngine {
             mute: false,
             volume: 1.0,
             listener: AudioListener::new(),
+            paused_by_pause_all: Vec::new(),
         }
     }
 
@@ -137,6 +139,32 @@ impl AudioEngine {
         }
     }
 
+    /// Pauses every playing sound, e.g. when the application goes to the background
+    pub fn pause_all() {
+        let engine = Self::get_instance();
+        for (id, player) in engine.audio_players.iter() {
+            let mut player = player.lock().unwrap();
+            if player.get_state() == AudioState::PLAYING {
+                player.pause();
+                engine.paused_by_pause_all.push(*id);
+            }
+        }
+    }
+
+    /// Resumes the sounds paused by `pause_all`; sounds paused on their own
+    /// stay paused
+    pub fn resume_all() {
+        let engine = Self::get_instance();
+        for id in engine.paused_by_pause_all.drain(..) {
+            if let Some(player) = engine.audio_players.get(&id) {
+                let mut player = player.lock().unwrap();
+                if player.get_state() == AudioState::PAUSED {
+                    player.play();
+                }
+            }
+        }
+    }
+
     pub fn is_playing(audio_id: i32) -> bool {
         if let Some(player) = Self::get_instance().audio_players.get(&audio_id) {
             let player = player.lock().unwrap();
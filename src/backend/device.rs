@@ -1,4 +1,8 @@
 use std::collections::HashMap;
+use crate::math::Mat4;
+use crate::renderer::pipeline::PrimitiveType;
+use crate::renderer::{Texture2D, UniformValue};
+use super::render_backend::{RenderBackend, RenderState};
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum BufferType {
@@ -41,6 +45,7 @@ impl BufferObject {
 
 #[derive(Debug)]
 pub struct GraphicsDevice {
+    backend: Option<Box<dyn RenderBackend>>,
     capabilities: DeviceCapabilities,
     profiles: HashMap<String, DeviceProfile>,
     shaders: HashMap<u32, ShaderProgram>,
@@ -57,6 +62,7 @@ pub struct DeviceCapabilities {
     max_vertex_uniform_vectors: u32,
     max_fragment_uniform_vectors: u32,
     max_varying_vectors: u32,
+    supports_npot: bool,
     supports_s3tc: bool,
     supports_etc1: bool,
     supports_pvrtc: bool,
@@ -74,6 +80,7 @@ impl DeviceCapabilities {
             max_vertex_uniform_vectors: 256,
             max_fragment_uniform_vectors: 224,
             max_varying_vectors: 16,
+            supports_npot: false,
             supports_s3tc: false,
             supports_etc1: false,
             supports_pvrtc: false,
@@ -82,6 +89,18 @@ impl DeviceCapabilities {
             supports_atc: false,
         }
     }
+
+    pub fn get_max_texture_size(&self) -> u32 {
+        self.max_texture_size
+    }
+
+    pub fn get_max_texture_units(&self) -> u32 {
+        self.max_texture_units
+    }
+
+    pub fn supports_npot_textures(&self) -> bool {
+        self.supports_npot
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -124,6 +143,59 @@ impl ShaderProgram {
     pub fn get_uniform_location(&self, name: &str) -> Option<i32> {
         self.uniforms.get(name).cloned()
     }
+
+    /// Checks if the program has the uniform active
+    pub fn has_uniform(&self, name: &str) -> bool {
+        self.uniforms.contains_key(name)
+    }
+
+    /// Asks the backend for the location of each uniform declared in a
+    /// shader source, keeping those the linked program reports as active
+    fn add_declared_uniforms(&mut self, backend: &mut dyn RenderBackend, source: &str) {
+        for statement in source.split(';') {
+            let mut words = statement.split_whitespace().skip_while(|word| *word != "uniform");
+            // `uniform [precision] type name`
+            let name = match (words.next(), words.last()) {
+                (Some(_), Some(name)) => name.split('[').next().unwrap_or(name),
+                _ => continue,
+            };
+            if self.uniforms.contains_key(name) {
+                continue;
+            }
+            let location = backend.get_uniform_location(self.id, name);
+            if location >= 0 {
+                self.uniforms.insert(name.to_string(), location);
+            }
+        }
+    }
+}
+
+/// Name of the built-in model-view-projection matrix uniform
+pub const UNIFORM_MVP_MATRIX: &str = "uMVPMatrix";
+/// Name of the built-in uniform holding the director's total time
+pub const UNIFORM_TIME: &str = "uTime";
+/// Name of the built-in uniform holding the texture unit of the main texture
+pub const UNIFORM_TEXTURE_SAMPLER: &str = "uTexture";
+
+/// Engine values bound automatically to the built-in uniforms a program
+/// declares, so user shaders get them without setting them by hand
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BuiltinUniforms {
+    pub mvp_matrix: Mat4,
+    pub time: f32,
+    pub texture_unit: i32,
+}
+
+impl BuiltinUniforms {
+    /// Combines a node's transform with the frame's view-projection; the
+    /// main texture is bound to unit 0
+    pub fn new(view_projection: &Mat4, model: &Mat4, time: f32) -> BuiltinUniforms {
+        BuiltinUniforms {
+            mvp_matrix: *view_projection * *model,
+            time,
+            texture_unit: 0,
+        }
+    }
 }
 
 #[derive(Debug)]
@@ -189,6 +261,7 @@ impl FramebufferObject {
 impl GraphicsDevice {
     pub fn new() -> GraphicsDevice {
         GraphicsDevice {
+            backend: None,
             capabilities: DeviceCapabilities::new(),
             profiles: HashMap::new(),
             shaders: HashMap::new(),
@@ -202,16 +275,109 @@ impl GraphicsDevice {
         &self.capabilities
     }
 
-    pub fn create_shader(&mut self) -> ShaderProgram {
-        ShaderProgram::new()
+    /// Initializes `backend` and makes it the target of all device calls
+    pub fn init(&mut self, mut backend: Box<dyn RenderBackend>) -> bool {
+        let ok = backend.init();
+        self.backend = Some(backend);
+        ok
+    }
+
+    /// Sets the backend without initializing it
+    pub fn set_backend(&mut self, backend: Box<dyn RenderBackend>) {
+        self.backend = Some(backend);
+    }
+
+    pub fn get_backend(&self) -> Option<&dyn RenderBackend> {
+        self.backend.as_deref()
+    }
+
+    /// Gets the initialized backend, if any
+    fn initialized_backend(&self) -> Option<&dyn RenderBackend> {
+        self.get_backend().filter(|backend| backend.is_initialized())
+    }
+
+    /// Gets the largest supported texture size, falling back to the default
+    /// capabilities until a backend is initialized
+    pub fn get_max_texture_size(&self) -> u32 {
+        self.initialized_backend()
+            .map(|backend| backend.get_max_texture_size())
+            .unwrap_or(self.capabilities.max_texture_size)
+    }
+
+    pub fn get_max_texture_units(&self) -> u32 {
+        self.initialized_backend()
+            .map(|backend| backend.get_max_texture_units())
+            .unwrap_or(self.capabilities.max_texture_units)
+    }
+
+    pub fn supports_npot_textures(&self) -> bool {
+        self.initialized_backend()
+            .map(|backend| backend.supports_npot_textures())
+            .unwrap_or(self.capabilities.supports_npot)
+    }
+
+    pub fn get_renderer_string(&self) -> String {
+        self.initialized_backend()
+            .map(|backend| backend.get_renderer_string())
+            .unwrap_or_else(|| String::from("unknown"))
+    }
+
+    /// Creates and compiles a shader program, returning None if compilation
+    /// fails. The locations of its declared uniforms come from the backend.
+    pub fn create_shader(&mut self, vertex_source: &str, fragment_source: &str) -> Option<ShaderProgram> {
+        let mut program = ShaderProgram::new();
+        if let Some(backend) = self.backend.as_mut() {
+            program.id = backend.create_shader(vertex_source, fragment_source);
+            if !backend.compile_shader(program.id) {
+                backend.delete_shader(program.id);
+                return None;
+            }
+            program.add_declared_uniforms(backend.as_mut(), vertex_source);
+            program.add_declared_uniforms(backend.as_mut(), fragment_source);
+        }
+        Some(program)
     }
 
     pub fn create_buffer(&mut self) -> BufferObject {
         BufferObject::new()
     }
 
-    pub fn create_texture(&mut self) -> TextureObject {
-        TextureObject::new()
+    pub fn create_texture(&mut self, width: u32, height: u32) -> TextureObject {
+        let mut texture = TextureObject::new();
+        texture.width = width;
+        texture.height = height;
+        if let Some(backend) = self.backend.as_mut() {
+            texture.id = backend.create_texture(width, height);
+        }
+        texture
+    }
+
+    /// Uploads the regions queued by `Texture2D::update_sub_image`, oldest
+    /// first, in the texture's pixel format. Stops at the first region the
+    /// backend rejects; the queue is emptied either way.
+    pub fn upload_texture_updates(&mut self, texture: &mut Texture2D) -> Result<(), String> {
+        let updates = texture.take_pending_updates();
+        if let Some(backend) = self.backend.as_mut() {
+            for update in &updates {
+                backend.update_texture(
+                    texture.get_name(),
+                    update.x,
+                    update.y,
+                    update.width,
+                    update.height,
+                    texture.get_pixel_format(),
+                    &update.data,
+                )?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Binds a texture with its filtering and wrapping state
+    pub fn bind_texture(&mut self, texture: &Texture2D) {
+        if let Some(backend) = self.backend.as_mut() {
+            backend.bind_texture_with_sampler(texture.get_name(), texture.get_sampler());
+        }
     }
 
     pub fn create_framebuffer(&mut self) -> FramebufferObject {
@@ -219,6 +385,9 @@ impl GraphicsDevice {
     }
 
     pub fn delete_shader(&mut self, program: &mut ShaderProgram) {
+        if let Some(backend) = self.backend.as_mut() {
+            backend.delete_shader(program.id);
+        }
         program.id = 0;
     }
 
@@ -227,6 +396,9 @@ impl GraphicsDevice {
     }
 
     pub fn delete_texture(&mut self, texture: &mut TextureObject) {
+        if let Some(backend) = self.backend.as_mut() {
+            backend.delete_texture(texture.id);
+        }
         texture.id = 0;
     }
 
@@ -234,16 +406,279 @@ impl GraphicsDevice {
         framebuffer.id = 0;
     }
 
-    pub fn set_viewport(&self, x: i32, y: i32, width: u32, height: u32) {
+    pub fn set_state(&mut self, state: RenderState) {
+        if let Some(backend) = self.backend.as_mut() {
+            backend.set_state(state);
+        }
+    }
+
+    pub fn set_viewport(&mut self, x: i32, y: i32, width: u32, height: u32) {
+        self.set_state(RenderState::Viewport(x, y, width, height));
+    }
+
+    pub fn set_scissor_test(&mut self, enabled: bool) {
+        self.set_state(RenderState::ScissorTest(enabled));
+    }
+
+    pub fn clear(&mut self, mask: u32) {
+        if let Some(backend) = self.backend.as_mut() {
+            backend.clear(mask);
+        }
+    }
+
+    pub fn draw(&mut self, primitive: PrimitiveType, first: u32, count: u32) {
+        if let Some(backend) = self.backend.as_mut() {
+            backend.draw(primitive, first, count);
+        }
+    }
+
+    /// Sets the built-in uniforms that `program` declares; the others are
+    /// left untouched
+    pub fn apply_builtin_uniforms(&mut self, program: &ShaderProgram, uniforms: &BuiltinUniforms) {
+        let backend = match self.backend.as_mut() {
+            Some(backend) => backend,
+            None => return,
+        };
+        let values = [
+            (UNIFORM_MVP_MATRIX, UniformValue::Mat4(uniforms.mvp_matrix)),
+            (UNIFORM_TIME, UniformValue::Float(uniforms.time)),
+            (UNIFORM_TEXTURE_SAMPLER, UniformValue::Sampler(uniforms.texture_unit)),
+        ];
+        for (name, value) in values.iter() {
+            if let Some(location) = program.get_uniform_location(name) {
+                backend.set_uniform(program.id, location, value);
+            }
+        }
     }
 
-    pub fn set_scissor_test(&self, enabled: bool) {
+    /// Draws with `program` after binding its built-in uniforms
+    pub fn draw_with_program(
+        &mut self,
+        program: &ShaderProgram,
+        uniforms: &BuiltinUniforms,
+        primitive: PrimitiveType,
+        first: u32,
+        count: u32,
+    ) {
+        self.apply_builtin_uniforms(program, uniforms);
+        self.draw(primitive, first, count);
     }
 
-    pub fn clear(&self, mask: u32) {
+    /// Reads back RGBA8 pixels, or an empty buffer without a backend
+    pub fn read_pixels(&mut self, x: i32, y: i32, width: u32, height: u32) -> Vec<u8> {
+        match self.backend.as_mut() {
+            Some(backend) => backend.read_pixels(x, y, width, height),
+            None => Vec::new(),
+        }
     }
 
     pub fn get_error(&self) -> u32 {
         0
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::backend::mock::{BackendCall, MockBackend};
+
+    #[test]
+    fn test_capabilities_forward_to_backend() {
+        let mut backend = MockBackend::new();
+        backend.set_max_texture_size(16384);
+        let mut device = GraphicsDevice::new();
+        assert!(device.init(Box::new(backend)));
+
+        assert_eq!(device.get_max_texture_size(), 16384);
+        assert_eq!(device.get_max_texture_units(), 8);
+        assert!(device.supports_npot_textures());
+        assert_eq!(device.get_renderer_string(), "Mock");
+    }
+
+    #[test]
+    fn test_capabilities_fall_back_when_uninitialized() {
+        let mut device = GraphicsDevice::new();
+        assert_eq!(device.get_max_texture_size(), 4096);
+        assert_eq!(device.get_renderer_string(), "unknown");
+
+        device.set_backend(Box::new(MockBackend::new()));
+        assert_eq!(device.get_max_texture_size(), 4096);
+        assert_eq!(device.get_max_texture_units(), 8);
+        assert!(!device.supports_npot_textures());
+        assert_eq!(device.get_renderer_string(), "unknown");
+    }
+
+    #[test]
+    fn test_mock_backend_records_draw_sequence() {
+        let backend = MockBackend::new();
+        let log = backend.get_log();
+        let mut device = GraphicsDevice::new();
+        device.init(Box::new(backend));
+
+        let mut texture = device.create_texture(64, 32);
+        let program = device.create_shader("void main() {}", "void main() {}").unwrap();
+        device.set_viewport(0, 0, 64, 32);
+        device.clear(0x4000);
+        device.draw(PrimitiveType::TRIANGLES, 0, 6);
+        let pixels = device.read_pixels(0, 0, 2, 2);
+        device.delete_texture(&mut texture);
+
+        assert_eq!(pixels.len(), 16);
+        assert_eq!(texture.get_id(), 0);
+        assert_eq!(
+            *log.borrow(),
+            vec![
+                BackendCall::Init,
+                BackendCall::CreateTexture { id: 1, width: 64, height: 32 },
+                BackendCall::CreateShader(program.get_id()),
+                BackendCall::CompileShader(program.get_id()),
+                BackendCall::SetState(RenderState::Viewport(0, 0, 64, 32)),
+                BackendCall::Clear(0x4000),
+                BackendCall::Draw { primitive: PrimitiveType::TRIANGLES, first: 0, count: 6 },
+                BackendCall::ReadPixels { x: 0, y: 0, width: 2, height: 2 },
+                BackendCall::DeleteTexture(1),
+            ]
+        );
+    }
+
+    const MVP_VERTEX_SHADER: &str = "
+        attribute vec4 a_position;
+        uniform mat4 uMVPMatrix;
+        uniform float uTime;
+        void main() { gl_Position = uMVPMatrix * a_position; }
+    ";
+
+    fn device_with_log() -> (GraphicsDevice, crate::backend::mock::CallLog) {
+        let backend = MockBackend::new();
+        let log = backend.get_log();
+        let mut device = GraphicsDevice::new();
+        device.init(Box::new(backend));
+        (device, log)
+    }
+
+    fn uniform_calls(log: &crate::backend::mock::CallLog) -> Vec<BackendCall> {
+        log.borrow()
+            .iter()
+            .filter(|call| matches!(call, BackendCall::SetUniform { .. }))
+            .cloned()
+            .collect()
+    }
+
+    #[test]
+    fn test_declared_builtin_uniforms_are_bound() {
+        let (mut device, log) = device_with_log();
+        let program = device
+            .create_shader(MVP_VERTEX_SHADER, "uniform lowp sampler2D uTexture; void main() {}")
+            .unwrap();
+        assert_eq!(program.get_uniform_location(UNIFORM_MVP_MATRIX), Some(0));
+        assert!(program.has_uniform(UNIFORM_TEXTURE_SAMPLER));
+
+        let mut node = crate::base::Node::new();
+        node.set_position(crate::math::Vec2::new(100.0, 50.0));
+        let view_projection = Mat4::create_orthographic_off_center(0.0, 960.0, 0.0, 640.0, -1024.0, 1024.0);
+        let uniforms = BuiltinUniforms::new(&view_projection, node.get_local_transform(), 2.5);
+        device.draw_with_program(&program, &uniforms, PrimitiveType::TRIANGLES, 0, 6);
+
+        assert_eq!(
+            uniform_calls(&log),
+            vec![
+                BackendCall::SetUniform {
+                    program: program.get_id(),
+                    location: 0,
+                    value: UniformValue::Mat4(view_projection * *node.get_local_transform()),
+                },
+                BackendCall::SetUniform { program: program.get_id(), location: 1, value: UniformValue::Float(2.5) },
+                BackendCall::SetUniform {
+                    program: program.get_id(),
+                    location: 2,
+                    value: UniformValue::Sampler(0),
+                },
+            ]
+        );
+        assert_eq!(log.borrow().last(), Some(&BackendCall::Draw { primitive: PrimitiveType::TRIANGLES, first: 0, count: 6 }));
+    }
+
+    #[test]
+    fn test_programs_without_builtins_are_untouched() {
+        let (mut device, log) = device_with_log();
+        let program = device
+            .create_shader("uniform mat4 u_custom; void main() {}", "void main() {}")
+            .unwrap();
+        let uniforms = BuiltinUniforms::new(&Mat4::IDENTITY, &Mat4::IDENTITY, 1.0);
+        device.draw_with_program(&program, &uniforms, PrimitiveType::TRIANGLES, 0, 3);
+
+        assert!(uniform_calls(&log).is_empty());
+        assert!(!program.has_uniform(UNIFORM_MVP_MATRIX));
+        assert!(program.has_uniform("u_custom"));
+    }
+
+    #[test]
+    fn test_builtin_shaders_expose_builtin_uniforms() {
+        use crate::shader::BuiltInShaders;
+        let (mut device, _log) = device_with_log();
+        let program = device
+            .create_shader(BuiltInShaders::POSITION_TEXTURE_COLOR_VERT, BuiltInShaders::POSITION_TEXTURE_COLOR_FRAG)
+            .unwrap();
+        assert!(program.has_uniform(UNIFORM_MVP_MATRIX));
+        assert!(program.has_uniform(UNIFORM_TEXTURE_SAMPLER));
+        assert!(!program.has_uniform(UNIFORM_TIME));
+    }
+
+    #[test]
+    fn test_texture_sub_image_upload() {
+        let (mut device, log) = device_with_log();
+        let mut texture = Texture2D::new();
+        texture.update(&[], 8, 8, crate::renderer::PixelFormat::RGBA8888);
+        let pixels: Vec<u8> = (0..2 * 3 * 4).collect();
+        texture.update_sub_image(4, 5, 2, 3, &pixels).unwrap();
+        assert!(texture.update_sub_image(7, 0, 2, 1, &[0; 8]).is_err());
+
+        device.upload_texture_updates(&mut texture).unwrap();
+        assert_eq!(
+            log.borrow().last(),
+            Some(&BackendCall::UpdateTexture {
+                id: texture.get_name(),
+                x: 4,
+                y: 5,
+                width: 2,
+                height: 3,
+                format: crate::renderer::PixelFormat::RGBA8888,
+                data: pixels,
+            })
+        );
+        assert!(texture.get_pending_updates().is_empty());
+    }
+
+    #[test]
+    fn test_texture_sub_image_upload_keeps_format() {
+        let (mut device, log) = device_with_log();
+        let mut texture = Texture2D::new();
+        texture.update(&[], 4, 4, crate::renderer::PixelFormat::I8);
+        texture.update_sub_image(0, 0, 2, 2, &[1, 2, 3, 4]).unwrap();
+        device.upload_texture_updates(&mut texture).unwrap();
+        assert!(matches!(
+            log.borrow().last(),
+            Some(BackendCall::UpdateTexture { format: crate::renderer::PixelFormat::I8, .. })
+        ));
+
+        let mut device = GraphicsDevice::new();
+        device.set_backend(Box::new(crate::backend::OpenGLBackend::new()));
+        texture.update(&[], 4, 4, crate::renderer::PixelFormat::AI48);
+        texture.update_sub_image(0, 0, 1, 1, &[0, 0]).unwrap();
+        assert!(device.upload_texture_updates(&mut texture).is_err());
+        assert!(texture.get_pending_updates().is_empty());
+    }
+
+    #[test]
+    fn test_bind_texture_applies_sampler() {
+        let (mut device, log) = device_with_log();
+        let mut texture = Texture2D::new();
+        texture.set_aliased();
+
+        device.bind_texture(&texture);
+        assert_eq!(
+            log.borrow().last(),
+            Some(&BackendCall::BindTexture { id: texture.get_name(), sampler: texture.get_sampler().clone() })
+        );
+    }
+}
@@ -0,0 +1,199 @@
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::rc::Rc;
+use crate::renderer::pipeline::PrimitiveType;
+use crate::renderer::{PixelFormat, Sampler, UniformValue};
+use super::render_backend::{RenderBackend, RenderState};
+
+/// A call made to a `MockBackend`
+#[derive(Debug, Clone, PartialEq)]
+pub enum BackendCall {
+    Init,
+    CreateTexture { id: u32, width: u32, height: u32 },
+    DeleteTexture(u32),
+    UpdateTexture { id: u32, x: u32, y: u32, width: u32, height: u32, format: PixelFormat, data: Vec<u8> },
+    BindTexture { id: u32, sampler: Sampler },
+    CreateShader(u32),
+    CompileShader(u32),
+    DeleteShader(u32),
+    SetUniform { program: u32, location: i32, value: UniformValue },
+    SetState(RenderState),
+    Clear(u32),
+    Draw { primitive: PrimitiveType, first: u32, count: u32 },
+    ReadPixels { x: i32, y: i32, width: u32, height: u32 },
+}
+
+/// Shared log of the calls made to a mock backend
+pub type CallLog = Rc<RefCell<Vec<BackendCall>>>;
+
+/// Headless backend that records every call instead of talking to a GPU.
+///
+/// Useful for tests and CI, where no graphics context is available.
+#[derive(Debug)]
+pub struct MockBackend {
+    log: CallLog,
+    initialized: bool,
+    next_id: u32,
+    max_texture_size: u32,
+    max_texture_units: u32,
+    supports_npot: bool,
+    sources: HashMap<u32, String>,
+    uniform_locations: HashMap<u32, Vec<String>>,
+}
+
+impl MockBackend {
+    pub fn new() -> MockBackend {
+        MockBackend::with_log(Rc::new(RefCell::new(Vec::new())))
+    }
+
+    /// Creates a mock that records into an existing log, so callers can keep
+    /// inspecting it after handing the backend to a device
+    pub fn with_log(log: CallLog) -> MockBackend {
+        MockBackend {
+            log,
+            initialized: false,
+            next_id: 1,
+            max_texture_size: 2048,
+            max_texture_units: 8,
+            supports_npot: true,
+            sources: HashMap::new(),
+            uniform_locations: HashMap::new(),
+        }
+    }
+
+    pub fn get_log(&self) -> CallLog {
+        self.log.clone()
+    }
+
+    pub fn set_max_texture_size(&mut self, size: u32) {
+        self.max_texture_size = size;
+    }
+
+    fn record(&self, call: BackendCall) {
+        self.log.borrow_mut().push(call);
+    }
+
+    fn next_id(&mut self) -> u32 {
+        let id = self.next_id;
+        self.next_id += 1;
+        id
+    }
+}
+
+impl Default for MockBackend {
+    fn default() -> Self {
+        MockBackend::new()
+    }
+}
+
+impl RenderBackend for MockBackend {
+    fn init(&mut self) -> bool {
+        self.initialized = true;
+        self.record(BackendCall::Init);
+        true
+    }
+
+    fn is_initialized(&self) -> bool {
+        self.initialized
+    }
+
+    fn get_max_texture_size(&self) -> u32 {
+        self.max_texture_size
+    }
+
+    fn get_max_texture_units(&self) -> u32 {
+        self.max_texture_units
+    }
+
+    fn supports_npot_textures(&self) -> bool {
+        self.supports_npot
+    }
+
+    fn get_renderer_string(&self) -> String {
+        String::from("Mock")
+    }
+
+    fn create_texture(&mut self, width: u32, height: u32) -> u32 {
+        let id = self.next_id();
+        self.record(BackendCall::CreateTexture { id, width, height });
+        id
+    }
+
+    fn delete_texture(&mut self, texture: u32) {
+        self.record(BackendCall::DeleteTexture(texture));
+    }
+
+    fn update_texture(
+        &mut self,
+        texture: u32,
+        x: u32,
+        y: u32,
+        width: u32,
+        height: u32,
+        format: PixelFormat,
+        data: &[u8],
+    ) -> Result<(), String> {
+        self.record(BackendCall::UpdateTexture { id: texture, x, y, width, height, format, data: data.to_vec() });
+        Ok(())
+    }
+
+    fn bind_texture_with_sampler(&mut self, texture: u32, sampler: &Sampler) {
+        self.record(BackendCall::BindTexture { id: texture, sampler: sampler.clone() });
+    }
+
+    fn create_shader(&mut self, vertex_source: &str, fragment_source: &str) -> u32 {
+        let id = self.next_id();
+        self.sources.insert(id, format!("{}\n{}", vertex_source, fragment_source));
+        self.record(BackendCall::CreateShader(id));
+        id
+    }
+
+    fn compile_shader(&mut self, program: u32) -> bool {
+        self.record(BackendCall::CompileShader(program));
+        true
+    }
+
+    fn delete_shader(&mut self, program: u32) {
+        self.record(BackendCall::DeleteShader(program));
+    }
+
+    /// Hands out locations in query order to the names that appear in the
+    /// program's sources, like a driver numbering its active uniforms
+    fn get_uniform_location(&mut self, program: u32, name: &str) -> i32 {
+        let declared = self.sources.get(&program).map_or(false, |source| {
+            source.split(|c: char| !c.is_alphanumeric() && c != '_').any(|word| word == name)
+        });
+        if !declared {
+            return -1;
+        }
+        let locations = self.uniform_locations.entry(program).or_default();
+        match locations.iter().position(|known| known == name) {
+            Some(location) => location as i32,
+            None => {
+                locations.push(name.to_string());
+                locations.len() as i32 - 1
+            }
+        }
+    }
+
+    fn set_uniform(&mut self, program: u32, location: i32, value: &UniformValue) {
+        self.record(BackendCall::SetUniform { program, location, value: value.clone() });
+    }
+
+    fn set_state(&mut self, state: RenderState) {
+        self.record(BackendCall::SetState(state));
+    }
+
+    fn clear(&mut self, mask: u32) {
+        self.record(BackendCall::Clear(mask));
+    }
+
+    fn draw(&mut self, primitive: PrimitiveType, first: u32, count: u32) {
+        self.record(BackendCall::Draw { primitive, first, count });
+    }
+
+    fn read_pixels(&mut self, x: i32, y: i32, width: u32, height: u32) -> Vec<u8> {
+        self.record(BackendCall::ReadPixels { x, y, width, height });
+        vec![0; (width * height * 4) as usize]
+    }
+}
@@ -1,5 +1,9 @@
 pub mod opengl;
 pub mod device;
+pub mod render_backend;
+pub mod mock;
 
 pub use opengl::OpenGLBackend;
-pub use device::GraphicsDevice;
+pub use device::{GraphicsDevice, BuiltinUniforms, UNIFORM_MVP_MATRIX, UNIFORM_TIME, UNIFORM_TEXTURE_SAMPLER};
+pub use render_backend::{RenderBackend, RenderState};
+pub use mock::{MockBackend, BackendCall};
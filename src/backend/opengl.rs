@@ -1,33 +1,72 @@
-use super::device::{GraphicsDevice, ShaderProgram, BufferObject, TextureObject, FramebufferObject, BufferType, BufferUsage};
+use crate::renderer::pipeline::PrimitiveType;
+use crate::renderer::{PixelFormat, Sampler, TextureFilter, TextureWrap, UniformValue};
+use super::device::{BufferType, BufferUsage};
+use super::render_backend::{RenderBackend, RenderState};
+
+const GL_MAX_TEXTURE_SIZE: u32 = 0x0D33;
+const GL_MAX_TEXTURE_IMAGE_UNITS: u32 = 0x8872;
+const GL_TEXTURE_2D: u32 = 0x0DE1;
+const GL_RGBA: u32 = 0x1908;
+const GL_RED: u32 = 0x1903;
+const GL_RGB: u32 = 0x1907;
+const GL_LUMINANCE: u32 = 0x1909;
+const GL_LUMINANCE_ALPHA: u32 = 0x190A;
+const GL_UNSIGNED_BYTE: u32 = 0x1401;
+const GL_FLOAT: u32 = 0x1406;
+const GL_HALF_FLOAT: u32 = 0x140B;
+const GL_UNSIGNED_SHORT_4_4_4_4: u32 = 0x8033;
+const GL_UNSIGNED_SHORT_5_5_5_1: u32 = 0x8034;
+const GL_UNSIGNED_SHORT_5_6_5: u32 = 0x8363;
+const GL_NEAREST: u32 = 0x2600;
+const GL_LINEAR: u32 = 0x2601;
+const GL_NEAREST_MIPMAP_NEAREST: u32 = 0x2700;
+const GL_LINEAR_MIPMAP_NEAREST: u32 = 0x2701;
+const GL_NEAREST_MIPMAP_LINEAR: u32 = 0x2702;
+const GL_LINEAR_MIPMAP_LINEAR: u32 = 0x2703;
+const GL_REPEAT: u32 = 0x2901;
+const GL_CLAMP_TO_EDGE: u32 = 0x812F;
+const GL_MIRRORED_REPEAT: u32 = 0x8370;
+const GL_DEPTH_TEST: u32 = 0x0B71;
+const GL_BLEND: u32 = 0x0BE2;
 
 #[derive(Debug)]
 pub struct OpenGLBackend {
-    device: GraphicsDevice,
     version: (u32, u32),
     renderer: String,
     vendor: String,
+    initialized: bool,
+    max_texture_size: u32,
+    max_texture_units: u32,
+    supports_npot: bool,
+    next_id: u32,
 }
 
 impl OpenGLBackend {
     pub fn new() -> OpenGLBackend {
         OpenGLBackend {
-            device: GraphicsDevice::new(),
             version: (0, 0),
             renderer: String::new(),
             vendor: String::new(),
+            initialized: false,
+            max_texture_size: 0,
+            max_texture_units: 0,
+            supports_npot: false,
+            next_id: 1,
         }
     }
 
-    pub fn init(&mut self) -> bool {
-        true
-    }
-
-    pub fn get_device(&self) -> &GraphicsDevice {
-        &self.device
+    fn get_integer(&self, pname: u32) -> u32 {
+        match pname {
+            GL_MAX_TEXTURE_SIZE => 4096,
+            GL_MAX_TEXTURE_IMAGE_UNITS => 16,
+            _ => 0,
+        }
     }
 
-    pub fn get_device_mut(&mut self) -> &mut GraphicsDevice {
-        &mut self.device
+    fn gen_object(&mut self) -> u32 {
+        let id = self.next_id;
+        self.next_id += 1;
+        id
     }
 
     pub fn get_version(&self) -> (u32, u32) {
@@ -42,10 +81,6 @@ impl OpenGLBackend {
         &self.vendor
     }
 
-    pub fn create_shader_program(&mut self) -> ShaderProgram {
-        self.device.create_shader()
-    }
-
     pub fn shader_source(&self, program: u32, source: &str) {
     }
 
@@ -82,10 +117,6 @@ impl OpenGLBackend {
     pub fn set_uniform_matrix4(&self, location: i32, transpose: bool, matrix: &[f32]) {
     }
 
-    pub fn create_buffer(&mut self) -> BufferObject {
-        self.device.create_buffer()
-    }
-
     pub fn bind_buffer(&self, buffer_type: BufferType, buffer: u32) {
     }
 
@@ -95,17 +126,13 @@ impl OpenGLBackend {
     pub fn buffer_sub_data(&self, buffer_type: BufferType, offset: usize, data: &[u8]) {
     }
 
-    pub fn create_texture(&mut self) -> TextureObject {
-        self.device.create_texture()
-    }
-
     pub fn bind_texture(&self, target: u32, texture: u32) {
     }
 
     pub fn tex_image_2d(&self, target: u32, level: i32, internal_format: i32, width: u32, height: u32, border: i32, format: u32, data: Option<&[u8]>) {
     }
 
-    pub fn tex_sub_image_2d(&self, target: u32, level: i32, x_offset: i32, y_offset: i32, width: u32, height: u32, format: u32, data: &[u8]) {
+    pub fn tex_sub_image_2d(&self, target: u32, level: i32, x_offset: i32, y_offset: i32, width: u32, height: u32, format: u32, data_type: u32, data: &[u8]) {
     }
 
     pub fn set_texture_params(&self, min_filter: u32, mag_filter: u32, wrap_s: u32, wrap_t: u32) {
@@ -114,10 +141,6 @@ impl OpenGLBackend {
     pub fn generate_mipmap(&self, target: u32) {
     }
 
-    pub fn create_framebuffer(&mut self) -> FramebufferObject {
-        self.device.create_framebuffer()
-    }
-
     pub fn bind_framebuffer(&self, target: u32, framebuffer: u32) {
     }
 
@@ -128,32 +151,16 @@ impl OpenGLBackend {
         0
     }
 
-    pub fn delete_shader_program(&mut self, program: &mut ShaderProgram) {
-        self.device.delete_shader(program);
-    }
-
-    pub fn delete_buffer(&mut self, buffer: &mut BufferObject) {
-        self.device.delete_buffer(buffer);
-    }
-
-    pub fn delete_texture(&mut self, texture: &mut TextureObject) {
-        self.device.delete_texture(texture);
-    }
-
-    pub fn delete_framebuffer(&mut self, framebuffer: &mut FramebufferObject) {
-        self.device.delete_framebuffer(framebuffer);
-    }
-
     pub fn set_viewport(&self, x: i32, y: i32, width: u32, height: u32) {
-        self.device.set_viewport(x, y, width, height);
     }
 
     pub fn set_scissor_test(&self, enabled: bool) {
-        self.device.set_scissor_test(enabled);
     }
 
-    pub fn clear(&self, mask: u32) {
-        self.device.clear(mask);
+    pub fn clear_color(&self, r: f32, g: f32, b: f32, a: f32) {
+    }
+
+    pub fn draw_arrays(&self, mode: u32, first: i32, count: u32) {
     }
 
     pub fn enable(&self, cap: u32) {
@@ -165,6 +172,9 @@ impl OpenGLBackend {
     pub fn blend_func(&self, sfactor: u32, dfactor: u32) {
     }
 
+    pub fn blend_equation(&self, mode: u32) {
+    }
+
     pub fn depth_func(&self, func: u32) {
     }
 
@@ -177,3 +187,180 @@ impl OpenGLBackend {
     pub fn color_mask(&self, red: bool, green: bool, blue: bool, alpha: bool) {
     }
 }
+
+fn primitive_mode(primitive: PrimitiveType) -> u32 {
+    match primitive {
+        PrimitiveType::POINTS => 0x0000,
+        PrimitiveType::LINES => 0x0001,
+        PrimitiveType::LINE_STRIP => 0x0003,
+        PrimitiveType::TRIANGLES => 0x0004,
+        PrimitiveType::TRIANGLE_STRIP => 0x0005,
+        PrimitiveType::TRIANGLE_FAN => 0x0006,
+    }
+}
+
+/// Gets the GL format and data type of pixel rows in `format`
+fn pixel_transfer_format(format: PixelFormat) -> Option<(u32, u32)> {
+    match format {
+        PixelFormat::RGBA8888 | PixelFormat::SRGB8_A8 => Some((GL_RGBA, GL_UNSIGNED_BYTE)),
+        PixelFormat::RGB888 | PixelFormat::SRGB8 => Some((GL_RGB, GL_UNSIGNED_BYTE)),
+        PixelFormat::RGB565 => Some((GL_RGB, GL_UNSIGNED_SHORT_5_6_5)),
+        PixelFormat::RGBA4444 => Some((GL_RGBA, GL_UNSIGNED_SHORT_4_4_4_4)),
+        PixelFormat::RGB5A1 => Some((GL_RGBA, GL_UNSIGNED_SHORT_5_5_5_1)),
+        PixelFormat::AI88 => Some((GL_LUMINANCE_ALPHA, GL_UNSIGNED_BYTE)),
+        PixelFormat::I8 => Some((GL_LUMINANCE, GL_UNSIGNED_BYTE)),
+        PixelFormat::RGBA16F => Some((GL_RGBA, GL_HALF_FLOAT)),
+        PixelFormat::RGB16F => Some((GL_RGB, GL_HALF_FLOAT)),
+        PixelFormat::R16F => Some((GL_RED, GL_HALF_FLOAT)),
+        PixelFormat::RGBA32F => Some((GL_RGBA, GL_FLOAT)),
+        PixelFormat::RGB32F => Some((GL_RGB, GL_FLOAT)),
+        _ => None,
+    }
+}
+
+fn texture_filter(filter: TextureFilter) -> u32 {
+    match filter {
+        TextureFilter::NEAREST => GL_NEAREST,
+        TextureFilter::LINEAR => GL_LINEAR,
+        TextureFilter::NEAREST_MIPMAP_NEAREST => GL_NEAREST_MIPMAP_NEAREST,
+        TextureFilter::LINEAR_MIPMAP_NEAREST => GL_LINEAR_MIPMAP_NEAREST,
+        TextureFilter::NEAREST_MIPMAP_LINEAR => GL_NEAREST_MIPMAP_LINEAR,
+        TextureFilter::LINEAR_MIPMAP_LINEAR => GL_LINEAR_MIPMAP_LINEAR,
+    }
+}
+
+fn texture_wrap(wrap: TextureWrap) -> u32 {
+    match wrap {
+        TextureWrap::REPEAT => GL_REPEAT,
+        TextureWrap::CLAMP_TO_EDGE => GL_CLAMP_TO_EDGE,
+        TextureWrap::MIRRORED_REPEAT => GL_MIRRORED_REPEAT,
+    }
+}
+
+impl RenderBackend for OpenGLBackend {
+    /// Queries the driver for its version and limits
+    fn init(&mut self) -> bool {
+        self.version = (3, 3);
+        self.renderer = String::from("OpenGL");
+        self.max_texture_size = self.get_integer(GL_MAX_TEXTURE_SIZE);
+        self.max_texture_units = self.get_integer(GL_MAX_TEXTURE_IMAGE_UNITS);
+        // NPOT textures are core since OpenGL 2.0 / OpenGL ES 3.0
+        self.supports_npot = self.version.0 >= 2;
+        self.initialized = true;
+        true
+    }
+
+    fn is_initialized(&self) -> bool {
+        self.initialized
+    }
+
+    fn get_max_texture_size(&self) -> u32 {
+        self.max_texture_size
+    }
+
+    fn get_max_texture_units(&self) -> u32 {
+        self.max_texture_units
+    }
+
+    fn supports_npot_textures(&self) -> bool {
+        self.supports_npot
+    }
+
+    fn get_renderer_string(&self) -> String {
+        format!("{} {}.{}", self.renderer, self.version.0, self.version.1)
+    }
+
+    fn create_texture(&mut self, width: u32, height: u32) -> u32 {
+        let texture = self.gen_object();
+        self.bind_texture(GL_TEXTURE_2D, texture);
+        self.tex_image_2d(GL_TEXTURE_2D, 0, GL_RGBA as i32, width, height, 0, GL_RGBA, None);
+        texture
+    }
+
+    fn delete_texture(&mut self, texture: u32) {
+    }
+
+    fn update_texture(
+        &mut self,
+        texture: u32,
+        x: u32,
+        y: u32,
+        width: u32,
+        height: u32,
+        format: PixelFormat,
+        data: &[u8],
+    ) -> Result<(), String> {
+        let (gl_format, data_type) =
+            pixel_transfer_format(format).ok_or_else(|| format!("Pixel format {:?} cannot be uploaded", format))?;
+        self.bind_texture(GL_TEXTURE_2D, texture);
+        self.tex_sub_image_2d(GL_TEXTURE_2D, 0, x as i32, y as i32, width, height, gl_format, data_type, data);
+        Ok(())
+    }
+
+    fn bind_texture_with_sampler(&mut self, texture: u32, sampler: &Sampler) {
+        self.bind_texture(GL_TEXTURE_2D, texture);
+        self.set_texture_params(
+            texture_filter(sampler.get_min_filter()),
+            texture_filter(sampler.get_mag_filter()),
+            texture_wrap(sampler.get_wrap_s()),
+            texture_wrap(sampler.get_wrap_t()),
+        );
+    }
+
+    fn create_shader(&mut self, vertex_source: &str, fragment_source: &str) -> u32 {
+        let program = self.gen_object();
+        self.shader_source(program, vertex_source);
+        self.shader_source(program, fragment_source);
+        program
+    }
+
+    fn compile_shader(&mut self, program: u32) -> bool {
+        OpenGLBackend::compile_shader(self, program) && self.link_program(program)
+    }
+
+    fn delete_shader(&mut self, program: u32) {
+    }
+
+    fn get_uniform_location(&mut self, program: u32, name: &str) -> i32 {
+        self.get_program_uniform_location(program, name)
+    }
+
+    fn set_uniform(&mut self, program: u32, location: i32, value: &UniformValue) {
+        self.use_program(program);
+        match value {
+            UniformValue::Float(v) => self.set_uniform_float(location, *v),
+            UniformValue::Vec2(v) => self.set_uniform_vec2(location, v.x, v.y),
+            UniformValue::Vec3(v) => self.set_uniform_vec3(location, v.x, v.y, v.z),
+            UniformValue::Vec4(v) => self.set_uniform_vec4(location, v.x, v.y, v.z, v.w),
+            UniformValue::Mat4(m) => self.set_uniform_matrix4(location, false, &m.m),
+            UniformValue::Int(v) | UniformValue::Sampler(v) => self.set_uniform_int(location, *v),
+            // Integer vectors are not used by the engine's shaders
+            UniformValue::IVec2(_) | UniformValue::IVec3(_) | UniformValue::IVec4(_) => {}
+        }
+    }
+
+    fn set_state(&mut self, state: RenderState) {
+        match state {
+            RenderState::Viewport(x, y, width, height) => self.set_viewport(x, y, width, height),
+            RenderState::ScissorTest(enabled) => self.set_scissor_test(enabled),
+            RenderState::DepthTest(true) => self.enable(GL_DEPTH_TEST),
+            RenderState::DepthTest(false) => self.disable(GL_DEPTH_TEST),
+            RenderState::Blend(true) => self.enable(GL_BLEND),
+            RenderState::Blend(false) => self.disable(GL_BLEND),
+            RenderState::BlendFunc(src, dst) => self.blend_func(src, dst),
+            RenderState::BlendEquation(mode) => self.blend_equation(mode),
+            RenderState::ClearColor(r, g, b, a) => self.clear_color(r, g, b, a),
+        }
+    }
+
+    fn clear(&mut self, mask: u32) {
+    }
+
+    fn draw(&mut self, primitive: PrimitiveType, first: u32, count: u32) {
+        self.draw_arrays(primitive_mode(primitive), first as i32, count);
+    }
+
+    fn read_pixels(&mut self, x: i32, y: i32, width: u32, height: u32) -> Vec<u8> {
+        vec![0; (width * height * 4) as usize]
+    }
+}
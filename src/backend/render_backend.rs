@@ -0,0 +1,89 @@
+use std::fmt;
+use crate::renderer::pipeline::PrimitiveType;
+use crate::renderer::{PixelFormat, Sampler, UniformValue};
+
+/// Fixed-function state that a backend can be asked to change
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum RenderState {
+    Viewport(i32, i32, u32, u32),
+    ScissorTest(bool),
+    DepthTest(bool),
+    Blend(bool),
+    BlendFunc(u32, u32),
+    BlendEquation(u32),
+    ClearColor(f32, f32, f32, f32),
+}
+
+/// Interface to the graphics API that a `GraphicsDevice` drives.
+///
+/// Capability queries report what the driver supports once the backend has
+/// been initialized. Resource handles are plain ids owned by the backend.
+pub trait RenderBackend: fmt::Debug {
+    /// Initializes the backend, returning false if it is unavailable
+    fn init(&mut self) -> bool;
+
+    /// Checks if the backend has been initialized and can answer queries
+    fn is_initialized(&self) -> bool;
+
+    /// Gets the largest supported texture width or height in pixels
+    fn get_max_texture_size(&self) -> u32;
+
+    /// Gets the number of texture units available to fragment shaders
+    fn get_max_texture_units(&self) -> u32;
+
+    /// Checks if textures with non-power-of-two sizes are supported
+    fn supports_npot_textures(&self) -> bool;
+
+    /// Gets the renderer description reported by the driver
+    fn get_renderer_string(&self) -> String;
+
+    /// Creates an empty RGBA8 texture and returns its id
+    fn create_texture(&mut self, width: u32, height: u32) -> u32;
+
+    /// Deletes a texture
+    fn delete_texture(&mut self, texture: u32);
+
+    /// Replaces the pixels of a region of a texture with tightly packed rows
+    /// in `format`, failing if the backend cannot upload that format
+    fn update_texture(
+        &mut self,
+        texture: u32,
+        x: u32,
+        y: u32,
+        width: u32,
+        height: u32,
+        format: PixelFormat,
+        data: &[u8],
+    ) -> Result<(), String>;
+
+    /// Binds a texture and applies its filtering and wrapping state
+    fn bind_texture_with_sampler(&mut self, texture: u32, sampler: &Sampler);
+
+    /// Creates a shader program from vertex and fragment sources and returns its id
+    fn create_shader(&mut self, vertex_source: &str, fragment_source: &str) -> u32;
+
+    /// Compiles and links a shader program
+    fn compile_shader(&mut self, program: u32) -> bool;
+
+    /// Deletes a shader program
+    fn delete_shader(&mut self, program: u32);
+
+    /// Gets the location of an active uniform of a linked shader program, or
+    /// -1 if the program has no such uniform
+    fn get_uniform_location(&mut self, program: u32, name: &str) -> i32;
+
+    /// Sets the uniform at `location` of a shader program
+    fn set_uniform(&mut self, program: u32, location: i32, value: &UniformValue);
+
+    /// Changes a piece of render state
+    fn set_state(&mut self, state: RenderState);
+
+    /// Clears the buffers selected by `mask`
+    fn clear(&mut self, mask: u32);
+
+    /// Draws `count` vertices starting at `first` from the bound buffers
+    fn draw(&mut self, primitive: PrimitiveType, first: u32, count: u32);
+
+    /// Reads back RGBA8 pixels from the current framebuffer
+    fn read_pixels(&mut self, x: i32, y: i32, width: u32, height: u32) -> Vec<u8>;
+}
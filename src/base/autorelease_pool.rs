@@ -1,46 +1,47 @@
-use std::collections::LinkedList;
+use std::any::Any;
+use std::cell::RefCell;
 use std::rc::Rc;
-use crate::base::{Ref, RefPtr};
+use crate::base::RefPtr;
 
 /// Autorelease pool manages objects that are autoreleased
 ///
-/// In Rust, we use Rc for automatic reference counting, so this is primarily
-/// for compatibility with the C++ API and for objects that need delayed cleanup.
+/// The pool keeps a strong reference to every added object until it is
+/// drained, so objects created during a frame stay alive until the frame ends
+/// even if every other owner has let go of them.
 #[derive(Debug)]
 pub struct AutoreleasePool {
-    managed_objects: LinkedList<RefPtr<Ref>>,
+    managed_objects: Vec<Rc<dyn Any>>,
     name: String,
 }
 
 impl AutoreleasePool {
     /// Creates a new autorelease pool with a default name
     pub fn new() -> AutoreleasePool {
-        AutoreleasePool {
-            managed_objects: LinkedList::new(),
-            name: String::from("autorelease"),
-        }
+        AutoreleasePool::with_name("autorelease")
     }
 
     /// Creates a new autorelease pool with a custom name
     pub fn with_name(name: &str) -> AutoreleasePool {
         AutoreleasePool {
-            managed_objects: LinkedList::new(),
+            managed_objects: Vec::new(),
             name: name.to_string(),
         }
     }
 
-    /// Adds an object to the pool
-    pub fn add_object(&mut self, obj: RefPtr<Ref>) {
-        self.managed_objects.push_back(obj);
+    /// Adds an object to the pool, retaining it until the next drain
+    pub fn add<T: 'static>(&mut self, obj: RefPtr<T>) {
+        let obj: Rc<T> = obj.into();
+        self.managed_objects.push(obj);
     }
 
-    /// Removes an object from the pool
-    pub fn remove_object(&mut self, obj: &RefPtr<Ref>) {
+    /// Releases every object held by the pool
+    pub fn drain(&mut self) {
+        self.managed_objects.clear();
     }
 
-    /// Clears the pool
-    pub fn clear(&mut self) {
-        self.managed_objects.clear();
+    /// Gets the number of objects held by the pool
+    pub fn get_object_count(&self) -> usize {
+        self.managed_objects.len()
     }
 
     /// Gets the name of the pool
@@ -49,54 +50,99 @@ impl AutoreleasePool {
     }
 }
 
-/// Pool manager manages all autorelease pools
-#[derive(Debug)]
-pub struct PoolManager {
-    pools: LinkedList<AutoreleasePool>,
+impl Default for AutoreleasePool {
+    fn default() -> Self {
+        AutoreleasePool::new()
+    }
 }
 
-impl PoolManager {
-    /// Gets the singleton instance
-    pub fn get_instance() -> &'static mut PoolManager {
-        static mut POOL_MANAGER: Option<PoolManager> = None;
-        unsafe {
-            if POOL_MANAGER.is_none() {
-                POOL_MANAGER = Some(PoolManager::new());
-            }
-            POOL_MANAGER.as_mut().unwrap()
-        }
-    }
+thread_local! {
+    static POOL_STACK: RefCell<Vec<AutoreleasePool>> = RefCell::new(vec![AutoreleasePool::new()]);
+}
+
+/// Pushes a new pool; objects autoreleased afterwards go to it
+pub fn push_pool(pool: AutoreleasePool) {
+    POOL_STACK.with(|stack| stack.borrow_mut().push(pool));
+}
 
-    /// Creates a new pool manager
-    pub fn new() -> PoolManager {
-        PoolManager {
-            pools: LinkedList::new(),
+/// Pops the current pool, draining it. The default pool is never popped.
+pub fn pop_pool() {
+    let pool = POOL_STACK.with(|stack| {
+        let mut stack = stack.borrow_mut();
+        if stack.len() > 1 {
+            stack.pop()
+        } else {
+            None
         }
-    }
+    });
+    // Dropped outside the borrow so destructors may autorelease again
+    drop(pool);
+}
 
-    /// Gets the current pool
-    pub fn get_current_pool(&mut self) -> &mut AutoreleasePool {
-        if self.pools.is_empty() {
-            self.pools.push_back(AutoreleasePool::new());
+/// Adds an object to the current pool
+pub fn autorelease<T: 'static>(obj: RefPtr<T>) {
+    POOL_STACK.with(|stack| {
+        if let Some(pool) = stack.borrow_mut().last_mut() {
+            pool.add(obj);
         }
-        self.pools.back_mut().unwrap()
-    }
+    });
+}
+
+/// Drains the current pool, called by the director at the end of each frame
+pub fn drain_current_pool() {
+    let objects = POOL_STACK.with(|stack| {
+        stack
+            .borrow_mut()
+            .last_mut()
+            .map(|pool| std::mem::take(&mut pool.managed_objects))
+    });
+    drop(objects);
+}
+
+/// Gets the number of pools on the current thread's stack
+pub fn get_pool_count() -> usize {
+    POOL_STACK.with(|stack| stack.borrow().len())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
 
-    /// Pushes a new pool onto the stack
-    pub fn push_pool(&mut self, pool: AutoreleasePool) {
-        self.pools.push_back(pool);
+    #[test]
+    fn test_pool_retains_until_drain() {
+        let obj = RefPtr::new(42);
+        let mut pool = AutoreleasePool::new();
+        pool.add(obj.clone());
+
+        assert_eq!(obj.get_reference_count(), 2);
+        pool.drain();
+        assert_eq!(obj.get_reference_count(), 1);
+        assert_eq!(pool.get_object_count(), 0);
     }
 
-    /// Pops the current pool
-    pub fn pop_pool(&mut self) {
-        if let Some(pool) = self.pools.pop_back() {
-        }
+    #[test]
+    fn test_current_pool_stack() {
+        let outer = RefPtr::new(String::from("outer"));
+        let inner = RefPtr::new(String::from("inner"));
+
+        autorelease(outer.clone());
+        push_pool(AutoreleasePool::with_name("inner"));
+        assert_eq!(get_pool_count(), 2);
+        autorelease(inner.clone());
+        assert_eq!(inner.get_reference_count(), 2);
+
+        pop_pool();
+        assert_eq!(get_pool_count(), 1);
+        assert_eq!(inner.get_reference_count(), 1);
+        assert_eq!(outer.get_reference_count(), 2);
+
+        drain_current_pool();
+        assert_eq!(outer.get_reference_count(), 1);
     }
 
-    /// Clears all pools
-    pub fn clear_all_pools(&mut self) {
-        for pool in &mut self.pools {
-            pool.clear();
-        }
+    #[test]
+    fn test_default_pool_is_never_popped() {
+        pop_pool();
+        assert_eq!(get_pool_count(), 1);
     }
 }
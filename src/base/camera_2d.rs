@@ -0,0 +1,245 @@
+use std::rc::Weak;
+use std::cell::RefCell;
+use crate::base::{Node, NodeRef, Rect, Size};
+use crate::math::{Mat4, Vec2, Vec3};
+
+/// Camera2D pans, zooms and rotates the 2D scene without moving its nodes.
+///
+/// `position` is the world point shown at the center of the view. The view
+/// matrix is combined with the director's 2D projection each frame; this is
+/// separate from the 3D `Camera`.
+#[derive(Debug)]
+pub struct Camera2D {
+    position: Vec2,
+    zoom: f32,
+    rotation: f32,
+    view_size: Size,
+    follow_target: Option<Weak<RefCell<Node>>>,
+    follow_lerp: f32,
+}
+
+impl Camera2D {
+    /// Creates a camera over a view of the given size, centered so that its
+    /// view matrix starts as the identity
+    pub fn new(view_size: Size) -> Camera2D {
+        Camera2D {
+            position: Vec2::new(view_size.width / 2.0, view_size.height / 2.0),
+            zoom: 1.0,
+            rotation: 0.0,
+            view_size,
+            follow_target: None,
+            follow_lerp: 1.0,
+        }
+    }
+
+    /// Gets the world point at the center of the view
+    pub fn get_position(&self) -> Vec2 {
+        self.position
+    }
+
+    /// Sets the world point at the center of the view
+    pub fn set_position(&mut self, position: Vec2) {
+        self.position = position;
+    }
+
+    /// Gets the zoom factor
+    pub fn get_zoom(&self) -> f32 {
+        self.zoom
+    }
+
+    /// Sets the zoom factor; values above 1 magnify. Non-positive zooms are
+    /// ignored.
+    pub fn set_zoom(&mut self, zoom: f32) {
+        if zoom > 0.0 {
+            self.zoom = zoom;
+        }
+    }
+
+    /// Gets the rotation in degrees
+    pub fn get_rotation(&self) -> f32 {
+        self.rotation
+    }
+
+    /// Sets the rotation in degrees. Like node rotation it is clockwise, so
+    /// the scene appears to turn counterclockwise.
+    pub fn set_rotation(&mut self, rotation: f32) {
+        self.rotation = rotation;
+    }
+
+    /// Gets the size of the view
+    pub fn get_view_size(&self) -> Size {
+        self.view_size
+    }
+
+    /// Sets the size of the view without moving the camera
+    pub fn set_view_size(&mut self, view_size: Size) {
+        self.view_size = view_size;
+    }
+
+    /// Follows a node, moving `lerp` of the remaining distance toward its
+    /// position on every `update`. A lerp of 1 snaps to the node.
+    pub fn follow(&mut self, node: &NodeRef, lerp: f32) {
+        self.follow_target = Some(std::rc::Rc::downgrade(node));
+        self.follow_lerp = lerp.clamp(0.0, 1.0);
+    }
+
+    /// Stops following a node
+    pub fn stop_follow(&mut self) {
+        self.follow_target = None;
+    }
+
+    /// Checks if the camera follows a live node
+    pub fn is_following(&self) -> bool {
+        self.follow_target.as_ref().map_or(false, |target| target.strong_count() > 0)
+    }
+
+    /// Moves toward the followed node. The target is dropped once the node
+    /// no longer exists.
+    pub fn update(&mut self) {
+        let target = match self.follow_target.as_ref().map(|target| target.upgrade()) {
+            Some(Some(target)) => target,
+            Some(None) => {
+                self.follow_target = None;
+                return;
+            }
+            None => return,
+        };
+        let target_position = *target.borrow().get_position();
+        self.position = self.position + (target_position - self.position) * self.follow_lerp;
+    }
+
+    /// Gets the view matrix, mapping world coordinates to view coordinates
+    pub fn get_view_matrix(&self) -> Mat4 {
+        let (sin, cos) = self.rotation.to_radians().sin_cos();
+        let mut view = Mat4::IDENTITY;
+        view.m[0] = cos * self.zoom;
+        view.m[1] = sin * self.zoom;
+        view.m[4] = -sin * self.zoom;
+        view.m[5] = cos * self.zoom;
+        view.m[12] = self.view_size.width / 2.0 - (view.m[0] * self.position.x + view.m[4] * self.position.y);
+        view.m[13] = self.view_size.height / 2.0 - (view.m[1] * self.position.x + view.m[5] * self.position.y);
+        view
+    }
+
+    /// Converts a world point into view coordinates
+    pub fn world_to_view(&self, point: Vec2) -> Vec2 {
+        let point = self.get_view_matrix().transform_point(&Vec3::new(point.x, point.y, 0.0));
+        Vec2::new(point.x, point.y)
+    }
+
+    /// Converts a point in view coordinates back into the world
+    pub fn view_to_world(&self, point: Vec2) -> Vec2 {
+        let (sin, cos) = self.rotation.to_radians().sin_cos();
+        let center = Vec2::new(self.view_size.width / 2.0, self.view_size.height / 2.0);
+        let offset = (point - center) / self.zoom;
+        self.position + Vec2::new(cos * offset.x + sin * offset.y, cos * offset.y - sin * offset.x)
+    }
+
+    /// Gets the world rectangle covering the whole view. When rotated this
+    /// is the bounding rectangle of the view, so it covers a little more.
+    pub fn get_visible_rect(&self) -> Rect {
+        let (width, height) = (self.view_size.width, self.view_size.height);
+        let corners = [
+            Vec2::ZERO,
+            Vec2::new(width, 0.0),
+            Vec2::new(0.0, height),
+            Vec2::new(width, height),
+        ]
+        .map(|corner| self.view_to_world(corner));
+        bounding_rect(&corners)
+    }
+}
+
+/// Gets the smallest rectangle containing all points
+pub(crate) fn bounding_rect(points: &[Vec2]) -> Rect {
+    let (mut min, mut max) = (points[0], points[0]);
+    for point in &points[1..] {
+        min = Vec2::new(min.x.min(point.x), min.y.min(point.y));
+        max = Vec2::new(max.x.max(point.x), max.y.max(point.y));
+    }
+    Rect::new(min.x, min.y, max.x - min.x, max.y - min.y)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::rc::Rc;
+
+    fn assert_near(a: Vec2, b: Vec2) {
+        assert!((a - b).length() < 1e-4, "{:?} != {:?}", a, b);
+    }
+
+    #[test]
+    fn test_default_view_is_identity() {
+        let camera = Camera2D::new(Size::new(960.0, 640.0));
+        assert_eq!(camera.get_view_matrix(), Mat4::IDENTITY);
+    }
+
+    #[test]
+    fn test_zoom_scales_around_center() {
+        let mut camera = Camera2D::new(Size::new(960.0, 640.0));
+        camera.set_zoom(2.0);
+        let view = camera.get_view_matrix();
+        assert_eq!(view.m[0], 2.0);
+        assert_eq!(view.m[5], 2.0);
+        assert_near(camera.world_to_view(Vec2::new(480.0, 320.0)), Vec2::new(480.0, 320.0));
+        assert_near(camera.world_to_view(Vec2::new(490.0, 330.0)), Vec2::new(500.0, 340.0));
+
+        camera.set_zoom(0.0);
+        assert_eq!(camera.get_zoom(), 2.0);
+    }
+
+    #[test]
+    fn test_panning_shifts_view() {
+        let mut camera = Camera2D::new(Size::new(960.0, 640.0));
+        camera.set_position(Vec2::new(580.0, 300.0));
+        let view = camera.get_view_matrix();
+        assert_eq!((view.m[12], view.m[13]), (-100.0, 20.0));
+        assert_near(camera.world_to_view(Vec2::new(580.0, 300.0)), Vec2::new(480.0, 320.0));
+
+        camera.set_rotation(90.0);
+        assert_near(camera.world_to_view(Vec2::new(590.0, 300.0)), Vec2::new(480.0, 330.0));
+    }
+
+    #[test]
+    fn test_visible_rect_follows_camera() {
+        let mut camera = Camera2D::new(Size::new(960.0, 640.0));
+        assert_eq!(camera.get_visible_rect(), Rect::new(0.0, 0.0, 960.0, 640.0));
+
+        camera.set_position(Vec2::new(1000.0, 320.0));
+        camera.set_zoom(2.0);
+        assert_eq!(camera.get_visible_rect(), Rect::new(760.0, 160.0, 480.0, 320.0));
+        assert_near(camera.view_to_world(camera.world_to_view(Vec2::new(900.0, 250.0))), Vec2::new(900.0, 250.0));
+
+        camera.set_rotation(30.0);
+        assert_near(camera.view_to_world(camera.world_to_view(Vec2::new(900.0, 250.0))), Vec2::new(900.0, 250.0));
+    }
+
+    #[test]
+    fn test_follow_moves_partway() {
+        let mut camera = Camera2D::new(Size::new(200.0, 200.0));
+        let node = Node::create();
+        node.borrow_mut().set_position(Vec2::new(200.0, 100.0));
+        camera.follow(&node, 0.5);
+
+        camera.update();
+        assert_near(camera.get_position(), Vec2::new(150.0, 100.0));
+        camera.update();
+        assert_near(camera.get_position(), Vec2::new(175.0, 100.0));
+
+        drop(node);
+        camera.update();
+        assert!(!camera.is_following());
+        assert_near(camera.get_position(), Vec2::new(175.0, 100.0));
+    }
+
+    #[test]
+    fn test_follow_snaps_with_full_lerp() {
+        let mut camera = Camera2D::new(Size::new(200.0, 200.0));
+        let node: NodeRef = Rc::new(RefCell::new(Node::new()));
+        node.borrow_mut().set_position(Vec2::new(-40.0, 60.0));
+        camera.follow(&node, 1.0);
+        camera.update();
+        assert_eq!(camera.get_position(), Vec2::new(-40.0, 60.0));
+    }
+}
@@ -1,8 +1,154 @@
-use std::cell::RefCell;
+use std::cell::{Cell, RefCell};
+use std::fmt;
 use std::rc::Rc;
-use crate::base::{Size, Ref, RefPtr};
+use crate::base::{Size, Rect, Ref, RefPtr};
+use crate::base::types::Color3B;
+use crate::base::autorelease_pool;
 use crate::base::scheduler::Scheduler;
+use crate::base::camera_2d::Camera2D;
 use crate::base::event::{EventDispatcher, EventType};
+use crate::sprite::Sprite;
+use crate::renderer::QuadRenderer;
+use crate::action::{Action, ActionManager, CallFunc, FiniteTimeAction, Sequence};
+
+/// Time source driving the director's main loop
+pub trait Clock: fmt::Debug {
+    /// Gets the current time in seconds from an arbitrary fixed origin
+    fn now_seconds(&self) -> f64;
+}
+
+/// Wall clock measuring seconds since its creation
+#[derive(Debug)]
+pub struct SystemClock {
+    start: std::time::Instant,
+}
+
+impl SystemClock {
+    /// Creates a clock starting at zero now
+    pub fn new() -> SystemClock {
+        SystemClock {
+            start: std::time::Instant::now(),
+        }
+    }
+}
+
+impl Clock for SystemClock {
+    fn now_seconds(&self) -> f64 {
+        self.start.elapsed().as_secs_f64()
+    }
+}
+
+/// Corner of the window that world coordinates are measured from
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OriginMode {
+    /// Cocos default: origin at the bottom-left, y grows upward
+    BottomLeft,
+    /// UI style: origin at the top-left, y grows downward
+    TopLeft,
+}
+
+/// How a design resolution is fitted into the window
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ResolutionPolicy {
+    /// Stretches the design to fill the window, ignoring its aspect ratio
+    ExactFit,
+    /// Shows the whole design, letterboxing the spare window area
+    ShowAll,
+    /// Fills the window, cropping the design along one axis
+    NoBorder,
+    /// Keeps the design width and fits the height to the window
+    FixedWidth,
+    /// Keeps the design height and fits the width to the window
+    FixedHeight,
+}
+
+/// Near and far planes of the 2D projection
+const PROJECTION_2D_Z_NEAR: f32 = -1024.0;
+const PROJECTION_2D_Z_FAR: f32 = 1024.0;
+
+/// Number of frames kept for the rolling frame statistics
+pub const FRAME_STATS_WINDOW: usize = 60;
+
+/// Rolling frame-time statistics over the last `FRAME_STATS_WINDOW` frames
+#[derive(Debug, Clone)]
+pub struct FrameStats {
+    samples: [f32; FRAME_STATS_WINDOW],
+    next: usize,
+    count: usize,
+    last: f32,
+}
+
+impl FrameStats {
+    /// Creates empty statistics
+    pub fn new() -> FrameStats {
+        FrameStats {
+            samples: [0.0; FRAME_STATS_WINDOW],
+            next: 0,
+            count: 0,
+            last: 0.0,
+        }
+    }
+
+    /// Records the duration of a frame in seconds, overwriting the oldest
+    /// sample once the window is full
+    pub fn record(&mut self, delta: f32) {
+        self.samples[self.next] = delta;
+        self.next = (self.next + 1) % FRAME_STATS_WINDOW;
+        self.count = (self.count + 1).min(FRAME_STATS_WINDOW);
+        self.last = delta;
+    }
+
+    /// Clears every recorded frame
+    pub fn reset(&mut self) {
+        *self = FrameStats::new();
+    }
+
+    fn window(&self) -> &[f32] {
+        &self.samples[..self.count]
+    }
+
+    /// Gets the frame rate averaged over the window
+    pub fn get_frame_rate(&self) -> f32 {
+        let total: f32 = self.window().iter().sum();
+        if total > 0.0 {
+            self.count as f32 / total
+        } else {
+            0.0
+        }
+    }
+
+    /// Gets the duration of the last frame in milliseconds
+    pub fn get_frame_time_ms(&self) -> f32 {
+        self.last * 1000.0
+    }
+
+    /// Gets the shortest frame in the window in milliseconds
+    pub fn get_min_frame_time_ms(&self) -> f32 {
+        self.window().iter().copied().reduce(f32::min).unwrap_or(0.0) * 1000.0
+    }
+
+    /// Gets the longest frame in the window in milliseconds
+    pub fn get_max_frame_time_ms(&self) -> f32 {
+        self.window().iter().copied().reduce(f32::max).unwrap_or(0.0) * 1000.0
+    }
+}
+
+/// State of `Director::pause_all_except` while it is active
+#[derive(Debug, Default)]
+struct ModalPause {
+    /// Handle addresses of the nodes kept running
+    excluded: Vec<usize>,
+    /// Action targets paused by the modal pause
+    action_targets: Vec<NodeRef>,
+    /// Update targets paused by the modal pause
+    update_targets: Vec<usize>,
+}
+
+thread_local! {
+    /// Kept outside the director so the action manager and scheduler can
+    /// consult it while the director is borrowed, e.g. from inside an action
+    static MODAL_PAUSE: RefCell<Option<ModalPause>> = RefCell::new(None);
+}
 
 /// Director is the main object that runs the scene.
 ///
@@ -12,25 +158,34 @@ use crate::base::event::{EventDispatcher, EventType};
 pub struct Director {
     running_scene: RefPtr<Scene>,
     next_scene: Option<RefPtr<Scene>>,
-    scheduler: RefPtr<Scheduler>,
+    scheduler: Rc<RefCell<Scheduler>>,
     event_dispatcher: RefPtr<EventDispatcher>,
     delta_time: f32,
     total_time: f32,
-    last_update_time: std::time::Instant,
+    clock: Box<dyn Clock>,
+    last_update_time: f64,
     is_paused: bool,
+    paused_in_background: bool,
     is_cleanup: bool,
+    frame_stats: FrameStats,
+    display_stats: bool,
+    content_scale_factor: f32,
+    origin_mode: OriginMode,
+    camera_2d: Camera2D,
+    frame_size: Size,
+    design_resolution: Option<(Size, ResolutionPolicy)>,
 }
 
 impl Director {
-    /// Gets the singleton instance of Director
-    pub fn get_instance() -> RefPtr<Director> {
-        static mut DIRECTOR: Option<RefCell<RefPtr<Director>>> = None;
-        unsafe {
-            if DIRECTOR.is_none() {
-                DIRECTOR = Some(RefCell::new(RefPtr::new(Director::new())));
-            }
-            DIRECTOR.as_ref().unwrap().borrow().clone()
+    /// Gets the director of the current thread.
+    ///
+    /// Every call returns a handle to the same director; borrow it mutably
+    /// only for the duration of a call.
+    pub fn get_instance() -> Rc<RefCell<Director>> {
+        thread_local! {
+            static DIRECTOR: Rc<RefCell<Director>> = Rc::new(RefCell::new(Director::new()));
         }
+        DIRECTOR.with(Rc::clone)
     }
 
     /// Creates a new Director
@@ -38,13 +193,22 @@ impl Director {
         Director {
             running_scene: RefPtr::new(Scene::new()),
             next_scene: None,
-            scheduler: RefPtr::new(Scheduler::new()),
+            scheduler: Scheduler::get_instance(),
             event_dispatcher: RefPtr::new(EventDispatcher::new()),
             delta_time: 0.0,
             total_time: 0.0,
-            last_update_time: std::time::Instant::now(),
+            clock: Box::new(SystemClock::new()),
+            last_update_time: 0.0,
             is_paused: false,
+            paused_in_background: false,
             is_cleanup: false,
+            frame_stats: FrameStats::new(),
+            display_stats: false,
+            content_scale_factor: 1.0,
+            origin_mode: OriginMode::BottomLeft,
+            camera_2d: Camera2D::new(Size::new(960.0, 640.0)),
+            frame_size: Size::new(960.0, 640.0),
+            design_resolution: None,
         }
     }
 
@@ -53,8 +217,8 @@ impl Director {
         &self.running_scene
     }
 
-    /// Gets the scheduler
-    pub fn get_scheduler(&self) -> &RefPtr<Scheduler> {
+    /// Gets the scheduler, shared with every director of the thread
+    pub fn get_scheduler(&self) -> &Rc<RefCell<Scheduler>> {
         &self.scheduler
     }
 
@@ -73,6 +237,31 @@ impl Director {
         self.total_time
     }
 
+    /// Gets the frame rate averaged over the recent frames
+    pub fn get_frame_rate(&self) -> f32 {
+        self.frame_stats.get_frame_rate()
+    }
+
+    /// Gets the duration of the last frame in milliseconds
+    pub fn get_frame_time_ms(&self) -> f32 {
+        self.frame_stats.get_frame_time_ms()
+    }
+
+    /// Gets the rolling frame statistics
+    pub fn get_frame_stats(&self) -> &FrameStats {
+        &self.frame_stats
+    }
+
+    /// Sets whether the frame statistics should be displayed
+    pub fn set_display_stats(&mut self, display_stats: bool) {
+        self.display_stats = display_stats;
+    }
+
+    /// Checks if the frame statistics are displayed
+    pub fn is_display_stats(&self) -> bool {
+        self.display_stats
+    }
+
     /// Checks if the director is paused
     pub fn is_paused(&self) -> bool {
         self.is_paused
@@ -97,19 +286,37 @@ impl Director {
         self.running_scene = scene;
     }
 
+    /// Replaces the time source; the next frame is measured from its
+    /// current time
+    pub fn set_clock(&mut self, clock: Box<dyn Clock>) {
+        self.last_update_time = clock.now_seconds();
+        self.clock = clock;
+    }
+
     /// Main loop function
     pub fn main_loop(&mut self) {
-        let now = std::time::Instant::now();
-        let elapsed = now.duration_since(self.last_update_time);
+        let now = self.clock.now_seconds();
+        let elapsed = (now - self.last_update_time).max(0.0);
         self.last_update_time = now;
 
         // Calculate delta time in seconds
-        self.delta_time = elapsed.as_secs_f32();
+        self.main_loop_with_delta(elapsed as f32);
+    }
+
+    /// Runs one frame of the main loop with the given delta time
+    pub fn main_loop_with_delta(&mut self, delta_time: f32) {
+        self.delta_time = delta_time;
         self.total_time += self.delta_time;
+        self.frame_stats.record(self.delta_time);
 
         if !self.is_paused {
-            // Update the scheduler
-            self.scheduler.update(self.delta_time);
+            // Update the scheduler, which also runs the nodes' scheduled updates
+            Scheduler::update_instance(self.delta_time);
+            let scaled_delta = self.delta_time * self.scheduler.borrow().get_time_scale();
+
+            // Advance the actions of this thread's nodes
+            ActionManager::update_instance(scaled_delta);
+            self.camera_2d.update();
         }
 
         // Process scene transitions
@@ -118,6 +325,9 @@ impl Director {
         }
 
         // Render the current scene
+
+        // Release objects autoreleased during this frame
+        autorelease_pool::drain_current_pool();
     }
 
     /// Pauses the game
@@ -128,7 +338,85 @@ impl Director {
     /// Resumes the game
     pub fn resume(&mut self) {
         self.is_paused = false;
-        self.last_update_time = std::time::Instant::now();
+        self.last_update_time = self.clock.now_seconds();
+    }
+
+    /// Pauses the game when the application leaves the foreground,
+    /// remembering whether it was running
+    pub(crate) fn pause_for_background(&mut self) {
+        self.paused_in_background = !self.is_paused;
+        self.pause();
+    }
+
+    /// Resumes the game paused by `pause_for_background`; a game the user
+    /// paused beforehand stays paused
+    pub(crate) fn resume_from_background(&mut self) {
+        if self.paused_in_background {
+            self.paused_in_background = false;
+            self.resume();
+        }
+    }
+
+    /// Pauses the actions and per-frame scheduler updates of every node
+    /// except `node` and its descendants, e.g. while a modal dialog is open.
+    ///
+    /// Nodes are matched through their `NodeRef` handle; scheduler updates
+    /// are matched by the handle address, as `Rc::as_ptr(handle) as usize`.
+    /// Nodes outside the subtree that start actions or updates while the
+    /// pause is active start paused. `resume_all` undoes the pause.
+    pub fn pause_all_except(&mut self, node: &NodeRef) {
+        let mut excluded: Vec<NodeRef> = Vec::new();
+        let mut stack = vec![Rc::clone(node)];
+        while let Some(handle) = stack.pop() {
+            stack.extend(handle.borrow().get_children().iter().cloned());
+            excluded.push(handle);
+        }
+        let excluded_updates: Vec<usize> = excluded.iter().map(|handle| Rc::as_ptr(handle) as usize).collect();
+
+        let action_targets = ActionManager::get_instance()
+            .borrow_mut()
+            .pause_all_running_actions_except(&excluded);
+        let update_targets = self.scheduler.borrow_mut().pause_all_updates_except(&excluded_updates);
+        MODAL_PAUSE.with(|modal| {
+            let mut modal = modal.borrow_mut();
+            let modal = modal.get_or_insert_with(ModalPause::default);
+            modal.excluded = excluded_updates;
+            modal.action_targets.extend(action_targets);
+            modal.update_targets.extend(update_targets);
+        });
+    }
+
+    /// Resumes the actions and updates paused by `pause_all_except`
+    pub fn resume_all(&mut self) {
+        if let Some(modal) = MODAL_PAUSE.with(|modal| modal.borrow_mut().take()) {
+            ActionManager::get_instance().borrow_mut().resume_targets(&modal.action_targets);
+            self.scheduler.borrow_mut().resume_updates(&modal.update_targets);
+        }
+    }
+
+    /// Checks if a target getting its first actions must start paused
+    /// because `pause_all_except` is active and the target is outside the
+    /// kept subtree. Held targets are resumed by `resume_all`.
+    pub(crate) fn hold_new_action_target(target: &NodeRef) -> bool {
+        MODAL_PAUSE.with(|modal| match modal.borrow_mut().as_mut() {
+            Some(modal) if !modal.excluded.contains(&(Rc::as_ptr(target) as usize)) => {
+                modal.action_targets.push(Rc::clone(target));
+                true
+            }
+            _ => false,
+        })
+    }
+
+    /// Checks if a target scheduling its update must start paused, as
+    /// `hold_new_action_target` does for actions
+    pub(crate) fn hold_new_update_target(target: usize) -> bool {
+        MODAL_PAUSE.with(|modal| match modal.borrow_mut().as_mut() {
+            Some(modal) if !modal.excluded.contains(&target) => {
+                modal.update_targets.push(target);
+                true
+            }
+            _ => false,
+        })
     }
 
     /// Stops the game
@@ -136,19 +424,225 @@ impl Director {
         self.is_cleanup = true;
     }
 
-    /// Gets the frame size
+    /// Gets the frame size in pixels
+    pub fn get_frame_size(&self) -> Size {
+        self.frame_size
+    }
+
+    /// Sets the frame size in pixels, e.g. after the window was resized.
+    /// The 2D projection and camera follow the new size.
+    pub fn set_frame_size(&mut self, frame_size: Size) {
+        self.frame_size = frame_size;
+        self.resize_camera_2d();
+    }
+
+    /// Fits the 2D camera to the current win size, moving it with the view
+    /// center so an unmoved camera stays centered on the new size
+    fn resize_camera_2d(&mut self) {
+        let old_size = self.camera_2d.get_view_size();
+        let new_size = self.get_win_size();
+        let offset = crate::math::Vec2::new(
+            (new_size.width - old_size.width) / 2.0,
+            (new_size.height - old_size.height) / 2.0,
+        );
+        let position = self.camera_2d.get_position() + offset;
+        self.camera_2d.set_view_size(new_size);
+        self.camera_2d.set_position(position);
+    }
+
+    /// Sets the resolution the game is designed for. World coordinates span
+    /// the design size, fitted into the window by `policy`; `FixedWidth`
+    /// and `FixedHeight` grow or shrink the other axis to match the window.
+    /// Non-positive sizes are ignored.
+    pub fn set_design_resolution_size(&mut self, width: f32, height: f32, policy: ResolutionPolicy) {
+        if width > 0.0 && height > 0.0 {
+            self.design_resolution = Some((Size::new(width, height), policy));
+            self.resize_camera_2d();
+        }
+    }
+
+    /// Gets the design resolution and policy, if one was set
+    pub fn get_design_resolution(&self) -> Option<(Size, ResolutionPolicy)> {
+        self.design_resolution
+    }
+
+    /// Fits the design resolution into the frame, returning the adjusted
+    /// design size and the pixels per point along each axis
+    fn fit_design_resolution(&self) -> Option<(Size, f32, f32)> {
+        let (design, policy) = self.design_resolution?;
+        let frame = self.frame_size;
+        let scale_x = frame.width / design.width;
+        let scale_y = frame.height / design.height;
+        Some(match policy {
+            ResolutionPolicy::ExactFit => (design, scale_x, scale_y),
+            ResolutionPolicy::ShowAll => {
+                let scale = scale_x.min(scale_y);
+                (design, scale, scale)
+            }
+            ResolutionPolicy::NoBorder => {
+                let scale = scale_x.max(scale_y);
+                (design, scale, scale)
+            }
+            ResolutionPolicy::FixedWidth => (Size::new(design.width, frame.height / scale_x), scale_x, scale_x),
+            ResolutionPolicy::FixedHeight => (Size::new(frame.width / scale_y, design.height), scale_y, scale_y),
+        })
+    }
+
+    /// Gets the pixels per point along each axis and the offset in pixels
+    /// of the viewport inside the frame
+    fn get_screen_mapping(&self) -> (crate::math::Vec2, crate::math::Vec2) {
+        let viewport = self.get_viewport_rect();
+        match self.fit_design_resolution() {
+            Some((_, scale_x, scale_y)) => (crate::math::Vec2::new(scale_x, scale_y), viewport.origin),
+            None => (crate::math::Vec2::new(self.content_scale_factor, self.content_scale_factor), viewport.origin),
+        }
+    }
+
+    /// Gets the area of the frame, in pixels, that the design resolution
+    /// is drawn into. `ShowAll` leaves bars around it; `NoBorder` extends
+    /// past the frame.
+    pub fn get_viewport_rect(&self) -> Rect {
+        let frame = self.frame_size;
+        match self.fit_design_resolution() {
+            Some((design, scale_x, scale_y)) => {
+                let width = design.width * scale_x;
+                let height = design.height * scale_y;
+                Rect::new((frame.width - width) / 2.0, (frame.height - height) / 2.0, width, height)
+            }
+            None => Rect::new(0.0, 0.0, frame.width, frame.height),
+        }
+    }
+
+    /// Gets the window size in points, which is the design size when a
+    /// design resolution is set
     pub fn get_win_size(&self) -> Size {
-        Size::new(960.0, 640.0)
+        if let Some((design, _, _)) = self.fit_design_resolution() {
+            return design;
+        }
+        let frame_size = self.get_frame_size();
+        Size::new(
+            frame_size.width / self.content_scale_factor,
+            frame_size.height / self.content_scale_factor,
+        )
     }
 
-    /// Gets the visible size
+    /// Gets the size of the part of the world shown in the window, smaller
+    /// than the design size when `NoBorder` crops it
     pub fn get_visible_size(&self) -> Size {
-        self.get_win_size()
+        match self.fit_design_resolution() {
+            Some((design, scale_x, scale_y)) => Size::new(
+                design.width.min(self.frame_size.width / scale_x),
+                design.height.min(self.frame_size.height / scale_y),
+            ),
+            None => self.get_win_size(),
+        }
     }
 
-    /// Gets the visible origin
+    /// Gets the world position of the visible area's bottom-left corner
     pub fn get_visible_origin(&self) -> crate::math::Vec2 {
-        crate::math::Vec2::ZERO
+        let win_size = self.get_win_size();
+        let visible_size = self.get_visible_size();
+        let x = (win_size.width - visible_size.width) / 2.0;
+        let y = (win_size.height - visible_size.height) / 2.0;
+        match self.origin_mode {
+            OriginMode::BottomLeft => crate::math::Vec2::new(x, y),
+            OriginMode::TopLeft => crate::math::Vec2::new(x, y + visible_size.height),
+        }
+    }
+
+    /// Gets the number of pixels per point
+    pub fn get_content_scale_factor(&self) -> f32 {
+        self.content_scale_factor
+    }
+
+    /// Sets the number of pixels per point; non-positive factors are ignored
+    pub fn set_content_scale_factor(&mut self, scale_factor: f32) {
+        if scale_factor > 0.0 {
+            self.content_scale_factor = scale_factor;
+            self.resize_camera_2d();
+        }
+    }
+
+    /// Gets the origin mode
+    pub fn get_origin_mode(&self) -> OriginMode {
+        self.origin_mode
+    }
+
+    /// Sets the corner world coordinates are measured from
+    pub fn set_origin_mode(&mut self, origin_mode: OriginMode) {
+        self.origin_mode = origin_mode;
+    }
+
+    /// Gets the orthographic projection used for 2D rendering
+    pub fn get_projection_2d(&self) -> crate::math::Mat4 {
+        let size = self.get_win_size();
+        let (bottom, top) = match self.origin_mode {
+            OriginMode::BottomLeft => (0.0, size.height),
+            OriginMode::TopLeft => (size.height, 0.0),
+        };
+        crate::math::Mat4::create_orthographic_off_center(
+            0.0,
+            size.width,
+            bottom,
+            top,
+            PROJECTION_2D_Z_NEAR,
+            PROJECTION_2D_Z_FAR,
+        )
+    }
+
+    /// Gets the 2D camera of the scene
+    pub fn get_camera_2d(&self) -> &Camera2D {
+        &self.camera_2d
+    }
+
+    /// Gets the 2D camera of the scene mutably
+    pub fn get_camera_2d_mut(&mut self) -> &mut Camera2D {
+        &mut self.camera_2d
+    }
+
+    /// Gets the world rectangle visible through the 2D camera, used to cull
+    /// sprites in `Scene::render`
+    pub fn get_visible_rect(&self) -> Rect {
+        self.camera_2d.get_visible_rect()
+    }
+
+    /// Gets the 2D projection combined with the 2D camera's view, used as
+    /// the view-projection of each frame by `draw_scene`
+    pub fn get_view_projection_2d(&self) -> crate::math::Mat4 {
+        self.get_projection_2d() * self.camera_2d.get_view_matrix()
+    }
+
+    /// Draws the running scene through the 2D camera: sets the renderer's
+    /// view-projection and culls against the camera's visible rectangle
+    pub fn draw_scene(&self, renderer: &mut dyn QuadRenderer) {
+        renderer.set_view_projection_matrix(self.get_view_projection_2d());
+        self.running_scene.render(renderer, &self.get_visible_rect());
+    }
+
+    /// Converts a screen position in pixels, measured from the top-left of
+    /// the window as touches report it, into world coordinates
+    pub fn convert_to_gl(&self, screen: crate::math::Vec2) -> crate::math::Vec2 {
+        let (scale, offset) = self.get_screen_mapping();
+        let point = crate::math::Vec2::new((screen.x - offset.x) / scale.x, (screen.y - offset.y) / scale.y);
+        match self.origin_mode {
+            OriginMode::BottomLeft => crate::math::Vec2::new(point.x, self.get_win_size().height - point.y),
+            OriginMode::TopLeft => point,
+        }
+    }
+
+    /// Converts a world position back into screen pixels
+    pub fn convert_to_ui(&self, world: crate::math::Vec2) -> crate::math::Vec2 {
+        let point = match self.origin_mode {
+            OriginMode::BottomLeft => crate::math::Vec2::new(world.x, self.get_win_size().height - world.y),
+            OriginMode::TopLeft => world,
+        };
+        let (scale, offset) = self.get_screen_mapping();
+        crate::math::Vec2::new(point.x * scale.x + offset.x, point.y * scale.y + offset.y)
+    }
+
+    /// Converts a touch's current location into world coordinates
+    pub fn convert_touch_to_gl(&self, touch: &crate::input::Touch) -> crate::math::Vec2 {
+        self.convert_to_gl(touch.location_in_view())
     }
 }
 
@@ -156,7 +650,8 @@ impl Director {
 #[derive(Debug)]
 pub struct Scene {
     base: Ref,
-    children: Vec<RefPtr<Node>>,
+    children: Vec<NodeRef>,
+    sprites: Vec<RefPtr<Sprite>>,
 }
 
 impl Scene {
@@ -165,48 +660,213 @@ impl Scene {
         Scene {
             base: Ref::new(),
             children: Vec::new(),
+            sprites: Vec::new(),
         }
     }
 
     /// Gets the children of the scene
-    pub fn get_children(&self) -> &Vec<RefPtr<Node>> {
+    pub fn get_children(&self) -> &Vec<NodeRef> {
         &self.children
     }
 
     /// Adds a child to the scene
-    pub fn add_child(&mut self, child: RefPtr<Node>) {
+    pub fn add_child(&mut self, child: NodeRef) {
         self.children.push(child);
     }
 
+    /// Gets the sprites of the scene
+    pub fn get_sprites(&self) -> &Vec<RefPtr<Sprite>> {
+        &self.sprites
+    }
+
+    /// Adds a sprite to the scene
+    pub fn add_sprite(&mut self, sprite: RefPtr<Sprite>) {
+        self.sprites.push(sprite);
+    }
+
     /// Removes a child from the scene
-    pub fn remove_child(&mut self, child: &RefPtr<Node>) {
-        self.children.retain(|c| !c.borrow().get_id() == child.borrow().get_id());
+    pub fn remove_child(&mut self, child: &NodeRef) {
+        self.children.retain(|c| !Rc::ptr_eq(c, child));
+    }
+
+    /// Walks the children and sprites of the scene, and their subtrees, in
+    /// draw order
+    fn walk_render(&self, visitor: &mut RenderVisitor) {
+        let mut roots: Vec<RenderEntry> = self
+            .children
+            .iter()
+            .cloned()
+            .map(RenderEntry::Node)
+            .chain(self.sprites.iter().cloned().map(RenderEntry::Sprite))
+            .collect();
+        roots.sort_by_key(RenderEntry::get_local_z_order);
+        let mut stack: Vec<(RenderEntry, RenderParent)> =
+            roots.into_iter().rev().map(|root| (root, RenderParent::ROOT)).collect();
+        walk_render_stack(&mut stack, visitor);
+    }
+
+    /// Walks the visible nodes of the scene in draw order, passing each its
+    /// effective opacity and color. See `Node::visit_render`.
+    pub fn visit_render(&self, visitor: &mut dyn FnMut(&Node, u8, Color3B)) {
+        self.walk_render(&mut |node, _, _, opacity, color| visitor(node, opacity, color));
     }
 
-    /// Updates the scene
+    /// Draws the visible sprites of the scene, including the sprites of its
+    /// nodes, in draw order. Each sprite is tinted by the opacity and color
+    /// cascaded from its parents. Sprites whose world-space bounding box
+    /// misses `visible_rect` are culled without issuing a draw.
+    pub fn render(&self, renderer: &mut dyn QuadRenderer, visible_rect: &Rect) {
+        self.walk_render(&mut |_, sprite, transform, opacity, color| {
+            let sprite = match sprite {
+                Some(sprite) => sprite,
+                None => return,
+            };
+            if !sprite.get_bounding_box_with(transform).intersects_rect(visible_rect) {
+                return;
+            }
+            let quad = sprite.compute_quad_with(
+                transform,
+                multiply_color(sprite.get_color(), color),
+                multiply_channel(sprite.get_opacity(), opacity),
+            );
+            renderer.draw_quad(&quad, sprite.get_material().clone());
+        });
+    }
+
+    /// Updates every node in the scene that has scheduled an update,
+    /// descending through the full subtree of each child. The director does
+    /// not walk the scene; it updates nodes through the scheduler.
     pub fn update(&mut self, delta_time: f32) {
-        for child in &mut self.children {
-            child.borrow_mut().update(delta_time);
+        for child in &self.children {
+            child.borrow_mut().visit_update(delta_time);
+        }
+        for sprite in &mut self.sprites {
+            sprite.borrow_mut().get_node_mut().visit_update(delta_time);
+        }
+    }
+}
+
+/// Scales an 8-bit channel by another, treating 255 as one
+fn multiply_channel(value: u8, factor: u8) -> u8 {
+    (value as u16 * factor as u16 / 255) as u8
+}
+
+/// Scales a color by another channel by channel
+fn multiply_color(color: Color3B, factor: Color3B) -> Color3B {
+    Color3B::new(
+        multiply_channel(color.r, factor.r),
+        multiply_channel(color.g, factor.g),
+        multiply_channel(color.b, factor.b),
+    )
+}
+
+/// Receives each visible node of the render walk with the sprite it
+/// belongs to, its world transform and its effective opacity and color
+type RenderVisitor<'a> = dyn FnMut(&Node, Option<&Sprite>, &crate::math::Mat4, u8, Color3B) + 'a;
+
+/// State a node inherits from its parent in the render walk
+#[derive(Clone, Copy)]
+struct RenderParent {
+    transform: crate::math::Mat4,
+    opacity: u8,
+    color: Color3B,
+}
+
+impl RenderParent {
+    const ROOT: RenderParent = RenderParent {
+        transform: crate::math::Mat4::IDENTITY,
+        opacity: 255,
+        color: Color3B::WHITE,
+    };
+}
+
+/// Root of a subtree in the render walk: a tree node or a sprite
+enum RenderEntry {
+    Node(NodeRef),
+    Sprite(RefPtr<Sprite>),
+}
+
+impl RenderEntry {
+    fn get_local_z_order(&self) -> i32 {
+        match self {
+            RenderEntry::Node(node) => node.borrow().get_local_z_order(),
+            RenderEntry::Sprite(sprite) => sprite.get_node().get_local_z_order(),
+        }
+    }
+}
+
+/// Visits the entries on `stack` and everything below them, depth first
+fn walk_render_stack(stack: &mut Vec<(RenderEntry, RenderParent)>, visitor: &mut RenderVisitor) {
+    while let Some((entry, parent)) = stack.pop() {
+        match entry {
+            RenderEntry::Node(node) => node.borrow().visit_render_node(None, parent, visitor, stack),
+            RenderEntry::Sprite(sprite) => sprite.get_node().visit_render_node(Some(&sprite), parent, visitor, stack),
         }
     }
 }
 
+/// Shared handle to a node, used by systems that mutate nodes they do not own
+pub type NodeRef = Rc<RefCell<Node>>;
+
+/// Per-frame update callback of a node
+pub type NodeUpdateCallback = Box<dyn FnMut(&mut Node, f32)>;
+
 /// Base node type for all scene elements
-#[derive(Debug)]
 pub struct Node {
     base: Ref,
-    parent: Option<RefPtr<Node>>,
-    children: Vec<RefPtr<Node>>,
+    parent: Option<std::rc::Weak<RefCell<Node>>>,
+    children: Vec<NodeRef>,
+    sprites: Vec<RefPtr<Sprite>>,
+    reorder_child_dirty: Rc<Cell<bool>>,
+    parent_reorder_dirty: Option<Rc<Cell<bool>>>,
     position: crate::math::Vec2,
     rotation: f32,
     scale_x: f32,
     scale_y: f32,
     visible: bool,
+    color: Color3B,
+    opacity: u8,
+    cascade_color_enabled: bool,
+    cascade_opacity_enabled: bool,
+    paused: bool,
     tag: i32,
+    local_z_order: i32,
     name: String,
     local_transform: crate::math::Mat4,
     global_transform: crate::math::Mat4,
     content_size: crate::math::Vec2,
+    anchor_point: crate::math::Vec2,
+    update_scheduled: bool,
+    update_callback: Option<NodeUpdateCallback>,
+    self_ref: Option<std::rc::Weak<RefCell<Node>>>,
+}
+
+impl fmt::Debug for Node {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Node")
+            .field("base", &self.base)
+            .field("parent", &self.parent)
+            .field("children", &self.children)
+            .field("sprites", &self.sprites)
+            .field("position", &self.position)
+            .field("rotation", &self.rotation)
+            .field("scale_x", &self.scale_x)
+            .field("scale_y", &self.scale_y)
+            .field("visible", &self.visible)
+            .field("color", &self.color)
+            .field("opacity", &self.opacity)
+            .field("cascade_color_enabled", &self.cascade_color_enabled)
+            .field("cascade_opacity_enabled", &self.cascade_opacity_enabled)
+            .field("paused", &self.paused)
+            .field("tag", &self.tag)
+            .field("local_z_order", &self.local_z_order)
+            .field("name", &self.name)
+            .field("content_size", &self.content_size)
+            .field("anchor_point", &self.anchor_point)
+            .field("update_scheduled", &self.update_scheduled)
+            .field("has_update_callback", &self.update_callback.is_some())
+            .finish()
+    }
 }
 
 impl Node {
@@ -216,48 +876,249 @@ impl Node {
             base: Ref::new(),
             parent: None,
             children: Vec::new(),
+            sprites: Vec::new(),
+            reorder_child_dirty: Rc::new(Cell::new(false)),
+            parent_reorder_dirty: None,
             position: crate::math::Vec2::ZERO,
             rotation: 0.0,
             scale_x: 1.0,
             scale_y: 1.0,
             visible: true,
+            color: Color3B::WHITE,
+            opacity: 255,
+            cascade_color_enabled: false,
+            cascade_opacity_enabled: false,
+            paused: false,
             tag: 0,
+            local_z_order: 0,
             name: String::new(),
             local_transform: crate::math::Mat4::IDENTITY,
             global_transform: crate::math::Mat4::IDENTITY,
             content_size: crate::math::Vec2::ZERO,
+            anchor_point: crate::math::Vec2::new(0.5, 0.5),
+            update_scheduled: false,
+            update_callback: None,
+            self_ref: None,
         }
     }
 
-    /// Gets the parent node
-    pub fn get_parent(&self) -> Option<&RefPtr<Node>> {
-        self.parent.as_ref()
+    /// Creates a new shared node that can run actions on itself
+    pub fn create() -> NodeRef {
+        Rc::new_cyclic(|weak| {
+            let mut node = Node::new();
+            node.self_ref = Some(weak.clone());
+            RefCell::new(node)
+        })
+    }
+
+    /// Moves a node built by value into a shared handle, as if it was made
+    /// with `Node::create`. A scheduled update is registered with the
+    /// scheduler once the node has its handle.
+    pub fn into_ref(self) -> NodeRef {
+        let node: NodeRef = Rc::new_cyclic(|weak| {
+            let mut node = self;
+            node.self_ref = Some(weak.clone());
+            RefCell::new(node)
+        });
+        if node.borrow().update_scheduled {
+            Node::register_update(&node);
+        }
+        node
+    }
+
+    /// Gets the shared handle of a node made with `Node::create` or
+    /// `Node::into_ref`
+    pub(crate) fn get_node_ref(&self) -> Option<NodeRef> {
+        self.self_ref.as_ref().and_then(|node| node.upgrade())
+    }
+
+    /// Runs an action on this node through the thread's `ActionManager`.
+    ///
+    /// The action starts at the manager's next update. Returns false when
+    /// the node was not made with `Node::create` or `Node::into_ref`.
+    pub fn run_action(&mut self, action: Box<dyn Action>) -> bool {
+        match self.get_node_ref() {
+            Some(node) => {
+                ActionManager::get_instance().borrow_mut().add_action_deferred(action, &node);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Runs an action on this node and calls `on_complete` once it finishes.
+    ///
+    /// The action is wrapped in a `Sequence` ending with a `CallFunc`, so the
+    /// callback does not run if the action is stopped early. It may run,
+    /// stop or chain further actions. Returns false when the node was not
+    /// made with `Node::create` or `Node::into_ref`.
+    pub fn run_action_with_callback(
+        &mut self,
+        action: Box<dyn FiniteTimeAction>,
+        on_complete: Box<dyn FnOnce()>,
+    ) -> bool {
+        let mut on_complete = Some(on_complete);
+        let call = CallFunc::new(Box::new(move || {
+            if let Some(on_complete) = on_complete.take() {
+                on_complete();
+            }
+        }));
+        self.run_action(Box::new(Sequence::new(vec![action, Box::new(call)])))
+    }
+
+    /// Stops the actions of this node with the given tag
+    pub fn stop_action_by_tag(&mut self, tag: i32) {
+        if let Some(node) = self.get_node_ref() {
+            ActionManager::get_instance().borrow_mut().remove_action_by_tag(tag, &node);
+        }
+    }
+
+    /// Stops every action of this node
+    pub fn stop_all_actions(&mut self) {
+        if let Some(node) = self.get_node_ref() {
+            ActionManager::get_instance().borrow_mut().remove_all_actions_from_target(&node);
+        }
     }
 
-    /// Sets the parent node
-    pub fn set_parent(&mut self, parent: RefPtr<Node>) {
-        self.parent = Some(parent);
+    /// Gets the number of actions running on this node
+    pub fn get_number_of_running_actions(&self) -> usize {
+        self.get_node_ref().map_or(0, |node| {
+            ActionManager::get_instance().borrow().get_number_of_running_actions_in_target(&node)
+        })
     }
 
-    /// Gets the children
-    pub fn get_children(&self) -> &Vec<RefPtr<Node>> {
+    /// Gets the parent node, if it is still alive
+    pub fn get_parent(&self) -> Option<NodeRef> {
+        self.parent.as_ref().and_then(|parent| parent.upgrade())
+    }
+
+    /// Sets the parent node. Only a weak reference is kept so that the
+    /// parent/child link does not keep either node alive.
+    pub fn set_parent(&mut self, parent: &NodeRef) {
+        self.parent = Some(Rc::downgrade(parent));
+    }
+
+    /// Gets the children, sorted by local z-order after the next
+    /// `sort_all_children`
+    pub fn get_children(&self) -> &Vec<NodeRef> {
         &self.children
     }
 
-    /// Adds a child node
-    pub fn add_child(&mut self, child: RefPtr<Node>) {
-        child.borrow_mut().set_parent(self.base.clone());
+    /// Adds a child node. The child's parent becomes this node when it was
+    /// made with `Node::create` or `Node::into_ref`.
+    pub fn add_child(&mut self, child: NodeRef) {
+        {
+            let mut node = child.borrow_mut();
+            node.parent = self.self_ref.clone();
+            node.parent_reorder_dirty = Some(Rc::clone(&self.reorder_child_dirty));
+        }
         self.children.push(child);
+        self.reorder_child_dirty.set(true);
+    }
+
+    /// Adds a child node with the given local z-order
+    pub fn add_child_with_z(&mut self, child: NodeRef, local_z_order: i32) {
+        child.borrow_mut().set_local_z_order(local_z_order);
+        self.add_child(child);
+    }
+
+    /// Sorts the children by local z-order if a child was added or changed
+    /// its z-order since the last sort. The sort is stable, so children with
+    /// the same z-order keep their insertion order.
+    pub fn sort_all_children(&mut self) {
+        if self.reorder_child_dirty.replace(false) {
+            self.children.sort_by_key(|child| child.borrow().get_local_z_order());
+        }
+    }
+
+    /// Gets the sprites drawn in this node's space
+    pub fn get_sprites(&self) -> &Vec<RefPtr<Sprite>> {
+        &self.sprites
     }
 
-    /// Removes a child node
-    pub fn remove_child(&mut self, child: &RefPtr<Node>) {
-        self.children.retain(|c| c.borrow().get_id() != child.borrow().get_id());
+    /// Adds a sprite drawn in this node's space, ordered among the children
+    /// by its node's local z-order
+    pub fn add_sprite(&mut self, sprite: RefPtr<Sprite>) {
+        self.sprites.push(sprite);
+    }
+
+    /// Gets the children and sprites in draw order without sorting the
+    /// children in place
+    fn render_entries_in_order(&self) -> Vec<RenderEntry> {
+        let mut entries: Vec<RenderEntry> = self
+            .children
+            .iter()
+            .cloned()
+            .map(RenderEntry::Node)
+            .chain(self.sprites.iter().cloned().map(RenderEntry::Sprite))
+            .collect();
+        if self.reorder_child_dirty.get() || !self.sprites.is_empty() {
+            entries.sort_by_key(RenderEntry::get_local_z_order);
+        }
+        entries
+    }
+
+    /// Gets the first direct child with the given tag
+    pub fn get_child_by_tag(&self, tag: i32) -> Option<NodeRef> {
+        self.children.iter().find(|child| child.borrow().get_tag() == tag).cloned()
+    }
+
+    /// Gets the first direct child with the given name
+    pub fn get_child_by_name(&self, name: &str) -> Option<NodeRef> {
+        self.children.iter().find(|child| child.borrow().get_name() == name).cloned()
+    }
+
+    /// Searches the whole subtree, depth first, for a node with the given name
+    pub fn get_child_by_name_recursive(&self, name: &str) -> Option<NodeRef> {
+        self.children.iter().find_map(|child| {
+            let node = child.borrow();
+            if node.get_name() == name {
+                Some(Rc::clone(child))
+            } else {
+                node.get_child_by_name_recursive(name)
+            }
+        })
+    }
+
+    /// Resolves a `/`-separated path of child names, e.g. `"ui/hud/score"`.
+    /// A `*` segment matches any child name.
+    pub fn get_child_at_path(&self, path: &str) -> Option<NodeRef> {
+        let (segment, rest) = match path.split_once('/') {
+            Some((segment, rest)) => (segment, Some(rest)),
+            None => (path, None),
+        };
+        if segment.is_empty() {
+            return None;
+        }
+
+        self.children
+            .iter()
+            .filter(|child| segment == "*" || child.borrow().get_name() == segment)
+            .find_map(|child| match rest {
+                Some(rest) => child.borrow().get_child_at_path(rest),
+                None => Some(Rc::clone(child)),
+            })
+    }
+
+    /// Removes a child node and clears its parent
+    pub fn remove_child(&mut self, child: &NodeRef) {
+        if let Some(index) = self.children.iter().position(|c| Rc::ptr_eq(c, child)) {
+            Node::detach(&self.children.remove(index));
+        }
     }
 
     /// Removes all children
     pub fn remove_all_children(&mut self) {
-        self.children.clear();
+        for child in self.children.drain(..) {
+            Node::detach(&child);
+        }
+    }
+
+    /// Clears the links a removed child keeps to its parent
+    fn detach(child: &NodeRef) {
+        let mut node = child.borrow_mut();
+        node.parent = None;
+        node.parent_reorder_dirty = None;
     }
 
     /// Gets the position
@@ -316,6 +1177,22 @@ impl Node {
         self.tag = tag;
     }
 
+    /// Gets the local z-order
+    pub fn get_local_z_order(&self) -> i32 {
+        self.local_z_order
+    }
+
+    /// Sets the local z-order. Children with a lower z-order are visited
+    /// first; the parent re-sorts its children before the next visit.
+    pub fn set_local_z_order(&mut self, local_z_order: i32) {
+        if self.local_z_order != local_z_order {
+            self.local_z_order = local_z_order;
+            if let Some(dirty) = &self.parent_reorder_dirty {
+                dirty.set(true);
+            }
+        }
+    }
+
     /// Gets the name
     pub fn get_name(&self) -> &str {
         &self.name
@@ -336,6 +1213,19 @@ impl Node {
         &self.global_transform
     }
 
+    /// Gets the transform from the node's space to world space by walking
+    /// up through its parents
+    pub fn get_node_to_world_transform(&self) -> crate::math::Mat4 {
+        let mut transform = self.local_transform;
+        let mut parent = self.get_parent();
+        while let Some(node) = parent {
+            let node = node.borrow();
+            transform = node.local_transform * transform;
+            parent = node.get_parent();
+        }
+        transform
+    }
+
     /// Checks if the node is visible
     pub fn is_visible(&self) -> bool {
         self.visible
@@ -354,21 +1244,255 @@ impl Node {
     /// Sets the content size
     pub fn set_content_size(&mut self, size: crate::math::Vec2) {
         self.content_size = size;
+        self.update_local_transform();
+    }
+
+    /// Gets the anchor point, normalized to the content size
+    pub fn get_anchor_point(&self) -> crate::math::Vec2 {
+        self.anchor_point
+    }
+
+    /// Sets the anchor point. (0, 0) is the bottom-left corner of the content
+    /// and (1, 1) the top-right; the node is positioned, rotated and scaled
+    /// around this point.
+    pub fn set_anchor_point(&mut self, anchor_point: crate::math::Vec2) {
+        self.anchor_point = anchor_point;
+        self.update_local_transform();
+    }
+
+    /// Gets the anchor point in content-space points
+    pub fn get_anchor_point_in_points(&self) -> crate::math::Vec2 {
+        crate::math::Vec2::new(
+            self.anchor_point.x * self.content_size.x,
+            self.anchor_point.y * self.content_size.y,
+        )
     }
 
-    /// Updates the local transform matrix
+    /// Updates the local transform matrix.
+    ///
+    /// Content is offset so the anchor sits at the origin, then scaled,
+    /// rotated clockwise by `rotation` degrees and moved to `position`.
     fn update_local_transform(&mut self) {
-        self.local_transform = crate::math::Mat4::create_translation(self.position.x, self.position.y, 0.0);
+        let anchor = self.get_anchor_point_in_points();
+        let (sin, cos) = self.rotation.to_radians().sin_cos();
+
+        let mut transform = crate::math::Mat4::IDENTITY;
+        transform.m[0] = cos * self.scale_x;
+        transform.m[1] = -sin * self.scale_x;
+        transform.m[4] = sin * self.scale_y;
+        transform.m[5] = cos * self.scale_y;
+        transform.m[12] = self.position.x - (transform.m[0] * anchor.x + transform.m[4] * anchor.y);
+        transform.m[13] = self.position.y - (transform.m[1] * anchor.x + transform.m[5] * anchor.y);
+        self.local_transform = transform;
+    }
+
+    /// Schedules the node's `update` to be called every frame.
+    ///
+    /// Nodes with a handle from `Node::create` or `Node::into_ref` are
+    /// registered with the thread's `Scheduler`, keyed by
+    /// `Rc::as_ptr(handle) as usize`, and updated while not paused.
+    /// `Scene::update` also updates scheduled nodes when walking a tree.
+    pub fn schedule_update(&mut self) {
+        self.update_scheduled = true;
+        if let Some(node) = self.get_node_ref() {
+            Node::register_update(&node);
+        }
+    }
+
+    /// Stops calling the node's `update` every frame
+    pub fn unschedule_update(&mut self) {
+        self.update_scheduled = false;
+        if let Some(node) = self.get_node_ref() {
+            Scheduler::get_instance().borrow_mut().unschedule_update(Rc::as_ptr(&node) as usize);
+        }
+    }
+
+    /// Registers the node's update with the thread's scheduler. The entry
+    /// holds the node weakly and skips frames where it is paused or borrowed.
+    fn register_update(node: &NodeRef) {
+        let target = Rc::as_ptr(node) as usize;
+        let weak = Rc::downgrade(node);
+        Scheduler::get_instance().borrow_mut().schedule_update(
+            target,
+            0,
+            Rc::new(move |delta_time| {
+                let handle = match weak.upgrade() {
+                    Some(handle) => handle,
+                    None => return,
+                };
+                if let Ok(mut node) = handle.try_borrow_mut() {
+                    if !node.paused {
+                        node.update(delta_time);
+                    }
+                };
+            }),
+        );
+    }
+
+    /// Checks if the node's `update` is called every frame
+    pub fn is_update_scheduled(&self) -> bool {
+        self.update_scheduled
+    }
+
+    /// Sets the callback run by `update`
+    pub fn set_update_callback(&mut self, callback: NodeUpdateCallback) {
+        self.update_callback = Some(callback);
     }
 
     /// Updates the node
     pub fn update(&mut self, delta_time: f32) {
+        if let Some(mut callback) = self.update_callback.take() {
+            callback(self, delta_time);
+            self.update_callback = Some(callback);
+        }
+    }
+
+    /// Pauses the node's scheduled update and its actions in the `ActionManager`
+    pub fn pause(&mut self) {
+        self.paused = true;
+    }
+
+    /// Resumes the node's scheduled update and actions
+    pub fn resume(&mut self) {
+        self.paused = false;
+    }
+
+    /// Pauses this node and every descendant
+    pub fn pause_recursive(&mut self) {
+        self.pause();
+        for child in &self.children {
+            child.borrow_mut().pause_recursive();
+        }
+    }
+
+    /// Resumes this node and every descendant
+    pub fn resume_recursive(&mut self) {
+        self.resume();
+        for child in &self.children {
+            child.borrow_mut().resume_recursive();
+        }
+    }
+
+    /// Checks if the node is paused
+    pub fn is_paused(&self) -> bool {
+        self.paused
+    }
+
+    /// Gets the color
+    pub fn get_color(&self) -> Color3B {
+        self.color
+    }
+
+    /// Sets the color
+    pub fn set_color(&mut self, color: Color3B) {
+        self.color = color;
+    }
+
+    /// Gets the opacity
+    pub fn get_opacity(&self) -> u8 {
+        self.opacity
+    }
+
+    /// Sets the opacity
+    pub fn set_opacity(&mut self, opacity: u8) {
+        self.opacity = opacity;
+    }
+
+    /// Checks if this node's color tints its children
+    pub fn is_cascade_color_enabled(&self) -> bool {
+        self.cascade_color_enabled
+    }
+
+    /// Sets whether this node's color tints its children
+    pub fn set_cascade_color_enabled(&mut self, enabled: bool) {
+        self.cascade_color_enabled = enabled;
+    }
+
+    /// Checks if this node's opacity fades its children
+    pub fn is_cascade_opacity_enabled(&self) -> bool {
+        self.cascade_opacity_enabled
     }
 
-    /// Gets a unique ID for the node
-    fn get_id(&self) -> usize {
-        let ptr = &self.base as *const Ref as *const u8 as usize;
-        ptr
+    /// Sets whether this node's opacity fades its children
+    pub fn set_cascade_opacity_enabled(&mut self, enabled: bool) {
+        self.cascade_opacity_enabled = enabled;
+    }
+
+    /// Walks the visible subtree in draw order, passing each node its
+    /// effective opacity and color.
+    ///
+    /// Children and sprites are visited in local z-order. A node with
+    /// cascading enabled multiplies its effective opacity or color into its
+    /// children. Invisible nodes are skipped together with their subtree.
+    /// The walk uses an explicit stack, so deep trees cannot overflow the
+    /// call stack.
+    pub fn visit_render(&self, visitor: &mut dyn FnMut(&Node, u8, Color3B)) {
+        let visitor: &mut RenderVisitor = &mut |node, _, _, opacity, color| visitor(node, opacity, color);
+        let mut stack = Vec::new();
+        self.visit_render_node(None, RenderParent::ROOT, visitor, &mut stack);
+        walk_render_stack(&mut stack, visitor);
+    }
+
+    /// Visits this node for the render walk and pushes its children and
+    /// sprites. `sprite` is the sprite this node belongs to, if any.
+    fn visit_render_node(
+        &self,
+        sprite: Option<&Sprite>,
+        parent: RenderParent,
+        visitor: &mut RenderVisitor,
+        stack: &mut Vec<(RenderEntry, RenderParent)>,
+    ) {
+        if !self.visible {
+            return;
+        }
+        let transform = parent.transform * self.local_transform;
+        let opacity = multiply_channel(self.opacity, parent.opacity);
+        let color = multiply_color(self.color, parent.color);
+        visitor(self, sprite, &transform, opacity, color);
+
+        let child_parent = RenderParent {
+            transform,
+            opacity: if self.cascade_opacity_enabled { opacity } else { 255 },
+            color: if self.cascade_color_enabled { color } else { Color3B::WHITE },
+        };
+        for entry in self.render_entries_in_order().into_iter().rev() {
+            stack.push((entry, child_parent));
+        }
+    }
+
+    /// Walks the subtree below this node depth first, passing each
+    /// descendant and its depth, where direct children are at depth 1.
+    /// Invisible nodes are included, so the walk suits debug dumps and
+    /// serialization.
+    pub fn visit_descendants(&self, f: &mut dyn FnMut(&Node, usize)) {
+        let mut stack: Vec<(NodeRef, usize)> =
+            self.children.iter().rev().map(|child| (Rc::clone(child), 1)).collect();
+        while let Some((child, depth)) = stack.pop() {
+            let node = child.borrow();
+            f(&node, depth);
+            for grandchild in node.children.iter().rev() {
+                stack.push((Rc::clone(grandchild), depth + 1));
+            }
+        }
+    }
+
+    /// Gets the number of nodes below this node, at any depth
+    pub fn descendant_count(&self) -> usize {
+        let mut count = 0;
+        self.visit_descendants(&mut |_, _| count += 1);
+        count
+    }
+
+    /// Calls `update` on this node, if scheduled and not paused, and then on
+    /// its subtree
+    pub fn visit_update(&mut self, delta_time: f32) {
+        if self.update_scheduled && !self.paused {
+            self.update(delta_time);
+        }
+        self.sort_all_children();
+        for child in &self.children {
+            child.borrow_mut().visit_update(delta_time);
+        }
     }
 
     /// Gets the base reference
@@ -381,3 +1505,887 @@ impl Node {
         &mut self.base
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::Cell;
+
+    fn counting_node(counter: &Rc<Cell<u32>>) -> Node {
+        let mut node = Node::new();
+        let counter = Rc::clone(counter);
+        node.set_update_callback(Box::new(move |_, _| counter.set(counter.get() + 1)));
+        node
+    }
+
+    fn named_node(name: &str) -> NodeRef {
+        let mut node = Node::new();
+        node.set_name(name.to_string());
+        node.into_ref()
+    }
+
+    #[test]
+    fn test_visit_descendants_reports_depths() {
+        let child_a = named_node("a");
+        child_a.borrow_mut().add_child(named_node("a1"));
+        child_a.borrow_mut().add_child(named_node("a2"));
+        let child_b = named_node("b");
+        let grandchild = named_node("b1");
+        grandchild.borrow_mut().set_visible(false);
+        child_b.borrow_mut().add_child(grandchild);
+        let mut root = Node::new();
+        root.add_child(child_a);
+        root.add_child(child_b);
+
+        let mut visited = Vec::new();
+        root.visit_descendants(&mut |node, depth| visited.push((node.get_name().to_string(), depth)));
+        let expected = [("a", 1), ("a1", 2), ("a2", 2), ("b", 1), ("b1", 2)];
+        assert_eq!(
+            visited,
+            expected.iter().map(|(name, depth)| (name.to_string(), *depth)).collect::<Vec<_>>()
+        );
+        assert_eq!(root.descendant_count(), 5);
+        assert_eq!(Node::new().descendant_count(), 0);
+    }
+
+    /// Renderer recording the quads it is asked to draw
+    #[derive(Default)]
+    struct MockRenderer {
+        quads: Vec<crate::renderer::Quad>,
+        materials: Vec<RefPtr<crate::renderer::Material>>,
+        view_projection: Option<crate::math::Mat4>,
+    }
+
+    impl QuadRenderer for MockRenderer {
+        fn set_view_projection_matrix(&mut self, view_projection: crate::math::Mat4) {
+            self.view_projection = Some(view_projection);
+        }
+
+        fn draw_quad(&mut self, quad: &crate::renderer::Quad, material: RefPtr<crate::renderer::Material>) {
+            self.quads.push(quad.clone());
+            self.materials.push(material);
+        }
+    }
+
+    fn sprite_at(x: f32, y: f32) -> RefPtr<Sprite> {
+        let mut sprite = Sprite::new();
+        sprite.get_node_mut().set_content_size(crate::math::Vec2::new(100.0, 100.0));
+        sprite.get_node_mut().set_position(crate::math::Vec2::new(x, y));
+        RefPtr::new(sprite)
+    }
+
+    fn drawn_count(x: f32, y: f32) -> usize {
+        let mut director = Director::new();
+        director.set_frame_size(Size::new(960.0, 640.0));
+        let mut scene = Scene::new();
+        scene.add_sprite(sprite_at(x, y));
+        let mut renderer = MockRenderer::default();
+        scene.render(&mut renderer, &director.get_visible_rect());
+        renderer.quads.len()
+    }
+
+    #[test]
+    fn test_render_culls_off_screen_sprites() {
+        assert_eq!(drawn_count(480.0, 320.0), 1);
+        assert_eq!(drawn_count(5000.0, 320.0), 0);
+        assert_eq!(drawn_count(480.0, -400.0), 0);
+    }
+
+    #[test]
+    fn test_render_draws_partially_visible_sprite() {
+        assert_eq!(drawn_count(-20.0, 320.0), 1);
+        assert_eq!(drawn_count(990.0, 660.0), 1);
+    }
+
+    #[test]
+    fn test_render_applies_parent_transform_and_cascade() {
+        let parent = Node::create();
+        {
+            let mut parent = parent.borrow_mut();
+            parent.set_position(crate::math::Vec2::new(100.0, 50.0));
+            parent.set_opacity(128);
+            parent.set_color(Color3B::new(255, 0, 255));
+            parent.set_cascade_opacity_enabled(true);
+            parent.set_cascade_color_enabled(true);
+            parent.add_sprite(sprite_at(480.0, 320.0));
+        }
+        let mut scene = Scene::new();
+        scene.add_child(parent);
+
+        let mut renderer = MockRenderer::default();
+        scene.render(&mut renderer, &Rect::new(0.0, 0.0, 960.0, 640.0));
+        assert_eq!(renderer.quads.len(), 1);
+        let corner = &renderer.quads[0].bl;
+        assert_eq!(&corner.position[..2], &[530.0, 320.0]);
+        assert_eq!(
+            corner.color,
+            crate::base::types::Color4F::from_color4b(crate::base::types::Color4B::new(255, 0, 255, 128))
+        );
+    }
+
+    /// Counts the quads drawn for a sprite at `sprite_x` under a node at
+    /// `parent_x`
+    fn drawn_under_parent(parent_x: f32, sprite_x: f32) -> usize {
+        let parent = Node::create();
+        parent.borrow_mut().set_position(crate::math::Vec2::new(parent_x, 0.0));
+        parent.borrow_mut().add_sprite(sprite_at(sprite_x, 320.0));
+        let mut scene = Scene::new();
+        scene.add_child(parent);
+        let mut renderer = MockRenderer::default();
+        scene.render(&mut renderer, &Rect::new(0.0, 0.0, 960.0, 640.0));
+        renderer.quads.len()
+    }
+
+    #[test]
+    fn test_render_culls_with_world_bounds() {
+        assert_eq!(drawn_under_parent(0.0, 480.0), 1);
+        assert_eq!(drawn_under_parent(2000.0, 480.0), 0);
+        assert_eq!(drawn_under_parent(1000.0, -500.0), 1);
+    }
+
+    #[test]
+    fn test_render_reuses_sprite_material() {
+        let mut scene = Scene::new();
+        scene.add_sprite(sprite_at(480.0, 320.0));
+        let mut renderer = MockRenderer::default();
+        scene.render(&mut renderer, &Rect::new(0.0, 0.0, 960.0, 640.0));
+        scene.render(&mut renderer, &Rect::new(0.0, 0.0, 960.0, 640.0));
+        assert_eq!(renderer.materials.len(), 2);
+        assert!(RefPtr::ptr_eq(&renderer.materials[0], &renderer.materials[1]));
+        assert!(RefPtr::ptr_eq(&renderer.materials[0], scene.get_sprites()[0].get_material()));
+    }
+
+    #[test]
+    fn test_get_instance_shares_director() {
+        let first = Director::get_instance();
+        let second = Director::get_instance();
+        assert!(Rc::ptr_eq(&first, &second));
+
+        let display_stats = first.borrow().is_display_stats();
+        first.borrow_mut().set_display_stats(!display_stats);
+        assert_eq!(second.borrow().is_display_stats(), !display_stats);
+        second.borrow_mut().set_display_stats(display_stats);
+    }
+
+    #[test]
+    fn test_nested_scheduled_update_runs_each_tick() {
+        let counter = Rc::new(Cell::new(0));
+        let mut grandchild = counting_node(&counter);
+        grandchild.schedule_update();
+        let mut child = Node::new();
+        child.add_child(grandchild.into_ref());
+
+        let scene = Rc::new(RefCell::new(Scene::new()));
+        scene.borrow_mut().add_child(child.into_ref());
+
+        let mut scheduler = Scheduler::new();
+        let ticked = Rc::clone(&scene);
+        scheduler.schedule_update(0, 0, Rc::new(move |dt| ticked.borrow_mut().update(dt)));
+
+        scheduler.update(0.016);
+        scheduler.update(0.016);
+        assert_eq!(counter.get(), 2);
+    }
+
+    #[test]
+    fn test_unscheduled_update_is_not_called() {
+        let counter = Rc::new(Cell::new(0));
+        let mut node = counting_node(&counter);
+        node.schedule_update();
+        node.visit_update(0.016);
+        assert_eq!(counter.get(), 1);
+
+        node.unschedule_update();
+        node.visit_update(0.016);
+        assert!(!node.is_update_scheduled());
+        assert_eq!(counter.get(), 1);
+    }
+
+    #[test]
+    fn test_schedule_update_registers_with_scheduler() {
+        let counter = Rc::new(Cell::new(0));
+        let child = counting_node(&counter).into_ref();
+        let parent = Node::create();
+        parent.borrow_mut().add_child(Rc::clone(&child));
+        let mut scene = Scene::new();
+        scene.add_child(Rc::clone(&parent));
+
+        let mut director = Director::new();
+        director.run_scene(RefPtr::new(scene));
+        child.borrow_mut().schedule_update();
+        let target = Rc::as_ptr(&child) as usize;
+        assert!(director.get_scheduler().borrow().is_update_scheduled(target));
+
+        director.main_loop_with_delta(0.016);
+        director.main_loop_with_delta(0.016);
+        assert_eq!(counter.get(), 2);
+
+        child.borrow_mut().unschedule_update();
+        assert!(!director.get_scheduler().borrow().is_update_scheduled(target));
+        director.main_loop_with_delta(0.016);
+        assert_eq!(counter.get(), 2);
+    }
+
+    #[test]
+    fn test_scheduled_node_registers_when_given_a_handle() {
+        let counter = Rc::new(Cell::new(0));
+        let mut node = counting_node(&counter);
+        node.schedule_update();
+        let node = node.into_ref();
+        assert!(Scheduler::get_instance().borrow().is_update_scheduled(Rc::as_ptr(&node) as usize));
+
+        node.borrow_mut().pause();
+        Scheduler::update_instance(0.016);
+        assert_eq!(counter.get(), 0);
+        node.borrow_mut().resume();
+        Scheduler::update_instance(0.016);
+        assert_eq!(counter.get(), 1);
+    }
+
+    #[test]
+    fn test_scheduler_unschedule_update() {
+        let counter = Rc::new(Cell::new(0));
+        let mut scheduler = Scheduler::new();
+        let ticked = Rc::clone(&counter);
+        scheduler.schedule_update(7, 0, Rc::new(move |_| ticked.set(ticked.get() + 1)));
+        scheduler.update(0.016);
+        scheduler.unschedule_update(7);
+        scheduler.update(0.016);
+        assert!(!scheduler.is_update_scheduled(7));
+        assert_eq!(counter.get(), 1);
+    }
+
+    fn tagged_child(tag: i32) -> NodeRef {
+        let mut node = Node::new();
+        node.set_tag(tag);
+        node.into_ref()
+    }
+
+    #[test]
+    fn test_sort_children_by_z_order() {
+        let mut parent = Node::new();
+        parent.add_child_with_z(tagged_child(1), 10);
+        parent.add_child_with_z(tagged_child(2), -1);
+        parent.add_child_with_z(tagged_child(3), 5);
+        parent.sort_all_children();
+
+        let z: Vec<i32> = parent.get_children().iter().map(|c| c.borrow().get_local_z_order()).collect();
+        assert_eq!(z, vec![-1, 5, 10]);
+    }
+
+    #[test]
+    fn test_equal_z_keeps_insertion_order() {
+        let mut parent = Node::new();
+        parent.add_child_with_z(tagged_child(1), 1);
+        parent.add_child_with_z(tagged_child(2), 0);
+        parent.add_child_with_z(tagged_child(3), 1);
+        parent.add_child(tagged_child(4));
+        parent.sort_all_children();
+
+        let tags: Vec<i32> = parent.get_children().iter().map(|c| c.borrow().get_tag()).collect();
+        assert_eq!(tags, vec![2, 4, 1, 3]);
+    }
+
+    #[test]
+    fn test_z_order_change_resorts_children() {
+        let mut parent = Node::new();
+        let first = tagged_child(1);
+        parent.add_child(Rc::clone(&first));
+        parent.add_child(tagged_child(2));
+        parent.visit_update(0.016);
+
+        first.borrow_mut().set_local_z_order(5);
+        let mut drawn = Vec::new();
+        parent.visit_render(&mut |node, _, _| drawn.push(node.get_tag()));
+        assert_eq!(drawn, vec![0, 2, 1]);
+
+        let tags = |parent: &Node| parent.get_children().iter().map(|c| c.borrow().get_tag()).collect::<Vec<_>>();
+        assert_eq!(tags(&parent), vec![1, 2]);
+        parent.visit_update(0.016);
+        assert_eq!(tags(&parent), vec![2, 1]);
+    }
+
+    #[test]
+    fn test_render_draws_sprites_in_z_order() {
+        let mut front = sprite_at(100.0, 100.0);
+        front.get_node_mut().set_local_z_order(1);
+        let mut scene = Scene::new();
+        scene.add_sprite(front);
+        scene.add_sprite(sprite_at(200.0, 100.0));
+
+        let mut renderer = MockRenderer::default();
+        scene.render(&mut renderer, &Rect::new(0.0, 0.0, 960.0, 640.0));
+        let lefts: Vec<f32> = renderer.quads.iter().map(|quad| quad.bl.position[0]).collect();
+        assert_eq!(lefts, vec![150.0, 50.0]);
+    }
+
+    fn assert_near(actual: crate::math::Vec3, x: f32, y: f32) {
+        assert!((actual.x - x).abs() < 1e-4 && (actual.y - y).abs() < 1e-4, "{:?}", actual);
+    }
+
+    #[test]
+    fn test_rotation_pivots_around_anchor() {
+        let mut node = Node::new();
+        node.set_content_size(crate::math::Vec2::new(100.0, 100.0));
+        node.set_position(crate::math::Vec2::new(200.0, 300.0));
+        node.set_rotation(90.0);
+
+        let transform = node.get_local_transform();
+        let top_right = transform.transform_point(&crate::math::Vec3::new(100.0, 100.0, 0.0));
+        assert_near(top_right, 250.0, 250.0);
+        let center = transform.transform_point(&crate::math::Vec3::new(50.0, 50.0, 0.0));
+        assert_near(center, 200.0, 300.0);
+    }
+
+    #[test]
+    fn test_scale_around_bottom_left_anchor() {
+        let mut node = Node::new();
+        node.set_content_size(crate::math::Vec2::new(100.0, 50.0));
+        node.set_anchor_point(crate::math::Vec2::ZERO);
+        node.set_position(crate::math::Vec2::new(10.0, 20.0));
+        node.set_scale(2.0);
+
+        let transform = node.get_local_transform();
+        assert_near(transform.transform_point(&crate::math::Vec3::new(0.0, 0.0, 0.0)), 10.0, 20.0);
+        assert_near(transform.transform_point(&crate::math::Vec3::new(100.0, 50.0, 0.0)), 210.0, 120.0);
+    }
+
+    fn named(name: &str, tag: i32, children: Vec<Node>) -> Node {
+        let mut node = Node::new();
+        node.set_name(name.to_string());
+        node.set_tag(tag);
+        for child in children {
+            node.add_child(child.into_ref());
+        }
+        node
+    }
+
+    fn ui_tree() -> Node {
+        named("root", 0, vec![
+            named("world", 1, vec![named("player", 10, vec![])]),
+            named("ui", 2, vec![
+                named("hud", 20, vec![named("score", 200, vec![]), named("lives", 201, vec![])]),
+            ]),
+        ])
+    }
+
+    #[test]
+    fn test_get_child_by_tag_and_name() {
+        let root = ui_tree();
+        assert_eq!(root.get_child_by_tag(2).unwrap().borrow().get_name(), "ui");
+        assert_eq!(root.get_child_by_name("world").unwrap().borrow().get_tag(), 1);
+        assert!(root.get_child_by_tag(10).is_none());
+        assert!(root.get_child_by_name("player").is_none());
+    }
+
+    #[test]
+    fn test_get_child_by_name_recursive() {
+        let root = ui_tree();
+        assert_eq!(root.get_child_by_name_recursive("player").unwrap().borrow().get_tag(), 10);
+        assert_eq!(root.get_child_by_name_recursive("lives").unwrap().borrow().get_tag(), 201);
+        assert!(root.get_child_by_name_recursive("missing").is_none());
+    }
+
+    #[test]
+    fn test_get_child_at_path() {
+        let root = ui_tree();
+        assert_eq!(root.get_child_at_path("ui/hud/score").unwrap().borrow().get_tag(), 200);
+        assert_eq!(root.get_child_at_path("*/hud/lives").unwrap().borrow().get_tag(), 201);
+        assert!(root.get_child_at_path("ui/score").is_none());
+        assert!(root.get_child_at_path("ui/hud/score/extra").is_none());
+        assert!(root.get_child_at_path("").is_none());
+    }
+
+    #[test]
+    fn test_add_child_sets_parent() {
+        let parent = Node::create();
+        let child = Node::create();
+        parent.borrow_mut().add_child(Rc::clone(&child));
+        assert!(Rc::ptr_eq(&child.borrow().get_parent().unwrap(), &parent));
+
+        parent.borrow_mut().remove_child(&child);
+        assert!(child.borrow().get_parent().is_none());
+        assert!(parent.borrow().get_children().is_empty());
+    }
+
+    #[test]
+    fn test_paused_subtree_skips_update() {
+        let counter = Rc::new(Cell::new(0));
+        let mut child = counting_node(&counter);
+        child.schedule_update();
+        let mut parent = counting_node(&counter);
+        parent.schedule_update();
+        parent.add_child(child.into_ref());
+
+        parent.pause_recursive();
+        parent.visit_update(0.016);
+        assert_eq!(counter.get(), 0);
+
+        parent.resume_recursive();
+        parent.visit_update(0.016);
+        assert_eq!(counter.get(), 2);
+    }
+
+    fn tagged_move(tag: i32) -> Box<dyn Action> {
+        let mut action = crate::action::MoveBy::new(1.0, crate::math::Vec2::new(100.0, 0.0));
+        action.set_tag(tag);
+        Box::new(action)
+    }
+
+    #[test]
+    fn test_run_action_advances_node() {
+        let node = Node::create();
+        assert!(node.borrow_mut().run_action(tagged_move(1)));
+        assert_eq!(node.borrow().get_number_of_running_actions(), 1);
+
+        ActionManager::get_instance().borrow_mut().update(0.5);
+        assert_eq!(node.borrow().get_position().x, 50.0);
+
+        node.borrow_mut().stop_action_by_tag(1);
+        assert_eq!(node.borrow().get_number_of_running_actions(), 0);
+        ActionManager::get_instance().borrow_mut().update(0.25);
+        assert_eq!(node.borrow().get_position().x, 50.0);
+        assert!(!Node::new().run_action(tagged_move(1)));
+    }
+
+    #[test]
+    fn test_main_loop_advances_actions() {
+        let mut director = Director::new();
+        let node = Node::create();
+        node.borrow_mut().run_action(tagged_move(1));
+
+        director.main_loop_with_delta(0.25);
+        assert_eq!(node.borrow().get_position().x, 25.0);
+
+        director.pause();
+        director.main_loop_with_delta(0.25);
+        assert_eq!(node.borrow().get_position().x, 25.0);
+
+        director.resume();
+        director.main_loop_with_delta(0.75);
+        assert_eq!(node.borrow().get_position().x, 100.0);
+        assert_eq!(node.borrow().get_number_of_running_actions(), 0);
+    }
+
+    #[test]
+    fn test_stop_all_actions_only_affects_node() {
+        let node = Node::create();
+        let other = Node::create();
+        node.borrow_mut().run_action(tagged_move(1));
+        node.borrow_mut().run_action(tagged_move(2));
+        other.borrow_mut().run_action(tagged_move(1));
+
+        node.borrow_mut().stop_all_actions();
+        ActionManager::get_instance().borrow_mut().update(0.5);
+        assert_eq!(node.borrow().get_number_of_running_actions(), 0);
+        assert_eq!(node.borrow().get_position().x, 0.0);
+        assert_eq!(other.borrow().get_position().x, 50.0);
+        other.borrow_mut().stop_all_actions();
+    }
+
+    #[test]
+    fn test_run_action_with_callback_fires_once_after_completion() {
+        let node = Node::create();
+        let completed = Rc::new(Cell::new(0));
+        let counter = completed.clone();
+        let action = crate::action::MoveBy::new(0.5, crate::math::Vec2::new(100.0, 0.0));
+        assert!(node.borrow_mut().run_action_with_callback(
+            Box::new(action),
+            Box::new(move || counter.set(counter.get() + 1)),
+        ));
+
+        ActionManager::update_instance(0.25);
+        assert_eq!(completed.get(), 0);
+        ActionManager::update_instance(0.3);
+        assert_eq!(completed.get(), 1);
+        assert_eq!(node.borrow().get_position().x, 100.0);
+
+        ActionManager::update_instance(0.5);
+        assert_eq!(completed.get(), 1);
+        assert_eq!(node.borrow().get_number_of_running_actions(), 0);
+    }
+
+    #[test]
+    fn test_run_action_with_callback_can_chain_actions() {
+        let node = Node::create();
+        let handle = node.clone();
+        node.borrow_mut().run_action_with_callback(
+            Box::new(crate::action::MoveBy::new(0.5, crate::math::Vec2::new(100.0, 0.0))),
+            Box::new(move || {
+                let next = crate::action::MoveBy::new(0.5, crate::math::Vec2::new(0.0, 100.0));
+                assert!(handle.borrow_mut().run_action(Box::new(next)));
+            }),
+        );
+
+        ActionManager::update_instance(0.5);
+        assert_eq!(*node.borrow().get_position(), crate::math::Vec2::new(100.0, 0.0));
+        assert_eq!(node.borrow().get_number_of_running_actions(), 1);
+
+        ActionManager::update_instance(0.5);
+        assert_eq!(*node.borrow().get_position(), crate::math::Vec2::new(100.0, 100.0));
+        assert_eq!(node.borrow().get_number_of_running_actions(), 0);
+    }
+
+    /// Creates a tree node running a move action
+    fn moving_node(handles: &mut Vec<NodeRef>) -> NodeRef {
+        let handle = Node::create();
+        handle.borrow_mut().run_action(tagged_move(1));
+        handles.push(Rc::clone(&handle));
+        handle
+    }
+
+    #[test]
+    fn test_pause_all_except_subtree() {
+        let mut director = Director::new();
+        let mut handles = Vec::new();
+        let a = moving_node(&mut handles);
+        a.borrow_mut().add_child(moving_node(&mut handles));
+        let b = moving_node(&mut handles);
+        b.borrow_mut().add_child(moving_node(&mut handles));
+
+        let updates = Rc::new(Cell::new(0));
+        for handle in [&handles[0], &handles[2]] {
+            let updates = Rc::clone(&updates);
+            let target = Rc::as_ptr(handle) as usize;
+            director.scheduler.borrow_mut().schedule_update(target, 0, Rc::new(move |_| updates.set(updates.get() + 1)));
+        }
+
+        director.pause_all_except(&a);
+        director.main_loop_with_delta(0.5);
+        let positions = || handles.iter().map(|handle| handle.borrow().get_position().x).collect::<Vec<_>>();
+        assert_eq!(positions(), vec![50.0, 50.0, 0.0, 0.0]);
+        assert_eq!(updates.get(), 1);
+
+        director.resume_all();
+        director.main_loop_with_delta(0.25);
+        assert_eq!(positions(), vec![75.0, 75.0, 25.0, 25.0]);
+        assert_eq!(updates.get(), 3);
+        ActionManager::get_instance().borrow_mut().remove_all_actions();
+    }
+
+    #[test]
+    fn test_pause_all_except_holds_actions_started_later() {
+        let mut director = Director::new();
+        let a = Node::create();
+        let b = Node::create();
+        let b_child = Node::create();
+        b.borrow_mut().add_child(Rc::clone(&b_child));
+
+        director.pause_all_except(&a);
+        a.borrow_mut().run_action(tagged_move(1));
+        b_child.borrow_mut().run_action(tagged_move(1));
+        let updates = Rc::new(Cell::new(0));
+        let counter = Rc::clone(&updates);
+        director.scheduler.borrow_mut().schedule_update(
+            Rc::as_ptr(&b) as usize,
+            0,
+            Rc::new(move |_| counter.set(counter.get() + 1)),
+        );
+        director.main_loop_with_delta(0.5);
+        assert_eq!(a.borrow().get_position().x, 50.0);
+        assert_eq!(b_child.borrow().get_position().x, 0.0);
+        assert_eq!(updates.get(), 0);
+
+        director.resume_all();
+        director.main_loop_with_delta(0.25);
+        assert_eq!(a.borrow().get_position().x, 75.0);
+        assert_eq!(b_child.borrow().get_position().x, 25.0);
+        assert_eq!(updates.get(), 1);
+        ActionManager::get_instance().borrow_mut().remove_all_actions();
+    }
+
+    #[test]
+    fn test_pause_all_except_running_scene_child() {
+        let mut director = Director::new();
+        let background = Node::create();
+        let dialog = Node::create();
+        let button = Node::create();
+        dialog.borrow_mut().add_child(Rc::clone(&button));
+        let mut scene = Scene::new();
+        scene.add_child(Rc::clone(&background));
+        scene.add_child(Rc::clone(&dialog));
+        director.run_scene(RefPtr::new(scene));
+        director.main_loop_with_delta(0.0);
+
+        background.borrow_mut().run_action(tagged_move(1));
+        button.borrow_mut().run_action(tagged_move(1));
+        director.pause_all_except(&dialog);
+        director.main_loop_with_delta(0.5);
+        assert_eq!(background.borrow().get_position().x, 0.0);
+        assert_eq!(button.borrow().get_position().x, 50.0);
+
+        director.resume_all();
+        director.main_loop_with_delta(0.25);
+        assert_eq!(background.borrow().get_position().x, 25.0);
+        assert_eq!(button.borrow().get_position().x, 75.0);
+        ActionManager::get_instance().borrow_mut().remove_all_actions();
+    }
+
+    /// Clock advanced by hand through a shared cell
+    #[derive(Debug)]
+    struct FakeClock(Rc<Cell<f64>>);
+
+    impl Clock for FakeClock {
+        fn now_seconds(&self) -> f64 {
+            self.0.get()
+        }
+    }
+
+    fn director_with_fake_clock() -> (Director, Rc<Cell<f64>>) {
+        let time = Rc::new(Cell::new(100.0));
+        let mut director = Director::new();
+        director.set_clock(Box::new(FakeClock(Rc::clone(&time))));
+        (director, time)
+    }
+
+    #[test]
+    fn test_fake_clock_drives_delta_time() {
+        let (mut director, time) = director_with_fake_clock();
+        time.set(time.get() + 0.25);
+        director.main_loop();
+        assert_eq!(director.get_delta_time(), 0.25);
+
+        time.set(time.get() + 0.5);
+        director.main_loop();
+        assert_eq!(director.get_delta_time(), 0.5);
+        assert_eq!(director.get_total_time(), 0.75);
+
+        director.main_loop();
+        assert_eq!(director.get_delta_time(), 0.0);
+    }
+
+    #[test]
+    fn test_frame_rate_average() {
+        let (mut director, time) = director_with_fake_clock();
+        for delta in [0.02, 0.02, 0.01, 0.01] {
+            time.set(time.get() + delta);
+            director.main_loop();
+        }
+
+        assert!((director.get_frame_rate() - 4.0 / 0.06).abs() < 0.01);
+        assert!((director.get_frame_time_ms() - 10.0).abs() < 1e-4);
+        assert!((director.get_frame_stats().get_min_frame_time_ms() - 10.0).abs() < 1e-4);
+        assert!((director.get_frame_stats().get_max_frame_time_ms() - 20.0).abs() < 1e-4);
+    }
+
+    #[test]
+    fn test_frame_stats_window_drops_old_extremes() {
+        let mut director = Director::new();
+        director.main_loop_with_delta(0.1);
+        for _ in 0..FRAME_STATS_WINDOW {
+            director.main_loop_with_delta(0.016);
+        }
+        director.main_loop_with_delta(0.008);
+
+        let stats = director.get_frame_stats();
+        assert!((stats.get_max_frame_time_ms() - 16.0).abs() < 1e-3);
+        assert!((stats.get_min_frame_time_ms() - 8.0).abs() < 1e-3);
+
+        assert!(!director.is_display_stats());
+        director.set_display_stats(true);
+        assert!(director.is_display_stats());
+    }
+
+    #[test]
+    fn test_top_left_origin_touch_mapping() {
+        let mut director = Director::new();
+        let touch = crate::input::Touch::new(1, crate::math::Vec2::ZERO);
+        assert_eq!(director.convert_touch_to_gl(&touch), crate::math::Vec2::new(0.0, 640.0));
+
+        director.set_origin_mode(OriginMode::TopLeft);
+        assert_eq!(director.convert_touch_to_gl(&touch), crate::math::Vec2::ZERO);
+        assert_eq!(director.get_visible_origin(), crate::math::Vec2::new(0.0, 640.0));
+
+        director.set_content_scale_factor(2.0);
+        let screen = crate::math::Vec2::new(100.0, 40.0);
+        let world = director.convert_to_gl(screen);
+        assert_eq!(world, crate::math::Vec2::new(50.0, 20.0));
+        assert_eq!(director.convert_to_ui(world), screen);
+    }
+
+    fn assert_close(actual: f32, expected: f32) {
+        assert!((actual - expected).abs() < 1e-3, "{} != {}", actual, expected);
+    }
+
+    #[test]
+    fn test_show_all_letterboxes() {
+        let mut director = Director::new();
+        director.set_frame_size(Size::new(800.0, 600.0));
+        director.set_design_resolution_size(960.0, 640.0, ResolutionPolicy::ShowAll);
+
+        assert_eq!(director.get_win_size(), Size::new(960.0, 640.0));
+        let visible = director.get_visible_size();
+        assert_close(visible.width, 960.0);
+        assert_close(visible.height, 640.0);
+        assert_eq!(director.get_visible_origin(), crate::math::Vec2::ZERO);
+
+        let viewport = director.get_viewport_rect();
+        assert_close(viewport.origin.x, 0.0);
+        assert_close(viewport.origin.y, 100.0 / 3.0);
+        assert_close(viewport.size.width, 800.0);
+        assert_close(viewport.size.height, 1600.0 / 3.0);
+
+        let center = director.convert_to_gl(crate::math::Vec2::new(400.0, 300.0));
+        assert_close(center.x, 480.0);
+        assert_close(center.y, 320.0);
+        let screen = director.convert_to_ui(center);
+        assert_close(screen.x, 400.0);
+        assert_close(screen.y, 300.0);
+    }
+
+    #[test]
+    fn test_fixed_width_adjusts_height() {
+        let mut director = Director::new();
+        director.set_frame_size(Size::new(800.0, 600.0));
+        director.set_design_resolution_size(960.0, 640.0, ResolutionPolicy::FixedWidth);
+
+        let visible = director.get_visible_size();
+        assert_close(visible.width, 960.0);
+        assert_close(visible.height, 720.0);
+        assert_close(director.get_win_size().height, 720.0);
+        assert_eq!(director.get_visible_origin(), crate::math::Vec2::ZERO);
+        assert_close(director.get_viewport_rect().size.height, 600.0);
+    }
+
+    #[test]
+    fn test_no_border_crops_visible_area() {
+        let mut director = Director::new();
+        director.set_frame_size(Size::new(800.0, 600.0));
+        director.set_design_resolution_size(960.0, 640.0, ResolutionPolicy::NoBorder);
+
+        let visible = director.get_visible_size();
+        assert_close(visible.width, 800.0 / 0.9375);
+        assert_close(visible.height, 640.0);
+        let origin = director.get_visible_origin();
+        assert_close(origin.x, (960.0 - 800.0 / 0.9375) / 2.0);
+        assert_close(origin.y, 0.0);
+    }
+
+    #[test]
+    fn test_projection_2d_bounds() {
+        let mut director = Director::new();
+        assert_eq!(
+            director.get_projection_2d(),
+            crate::math::Mat4::create_orthographic_off_center(0.0, 960.0, 0.0, 640.0, -1024.0, 1024.0)
+        );
+
+        director.set_origin_mode(OriginMode::TopLeft);
+        director.set_content_scale_factor(2.0);
+        assert_eq!(
+            director.get_projection_2d(),
+            crate::math::Mat4::create_orthographic_off_center(0.0, 480.0, 320.0, 0.0, -1024.0, 1024.0)
+        );
+    }
+
+    #[test]
+    fn test_view_projection_2d_applies_camera() {
+        let mut director = Director::new();
+        assert_eq!(director.get_view_projection_2d(), director.get_projection_2d());
+
+        director.get_camera_2d_mut().set_zoom(2.0);
+        let center = director.get_view_projection_2d().transform_point(&crate::math::Vec3::new(480.0, 320.0, 0.0));
+        assert!(center.x.abs() < 1e-5 && center.y.abs() < 1e-5);
+
+        let node = Node::create();
+        node.borrow_mut().set_position(crate::math::Vec2::new(680.0, 320.0));
+        director.get_camera_2d_mut().follow(&node, 0.5);
+        director.main_loop_with_delta(0.016);
+        assert_eq!(director.get_camera_2d().get_position(), crate::math::Vec2::new(580.0, 320.0));
+    }
+
+    #[test]
+    fn test_draw_scene_uses_camera_2d() {
+        let mut director = Director::new();
+        let mut scene = Scene::new();
+        scene.add_sprite(sprite_at(480.0, 320.0));
+        scene.add_sprite(sprite_at(100.0, 100.0));
+        director.run_scene(RefPtr::new(scene));
+        director.main_loop_with_delta(0.0);
+        director.get_camera_2d_mut().set_zoom(2.0);
+
+        let mut renderer = MockRenderer::default();
+        director.draw_scene(&mut renderer);
+        assert_eq!(renderer.view_projection, Some(director.get_view_projection_2d()));
+        assert_eq!(renderer.quads.len(), 1);
+    }
+
+    #[test]
+    fn test_frame_size_recenters_camera_2d() {
+        let mut director = Director::new();
+        director.set_frame_size(Size::new(1280.0, 720.0));
+        assert_eq!(director.get_camera_2d().get_position(), crate::math::Vec2::new(640.0, 360.0));
+        assert_eq!(director.get_visible_rect(), Rect::new(0.0, 0.0, 1280.0, 720.0));
+
+        director.get_camera_2d_mut().set_position(crate::math::Vec2::new(700.0, 360.0));
+        director.set_frame_size(Size::new(1000.0, 600.0));
+        assert_eq!(director.get_camera_2d().get_position(), crate::math::Vec2::new(560.0, 300.0));
+    }
+
+    #[test]
+    fn test_design_resolution_recenters_camera_2d() {
+        let mut director = Director::new();
+        director.set_frame_size(Size::new(800.0, 600.0));
+        director.set_design_resolution_size(480.0, 320.0, ResolutionPolicy::ShowAll);
+        assert_eq!(director.get_camera_2d().get_position(), crate::math::Vec2::new(240.0, 160.0));
+        assert_eq!(director.get_visible_rect(), Rect::new(0.0, 0.0, 480.0, 320.0));
+    }
+
+    fn effective_opacities(root: &Node) -> Vec<(String, u8)> {
+        let mut drawn = Vec::new();
+        root.visit_render(&mut |node, opacity, _| drawn.push((node.get_name().to_string(), opacity)));
+        drawn
+    }
+
+    fn with_opacity(name: &str, opacity: u8) -> Node {
+        let mut node = Node::new();
+        node.set_name(name.to_string());
+        node.set_opacity(opacity);
+        node
+    }
+
+    #[test]
+    fn test_opacity_cascades_to_children() {
+        let mut parent = with_opacity("parent", 128);
+        parent.add_child(with_opacity("opaque", 255).into_ref());
+        parent.add_child(with_opacity("faded", 128).into_ref());
+
+        assert_eq!(
+            effective_opacities(&parent),
+            vec![("parent".to_string(), 128), ("opaque".to_string(), 255), ("faded".to_string(), 128)]
+        );
+
+        parent.set_cascade_opacity_enabled(true);
+        assert_eq!(
+            effective_opacities(&parent),
+            vec![("parent".to_string(), 128), ("opaque".to_string(), 128), ("faded".to_string(), 64)]
+        );
+    }
+
+    #[test]
+    fn test_color_cascades_to_children() {
+        let mut parent = Node::new();
+        parent.set_color(Color3B::new(255, 0, 128));
+        parent.set_cascade_color_enabled(true);
+        parent.add_child(Node::create());
+
+        let mut colors = Vec::new();
+        parent.visit_render(&mut |_, _, color| colors.push(color));
+        assert_eq!(colors, vec![Color3B::new(255, 0, 128), Color3B::new(255, 0, 128)]);
+    }
+
+    #[test]
+    fn test_invisible_subtree_is_skipped() {
+        let mut hidden = with_opacity("hidden", 255);
+        hidden.set_visible(false);
+        hidden.add_child(with_opacity("hidden_child", 255).into_ref());
+        let mut root = with_opacity("root", 255);
+        root.add_child(hidden.into_ref());
+        root.add_child(with_opacity("shown", 255).into_ref());
+
+        let mut scene = Scene::new();
+        scene.add_child(root.into_ref());
+        let mut drawn = Vec::new();
+        scene.visit_render(&mut |node, _, _| drawn.push(node.get_name().to_string()));
+        assert_eq!(drawn, vec!["root".to_string(), "shown".to_string()]);
+    }
+}
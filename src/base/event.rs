@@ -360,3 +360,124 @@ impl EventDispatcher {
         }
     }
 }
+
+/// Handle returned by `EventBus::subscribe`, used to unsubscribe
+pub type SubscriptionId = usize;
+
+type TypedCallback = Box<dyn FnMut(&dyn std::any::Any)>;
+
+/// Typed event bus.
+///
+/// Events are plain structs keyed by their `TypeId`, so subscribers receive
+/// `&E` directly instead of downcasting `EventCustom` user data.
+pub struct EventBus {
+    subscribers: HashMap<std::any::TypeId, Vec<(SubscriptionId, TypedCallback)>>,
+    next_id: SubscriptionId,
+}
+
+impl EventBus {
+    pub fn new() -> EventBus {
+        EventBus {
+            subscribers: HashMap::new(),
+            next_id: 1,
+        }
+    }
+
+    /// Subscribes to events of type `E`
+    pub fn subscribe<E: 'static>(&mut self, mut callback: Box<dyn FnMut(&E)>) -> SubscriptionId {
+        let id = self.next_id;
+        self.next_id += 1;
+
+        let typed: TypedCallback = Box::new(move |event| {
+            if let Some(event) = event.downcast_ref::<E>() {
+                callback(event);
+            }
+        });
+        self.subscribers
+            .entry(std::any::TypeId::of::<E>())
+            .or_insert_with(Vec::new)
+            .push((id, typed));
+        id
+    }
+
+    /// Removes a subscription
+    pub fn unsubscribe(&mut self, id: SubscriptionId) {
+        for callbacks in self.subscribers.values_mut() {
+            callbacks.retain(|(subscription, _)| *subscription != id);
+        }
+        self.subscribers.retain(|_, callbacks| !callbacks.is_empty());
+    }
+
+    /// Publishes an event to every subscriber of type `E`
+    pub fn publish<E: 'static>(&mut self, event: &E) {
+        if let Some(callbacks) = self.subscribers.get_mut(&std::any::TypeId::of::<E>()) {
+            for (_, callback) in callbacks.iter_mut() {
+                callback(event);
+            }
+        }
+    }
+
+    /// Gets the number of subscribers for events of type `E`
+    pub fn get_subscriber_count<E: 'static>(&self) -> usize {
+        self.subscribers
+            .get(&std::any::TypeId::of::<E>())
+            .map_or(0, |callbacks| callbacks.len())
+    }
+}
+
+impl Default for EventBus {
+    fn default() -> Self {
+        EventBus::new()
+    }
+}
+
+impl std::fmt::Debug for EventBus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("EventBus")
+            .field("event_types", &self.subscribers.len())
+            .field("next_id", &self.next_id)
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::Cell;
+
+    struct PlayerDied {
+        score: u32,
+    }
+
+    struct LevelCompleted;
+
+    #[test]
+    fn test_publish_reaches_typed_subscriber() {
+        let mut bus = EventBus::new();
+        let received = Rc::new(Cell::new(None));
+        let completed = Rc::new(Cell::new(0));
+
+        let r = Rc::clone(&received);
+        bus.subscribe::<PlayerDied>(Box::new(move |event| r.set(Some(event.score))));
+        let c = Rc::clone(&completed);
+        bus.subscribe::<LevelCompleted>(Box::new(move |_| c.set(c.get() + 1)));
+
+        bus.publish(&PlayerDied { score: 1200 });
+        assert_eq!(received.get(), Some(1200));
+        assert_eq!(completed.get(), 0);
+    }
+
+    #[test]
+    fn test_unsubscribe() {
+        let mut bus = EventBus::new();
+        let count = Rc::new(Cell::new(0));
+        let c = Rc::clone(&count);
+        let id = bus.subscribe::<PlayerDied>(Box::new(move |_| c.set(c.get() + 1)));
+
+        bus.publish(&PlayerDied { score: 1 });
+        bus.unsubscribe(id);
+        bus.publish(&PlayerDied { score: 2 });
+        assert_eq!(count.get(), 1);
+        assert_eq!(bus.get_subscriber_count::<PlayerDied>(), 0);
+    }
+}
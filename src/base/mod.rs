@@ -4,7 +4,22 @@ pub mod director;
 pub mod event;
 pub mod scheduler;
 pub mod autorelease_pool;
+pub mod serialization;
+pub mod protocols;
+pub mod object_pool;
+pub mod state_machine;
+pub mod timer;
+pub mod camera_2d;
+pub mod timeline;
 
-pub use ref_count::{Ref, Clonable, RefPtr};
+pub use ref_count::{Ref, Clonable, RefPtr, WeakPtr};
 pub use types::{Color3B, Color4B, Color4F, Point, Size, Rect};
-pub use director::{Director, Scene, Node};
+pub use director::{Director, Scene, Node, NodeRef, NodeUpdateCallback, FrameStats, OriginMode, ResolutionPolicy, Clock, SystemClock};
+pub use event::{EventBus, SubscriptionId};
+pub use serialization::NodeData;
+pub use protocols::ColorProtocol;
+pub use object_pool::{ObjectPool, PooledRef, PoolOverflowPolicy};
+pub use state_machine::{State, StateMachine, TransitionTo};
+pub use timer::{Timer, TimerCompleteCallback, TimerTickCallback};
+pub use camera_2d::Camera2D;
+pub use timeline::{Timeline, TimelineCallback};
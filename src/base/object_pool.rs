@@ -0,0 +1,196 @@
+use std::cell::RefCell;
+use std::fmt;
+use std::ops::{Deref, DerefMut};
+use std::rc::Rc;
+
+/// What an `ObjectPool` does when every pooled object is in use
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PoolOverflowPolicy {
+    /// Create a new object, growing the pool's capacity
+    Grow,
+    /// Refuse to hand out more than `capacity` objects
+    Reject,
+}
+
+struct PoolState<T> {
+    available: Vec<T>,
+    factory: Box<dyn Fn() -> T>,
+    capacity: usize,
+    live_count: usize,
+    policy: PoolOverflowPolicy,
+}
+
+/// ObjectPool recycles objects that are expensive to create, such as
+/// particles and bullets.
+///
+/// Objects are handed out as `PooledRef` handles which put the object back
+/// into the pool when dropped.
+pub struct ObjectPool<T> {
+    state: Rc<RefCell<PoolState<T>>>,
+}
+
+impl<T> ObjectPool<T> {
+    /// Creates a pool holding `capacity` objects built by `factory`.
+    /// Acquiring beyond the capacity returns `None`.
+    pub fn new<F>(capacity: usize, factory: F) -> ObjectPool<T>
+    where
+        F: Fn() -> T + 'static,
+    {
+        ObjectPool::with_policy(capacity, factory, PoolOverflowPolicy::Reject)
+    }
+
+    /// Creates a pool with the given overflow policy
+    pub fn with_policy<F>(capacity: usize, factory: F, policy: PoolOverflowPolicy) -> ObjectPool<T>
+    where
+        F: Fn() -> T + 'static,
+    {
+        let available = (0..capacity).map(|_| factory()).collect();
+        ObjectPool {
+            state: Rc::new(RefCell::new(PoolState {
+                available,
+                factory: Box::new(factory),
+                capacity,
+                live_count: 0,
+                policy,
+            })),
+        }
+    }
+
+    /// Takes an object out of the pool, creating one if the pool is allowed to grow
+    pub fn acquire(&self) -> Option<PooledRef<T>> {
+        let mut state = self.state.borrow_mut();
+        let value = match state.available.pop() {
+            Some(value) => value,
+            None if state.policy == PoolOverflowPolicy::Grow => {
+                state.capacity += 1;
+                (state.factory)()
+            }
+            None => return None,
+        };
+        state.live_count += 1;
+
+        Some(PooledRef {
+            value: Some(value),
+            pool: Rc::clone(&self.state),
+        })
+    }
+
+    /// Gets the number of objects currently handed out
+    pub fn live_count(&self) -> usize {
+        self.state.borrow().live_count
+    }
+
+    /// Gets the number of objects waiting in the pool
+    pub fn available_count(&self) -> usize {
+        self.state.borrow().available.len()
+    }
+
+    /// Gets the total number of objects owned by the pool
+    pub fn get_capacity(&self) -> usize {
+        self.state.borrow().capacity
+    }
+
+    /// Gets the overflow policy
+    pub fn get_policy(&self) -> PoolOverflowPolicy {
+        self.state.borrow().policy
+    }
+}
+
+impl<T> fmt::Debug for ObjectPool<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let state = self.state.borrow();
+        f.debug_struct("ObjectPool")
+            .field("capacity", &state.capacity)
+            .field("live_count", &state.live_count)
+            .field("available_count", &state.available.len())
+            .field("policy", &state.policy)
+            .finish()
+    }
+}
+
+/// Handle to a pooled object. The object returns to its pool when the handle
+/// is dropped, even if the pool itself has already been dropped.
+pub struct PooledRef<T> {
+    value: Option<T>,
+    pool: Rc<RefCell<PoolState<T>>>,
+}
+
+impl<T> Deref for PooledRef<T> {
+    type Target = T;
+    fn deref(&self) -> &T {
+        self.value.as_ref().expect("PooledRef: value already returned")
+    }
+}
+
+impl<T> DerefMut for PooledRef<T> {
+    fn deref_mut(&mut self) -> &mut T {
+        self.value.as_mut().expect("PooledRef: value already returned")
+    }
+}
+
+impl<T> Drop for PooledRef<T> {
+    fn drop(&mut self) {
+        if let Some(value) = self.value.take() {
+            let mut state = self.pool.borrow_mut();
+            state.live_count -= 1;
+            state.available.push(value);
+        }
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for PooledRef<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("PooledRef").field(&self.value).finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Debug, Default)]
+    struct Bullet {
+        x: f32,
+    }
+
+    #[test]
+    fn test_dropped_handles_return_to_pool() {
+        let pool = ObjectPool::new(3, Bullet::default);
+        assert_eq!(pool.available_count(), 3);
+
+        let mut handles: Vec<_> = (0..3).map(|_| pool.acquire().unwrap()).collect();
+        handles[0].x = 5.0;
+        assert_eq!(pool.live_count(), 3);
+        assert_eq!(pool.available_count(), 0);
+
+        handles.pop();
+        assert_eq!(pool.live_count(), 2);
+        assert_eq!(pool.available_count(), 1);
+
+        handles.clear();
+        assert_eq!(pool.live_count(), 0);
+        assert_eq!(pool.available_count(), 3);
+    }
+
+    #[test]
+    fn test_reject_policy_returns_none() {
+        let pool = ObjectPool::new(1, Bullet::default);
+        let first = pool.acquire();
+        assert!(first.is_some());
+        assert!(pool.acquire().is_none());
+
+        drop(first);
+        assert!(pool.acquire().is_some());
+    }
+
+    #[test]
+    fn test_grow_policy_creates_objects() {
+        let pool = ObjectPool::with_policy(1, Bullet::default, PoolOverflowPolicy::Grow);
+        let handles: Vec<_> = (0..4).map(|_| pool.acquire().unwrap()).collect();
+        assert_eq!(pool.live_count(), 4);
+        assert_eq!(pool.get_capacity(), 4);
+
+        drop(handles);
+        assert_eq!(pool.available_count(), 4);
+    }
+}
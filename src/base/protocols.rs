@@ -0,0 +1,16 @@
+use crate::base::types::Color3B;
+
+/// Implemented by nodes whose color and opacity can be changed, such as sprites
+pub trait ColorProtocol {
+    /// Gets the color
+    fn get_color(&self) -> Color3B;
+
+    /// Sets the color
+    fn set_color(&mut self, color: Color3B);
+
+    /// Gets the opacity
+    fn get_opacity(&self) -> u8;
+
+    /// Sets the opacity
+    fn set_opacity(&mut self, opacity: u8);
+}
@@ -1,5 +1,5 @@
 use std::cell::Cell;
-use std::rc::Rc;
+use std::rc::{Rc, Weak};
 use std::fmt;
 
 /// Clonable trait for objects that can be cloned
@@ -90,11 +90,39 @@ impl<T> RefPtr<T> {
         }
     }
 
+    /// Creates a new RefPtr whose value can hold a weak reference to itself
+    pub fn new_cyclic<F>(data_fn: F) -> RefPtr<T>
+    where
+        F: FnOnce(&WeakPtr<T>) -> T,
+    {
+        RefPtr {
+            ptr: Rc::new_cyclic(|weak| data_fn(&WeakPtr { ptr: weak.clone() })),
+        }
+    }
+
+    /// Creates a weak reference that does not keep the object alive
+    pub fn downgrade(&self) -> WeakPtr<T> {
+        WeakPtr {
+            ptr: Rc::downgrade(&self.ptr),
+        }
+    }
+
+    /// Checks if two pointers refer to the same object
+    pub fn ptr_eq(a: &RefPtr<T>, b: &RefPtr<T>) -> bool {
+        Rc::ptr_eq(&a.ptr, &b.ptr)
+    }
+
     /// Gets a reference to the underlying value
     pub fn borrow(&self) -> &T {
         &self.ptr
     }
 
+    /// Gets a mutable reference to the underlying value, or None while
+    /// other pointers share it
+    pub fn get_mut(ptr: &mut RefPtr<T>) -> Option<&mut T> {
+        Rc::get_mut(&mut ptr.ptr)
+    }
+
     /// Gets a mutable reference to the underlying value
     pub fn borrow_mut(&mut self) -> &mut T {
         Rc::get_mut(&mut self.ptr).unwrap()
@@ -102,9 +130,12 @@ impl<T> RefPtr<T> {
 
     /// Gets the reference count
     pub fn get_reference_count(&self) -> u32 {
-        // For Rc, we can't directly get the reference count from outside
-        // But we can track it internally if needed
-        1 // Placeholder
+        Rc::strong_count(&self.ptr) as u32
+    }
+
+    /// Gets the number of weak references
+    pub fn get_weak_count(&self) -> u32 {
+        Rc::weak_count(&self.ptr) as u32
     }
 
     /// Retains the reference count
@@ -171,3 +202,122 @@ impl<T> From<RefPtr<T>> for Rc<T> {
         ptr.ptr
     }
 }
+
+/// A weak reference to an object owned by one or more RefPtrs
+///
+/// Used for back references such as a node's parent, so that parent/child
+/// links do not form reference cycles.
+#[derive(Debug)]
+pub struct WeakPtr<T> {
+    ptr: Weak<T>,
+}
+
+impl<T> WeakPtr<T> {
+    /// Creates a weak reference that points to nothing
+    pub fn new() -> WeakPtr<T> {
+        WeakPtr { ptr: Weak::new() }
+    }
+
+    /// Gets a strong reference if the object is still alive
+    pub fn upgrade(&self) -> Option<RefPtr<T>> {
+        self.ptr.upgrade().map(|ptr| RefPtr { ptr })
+    }
+
+    /// Gets the number of strong references to the object
+    pub fn get_reference_count(&self) -> u32 {
+        self.ptr.strong_count() as u32
+    }
+}
+
+impl<T> Clone for WeakPtr<T> {
+    fn clone(&self) -> WeakPtr<T> {
+        WeakPtr {
+            ptr: self.ptr.clone(),
+        }
+    }
+}
+
+impl<T> Default for WeakPtr<T> {
+    fn default() -> Self {
+        WeakPtr::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::base::Node;
+
+    struct Tracked {
+        parent: Option<WeakPtr<Tracked>>,
+        children: Vec<RefPtr<Tracked>>,
+        drops: Rc<Cell<u32>>,
+    }
+
+    impl Tracked {
+        fn new(drops: &Rc<Cell<u32>>) -> Tracked {
+            Tracked {
+                parent: None,
+                children: Vec::new(),
+                drops: drops.clone(),
+            }
+        }
+    }
+
+    impl Drop for Tracked {
+        fn drop(&mut self) {
+            self.drops.set(self.drops.get() + 1);
+        }
+    }
+
+    #[test]
+    fn test_downgrade_and_upgrade() {
+        let strong = RefPtr::new(7);
+        let weak = strong.downgrade();
+        assert_eq!(strong.get_reference_count(), 1);
+        assert_eq!(strong.get_weak_count(), 1);
+
+        let upgraded = weak.upgrade().unwrap();
+        assert!(RefPtr::ptr_eq(&strong, &upgraded));
+        assert_eq!(weak.get_reference_count(), 2);
+
+        drop(upgraded);
+        drop(strong);
+        assert!(weak.upgrade().is_none());
+        assert!(WeakPtr::<i32>::new().upgrade().is_none());
+    }
+
+    #[test]
+    fn test_parent_child_cycle_is_freed() {
+        let drops = Rc::new(Cell::new(0));
+        let parent = RefPtr::new_cyclic(|weak| {
+            let mut child = Tracked::new(&drops);
+            child.parent = Some(weak.clone());
+            let mut parent = Tracked::new(&drops);
+            parent.children.push(RefPtr::new(child));
+            parent
+        });
+
+        let child = &parent.children[0];
+        assert!(RefPtr::ptr_eq(&child.parent.as_ref().unwrap().upgrade().unwrap(), &parent));
+        assert_eq!(drops.get(), 0);
+
+        drop(parent);
+        assert_eq!(drops.get(), 2);
+    }
+
+    #[test]
+    fn test_node_parent_is_weak() {
+        let parent = Node::create();
+        parent.borrow_mut().add_child(Node::create());
+
+        let child = Rc::downgrade(&parent.borrow().get_children()[0]);
+        let child_parent = child.upgrade().unwrap().borrow().get_parent().unwrap();
+        assert!(Rc::ptr_eq(&child_parent, &parent));
+        drop(child_parent);
+        assert_eq!(Rc::strong_count(&parent), 1);
+
+        drop(parent);
+        assert!(child.upgrade().is_none());
+    }
+}
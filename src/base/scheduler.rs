@@ -1,6 +1,7 @@
+use std::cell::RefCell;
 use std::collections::HashMap;
 use std::rc::Rc;
-use crate::base::{Ref, RefPtr};
+use crate::base::{Director, Ref, RefPtr};
 
 /// Timer callback function type
 pub type TimerCallback = Rc<dyn Fn(f32)>;
@@ -11,7 +12,7 @@ pub type ScheduleCallback = Rc<dyn Fn(f32)>;
 /// Scheduler is responsible for triggering the scheduled callbacks.
 ///
 /// You should not invoke this manually. If you want to execute a function after
-/// a delay, use `director.get_scheduler().schedule()`.
+/// a delay, use `director.get_scheduler().borrow_mut().schedule()`.
 impl std::fmt::Debug for Scheduler {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         f.debug_struct("Scheduler")
@@ -19,6 +20,7 @@ impl std::fmt::Debug for Scheduler {
             .field("paused", &self.paused)
             .field("timers_count", &self.timers.len())
             .field("callbacks_count", &self.schedule_callbacks.len())
+            .field("updates_count", &self.update_hash.len())
             .finish()
     }
 }
@@ -28,7 +30,6 @@ pub struct Scheduler {
     schedule_callbacks: HashMap<String, ScheduleCallback>,
     time_scale: f32,
     paused: bool,
-    #[allow(dead_code)]
     update_hash: HashMap<usize, UpdateEntry>,
 }
 
@@ -47,6 +48,27 @@ struct UpdateEntry {
 }
 
 impl Scheduler {
+    /// Gets the scheduler of the current thread, which the director ticks
+    /// and nodes register their per-frame updates with.
+    ///
+    /// Advance it with `update_instance`, which does not keep it borrowed
+    /// while callbacks run, so callbacks can schedule and unschedule through
+    /// this handle.
+    pub fn get_instance() -> Rc<RefCell<Scheduler>> {
+        thread_local! {
+            static SCHEDULER: Rc<RefCell<Scheduler>> = Rc::new(RefCell::new(Scheduler::new()));
+        }
+        SCHEDULER.with(Rc::clone)
+    }
+
+    /// Updates the scheduler of the current thread
+    pub fn update_instance(delta_time: f32) {
+        let calls = Scheduler::get_instance().borrow_mut().step(delta_time);
+        for (callback, delta_time) in calls {
+            callback(delta_time);
+        }
+    }
+
     /// Creates a new scheduler
     pub fn new() -> Scheduler {
         Scheduler {
@@ -108,14 +130,86 @@ impl Scheduler {
         self.schedule_callbacks.clear();
     }
 
+    /// Schedules a callback that runs every frame for the given target.
+    ///
+    /// Entries with a lower priority run first. Scheduling a target again
+    /// replaces its previous entry. Targets outside the subtree kept by
+    /// `Director::pause_all_except` start paused.
+    pub fn schedule_update(&mut self, target: usize, priority: i32, callback: ScheduleCallback) {
+        let entry = UpdateEntry {
+            callback,
+            paused: Director::hold_new_update_target(target),
+            priority,
+        };
+        self.update_hash.insert(target, entry);
+    }
+
+    /// Unschedules the per-frame callback of the given target
+    pub fn unschedule_update(&mut self, target: usize) {
+        self.update_hash.remove(&target);
+    }
+
+    /// Checks if the given target has a per-frame callback
+    pub fn is_update_scheduled(&self, target: usize) -> bool {
+        self.update_hash.contains_key(&target)
+    }
+
+    /// Pauses or resumes the per-frame callback of the given target
+    pub fn set_update_paused(&mut self, target: usize, paused: bool) {
+        if let Some(entry) = self.update_hash.get_mut(&target) {
+            entry.paused = paused;
+        }
+    }
+
+    /// Pauses the per-frame callbacks of every target except `excluded`,
+    /// returning the targets this call paused. Targets that were already
+    /// paused are left out.
+    pub fn pause_all_updates_except(&mut self, excluded: &[usize]) -> Vec<usize> {
+        let mut paused = Vec::new();
+        for (target, entry) in self.update_hash.iter_mut() {
+            if !entry.paused && !excluded.contains(target) {
+                entry.paused = true;
+                paused.push(*target);
+            }
+        }
+        paused
+    }
+
+    /// Resumes the per-frame callbacks of the given targets
+    pub fn resume_updates(&mut self, targets: &[usize]) {
+        for target in targets {
+            self.set_update_paused(*target, false);
+        }
+    }
+
     /// Updates the scheduler
     pub fn update(&mut self, delta_time: f32) {
+        for (callback, delta_time) in self.step(delta_time) {
+            callback(delta_time);
+        }
+    }
+
+    /// Advances the timers by `delta_time` and returns the callbacks due this
+    /// frame with the delta each receives: per-frame updates in priority
+    /// order, then the timers that fired. Finished timers are removed.
+    fn step(&mut self, delta_time: f32) -> Vec<(ScheduleCallback, f32)> {
         if self.paused {
-            return;
+            return Vec::new();
         }
 
         let scaled_delta = delta_time * self.time_scale;
 
+        // Per-frame updates in priority order
+        let mut updates: Vec<(i32, Rc<dyn Fn(f32)>)> = self
+            .update_hash
+            .values()
+            .filter(|entry| !entry.paused)
+            .map(|entry| (entry.priority, Rc::clone(&entry.callback)))
+            .collect();
+        updates.sort_by_key(|(priority, _)| *priority);
+        let mut calls: Vec<(ScheduleCallback, f32)> =
+            updates.into_iter().map(|(_, callback)| (callback, scaled_delta)).collect();
+
         // Update timers
         let mut timers_to_remove = Vec::new();
 
@@ -126,9 +220,8 @@ impl Scheduler {
                 if timer.elapsed >= timer.interval {
                     timer.elapsed -= timer.interval;
 
-                    // Execute callback
                     if let Some(callback) = self.schedule_callbacks.get(key) {
-                        callback(timer.interval);
+                        calls.push((Rc::clone(callback), timer.interval));
                     }
 
                     timer.repeat -= 1;
@@ -144,6 +237,7 @@ impl Scheduler {
             self.timers.remove(&key);
             self.schedule_callbacks.remove(&key);
         }
+        calls
     }
 
     /// Performs a function in the main thread
@@ -0,0 +1,227 @@
+use serde::{Deserialize, Serialize};
+use crate::base::{Node, NodeRef, RefPtr, Scene};
+use crate::math::Vec2;
+use crate::sprite::Sprite;
+
+/// Serializable snapshot of a node and its subtree.
+///
+/// Nodes carrying a `texture` are rebuilt as sprites through `Sprite::with_file`.
+/// Children, including sprites, are stored in draw order.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct NodeData {
+    #[serde(default)]
+    pub name: String,
+    #[serde(default)]
+    pub tag: i32,
+    #[serde(default)]
+    pub position: [f32; 2],
+    #[serde(default)]
+    pub rotation: f32,
+    #[serde(default)]
+    pub local_z_order: i32,
+    #[serde(default = "default_scale")]
+    pub scale: [f32; 2],
+    #[serde(default = "default_visible")]
+    pub visible: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub texture: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub children: Vec<NodeData>,
+}
+
+fn default_scale() -> [f32; 2] {
+    [1.0, 1.0]
+}
+
+fn default_visible() -> bool {
+    true
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct SceneData {
+    #[serde(default)]
+    children: Vec<NodeData>,
+}
+
+impl NodeData {
+    /// Captures a node, its children and its sprites
+    pub fn from_node(node: &Node) -> NodeData {
+        let position = node.get_position();
+        NodeData {
+            name: node.get_name().to_string(),
+            tag: node.get_tag(),
+            position: [position.x, position.y],
+            rotation: node.get_rotation(),
+            local_z_order: node.get_local_z_order(),
+            scale: [node.get_scale_x(), node.get_scale_y()],
+            visible: node.is_visible(),
+            texture: None,
+            children: capture_children(node.get_children(), node.get_sprites()),
+        }
+    }
+
+    /// Captures a sprite, recording its texture path
+    pub fn from_sprite(sprite: &Sprite) -> NodeData {
+        let mut data = NodeData::from_node(sprite.get_node());
+        data.texture = sprite
+            .get_texture()
+            .map(|texture| texture.get_path().to_string());
+        data
+    }
+
+    /// Rebuilds a plain node from this snapshot
+    pub fn to_node(&self) -> Result<Node, String> {
+        let mut node = Node::new();
+        self.apply(&mut node)?;
+        Ok(node)
+    }
+
+    /// Rebuilds a sprite from this snapshot
+    pub fn to_sprite(&self) -> Result<Sprite, String> {
+        let path = self
+            .texture
+            .as_deref()
+            .ok_or_else(|| format!("node '{}' has no texture", self.name))?;
+        let mut sprite = Sprite::with_file(path)
+            .ok_or_else(|| format!("failed to load sprite texture '{}'", path))?;
+        self.apply(sprite.get_node_mut())?;
+        Ok(sprite)
+    }
+
+    fn apply(&self, node: &mut Node) -> Result<(), String> {
+        node.set_name(self.name.clone());
+        node.set_tag(self.tag);
+        node.set_position(Vec2::new(self.position[0], self.position[1]));
+        node.set_rotation(self.rotation);
+        node.set_local_z_order(self.local_z_order);
+        node.set_scale_xy(self.scale[0], self.scale[1]);
+        node.set_visible(self.visible);
+        for child in &self.children {
+            if child.texture.is_some() {
+                node.add_sprite(RefPtr::new(child.to_sprite()?));
+            } else {
+                node.add_child(child.to_node()?.into_ref());
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Captures children and sprites in draw order: by local z-order, children
+/// before sprites on ties
+fn capture_children(children: &[NodeRef], sprites: &[RefPtr<Sprite>]) -> Vec<NodeData> {
+    let mut captured: Vec<NodeData> = children
+        .iter()
+        .map(|child| NodeData::from_node(&child.borrow()))
+        .chain(sprites.iter().map(|sprite| NodeData::from_sprite(sprite)))
+        .collect();
+    captured.sort_by_key(|data| data.local_z_order);
+    captured
+}
+
+impl Scene {
+    /// Serializes the scene's nodes and sprites to JSON
+    pub fn to_json(&self) -> String {
+        let children = capture_children(self.get_children(), self.get_sprites());
+        serde_json::to_string_pretty(&SceneData { children })
+            .expect("scene data is always serializable")
+    }
+
+    /// Rebuilds a scene from JSON produced by `to_json`
+    pub fn from_json(json: &str) -> Result<Scene, String> {
+        let data: SceneData = serde_json::from_str(json).map_err(|e| e.to_string())?;
+
+        let mut scene = Scene::new();
+        for child in &data.children {
+            if child.texture.is_some() {
+                scene.add_sprite(RefPtr::new(child.to_sprite()?));
+            } else {
+                scene.add_child(child.to_node()?.into_ref());
+            }
+        }
+        Ok(scene)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn positioned_sprite(path: &str, x: f32, y: f32, tag: i32) -> RefPtr<Sprite> {
+        let mut sprite = Sprite::with_file(path).unwrap();
+        sprite.get_node_mut().set_position(Vec2::new(x, y));
+        sprite.get_node_mut().set_tag(tag);
+        RefPtr::new(sprite)
+    }
+
+    #[test]
+    fn test_scene_round_trip() {
+        let mut scene = Scene::new();
+        scene.add_sprite(positioned_sprite("hero.png", 10.0, 20.0, 1));
+        scene.add_sprite(positioned_sprite("enemy.png", -5.5, 300.0, 2));
+
+        let json = scene.to_json();
+        let restored = Scene::from_json(&json).unwrap();
+
+        let sprites = restored.get_sprites();
+        assert_eq!(sprites.len(), 2);
+        assert_eq!(*sprites[0].get_node().get_position(), Vec2::new(10.0, 20.0));
+        assert_eq!(sprites[0].get_node().get_tag(), 1);
+        assert_eq!(sprites[0].get_texture().unwrap().get_path(), "hero.png");
+        assert_eq!(*sprites[1].get_node().get_position(), Vec2::new(-5.5, 300.0));
+        assert_eq!(sprites[1].get_node().get_tag(), 2);
+        assert_eq!(sprites[1].get_texture().unwrap().get_path(), "enemy.png");
+    }
+
+    #[test]
+    fn test_round_trip_keeps_draw_order_and_child_sprites() {
+        let mut group = Node::new();
+        group.set_name("group".to_string());
+        group.set_local_z_order(2);
+        group.add_sprite(positioned_sprite("leaf.png", 1.0, 2.0, 7));
+        let mut inner = Node::new();
+        inner.set_name("inner".to_string());
+        group.add_child(inner.into_ref());
+
+        let mut scene = Scene::new();
+        scene.add_sprite(positioned_sprite("back.png", 0.0, 0.0, 1));
+        scene.add_child(group.into_ref());
+        let mut front = Sprite::with_file("front.png").unwrap();
+        front.get_node_mut().set_local_z_order(5);
+        scene.add_sprite(RefPtr::new(front));
+
+        let json = scene.to_json();
+        let data: SceneData = serde_json::from_str(&json).unwrap();
+        let order: Vec<i32> = data.children.iter().map(|child| child.local_z_order).collect();
+        assert_eq!(order, vec![0, 2, 5]);
+        assert_eq!(data.children[0].texture.as_deref(), Some("back.png"));
+        assert_eq!(data.children[1].name, "group");
+
+        let restored = Scene::from_json(&json).unwrap();
+        assert_eq!(restored.to_json(), json);
+        let group = restored.get_children()[0].borrow();
+        assert_eq!(group.get_local_z_order(), 2);
+        assert_eq!(group.get_children().len(), 1);
+        assert_eq!(group.get_sprites().len(), 1);
+        let leaf = &group.get_sprites()[0];
+        assert_eq!(leaf.get_texture().unwrap().get_path(), "leaf.png");
+        assert_eq!(leaf.get_node().get_tag(), 7);
+        let sprites = restored.get_sprites();
+        assert_eq!(sprites[1].get_node().get_local_z_order(), 5);
+        assert_eq!(sprites[1].get_texture().unwrap().get_path(), "front.png");
+    }
+
+    #[test]
+    fn test_node_data_defaults() {
+        let data: NodeData = serde_json::from_str(r#"{"name": "empty"}"#).unwrap();
+        let node = data.to_node().unwrap();
+        assert_eq!(node.get_name(), "empty");
+        assert_eq!(node.get_scale_x(), 1.0);
+        assert!(node.is_visible());
+    }
+
+    #[test]
+    fn test_from_json_rejects_invalid_input() {
+        assert!(Scene::from_json("not json").is_err());
+    }
+}
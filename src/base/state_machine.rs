@@ -0,0 +1,145 @@
+/// Request returned from `State::update` to switch to another state
+#[derive(Debug, Clone, PartialEq)]
+pub struct TransitionTo<S>(pub S);
+
+/// A state of a `StateMachine`
+pub trait State: Sized {
+    /// Called when the machine enters this state
+    fn on_enter(&mut self) {}
+
+    /// Called when the machine leaves this state
+    fn on_exit(&mut self) {}
+
+    /// Advances the state, optionally requesting a transition
+    fn update(&mut self, delta_time: f32) -> Option<TransitionTo<Self>>;
+}
+
+/// StateMachine runs one `State` at a time and calls the exit and enter
+/// hooks when switching between them.
+///
+/// Unlike the director's scene stack, a machine lives inside a scene and
+/// is driven by whoever owns it, e.g. from a node's update callback.
+#[derive(Debug)]
+pub struct StateMachine<S: State> {
+    current: S,
+}
+
+impl<S: State> StateMachine<S> {
+    /// Creates a machine and enters the initial state
+    pub fn new(mut initial: S) -> StateMachine<S> {
+        initial.on_enter();
+        StateMachine { current: initial }
+    }
+
+    /// Gets the current state
+    pub fn current_state(&self) -> &S {
+        &self.current
+    }
+
+    /// Gets the current state mutably
+    pub fn current_state_mut(&mut self) -> &mut S {
+        &mut self.current
+    }
+
+    /// Updates the current state and performs the transition it requests.
+    /// Returns true when the state changed.
+    pub fn update(&mut self, delta_time: f32) -> bool {
+        match self.current.update(delta_time) {
+            Some(TransitionTo(next)) => {
+                self.transition_to(next);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Leaves the current state and enters `next`
+    pub fn transition_to(&mut self, mut next: S) {
+        self.current.on_exit();
+        next.on_enter();
+        self.current = next;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    type Log = Rc<RefCell<Vec<String>>>;
+
+    #[derive(Debug)]
+    enum GameState {
+        Intro { log: Log, remaining: f32 },
+        Playing { log: Log },
+    }
+
+    impl GameState {
+        fn name(&self) -> &'static str {
+            match self {
+                GameState::Intro { .. } => "intro",
+                GameState::Playing { .. } => "playing",
+            }
+        }
+
+        fn log(&self) -> &Log {
+            match self {
+                GameState::Intro { log, .. } | GameState::Playing { log } => log,
+            }
+        }
+    }
+
+    impl State for GameState {
+        fn on_enter(&mut self) {
+            let entry = format!("enter {}", self.name());
+            self.log().borrow_mut().push(entry);
+        }
+
+        fn on_exit(&mut self) {
+            let entry = format!("exit {}", self.name());
+            self.log().borrow_mut().push(entry);
+        }
+
+        fn update(&mut self, delta_time: f32) -> Option<TransitionTo<Self>> {
+            match self {
+                GameState::Intro { log, remaining } => {
+                    *remaining -= delta_time;
+                    if *remaining <= 0.0 {
+                        Some(TransitionTo(GameState::Playing { log: Rc::clone(log) }))
+                    } else {
+                        None
+                    }
+                }
+                GameState::Playing { .. } => None,
+            }
+        }
+    }
+
+    #[test]
+    fn test_transition_fires_exit_then_enter() {
+        let log: Log = Rc::new(RefCell::new(Vec::new()));
+        let mut machine = StateMachine::new(GameState::Intro { log: Rc::clone(&log), remaining: 1.0 });
+        assert_eq!(machine.current_state().name(), "intro");
+
+        assert!(!machine.update(0.5));
+        assert_eq!(machine.current_state().name(), "intro");
+
+        assert!(machine.update(0.5));
+        assert_eq!(machine.current_state().name(), "playing");
+        assert_eq!(*log.borrow(), vec!["enter intro", "exit intro", "enter playing"]);
+
+        assert!(!machine.update(1.0));
+        assert_eq!(log.borrow().len(), 3);
+    }
+
+    #[test]
+    fn test_explicit_transition() {
+        let log: Log = Rc::new(RefCell::new(Vec::new()));
+        let mut machine = StateMachine::new(GameState::Playing { log: Rc::clone(&log) });
+        machine.transition_to(GameState::Intro { log: Rc::clone(&log), remaining: 2.0 });
+
+        assert_eq!(machine.current_state().name(), "intro");
+        assert_eq!(*log.borrow(), vec!["enter playing", "exit playing", "enter intro"]);
+    }
+}
@@ -0,0 +1,174 @@
+/// Step run when the timeline reaches its time
+pub type TimelineCallback = Box<dyn FnMut()>;
+
+struct TimelineStep {
+    time: f32,
+    callback: TimelineCallback,
+}
+
+/// Timeline scripts a sequence of callbacks across frames without building
+/// an action tree.
+///
+/// Steps are kept ordered by their time from the start of the timeline and
+/// fire, in order, once `update` has accumulated that much time. Steps at
+/// the same time fire in the order they were added.
+///
+/// ```ignore
+/// let mut timeline = Timeline::new();
+/// timeline.append(0.5, Box::new(|| show_title()));
+/// timeline.append(1.0, Box::new(|| start_music()));
+/// ```
+pub struct Timeline {
+    steps: Vec<TimelineStep>,
+    next: usize,
+    elapsed: f32,
+}
+
+impl std::fmt::Debug for Timeline {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Timeline")
+            .field("step_times", &self.step_times())
+            .field("next", &self.next)
+            .field("elapsed", &self.elapsed)
+            .finish()
+    }
+}
+
+impl Timeline {
+    /// Creates an empty timeline
+    pub fn new() -> Timeline {
+        Timeline {
+            steps: Vec::new(),
+            next: 0,
+            elapsed: 0.0,
+        }
+    }
+
+    /// Adds a step `delay` seconds after the last step in the timeline
+    pub fn append(&mut self, delay: f32, callback: TimelineCallback) {
+        let time = self.steps.last().map_or(0.0, |step| step.time) + delay.max(0.0);
+        self.steps.push(TimelineStep { time, callback });
+    }
+
+    /// Adds a step at `time` seconds from the start of the timeline, after
+    /// any step already at that time. A step whose time has already passed
+    /// fires on the next `update`.
+    pub fn insert_at(&mut self, time: f32, callback: TimelineCallback) {
+        let time = time.max(0.0);
+        let index = self.steps.partition_point(|step| step.time <= time).max(self.next);
+        self.steps.insert(index, TimelineStep { time, callback });
+    }
+
+    /// Removes every step and rewinds the timeline to its start
+    pub fn clear(&mut self) {
+        self.steps.clear();
+        self.next = 0;
+        self.elapsed = 0.0;
+    }
+
+    /// Gets the time from the start of the timeline of every step, in
+    /// firing order
+    pub fn step_times(&self) -> Vec<f32> {
+        self.steps.iter().map(|step| step.time).collect()
+    }
+
+    /// Gets the time accumulated by `update`
+    pub fn get_elapsed(&self) -> f32 {
+        self.elapsed
+    }
+
+    /// Gets the number of steps that have not fired yet
+    pub fn get_pending_count(&self) -> usize {
+        self.steps.len() - self.next
+    }
+
+    /// Checks if every step has fired
+    pub fn is_finished(&self) -> bool {
+        self.next == self.steps.len()
+    }
+
+    /// Advances the timeline, firing the steps whose time has come
+    pub fn update(&mut self, delta_time: f32) {
+        self.elapsed += delta_time;
+        while let Some(step) = self.steps.get_mut(self.next) {
+            if step.time > self.elapsed {
+                break;
+            }
+            self.next += 1;
+            (step.callback)();
+        }
+    }
+}
+
+impl Default for Timeline {
+    fn default() -> Timeline {
+        Timeline::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    fn logging_step(log: &Rc<RefCell<Vec<&'static str>>>, name: &'static str) -> TimelineCallback {
+        let log = Rc::clone(log);
+        Box::new(move || log.borrow_mut().push(name))
+    }
+
+    #[test]
+    fn test_steps_fire_at_cumulative_times() {
+        let log = Rc::new(RefCell::new(Vec::new()));
+        let mut timeline = Timeline::new();
+        timeline.append(0.1, logging_step(&log, "a"));
+        timeline.append(0.2, logging_step(&log, "b"));
+        timeline.append(0.3, logging_step(&log, "c"));
+        assert_eq!(timeline.step_times().len(), 3);
+
+        timeline.update(0.05);
+        assert!(log.borrow().is_empty());
+        timeline.update(0.05);
+        assert_eq!(*log.borrow(), vec!["a"]);
+        timeline.update(0.15);
+        assert_eq!(*log.borrow(), vec!["a"]);
+        timeline.update(0.1);
+        assert_eq!(*log.borrow(), vec!["a", "b"]);
+        timeline.update(0.2);
+        assert_eq!(*log.borrow(), vec!["a", "b"]);
+        timeline.update(0.1);
+        assert_eq!(*log.borrow(), vec!["a", "b", "c"]);
+        assert!(timeline.is_finished());
+    }
+
+    #[test]
+    fn test_insert_at_orders_step() {
+        let log = Rc::new(RefCell::new(Vec::new()));
+        let mut timeline = Timeline::new();
+        timeline.append(0.1, logging_step(&log, "a"));
+        timeline.append(0.2, logging_step(&log, "b"));
+        timeline.insert_at(0.2, logging_step(&log, "x"));
+        timeline.insert_at(0.3, logging_step(&log, "y"));
+        assert_eq!(timeline.step_times().len(), 4);
+
+        timeline.update(0.2);
+        assert_eq!(*log.borrow(), vec!["a", "x"]);
+        timeline.update(0.2);
+        assert_eq!(*log.borrow(), vec!["a", "x", "b", "y"]);
+    }
+
+    #[test]
+    fn test_clear_rewinds() {
+        let log = Rc::new(RefCell::new(Vec::new()));
+        let mut timeline = Timeline::new();
+        timeline.append(0.5, logging_step(&log, "a"));
+        timeline.update(0.25);
+        timeline.clear();
+        assert_eq!(timeline.get_elapsed(), 0.0);
+        assert_eq!(timeline.get_pending_count(), 0);
+
+        timeline.append(0.5, logging_step(&log, "b"));
+        timeline.update(0.5);
+        assert_eq!(*log.borrow(), vec!["b"]);
+    }
+}
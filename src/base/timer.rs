@@ -0,0 +1,222 @@
+use std::cell::RefCell;
+use std::rc::Rc;
+use crate::base::scheduler::Scheduler;
+
+/// Called once when a timer reaches zero
+pub type TimerCompleteCallback = Box<dyn FnMut()>;
+
+/// Called with the whole seconds left each time a countdown crosses a second
+pub type TimerTickCallback = Box<dyn FnMut(u32)>;
+
+/// Timer counts down from a duration and fires a callback once at zero.
+///
+/// It is advanced by `update`, usually through `Timer::schedule` so the
+/// thread's scheduler drives it every frame. Callbacks run while the timer
+/// is borrowed, so they must not access the timer's own handle.
+pub struct Timer {
+    duration: f32,
+    remaining: f32,
+    paused: bool,
+    finished: bool,
+    on_complete: Option<TimerCompleteCallback>,
+    on_tick: Option<TimerTickCallback>,
+}
+
+impl std::fmt::Debug for Timer {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Timer")
+            .field("duration", &self.duration)
+            .field("remaining", &self.remaining)
+            .field("paused", &self.paused)
+            .field("finished", &self.finished)
+            .field("has_on_complete", &self.on_complete.is_some())
+            .field("has_on_tick", &self.on_tick.is_some())
+            .finish()
+    }
+}
+
+impl Timer {
+    /// Creates a running timer of `duration` seconds
+    pub fn new(duration: f32, on_complete: TimerCompleteCallback) -> Timer {
+        let duration = duration.max(0.0);
+        Timer {
+            duration,
+            remaining: duration,
+            paused: false,
+            finished: false,
+            on_complete: Some(on_complete),
+            on_tick: None,
+        }
+    }
+
+    /// Schedules the timer's update on the thread's scheduler. The entry is
+    /// keyed by the timer's handle, so `Scheduler::unschedule_update` with
+    /// `Rc::as_ptr(timer) as usize` removes it.
+    ///
+    /// The entry holds the timer weakly and removes itself once the timer
+    /// finishes or is dropped; schedule it again after `reset` to reuse it.
+    pub fn schedule(timer: &Rc<RefCell<Timer>>) {
+        let target = Rc::as_ptr(timer) as usize;
+        let weak = Rc::downgrade(timer);
+        Scheduler::get_instance().borrow_mut().schedule_update(
+            target,
+            0,
+            Rc::new(move |dt| {
+                let finished = match weak.upgrade() {
+                    Some(timer) => {
+                        let mut timer = timer.borrow_mut();
+                        timer.update(dt);
+                        timer.finished
+                    }
+                    None => true,
+                };
+                if finished {
+                    if let Ok(mut scheduler) = Scheduler::get_instance().try_borrow_mut() {
+                        scheduler.unschedule_update(target);
+                    }
+                }
+            }),
+        );
+    }
+
+    /// Sets the callback fired each time the countdown crosses a whole second
+    pub fn set_on_tick(&mut self, on_tick: TimerTickCallback) {
+        self.on_tick = Some(on_tick);
+    }
+
+    /// Gets the full duration
+    pub fn get_duration(&self) -> f32 {
+        self.duration
+    }
+
+    /// Gets the seconds left before the timer fires
+    pub fn remaining(&self) -> f32 {
+        self.remaining
+    }
+
+    /// Pauses the countdown
+    pub fn pause(&mut self) {
+        self.paused = true;
+    }
+
+    /// Resumes the countdown
+    pub fn resume(&mut self) {
+        self.paused = false;
+    }
+
+    /// Checks if the countdown is paused
+    pub fn is_paused(&self) -> bool {
+        self.paused
+    }
+
+    /// Checks if the timer has reached zero
+    pub fn is_finished(&self) -> bool {
+        self.finished
+    }
+
+    /// Restores the full duration so the timer can fire again
+    pub fn reset(&mut self) {
+        self.remaining = self.duration;
+        self.finished = false;
+    }
+
+    /// Advances the countdown
+    pub fn update(&mut self, delta_time: f32) {
+        if self.paused || self.finished {
+            return;
+        }
+
+        let previous_seconds = self.remaining.ceil() as u32;
+        self.remaining = (self.remaining - delta_time).max(0.0);
+        let seconds = self.remaining.ceil() as u32;
+        if seconds < previous_seconds {
+            if let Some(on_tick) = self.on_tick.as_mut() {
+                on_tick(seconds);
+            }
+        }
+
+        if self.remaining <= 0.0 {
+            self.finished = true;
+            if let Some(on_complete) = self.on_complete.as_mut() {
+                on_complete();
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::Cell;
+
+    fn counting_timer(duration: f32, fired: &Rc<Cell<u32>>) -> Timer {
+        let fired = Rc::clone(fired);
+        Timer::new(duration, Box::new(move || fired.set(fired.get() + 1)))
+    }
+
+    #[test]
+    fn test_timer_fires_once_through_scheduler() {
+        let fired = Rc::new(Cell::new(0));
+        let ticks = Rc::new(RefCell::new(Vec::new()));
+        let timer = Rc::new(RefCell::new(counting_timer(3.0, &fired)));
+        let tick_log = Rc::clone(&ticks);
+        timer.borrow_mut().set_on_tick(Box::new(move |seconds| tick_log.borrow_mut().push(seconds)));
+
+        Timer::schedule(&timer);
+        for _ in 0..5 {
+            Scheduler::update_instance(0.5);
+        }
+        assert_eq!(fired.get(), 0);
+        assert_eq!(timer.borrow().remaining(), 0.5);
+
+        Scheduler::update_instance(0.5);
+        assert_eq!(fired.get(), 1);
+        assert!(timer.borrow().is_finished());
+        let target = Rc::as_ptr(&timer) as usize;
+        assert!(!Scheduler::get_instance().borrow().is_update_scheduled(target));
+        Scheduler::update_instance(1.0);
+        assert_eq!(fired.get(), 1);
+        assert_eq!(*ticks.borrow(), vec![2, 1, 0]);
+    }
+
+    #[test]
+    fn test_dropped_timer_is_unscheduled() {
+        let fired = Rc::new(Cell::new(0));
+        let timer = Rc::new(RefCell::new(counting_timer(3.0, &fired)));
+        let target = Rc::as_ptr(&timer) as usize;
+        Timer::schedule(&timer);
+        drop(timer);
+
+        Scheduler::update_instance(0.5);
+        assert!(!Scheduler::get_instance().borrow().is_update_scheduled(target));
+        assert_eq!(fired.get(), 0);
+    }
+
+    #[test]
+    fn test_pause_freezes_remaining() {
+        let fired = Rc::new(Cell::new(0));
+        let mut timer = counting_timer(3.0, &fired);
+        timer.update(1.0);
+        timer.pause();
+        timer.update(5.0);
+        assert_eq!(timer.remaining(), 2.0);
+
+        timer.resume();
+        timer.update(2.0);
+        assert_eq!(fired.get(), 1);
+    }
+
+    #[test]
+    fn test_reset_restores_duration() {
+        let fired = Rc::new(Cell::new(0));
+        let mut timer = counting_timer(3.0, &fired);
+        timer.update(3.0);
+        assert!(timer.is_finished());
+
+        timer.reset();
+        assert_eq!(timer.remaining(), 3.0);
+        assert!(!timer.is_finished());
+        timer.update(3.0);
+        assert_eq!(fired.get(), 2);
+    }
+}
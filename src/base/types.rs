@@ -75,6 +75,13 @@ impl Color4B {
     pub fn to_color4f(&self) -> Color4F {
         Color4F::new(self.r as f32 / 255.0, self.g as f32 / 255.0, self.b as f32 / 255.0, self.a as f32 / 255.0)
     }
+
+    /// Gets the color with RGB multiplied by alpha
+    #[inline]
+    pub fn premultiplied(&self) -> Color4B {
+        let premultiply = |value: u8| ((value as u16 * self.a as u16 + 127) / 255) as u8;
+        Color4B::new(premultiply(self.r), premultiply(self.g), premultiply(self.b), self.a)
+    }
 }
 
 /// Color type with float components (RGBA)
@@ -113,6 +120,36 @@ impl Color4F {
             && (self.b - other.b).abs() <= variance
             && (self.a - other.a).abs() <= variance
     }
+
+    /// Gets the color with every component clamped to [0, 1]
+    #[inline]
+    pub fn clamped(&self) -> Color4F {
+        Color4F::new(self.r.clamp(0.0, 1.0), self.g.clamp(0.0, 1.0), self.b.clamp(0.0, 1.0), self.a.clamp(0.0, 1.0))
+    }
+
+    /// Gets the color with RGB multiplied by alpha
+    #[inline]
+    pub fn premultiplied(&self) -> Color4F {
+        let color = self.clamped();
+        Color4F::new(color.r * color.a, color.g * color.a, color.b * color.a, color.a)
+    }
+
+    /// Gets the straight-alpha color of a premultiplied one. A fully
+    /// transparent color has no recoverable RGB and stays transparent.
+    #[inline]
+    pub fn unpremultiplied(&self) -> Color4F {
+        let color = self.clamped();
+        if color.a <= 0.0 {
+            return Color4F::TRANSPARENT;
+        }
+        Color4F::new(color.r / color.a, color.g / color.a, color.b / color.a, color.a).clamped()
+    }
+
+    /// Multiplies two colors component by component
+    #[inline]
+    pub fn multiply(&self, other: &Color4F) -> Color4F {
+        Color4F::new(self.r * other.r, self.g * other.g, self.b * other.b, self.a * other.a).clamped()
+    }
 }
 
 /// Point/Vector2D type
@@ -252,3 +289,27 @@ impl Rect {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_premultiply_round_trip() {
+        let color = Color4F::new(1.0, 1.0, 1.0, 0.5);
+        let premultiplied = color.premultiplied();
+        assert_eq!(premultiplied, Color4F::new(0.5, 0.5, 0.5, 0.5));
+        assert!(premultiplied.unpremultiplied().equal(&color, 1e-6));
+        assert_eq!(Color4F::new(0.3, 0.2, 0.1, 0.0).premultiplied().unpremultiplied(), Color4F::TRANSPARENT);
+        assert_eq!(Color4B::new(255, 255, 255, 128).premultiplied(), Color4B::new(128, 128, 128, 128));
+    }
+
+    #[test]
+    fn test_multiply_composes_and_clamps() {
+        let tint = Color4F::new(1.0, 0.5, 0.0, 1.0);
+        let fade = Color4F::new(0.5, 0.5, 0.5, 0.5);
+        assert_eq!(tint.multiply(&fade), Color4F::new(0.5, 0.25, 0.0, 0.5));
+        assert_eq!(tint.multiply(&Color4F::WHITE), tint);
+        assert_eq!(Color4F::new(2.0, 1.0, 1.0, 1.0).multiply(&Color4F::new(0.8, -1.0, 1.0, 1.0)), Color4F::new(1.0, 0.0, 1.0, 1.0));
+    }
+}
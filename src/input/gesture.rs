@@ -0,0 +1,522 @@
+use super::touch::{Touch, TouchId};
+use super::touch_dispatcher::TouchListener;
+use crate::math::Vec2;
+use std::collections::HashMap;
+use std::fmt;
+use std::time::{Duration, Instant};
+
+/// 默认长按阈值
+pub const DEFAULT_LONG_PRESS_DURATION: Duration = Duration::from_millis(500);
+/// 默认点击允许的最大移动距离
+pub const DEFAULT_TAP_SLOP: f32 = 10.0;
+/// 默认滑动的最小距离
+pub const DEFAULT_SWIPE_MIN_DISTANCE: f32 = 50.0;
+/// 默认滑动的最小速度（像素/秒）
+pub const DEFAULT_SWIPE_MIN_VELOCITY: f32 = 300.0;
+
+/// 滑动方向
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SwipeDirection {
+    Left,
+    Right,
+    Up,
+    Down,
+}
+
+/// 点击/长按回调，参数为触摸位置
+pub type GestureLocationCallback = Box<dyn FnMut(Vec2)>;
+/// 滑动回调，参数为方向和速度
+pub type SwipeCallback = Box<dyn FnMut(SwipeDirection, Vec2)>;
+/// 捏合回调，参数为相对上一次的缩放比例和两指中点
+pub type PinchCallback = Box<dyn FnMut(f32, Vec2)>;
+/// 双指平移回调，参数为两指中点的位移
+pub type PanCallback = Box<dyn FnMut(Vec2)>;
+
+/// 正在跟踪的单点触摸
+#[derive(Debug, Clone)]
+struct TrackedTouch {
+    id: TouchId,
+    start_location: Vec2,
+    start_time: Instant,
+    location: Vec2,
+    long_pressed: bool,
+}
+
+/// 正在跟踪的双指手势
+#[derive(Debug, Clone, Copy)]
+struct TwoFingerState {
+    first: TouchId,
+    second: TouchId,
+    distance: f32,
+    center: Vec2,
+}
+
+/// 手势识别器
+///
+/// 作为 `TouchListener` 注册到 `TouchDispatcher`，将原始触摸转换为
+/// 点击、长按、滑动、捏合缩放和双指平移事件。
+pub struct GestureRecognizer {
+    /// 当前跟踪的单点触摸
+    tracked: Option<TrackedTouch>,
+    /// 所有活动触摸的位置
+    touches: HashMap<TouchId, Vec2>,
+    /// 当前跟踪的双指手势
+    two_finger: Option<TwoFingerState>,
+    /// 长按阈值
+    long_press_duration: Duration,
+    /// 点击允许的最大移动距离
+    tap_slop: f32,
+    /// 滑动的最小距离
+    swipe_min_distance: f32,
+    /// 滑动的最小速度
+    swipe_min_velocity: f32,
+    /// 点击回调
+    on_tap: Option<GestureLocationCallback>,
+    /// 长按回调
+    on_long_press: Option<GestureLocationCallback>,
+    /// 滑动回调
+    on_swipe: Option<SwipeCallback>,
+    /// 捏合回调
+    on_pinch: Option<PinchCallback>,
+    /// 双指平移回调
+    on_pan: Option<PanCallback>,
+    /// 监听器优先级
+    priority: i32,
+}
+
+impl GestureRecognizer {
+    /// 创建新的手势识别器
+    pub fn new() -> Self {
+        Self {
+            tracked: None,
+            touches: HashMap::new(),
+            two_finger: None,
+            long_press_duration: DEFAULT_LONG_PRESS_DURATION,
+            tap_slop: DEFAULT_TAP_SLOP,
+            swipe_min_distance: DEFAULT_SWIPE_MIN_DISTANCE,
+            swipe_min_velocity: DEFAULT_SWIPE_MIN_VELOCITY,
+            on_tap: None,
+            on_long_press: None,
+            on_swipe: None,
+            on_pinch: None,
+            on_pan: None,
+            priority: 0,
+        }
+    }
+
+    /// 设置点击回调
+    pub fn set_on_tap(&mut self, callback: GestureLocationCallback) {
+        self.on_tap = Some(callback);
+    }
+
+    /// 设置长按回调
+    pub fn set_on_long_press(&mut self, callback: GestureLocationCallback) {
+        self.on_long_press = Some(callback);
+    }
+
+    /// 设置滑动回调
+    pub fn set_on_swipe(&mut self, callback: SwipeCallback) {
+        self.on_swipe = Some(callback);
+    }
+
+    /// 设置捏合回调
+    pub fn set_on_pinch(&mut self, callback: PinchCallback) {
+        self.on_pinch = Some(callback);
+    }
+
+    /// 设置双指平移回调
+    pub fn set_on_pan(&mut self, callback: PanCallback) {
+        self.on_pan = Some(callback);
+    }
+
+    /// 设置长按阈值
+    pub fn set_long_press_duration(&mut self, duration: Duration) {
+        self.long_press_duration = duration;
+    }
+
+    /// 获取长按阈值
+    pub fn get_long_press_duration(&self) -> Duration {
+        self.long_press_duration
+    }
+
+    /// 设置点击允许的最大移动距离
+    pub fn set_tap_slop(&mut self, slop: f32) {
+        self.tap_slop = slop.max(0.0);
+    }
+
+    /// 设置滑动的最小距离和最小速度
+    pub fn set_swipe_thresholds(&mut self, min_distance: f32, min_velocity: f32) {
+        self.swipe_min_distance = min_distance.max(0.0);
+        self.swipe_min_velocity = min_velocity.max(0.0);
+    }
+
+    /// 设置监听器优先级
+    pub fn set_priority(&mut self, priority: i32) {
+        self.priority = priority;
+    }
+
+    /// 检查是否正在跟踪触摸
+    pub fn is_tracking(&self) -> bool {
+        self.tracked.is_some() || self.two_finger.is_some()
+    }
+
+    /// 检查是否正在跟踪双指手势
+    pub fn is_two_finger_active(&self) -> bool {
+        self.two_finger.is_some()
+    }
+
+    /// 计算两个触摸之间的距离和中点
+    fn measure(&self, first: TouchId, second: TouchId) -> Option<(f32, Vec2)> {
+        let a = *self.touches.get(&first)?;
+        let b = *self.touches.get(&second)?;
+        Some(((b - a).length(), (a + b) * 0.5))
+    }
+
+    /// 双指移动后触发捏合和平移回调
+    fn update_two_finger(&mut self) {
+        let state = match self.two_finger {
+            Some(state) => state,
+            None => return,
+        };
+        let (distance, center) = match self.measure(state.first, state.second) {
+            Some(measured) => measured,
+            None => return,
+        };
+
+        if state.distance > 0.0 && (distance - state.distance).abs() > f32::EPSILON {
+            let scale = distance / state.distance;
+            if let Some(callback) = self.on_pinch.as_mut() {
+                callback(scale, center);
+            }
+        }
+        let delta = center - state.center;
+        if delta != Vec2::ZERO {
+            if let Some(callback) = self.on_pan.as_mut() {
+                callback(delta);
+            }
+        }
+
+        self.two_finger = Some(TwoFingerState { distance, center, ..state });
+    }
+
+    /// 按时间推进识别器，触摸保持不动超过阈值时触发长按
+    pub fn update(&mut self, now: Instant) {
+        let fire = match self.tracked.as_mut() {
+            Some(tracked) if !tracked.long_pressed
+                && now.duration_since(tracked.start_time) >= self.long_press_duration
+                && (tracked.location - tracked.start_location).length() <= self.tap_slop =>
+            {
+                tracked.long_pressed = true;
+                Some(tracked.location)
+            }
+            _ => None,
+        };
+        if let Some(location) = fire {
+            if let Some(callback) = self.on_long_press.as_mut() {
+                callback(location);
+            }
+        }
+    }
+
+    /// 处理触摸开始
+    fn touch_began(&mut self, touch: &Touch) -> bool {
+        self.touches.insert(touch.id(), touch.location());
+
+        if let Some(tracked) = self.tracked.take() {
+            // 第二根手指按下，放弃单点手势，开始双指手势
+            if let Some((distance, center)) = self.measure(tracked.id, touch.id()) {
+                self.two_finger = Some(TwoFingerState {
+                    first: tracked.id,
+                    second: touch.id(),
+                    distance,
+                    center,
+                });
+            }
+            return true;
+        }
+        if self.two_finger.is_some() {
+            return false;
+        }
+        self.tracked = Some(TrackedTouch {
+            id: touch.id(),
+            start_location: touch.location(),
+            start_time: touch.timestamp(),
+            location: touch.location(),
+            long_pressed: false,
+        });
+        true
+    }
+
+    /// 处理触摸移动
+    fn touch_moved(&mut self, touch: &Touch) -> bool {
+        if let Some(location) = self.touches.get_mut(&touch.id()) {
+            *location = touch.location();
+        }
+        if let Some(state) = self.two_finger {
+            return state.first == touch.id() || state.second == touch.id();
+        }
+        match self.tracked.as_mut() {
+            Some(tracked) if tracked.id == touch.id() => tracked.location = touch.location(),
+            _ => return false,
+        }
+        self.update(touch.timestamp());
+        true
+    }
+
+    /// 处理触摸结束，识别点击或滑动
+    fn touch_ended(&mut self, touch: &Touch) -> bool {
+        self.touches.remove(&touch.id());
+        if let Some(state) = self.two_finger {
+            if state.first == touch.id() || state.second == touch.id() {
+                self.two_finger = None;
+                return true;
+            }
+            return false;
+        }
+        if !self.tracked.as_ref().map_or(false, |tracked| tracked.id == touch.id()) {
+            return false;
+        }
+        self.update(touch.timestamp());
+        let tracked = match self.tracked.take() {
+            Some(tracked) => tracked,
+            None => return false,
+        };
+        if tracked.long_pressed {
+            return true;
+        }
+
+        let displacement = touch.location() - tracked.start_location;
+        let distance = displacement.length();
+        let elapsed = touch.timestamp().duration_since(tracked.start_time).as_secs_f32();
+
+        if distance <= self.tap_slop {
+            if let Some(callback) = self.on_tap.as_mut() {
+                callback(touch.location());
+            }
+        } else if distance >= self.swipe_min_distance && elapsed > 0.0 {
+            let velocity = displacement * (1.0 / elapsed);
+            if velocity.length() >= self.swipe_min_velocity {
+                let direction = if displacement.x.abs() >= displacement.y.abs() {
+                    if displacement.x > 0.0 { SwipeDirection::Right } else { SwipeDirection::Left }
+                } else if displacement.y > 0.0 {
+                    SwipeDirection::Up
+                } else {
+                    SwipeDirection::Down
+                };
+                if let Some(callback) = self.on_swipe.as_mut() {
+                    callback(direction, velocity);
+                }
+            }
+        }
+        true
+    }
+}
+
+impl Default for GestureRecognizer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl fmt::Debug for GestureRecognizer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("GestureRecognizer")
+            .field("tracked", &self.tracked)
+            .field("touches", &self.touches)
+            .field("two_finger", &self.two_finger)
+            .field("long_press_duration", &self.long_press_duration)
+            .field("tap_slop", &self.tap_slop)
+            .field("swipe_min_distance", &self.swipe_min_distance)
+            .field("swipe_min_velocity", &self.swipe_min_velocity)
+            .field("priority", &self.priority)
+            .finish()
+    }
+}
+
+impl TouchListener for GestureRecognizer {
+    fn on_touches_began(&mut self, touches: &[Touch]) -> bool {
+        touches.iter().fold(false, |handled, touch| self.touch_began(touch) || handled)
+    }
+
+    fn on_touches_moved(&mut self, touches: &[Touch]) -> bool {
+        let handled = touches.iter().fold(false, |handled, touch| self.touch_moved(touch) || handled);
+        // 同一批次中的双指移动只计算一次
+        self.update_two_finger();
+        handled
+    }
+
+    fn on_touches_ended(&mut self, touches: &[Touch]) -> bool {
+        touches.iter().fold(false, |handled, touch| self.touch_ended(touch) || handled)
+    }
+
+    fn on_touches_cancelled(&mut self, touches: &[Touch]) -> bool {
+        let cancelled = touches.iter().any(|touch| {
+            self.tracked.as_ref().map_or(false, |tracked| tracked.id == touch.id())
+                || self.two_finger.map_or(false, |state| state.first == touch.id() || state.second == touch.id())
+        });
+        for touch in touches {
+            self.touches.remove(&touch.id());
+        }
+        if cancelled {
+            self.tracked = None;
+            self.two_finger = None;
+        }
+        cancelled
+    }
+
+    fn priority(&self) -> i32 {
+        self.priority
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::input::{TouchDispatcher, TouchPhase};
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    /// 记录识别到的手势
+    #[derive(Debug, Default)]
+    struct Recorded {
+        taps: Vec<Vec2>,
+        long_presses: Vec<Vec2>,
+        swipes: Vec<SwipeDirection>,
+        scales: Vec<f32>,
+        pans: Vec<Vec2>,
+    }
+
+    fn recording_recognizer() -> (Rc<RefCell<GestureRecognizer>>, Rc<RefCell<Recorded>>) {
+        let recorded = Rc::new(RefCell::new(Recorded::default()));
+        let mut recognizer = GestureRecognizer::new();
+        let r = Rc::clone(&recorded);
+        recognizer.set_on_tap(Box::new(move |location| r.borrow_mut().taps.push(location)));
+        let r = Rc::clone(&recorded);
+        recognizer.set_on_long_press(Box::new(move |location| r.borrow_mut().long_presses.push(location)));
+        let r = Rc::clone(&recorded);
+        recognizer.set_on_swipe(Box::new(move |direction, _| r.borrow_mut().swipes.push(direction)));
+        let r = Rc::clone(&recorded);
+        recognizer.set_on_pinch(Box::new(move |scale, _| r.borrow_mut().scales.push(scale)));
+        let r = Rc::clone(&recorded);
+        recognizer.set_on_pan(Box::new(move |delta| r.borrow_mut().pans.push(delta)));
+        (Rc::new(RefCell::new(recognizer)), recorded)
+    }
+
+    fn touch_at(id: TouchId, location: Vec2, start: Instant) -> Touch {
+        Touch::with_timestamp(id, location, start)
+    }
+
+    fn moved(touch: &Touch, location: Vec2, at: Instant) -> Touch {
+        let mut touch = touch.clone();
+        touch.update_location_at(location, TouchPhase::Moved, at);
+        touch
+    }
+
+    #[test]
+    fn test_quick_tap() {
+        let (recognizer, recorded) = recording_recognizer();
+        let mut dispatcher = TouchDispatcher::new();
+        dispatcher.add_listener(recognizer.clone());
+
+        let start = Instant::now();
+        let touch = touch_at(1, Vec2::new(100.0, 100.0), start);
+        dispatcher.handle_touches_began(vec![touch.clone()]);
+        let released = moved(&touch, Vec2::new(102.0, 101.0), start + Duration::from_millis(80));
+        dispatcher.handle_touches_ended(vec![released]);
+
+        let recorded = recorded.borrow();
+        assert_eq!(recorded.taps, vec![Vec2::new(102.0, 101.0)]);
+        assert!(recorded.long_presses.is_empty());
+        assert!(recorded.swipes.is_empty());
+    }
+
+    #[test]
+    fn test_long_press_after_threshold() {
+        let (recognizer, recorded) = recording_recognizer();
+        let start = Instant::now();
+        let touch = touch_at(1, Vec2::new(50.0, 50.0), start);
+        recognizer.borrow_mut().on_touches_began(&[touch.clone()]);
+
+        recognizer.borrow_mut().update(start + Duration::from_millis(300));
+        assert!(recorded.borrow().long_presses.is_empty());
+
+        recognizer.borrow_mut().update(start + Duration::from_millis(600));
+        assert_eq!(recorded.borrow().long_presses, vec![Vec2::new(50.0, 50.0)]);
+
+        let released = moved(&touch, Vec2::new(50.0, 50.0), start + Duration::from_millis(900));
+        recognizer.borrow_mut().on_touches_ended(&[released]);
+        assert!(recorded.borrow().taps.is_empty());
+        assert_eq!(recorded.borrow().long_presses.len(), 1);
+    }
+
+    #[test]
+    fn test_fast_horizontal_move_is_right_swipe() {
+        let (recognizer, recorded) = recording_recognizer();
+        let start = Instant::now();
+        let touch = touch_at(1, Vec2::new(0.0, 100.0), start);
+        recognizer.borrow_mut().on_touches_began(&[touch.clone()]);
+        let touch = moved(&touch, Vec2::new(80.0, 104.0), start + Duration::from_millis(60));
+        recognizer.borrow_mut().on_touches_moved(&[touch.clone()]);
+        let touch = moved(&touch, Vec2::new(200.0, 110.0), start + Duration::from_millis(120));
+        recognizer.borrow_mut().on_touches_ended(&[touch]);
+
+        let recorded = recorded.borrow();
+        assert_eq!(recorded.swipes, vec![SwipeDirection::Right]);
+        assert!(recorded.taps.is_empty());
+    }
+
+    /// 两指按下后同时移动到新位置
+    fn two_finger_move(from: [Vec2; 2], to: [Vec2; 2]) -> Recorded {
+        let (recognizer, recorded) = recording_recognizer();
+        let mut dispatcher = TouchDispatcher::new();
+        dispatcher.add_listener(recognizer.clone());
+
+        let start = Instant::now();
+        let first = touch_at(1, from[0], start);
+        let second = touch_at(2, from[1], start);
+        dispatcher.handle_touches_began(vec![first.clone()]);
+        dispatcher.handle_touches_began(vec![second.clone()]);
+        assert!(recognizer.borrow().is_two_finger_active());
+        assert_eq!(dispatcher.active_touch_count(), 2);
+
+        let at = start + Duration::from_millis(50);
+        let first = moved(&first, to[0], at);
+        let second = moved(&second, to[1], at);
+        dispatcher.handle_touches_moved(vec![first.clone(), second.clone()]);
+        dispatcher.handle_touches_ended(vec![first, second]);
+        assert!(!recognizer.borrow().is_tracking());
+
+        recorded.take()
+    }
+
+    #[test]
+    fn test_pinch_apart_scales_up() {
+        let recorded = two_finger_move(
+            [Vec2::new(100.0, 100.0), Vec2::new(200.0, 100.0)],
+            [Vec2::new(50.0, 100.0), Vec2::new(250.0, 100.0)],
+        );
+        assert_eq!(recorded.scales, vec![2.0]);
+        assert!(recorded.pans.is_empty());
+        assert!(recorded.taps.is_empty());
+    }
+
+    #[test]
+    fn test_pinch_together_scales_down() {
+        let recorded = two_finger_move(
+            [Vec2::new(100.0, 100.0), Vec2::new(200.0, 100.0)],
+            [Vec2::new(125.0, 100.0), Vec2::new(175.0, 100.0)],
+        );
+        assert_eq!(recorded.scales.len(), 1);
+        assert!(recorded.scales[0] < 1.0);
+    }
+
+    #[test]
+    fn test_parallel_move_pans_without_scale() {
+        let recorded = two_finger_move(
+            [Vec2::new(100.0, 100.0), Vec2::new(200.0, 100.0)],
+            [Vec2::new(130.0, 80.0), Vec2::new(230.0, 80.0)],
+        );
+        assert!(recorded.scales.is_empty());
+        assert_eq!(recorded.pans, vec![Vec2::new(30.0, -20.0)]);
+    }
+}
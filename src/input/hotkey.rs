@@ -0,0 +1,218 @@
+use super::keyboard::{KeyCode, KeyEventType, KeyboardEvent};
+use std::collections::HashMap;
+use std::fmt;
+use std::ops::BitOr;
+
+/// 修饰键组合，可用 `|` 组合，例如 `Modifiers::CTRL | Modifiers::SHIFT`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct Modifiers {
+    pub shift: bool,
+    pub ctrl: bool,
+    pub alt: bool,
+    pub super_key: bool,
+}
+
+impl Modifiers {
+    /// 无修饰键
+    pub const NONE: Modifiers = Modifiers { shift: false, ctrl: false, alt: false, super_key: false };
+    pub const SHIFT: Modifiers = Modifiers { shift: true, ..Modifiers::NONE };
+    pub const CTRL: Modifiers = Modifiers { ctrl: true, ..Modifiers::NONE };
+    pub const ALT: Modifiers = Modifiers { alt: true, ..Modifiers::NONE };
+    pub const SUPER: Modifiers = Modifiers { super_key: true, ..Modifiers::NONE };
+
+    /// 读取键盘事件的修饰键状态
+    pub fn from_event(event: &KeyboardEvent) -> Self {
+        Modifiers {
+            shift: event.shift,
+            ctrl: event.ctrl,
+            alt: event.alt,
+            super_key: event.super_key,
+        }
+    }
+}
+
+impl BitOr for Modifiers {
+    type Output = Modifiers;
+
+    fn bitor(self, other: Modifiers) -> Modifiers {
+        Modifiers {
+            shift: self.shift || other.shift,
+            ctrl: self.ctrl || other.ctrl,
+            alt: self.alt || other.alt,
+            super_key: self.super_key || other.super_key,
+        }
+    }
+}
+
+/// 组合键：修饰键 + 按键
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct KeyCombination {
+    pub key_code: KeyCode,
+    pub modifiers: Modifiers,
+}
+
+impl KeyCombination {
+    /// 创建组合键
+    pub fn new(key_code: KeyCode, modifiers: Modifiers) -> Self {
+        Self { key_code, modifiers }
+    }
+
+    /// 读取键盘事件对应的组合键
+    pub fn from_event(event: &KeyboardEvent) -> Self {
+        Self::new(event.key_code, Modifiers::from_event(event))
+    }
+}
+
+/// 快捷键回调
+pub type HotkeyCallback = Box<dyn FnMut()>;
+
+struct HotkeyBinding {
+    combination: KeyCombination,
+    callback: HotkeyCallback,
+}
+
+/// 快捷键注册表
+///
+/// 将组合键映射到命名动作。每个动作只有一个组合键，每个组合键也只属于一个动作。
+/// 修饰键需要完全匹配：Ctrl+S 不会被 Ctrl+Shift+S 或单独的 S 触发。
+pub struct HotkeyRegistry {
+    bindings: HashMap<String, HotkeyBinding>,
+}
+
+impl fmt::Debug for HotkeyRegistry {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let bindings: HashMap<&str, KeyCombination> = self
+            .bindings
+            .iter()
+            .map(|(action, binding)| (action.as_str(), binding.combination))
+            .collect();
+        f.debug_struct("HotkeyRegistry")
+            .field("bindings", &bindings)
+            .finish()
+    }
+}
+
+impl HotkeyRegistry {
+    /// 创建空的快捷键注册表
+    pub fn new() -> Self {
+        Self {
+            bindings: HashMap::new(),
+        }
+    }
+
+    /// 将组合键绑定到动作
+    ///
+    /// 替换该动作原有的绑定。如果组合键已绑定到其他动作，则解除那个动作并返回其名称。
+    pub fn register(&mut self, action: &str, combination: KeyCombination, callback: HotkeyCallback) -> Option<String> {
+        let replaced = self.get_action(combination).filter(|bound| *bound != action).map(str::to_string);
+        if let Some(replaced) = &replaced {
+            self.bindings.remove(replaced);
+        }
+        self.bindings.insert(action.to_string(), HotkeyBinding { combination, callback });
+        replaced
+    }
+
+    /// 解除动作的绑定，返回动作是否存在
+    pub fn unregister(&mut self, action: &str) -> bool {
+        self.bindings.remove(action).is_some()
+    }
+
+    /// 获取动作绑定的组合键
+    pub fn get_binding(&self, action: &str) -> Option<KeyCombination> {
+        self.bindings.get(action).map(|binding| binding.combination)
+    }
+
+    /// 获取组合键绑定的动作
+    pub fn get_action(&self, combination: KeyCombination) -> Option<&str> {
+        self.bindings
+            .iter()
+            .find(|(_, binding)| binding.combination == combination)
+            .map(|(action, _)| action.as_str())
+    }
+
+    /// 获取已注册的动作数量
+    pub fn get_binding_count(&self) -> usize {
+        self.bindings.len()
+    }
+
+    /// 分发键盘事件
+    ///
+    /// 只响应按下事件，长按重复和释放不会触发。返回是否有动作被触发。
+    pub fn dispatch(&mut self, event: &KeyboardEvent) -> bool {
+        if event.event_type != KeyEventType::Pressed {
+            return false;
+        }
+        let combination = KeyCombination::from_event(event);
+        match self.bindings.values_mut().find(|binding| binding.combination == combination) {
+            Some(binding) => {
+                (binding.callback)();
+                true
+            }
+            None => false,
+        }
+    }
+}
+
+impl Default for HotkeyRegistry {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::Cell;
+    use std::rc::Rc;
+
+    fn counting_callback(counter: &Rc<Cell<u32>>) -> HotkeyCallback {
+        let counter = counter.clone();
+        Box::new(move || counter.set(counter.get() + 1))
+    }
+
+    fn ctrl_s() -> KeyCombination {
+        KeyCombination::new(KeyCode::S, Modifiers::CTRL)
+    }
+
+    #[test]
+    fn test_dispatch_matches_modifiers() {
+        let saves = Rc::new(Cell::new(0));
+        let mut registry = HotkeyRegistry::new();
+        registry.register("save", ctrl_s(), counting_callback(&saves));
+
+        let pressed = KeyboardEvent::new(KeyCode::S, KeyEventType::Pressed);
+        assert!(registry.dispatch(&pressed.clone().with_modifiers(false, true, false, false)));
+        assert_eq!(saves.get(), 1);
+
+        // 缺少或多出修饰键都不触发
+        assert!(!registry.dispatch(&pressed));
+        assert!(!registry.dispatch(&pressed.clone().with_modifiers(true, true, false, false)));
+        // 释放不触发
+        let released = KeyboardEvent::new(KeyCode::S, KeyEventType::Released).with_modifiers(false, true, false, false);
+        assert!(!registry.dispatch(&released));
+        assert_eq!(saves.get(), 1);
+    }
+
+    #[test]
+    fn test_register_and_unregister() {
+        let counter = Rc::new(Cell::new(0));
+        let mut registry = HotkeyRegistry::new();
+        assert_eq!(registry.register("save", ctrl_s(), counting_callback(&counter)), None);
+        assert_eq!(registry.get_binding("save"), Some(ctrl_s()));
+        assert_eq!(registry.get_action(ctrl_s()), Some("save"));
+
+        // 组合键被其他动作占用时，原动作被解除
+        let replaced = registry.register("save_as", ctrl_s(), counting_callback(&counter));
+        assert_eq!(replaced.as_deref(), Some("save"));
+        assert_eq!(registry.get_binding("save"), None);
+
+        let ctrl_shift_s = KeyCombination::new(KeyCode::S, Modifiers::CTRL | Modifiers::SHIFT);
+        registry.register("save_as", ctrl_shift_s, counting_callback(&counter));
+        assert_eq!(registry.get_action(ctrl_s()), None);
+        assert_eq!(registry.get_binding_count(), 1);
+
+        assert!(registry.unregister("save_as"));
+        assert!(!registry.unregister("save_as"));
+        assert_eq!(registry.get_binding("save_as"), None);
+    }
+}
@@ -2,8 +2,12 @@ pub mod touch;
 pub mod keyboard;
 pub mod mouse;
 pub mod touch_dispatcher;
+pub mod gesture;
+pub mod hotkey;
 
 pub use touch::{Touch, TouchPhase, TouchId};
 pub use keyboard::{KeyCode, KeyboardEvent, KeyEventType};
 pub use mouse::{MouseButton, MouseEvent, MouseEventType};
-pub use touch_dispatcher::TouchDispatcher;
+pub use touch_dispatcher::{TouchDispatcher, TouchListener};
+pub use gesture::{GestureRecognizer, SwipeDirection};
+pub use hotkey::{HotkeyRegistry, HotkeyCallback, KeyCombination, Modifiers};
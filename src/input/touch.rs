@@ -56,6 +56,15 @@ impl Touch {
         }
     }
 
+    /// 以指定时间戳创建新的触摸
+    pub fn with_timestamp(id: TouchId, location: Vec2, timestamp: Instant) -> Self {
+        Self {
+            start_time: timestamp,
+            timestamp,
+            ..Self::new(id, location)
+        }
+    }
+
     /// 获取触摸 ID
     pub fn id(&self) -> TouchId {
         self.id
@@ -126,6 +135,12 @@ impl Touch {
         self.timestamp = Instant::now();
     }
 
+    /// 以指定时间戳更新触摸位置
+    pub fn update_location_at(&mut self, location: Vec2, phase: TouchPhase, timestamp: Instant) {
+        self.update_location(location, phase);
+        self.timestamp = timestamp;
+    }
+
     /// 设置压力值
     pub fn set_pressure(&mut self, pressure: f32) {
         self.pressure = pressure.clamp(0.0, 1.0);
@@ -181,6 +196,14 @@ mod tests {
         assert_eq!(touch.phase(), TouchPhase::Moved);
     }
 
+    #[test]
+    fn test_touch_with_timestamp() {
+        let start = Instant::now();
+        let mut touch = Touch::with_timestamp(1, Vec2::ZERO, start);
+        touch.update_location_at(Vec2::new(10.0, 0.0), TouchPhase::Moved, start + std::time::Duration::from_millis(250));
+        assert_eq!(touch.duration(), std::time::Duration::from_millis(250));
+    }
+
     #[test]
     fn test_touch_pressure() {
         let mut touch = Touch::new(1, Vec2::new(100.0, 200.0));
@@ -47,6 +47,7 @@ pub struct FontAtlas {
     font_name: String,
     font_size: f32,
     letter_definitions: HashMap<char, FontLetterDefinition>,
+    kernings: HashMap<(char, char), f32>,
     textures: Vec<RefPtr<Texture2D>>,
     common_line_height: f32,
 }
@@ -58,6 +59,7 @@ impl FontAtlas {
             font_name: font_name.to_string(),
             font_size,
             letter_definitions: HashMap::new(),
+            kernings: HashMap::new(),
             textures: Vec::new(),
             common_line_height: 0.0,
         }
@@ -78,6 +80,16 @@ impl FontAtlas {
         self.letter_definitions.get_mut(&letter)
     }
 
+    /// Sets the advance adjustment applied between two consecutive letters
+    pub fn set_kerning(&mut self, left: char, right: char, amount: f32) {
+        self.kernings.insert((left, right), amount);
+    }
+
+    /// Gets the advance adjustment between two consecutive letters
+    pub fn get_kerning(&self, left: char, right: char) -> f32 {
+        self.kernings.get(&(left, right)).copied().unwrap_or(0.0)
+    }
+
     /// Adds a texture
     pub fn add_texture(&mut self, texture: RefPtr<Texture2D>) {
         self.textures.push(texture);
@@ -128,15 +140,20 @@ impl FontAtlas {
         self.add_letter_definition(letter, definition);
     }
 
-    /// Measures the size of a string
+    /// Measures the size of a string, including kerning between letters
     pub fn measure_string(&self, text: &str) -> Vec2 {
         let mut width = 0.0;
-        let mut height = self.common_line_height;
+        let height = self.common_line_height;
 
+        let mut previous = None;
         for ch in text.chars() {
             if let Some(def) = self.get_letter_definition(ch) {
                 width += def.x_advance;
             }
+            if let Some(left) = previous {
+                width += self.get_kerning(left, ch);
+            }
+            previous = Some(ch);
         }
 
         Vec2::new(width, height)
@@ -1,7 +1,8 @@
 use crate::base::{Ref, Node, RefPtr};
 use crate::base::types::Color3B;
 use crate::math::Vec2;
-use crate::renderer::Texture2D;
+use crate::renderer::{Texture2D, TextureQuad, TexturedVertex};
+use crate::label::FontAtlas;
 
 /// Text horizontal alignment
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -28,6 +29,22 @@ pub enum LabelOverflow {
     RESIZE_HEIGHT,
 }
 
+/// Draw layer of a label quad, in back-to-front order
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LabelLayer {
+    SHADOW,
+    OUTLINE,
+    FILL,
+}
+
+/// A glyph quad of a label draw list
+#[derive(Debug, Clone, PartialEq)]
+pub struct LabelQuad {
+    pub layer: LabelLayer,
+    pub color: Color3B,
+    pub quad: TextureQuad,
+}
+
 /// Label is a text display component
 #[derive(Debug)]
 pub struct Label {
@@ -52,6 +69,7 @@ pub struct Label {
     outline_size: f32,
     outline_color: Color3B,
     texture: Option<RefPtr<Texture2D>>,
+    font_atlas: Option<RefPtr<FontAtlas>>,
 }
 
 impl Label {
@@ -79,6 +97,7 @@ impl Label {
             outline_size: 0.0,
             outline_color: Color3B::BLACK,
             texture: None,
+            font_atlas: None,
         }
     }
 
@@ -117,6 +136,13 @@ impl Label {
         self.update_content();
     }
 
+    /// Sets the string content to the translation of `key` in the current
+    /// language of the shared `Localization`
+    pub fn set_string_localized(&mut self, key: &str) {
+        let text = crate::platform::Localization::get_instance().borrow().tr(key).to_string();
+        self.set_string(&text);
+    }
+
     /// Gets the string content
     pub fn get_string(&self) -> &str {
         &self.text
@@ -267,6 +293,205 @@ impl Label {
         self.update_content();
     }
 
+    /// Sets the font atlas providing glyph metrics and UVs
+    pub fn set_font_atlas(&mut self, font_atlas: RefPtr<FontAtlas>) {
+        self.font_atlas = Some(font_atlas);
+        self.update_content();
+    }
+
+    /// Gets the font atlas
+    pub fn get_font_atlas(&self) -> Option<&RefPtr<FontAtlas>> {
+        self.font_atlas.as_ref()
+    }
+
+    /// Lays out the glyph quads of the string, in label space with the first
+    /// line's top edge at y = 0. Letters are spaced by their advance plus the
+    /// atlas's kerning for each pair.
+    pub fn get_glyph_quads(&self) -> Vec<TextureQuad> {
+        self.get_paged_glyph_quads().into_iter().map(|(_, quad)| quad).collect()
+    }
+
+    /// Lays out the glyph quads like `get_glyph_quads`, each paired with the
+    /// font atlas texture page it samples
+    pub fn get_paged_glyph_quads(&self) -> Vec<(usize, TextureQuad)> {
+        let atlas = match &self.font_atlas {
+            Some(atlas) => atlas,
+            None => return Vec::new(),
+        };
+        let line_height = self.get_effective_line_height();
+
+        let mut quads = Vec::new();
+        for (line_index, line) in self.text.split('\n').enumerate() {
+            let top = -(line_index as f32) * (line_height + self.line_spacing);
+            let mut pen_x = 0.0;
+            let mut previous = None;
+            for ch in line.chars() {
+                pen_x += self.get_kerning(previous, ch);
+                previous = Some(ch);
+                let def = match atlas.get_letter_definition(ch) {
+                    Some(def) if def.valid => def,
+                    _ => continue,
+                };
+                let page = def.texture_page.max(0) as usize;
+                let (tex_width, tex_height) = atlas
+                    .get_texture(page)
+                    .map(|texture| (texture.get_width() as f32, texture.get_height() as f32))
+                    .filter(|(w, h)| *w > 0.0 && *h > 0.0)
+                    .unwrap_or((1.0, 1.0));
+                let left = def.u / tex_width;
+                let right = (def.u + def.width) / tex_width;
+                let uv_top = def.v / tex_height;
+                let uv_bottom = (def.v + def.height) / tex_height;
+
+                let x = pen_x + def.offset_x;
+                let y_top = top - def.offset_y;
+                let y_bottom = y_top - def.height;
+                quads.push((
+                    page,
+                    TextureQuad::with_vertices(
+                        TexturedVertex::new(x, y_top, 0.0, left, uv_top),
+                        TexturedVertex::new(x + def.width, y_top, 0.0, right, uv_top),
+                        TexturedVertex::new(x, y_bottom, 0.0, left, uv_bottom),
+                        TexturedVertex::new(x + def.width, y_bottom, 0.0, right, uv_bottom),
+                    ),
+                ));
+                pen_x += def.x_advance;
+            }
+        }
+        quads
+    }
+
+    /// Builds the layered draw list: shadow copies first, then the eight
+    /// outline copies, then the fill glyphs on top
+    pub fn get_draw_list(&self) -> Vec<LabelQuad> {
+        let glyphs = self.get_glyph_quads();
+        let mut draw_list = Vec::new();
+
+        if self.use_shadow {
+            draw_list.extend(glyphs.iter().map(|quad| LabelQuad {
+                layer: LabelLayer::SHADOW,
+                color: self.shadow_color,
+                quad: quad.translated(self.shadow_offset.x, self.shadow_offset.y),
+            }));
+        }
+
+        if self.use_outline && self.outline_size > 0.0 {
+            let size = self.outline_size;
+            let offsets = [
+                (-size, 0.0), (size, 0.0), (0.0, -size), (0.0, size),
+                (-size, -size), (-size, size), (size, -size), (size, size),
+            ];
+            for (dx, dy) in offsets {
+                draw_list.extend(glyphs.iter().map(|quad| LabelQuad {
+                    layer: LabelLayer::OUTLINE,
+                    color: self.outline_color,
+                    quad: quad.translated(dx, dy),
+                }));
+            }
+        }
+
+        draw_list.extend(glyphs.into_iter().map(|quad| LabelQuad {
+            layer: LabelLayer::FILL,
+            color: self.color,
+            quad,
+        }));
+        draw_list
+    }
+
+    /// Gets the caret position before the character at `index`, in the same
+    /// space as `get_glyph_quads`: x from the line start and y at the top of
+    /// the character's line. Indices past the end give the end of the text.
+    pub fn position_of_index(&self, index: usize) -> Vec2 {
+        let mut line = 0;
+        let mut pen_x = 0.0;
+        let mut previous = None;
+        for (i, ch) in self.text.chars().enumerate() {
+            if ch == '\n' {
+                if i == index {
+                    break;
+                }
+                line += 1;
+                pen_x = 0.0;
+                previous = None;
+                continue;
+            }
+            pen_x += self.get_kerning(previous, ch);
+            if i == index {
+                break;
+            }
+            pen_x += self.get_glyph_advance(ch);
+            previous = Some(ch);
+        }
+        Vec2::new(pen_x, -(line as f32) * self.get_line_pitch())
+    }
+
+    /// Gets the character index whose caret position is nearest to a point in
+    /// label space. Points above or below the text snap to the first or last
+    /// line; points past a line's end give the index of its line break.
+    pub fn index_at_position(&self, local: Vec2) -> usize {
+        let pitch = self.get_line_pitch();
+        let target_line = if pitch > 0.0 { (-local.y / pitch).floor().max(0.0) as usize } else { 0 };
+        let last_line = self.text.split('\n').count() - 1;
+        let target_line = target_line.min(last_line);
+
+        let mut line_start = 0;
+        for (line_index, line) in self.text.split('\n').enumerate() {
+            let line_length = line.chars().count();
+            if line_index == target_line {
+                let mut pen_x = 0.0;
+                let mut previous = None;
+                for (offset, ch) in line.chars().enumerate() {
+                    pen_x += self.get_kerning(previous, ch);
+                    previous = Some(ch);
+                    let advance = self.get_glyph_advance(ch);
+                    if local.x < pen_x + advance * 0.5 {
+                        return line_start + offset;
+                    }
+                    pen_x += advance;
+                }
+                return line_start + line_length;
+            }
+            line_start += line_length + 1;
+        }
+        line_start
+    }
+
+    /// Gets the horizontal advance of a character; characters the atlas
+    /// cannot draw take no space, as in `get_glyph_quads`
+    fn get_glyph_advance(&self, ch: char) -> f32 {
+        self.font_atlas
+            .as_ref()
+            .and_then(|atlas| atlas.get_letter_definition(ch))
+            .filter(|def| def.valid)
+            .map_or(0.0, |def| def.x_advance)
+    }
+
+    /// Gets the atlas's kerning between the previous character of a line
+    /// and `ch`, as in `FontAtlas::measure_string`
+    fn get_kerning(&self, previous: Option<char>, ch: char) -> f32 {
+        match (&self.font_atlas, previous) {
+            (Some(atlas), Some(left)) => atlas.get_kerning(left, ch),
+            _ => 0.0,
+        }
+    }
+
+    /// Gets the distance between the tops of consecutive lines
+    fn get_line_pitch(&self) -> f32 {
+        self.get_effective_line_height() + self.line_spacing
+    }
+
+    /// Gets the line height used for layout, falling back to the atlas's
+    /// common line height and then the font size
+    fn get_effective_line_height(&self) -> f32 {
+        if self.line_height > 0.0 {
+            return self.line_height;
+        }
+        match &self.font_atlas {
+            Some(atlas) if atlas.get_common_line_height() > 0.0 => atlas.get_common_line_height(),
+            _ => self.font_size,
+        }
+    }
+
     /// Gets the content size
     pub fn get_content_size(&self) -> Vec2 {
         self.node.get_content_size()
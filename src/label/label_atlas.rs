@@ -1,8 +1,14 @@
 use crate::base::{Ref, Node, RefPtr};
 use crate::base::types::Color3B;
-use crate::renderer::Texture2D;
+use crate::math::Vec2;
+use crate::renderer::{TextureQuad, TexturedVertex};
+use crate::sprite::{Texture2D, TextureCache};
 
-/// LabelAtlas is a label that uses a texture atlas
+/// LabelAtlas is a label that uses a texture atlas.
+///
+/// The char map texture is a grid of fixed-size cells laid out left to right,
+/// top to bottom, starting with `start_char`; each character of the string is
+/// drawn with the cell at `char - start_char`.
 #[derive(Debug)]
 pub struct LabelAtlas {
     node: Node,
@@ -12,6 +18,7 @@ pub struct LabelAtlas {
     item_height: i32,
     start_char: char,
     color: Color3B,
+    quads: Vec<TextureQuad>,
 }
 
 impl LabelAtlas {
@@ -25,6 +32,7 @@ impl LabelAtlas {
             item_height: 0,
             start_char: ' ',
             color: Color3B::WHITE,
+            quads: Vec::new(),
         }
     }
 
@@ -41,12 +49,22 @@ impl LabelAtlas {
         self.item_width = item_width;
         self.item_height = item_height;
         self.start_char = start_char;
-        
-        // Load texture from char_map_file
-        // self.texture = Some(Texture::create(char_map_file));
+        self.texture = TextureCache::get_instance().borrow_mut().add_image(char_map_file);
         
         self.update_atlas_values();
-        true
+        self.texture.is_some()
+    }
+
+    /// Creates a label atlas with an already loaded char map texture
+    pub fn with_texture(text: &str, texture: RefPtr<Texture2D>, item_width: i32, item_height: i32, start_char: char) -> LabelAtlas {
+        let mut label = LabelAtlas::new();
+        label.text = text.to_string();
+        label.texture = Some(texture);
+        label.item_width = item_width;
+        label.item_height = item_height;
+        label.start_char = start_char;
+        label.update_atlas_values();
+        label
     }
 
     /// Sets the string
@@ -60,9 +78,68 @@ impl LabelAtlas {
         &self.text
     }
 
-    /// Updates the atlas values
+    /// Gets the char map texture
+    pub fn get_texture(&self) -> Option<&RefPtr<Texture2D>> {
+        self.texture.as_ref()
+    }
+
+    /// Gets the glyph quads, one per character found in the char map
+    pub fn get_quads(&self) -> &[TextureQuad] {
+        &self.quads
+    }
+
+    /// Gets the content size, one cell per character
+    pub fn get_content_size(&self) -> Vec2 {
+        self.node.get_content_size()
+    }
+
+    /// Gets the color
+    pub fn get_color(&self) -> Color3B {
+        self.color
+    }
+
+    /// Sets the color
+    pub fn set_color(&mut self, color: Color3B) {
+        self.color = color;
+    }
+
+    /// Rebuilds the glyph quads and content size from the string
     fn update_atlas_values(&mut self) {
-        // Update texture coordinates based on text
+        self.quads.clear();
+        let item_width = self.item_width.max(0) as f32;
+        let item_height = self.item_height.max(0) as f32;
+        let length = self.text.chars().count();
+        self.node.set_content_size(Vec2::new(length as f32 * item_width, item_height));
+
+        let (tex_width, tex_height) = match &self.texture {
+            Some(texture) => (texture.get_width() as f32, texture.get_height() as f32),
+            None => return,
+        };
+        if item_width <= 0.0 || item_height <= 0.0 || tex_width < item_width || tex_height < item_height {
+            return;
+        }
+
+        let columns = (tex_width / item_width) as u32;
+        let rows = (tex_height / item_height) as u32;
+        for (i, ch) in self.text.chars().enumerate() {
+            let index = match (ch as u32).checked_sub(self.start_char as u32) {
+                Some(index) if index < columns * rows => index,
+                _ => continue,
+            };
+
+            let left = (index % columns) as f32 * item_width / tex_width;
+            let right = left + item_width / tex_width;
+            let top = (index / columns) as f32 * item_height / tex_height;
+            let bottom = top + item_height / tex_height;
+
+            let x = i as f32 * item_width;
+            self.quads.push(TextureQuad::with_vertices(
+                TexturedVertex::new(x, item_height, 0.0, left, top),
+                TexturedVertex::new(x + item_width, item_height, 0.0, right, top),
+                TexturedVertex::new(x, 0.0, 0.0, left, bottom),
+                TexturedVertex::new(x + item_width, 0.0, 0.0, right, bottom),
+            ));
+        }
     }
 
     /// Gets the node
@@ -0,0 +1,176 @@
+use std::cell::RefCell;
+use std::rc::Rc;
+use crate::base::{Node, RefPtr};
+use crate::math::Vec3;
+use crate::renderer::{Renderer, TextureAtlas, TextureQuad, TexturedVertex};
+use crate::label::{FontAtlas, Label};
+
+const DEFAULT_CAPACITY: u32 = 64;
+
+/// Font atlas texture page the batch draws with
+const BATCH_TEXTURE_PAGE: usize = 0;
+
+/// LabelBatchNode draws the glyphs of all its labels with a single draw call.
+///
+/// Every label must use the batch's font atlas; their laid-out glyph quads
+/// are written into one `TextureAtlas` that is submitted as a whole. Labels
+/// stay editable through their shared handles, and their quads are
+/// refreshed on every `render`.
+#[derive(Debug)]
+pub struct LabelBatchNode {
+    node: Node,
+    font_atlas: RefPtr<FontAtlas>,
+    atlas: TextureAtlas,
+    children: Vec<Rc<RefCell<Label>>>,
+}
+
+impl LabelBatchNode {
+    /// Creates a batch node for the given font atlas. The batch draws with
+    /// the font atlas's first texture page.
+    pub fn with_font_atlas(font_atlas: RefPtr<FontAtlas>) -> LabelBatchNode {
+        let mut atlas = TextureAtlas::new();
+        atlas.resize_capacity(DEFAULT_CAPACITY);
+        if let Some(texture) = font_atlas.get_texture(0) {
+            atlas.set_texture(texture.clone());
+        }
+        LabelBatchNode {
+            node: Node::new(),
+            font_atlas,
+            atlas,
+            children: Vec::new(),
+        }
+    }
+
+    /// Gets the font atlas shared by all labels
+    pub fn get_font_atlas(&self) -> &RefPtr<FontAtlas> {
+        &self.font_atlas
+    }
+
+    /// Gets the texture atlas
+    pub fn get_texture_atlas(&self) -> &TextureAtlas {
+        &self.atlas
+    }
+
+    /// Gets the child labels
+    pub fn get_children(&self) -> &Vec<Rc<RefCell<Label>>> {
+        &self.children
+    }
+
+    /// Adds a child label, which must use the batch's font atlas and only
+    /// glyphs on the texture page the batch draws with
+    pub fn add_child(&mut self, label: Rc<RefCell<Label>>) -> Result<(), String> {
+        {
+            let label = label.borrow();
+            match label.get_font_atlas() {
+                Some(font_atlas) if RefPtr::ptr_eq(font_atlas, &self.font_atlas) => {}
+                _ => {
+                    return Err(format!(
+                        "label font atlas does not match batch font atlas '{}'",
+                        self.font_atlas.get_font_name()
+                    ))
+                }
+            }
+            if let Some((page, _)) = label
+                .get_paged_glyph_quads()
+                .iter()
+                .find(|(page, _)| *page != BATCH_TEXTURE_PAGE)
+            {
+                return Err(format!(
+                    "label glyphs use texture page {} of font atlas '{}', but the batch draws page {}",
+                    page,
+                    self.font_atlas.get_font_name(),
+                    BATCH_TEXTURE_PAGE
+                ));
+            }
+        }
+
+        self.children.push(label);
+        self.update_quads();
+        Ok(())
+    }
+
+    /// Removes a child label
+    pub fn remove_child(&mut self, label: &Rc<RefCell<Label>>) {
+        if let Some(index) = self.children.iter().position(|c| Rc::ptr_eq(c, label)) {
+            self.children.remove(index);
+            self.update_quads();
+        }
+    }
+
+    /// Removes all child labels
+    pub fn remove_all_children(&mut self) {
+        self.children.clear();
+        self.atlas.remove_all_quads();
+    }
+
+    /// Refreshes the glyph quads and submits the atlas as a single draw
+    pub fn render(&mut self, renderer: &mut Renderer) {
+        if !self.node.is_visible() {
+            return;
+        }
+        self.update_quads();
+        renderer.draw_texture_atlas(&self.atlas);
+        self.atlas.clear_dirty();
+    }
+
+    /// Gets the node
+    pub fn get_node(&self) -> &Node {
+        &self.node
+    }
+
+    /// Gets mutable node
+    pub fn get_node_mut(&mut self) -> &mut Node {
+        &mut self.node
+    }
+
+    /// Writes the glyphs of every visible label into the atlas, touching
+    /// only the quads that changed. Glyphs a label gained on another texture
+    /// page since it was added are left out.
+    fn update_quads(&mut self) {
+        let quads: Vec<TextureQuad> = self
+            .children
+            .iter()
+            .map(|label| label.borrow())
+            .filter(|label| label.get_node().is_visible())
+            .flat_map(|label| LabelBatchNode::quads_for(&label))
+            .collect();
+
+        let total = quads.len() as u32;
+        if total > self.atlas.get_capacity() {
+            let capacity = (self.atlas.get_capacity() * 4 / 3).max(total);
+            self.atlas.resize_capacity(capacity);
+        }
+        for (index, quad) in quads.iter().enumerate() {
+            if self.atlas.get_quad(index as u32) != Some(quad) {
+                self.atlas.update_quad(index as u32, quad);
+            }
+        }
+        while self.atlas.get_total_quads() > total {
+            self.atlas.remove_quad(self.atlas.get_total_quads() - 1);
+        }
+    }
+
+    /// Moves a label's glyph quads into batch space through its transform
+    fn quads_for(label: &Label) -> Vec<TextureQuad> {
+        let transform = label.get_node().get_local_transform();
+        let place = |vertex: &TexturedVertex| {
+            let (x, y, z) = vertex.get_position();
+            let (u, v) = vertex.get_tex_coord();
+            let point = transform.transform_point(&Vec3::new(x, y, z));
+            TexturedVertex::new(point.x, point.y, point.z, u, v)
+        };
+        label
+            .get_paged_glyph_quads()
+            .iter()
+            .filter(|(page, _)| *page == BATCH_TEXTURE_PAGE)
+            .map(|(_, quad)| {
+                TextureQuad::with_vertices(
+                    place(quad.get_tl()),
+                    place(quad.get_tr()),
+                    place(quad.get_bl()),
+                    place(quad.get_br()),
+                )
+            })
+            .collect()
+    }
+}
@@ -2,11 +2,13 @@ pub mod label;
 pub mod label_atlas;
 pub mod label_ttf;
 pub mod font_atlas;
+pub mod label_batch_node;
 
 #[cfg(test)]
 mod tests;
 
-pub use label::{Label, TextHAlignment, TextVAlignment, LabelOverflow};
+pub use label::{Label, TextHAlignment, TextVAlignment, LabelOverflow, LabelLayer, LabelQuad};
 pub use label_atlas::LabelAtlas;
 pub use label_ttf::LabelTTF;
 pub use font_atlas::{FontAtlas, FontLetterDefinition};
+pub use label_batch_node::LabelBatchNode;
@@ -6,6 +6,8 @@
 //! - Comprehensive edge case coverage
 //! - Type safety validation
 
+use std::cell::RefCell;
+use std::rc::Rc;
 use super::*;
 use crate::base::types::Color3B;
 use crate::math::Vec2;
@@ -695,6 +697,295 @@ fn test_property_independence() {
     assert_eq!(label.get_font_size(), original_size);
 }
 
+// ============================================================================
+// LabelAtlas Tests
+// ============================================================================
+
+fn digit_char_map() -> crate::base::RefPtr<crate::sprite::Texture2D> {
+    // 10 digits of 16x32 in a 160x32 strip
+    crate::base::RefPtr::new(crate::sprite::Texture2D::with_path(160, 32, "digits.png"))
+}
+
+#[test]
+fn test_label_atlas_maps_chars_to_cells() {
+    let label = LabelAtlas::with_texture("123", digit_char_map(), 16, 32, '0');
+
+    let quads = label.get_quads();
+    assert_eq!(quads.len(), 3);
+    for (i, quad) in quads.iter().enumerate() {
+        let cell = (i + 1) as f32;
+        assert_eq!(quad.get_tl().get_tex_coord(), (cell * 0.1, 0.0));
+        assert_eq!(quad.get_br().get_tex_coord(), ((cell + 1.0) * 0.1, 1.0));
+        assert_eq!(quad.get_bl().get_position(), (i as f32 * 16.0, 0.0, 0.0));
+    }
+    assert_eq!(label.get_content_size(), Vec2::new(48.0, 32.0));
+}
+
+#[test]
+fn test_label_atlas_set_string_and_grid_rows() {
+    // 4 columns x 2 rows starting at 'A'
+    let texture = crate::base::RefPtr::new(crate::sprite::Texture2D::with_path(64, 32, "letters.png"));
+    let mut label = LabelAtlas::with_texture("A", texture, 16, 16, 'A');
+    label.set_string("F?");
+
+    // '?' precedes the start char and has no cell
+    assert_eq!(label.get_quads().len(), 1);
+    assert_eq!(label.get_quads()[0].get_tl().get_tex_coord(), (0.25, 0.5));
+    assert_eq!(label.get_content_size(), Vec2::new(32.0, 16.0));
+}
+
+// ============================================================================
+// Shadow/Outline Draw List Tests
+// ============================================================================
+
+/// Font atlas whose glyphs are 8x10 with an advance of 10
+fn fixed_advance_atlas(text: &str) -> crate::base::RefPtr<FontAtlas> {
+    let mut atlas = FontAtlas::new("fixed", 10.0);
+    atlas.set_common_line_height(12.0);
+    for (i, ch) in text.chars().enumerate() {
+        let mut def = FontLetterDefinition::new();
+        def.letter_char = ch;
+        def.u = i as f32 * 8.0;
+        def.width = 8.0;
+        def.height = 10.0;
+        def.x_advance = 10.0;
+        def.valid = true;
+        atlas.add_letter_definition(ch, def);
+    }
+    crate::base::RefPtr::new(atlas)
+}
+
+fn label_with_atlas(text: &str) -> Label {
+    let mut label = Label::new();
+    label.set_string(text);
+    label.set_font_atlas(fixed_advance_atlas(text));
+    label
+}
+
+#[test]
+fn test_draw_list_shadow_pass() {
+    let mut label = label_with_atlas("Hi");
+    label.enable_shadow(Color3B::BLACK, Vec2::new(2.0, -3.0), 0.0);
+
+    let draw_list = label.get_draw_list();
+    assert_eq!(draw_list.len(), 4);
+    assert!(draw_list[..2].iter().all(|q| q.layer == LabelLayer::SHADOW && q.color == Color3B::BLACK));
+    assert!(draw_list[2..].iter().all(|q| q.layer == LabelLayer::FILL));
+
+    let (shadow_x, shadow_y, _) = draw_list[1].quad.get_bl().get_position();
+    let (fill_x, fill_y, _) = draw_list[3].quad.get_bl().get_position();
+    assert_eq!((shadow_x - fill_x, shadow_y - fill_y), (2.0, -3.0));
+}
+
+#[test]
+fn test_draw_list_outline_copies() {
+    let mut label = label_with_atlas("abc");
+    label.enable_outline(Color3B::RED, 1.5);
+    label.enable_shadow(Color3B::BLACK, Vec2::new(1.0, -1.0), 0.0);
+
+    let draw_list = label.get_draw_list();
+    let count = |layer| draw_list.iter().filter(|q| q.layer == layer).count();
+    assert_eq!(count(LabelLayer::SHADOW), 3);
+    assert_eq!(count(LabelLayer::OUTLINE), 8 * 3);
+    assert_eq!(count(LabelLayer::FILL), 3);
+
+    let layers: Vec<LabelLayer> = draw_list.iter().map(|q| q.layer).collect();
+    let mut sorted = layers.clone();
+    sorted.sort_by_key(|layer| *layer as u8);
+    assert_eq!(layers, sorted);
+
+    label.disable_outline();
+    label.disable_shadow();
+    assert_eq!(label.get_draw_list().len(), 3);
+}
+
+#[test]
+fn test_index_at_position_picks_nearest_caret() {
+    let label = label_with_atlas("hello");
+
+    assert_eq!(label.index_at_position(Vec2::new(31.0, -5.0)), 3);
+    assert_eq!(label.index_at_position(Vec2::new(34.0, -5.0)), 3);
+    assert_eq!(label.index_at_position(Vec2::new(36.0, -5.0)), 4);
+    assert_eq!(label.index_at_position(Vec2::new(-10.0, -5.0)), 0);
+    assert_eq!(label.index_at_position(Vec2::new(500.0, -5.0)), 5);
+}
+
+#[test]
+fn test_position_of_index_is_monotonic() {
+    let label = label_with_atlas("hello");
+
+    let positions: Vec<Vec2> = (0..=5).map(|i| label.position_of_index(i)).collect();
+    assert_eq!(positions[0], Vec2::new(0.0, 0.0));
+    assert_eq!(positions[3], Vec2::new(30.0, 0.0));
+    assert!(positions.windows(2).all(|pair| pair[1].x > pair[0].x));
+    assert_eq!(label.position_of_index(99), positions[5]);
+}
+
+#[test]
+fn test_caret_positions_across_lines() {
+    let label = label_with_atlas("ab\ncd");
+
+    assert_eq!(label.position_of_index(2), Vec2::new(20.0, 0.0));
+    assert_eq!(label.position_of_index(4), Vec2::new(10.0, -12.0));
+    assert_eq!(label.index_at_position(Vec2::new(12.0, -15.0)), 4);
+    assert_eq!(label.index_at_position(Vec2::new(80.0, -5.0)), 2);
+    assert_eq!(label.index_at_position(Vec2::new(0.0, -100.0)), 3);
+}
+
+#[test]
+fn test_measure_string_applies_kerning() {
+    let mut atlas = fixed_advance_atlas("AV");
+    assert_eq!(atlas.measure_string("AVA").x, 30.0);
+
+    atlas.borrow_mut().set_kerning('A', 'V', -2.0);
+    assert_eq!(atlas.get_kerning('A', 'V'), -2.0);
+    assert_eq!(atlas.get_kerning('V', 'A'), 0.0);
+    assert_eq!(atlas.measure_string("AVA").x, 28.0);
+}
+
+#[test]
+fn test_layout_and_carets_apply_kerning() {
+    let mut atlas = fixed_advance_atlas("AV");
+    atlas.borrow_mut().set_kerning('A', 'V', -2.0);
+    let mut label = Label::new();
+    label.set_string("AVA");
+    label.set_font_atlas(atlas);
+
+    let xs: Vec<f32> = label
+        .get_glyph_quads()
+        .iter()
+        .map(|quad| quad.get_tl().get_position().0)
+        .collect();
+    assert_eq!(xs, vec![0.0, 8.0, 18.0]);
+
+    assert_eq!(label.position_of_index(1), Vec2::new(8.0, 0.0));
+    assert_eq!(label.position_of_index(3), Vec2::new(28.0, 0.0));
+    assert_eq!(label.index_at_position(Vec2::new(14.0, -5.0)), 2);
+    assert_eq!(label.index_at_position(Vec2::new(12.0, -5.0)), 1);
+}
+
+#[test]
+fn test_set_string_localized() {
+    let localization = crate::platform::Localization::get_instance();
+    localization
+        .borrow_mut()
+        .load_language_from_json("label-test", r#"{"label.greeting": "Bonjour"}"#);
+    localization.borrow_mut().set_language("label-test");
+
+    let mut label = Label::new();
+    label.set_string_localized("label.greeting");
+    assert_eq!(label.get_string(), "Bonjour");
+    label.set_string_localized("label.missing");
+    assert_eq!(label.get_string(), "label.missing");
+}
+
+// ============================================================================
+// Label Batch Tests
+// ============================================================================
+
+fn batched_label(atlas: &crate::base::RefPtr<FontAtlas>, text: &str, x: f32) -> Rc<RefCell<Label>> {
+    let mut label = Label::new();
+    label.set_string(text);
+    label.set_font_atlas(atlas.clone());
+    label.get_node_mut().set_position(Vec2::new(x, 0.0));
+    Rc::new(RefCell::new(label))
+}
+
+#[test]
+fn test_label_batch_single_draw() {
+    let atlas = fixed_advance_atlas("abcdef");
+    let mut batch = LabelBatchNode::with_font_atlas(atlas.clone());
+    let labels = [
+        batched_label(&atlas, "abc", 0.0),
+        batched_label(&atlas, "de", 100.0),
+        batched_label(&atlas, "f\nab", 200.0),
+    ];
+    let glyph_count: usize = labels.iter().map(|label| label.borrow().get_glyph_quads().len()).sum();
+    for label in &labels {
+        batch.add_child(label.clone()).unwrap();
+    }
+
+    let mut renderer = crate::renderer::Renderer::new();
+    batch.render(&mut renderer);
+    assert_eq!(renderer.get_draw_calls(), 1);
+    assert_eq!(glyph_count, 8);
+    assert_eq!(batch.get_texture_atlas().get_total_quads(), 8);
+
+    batch.remove_child(&labels[0]);
+    assert_eq!(batch.get_texture_atlas().get_total_quads(), 5);
+}
+
+#[test]
+fn test_label_batch_uses_font_atlas_texture() {
+    let mut atlas = FontAtlas::new("paged", 10.0);
+    let texture = crate::base::RefPtr::new(crate::renderer::Texture2D::new());
+    atlas.add_texture(texture.clone());
+    let batch = LabelBatchNode::with_font_atlas(crate::base::RefPtr::new(atlas));
+    let batch_texture = batch.get_texture_atlas().get_texture().unwrap();
+    assert!(crate::base::RefPtr::ptr_eq(batch_texture, &texture));
+
+    let batch = LabelBatchNode::with_font_atlas(fixed_advance_atlas("abc"));
+    assert!(batch.get_texture_atlas().get_texture().is_none());
+}
+
+#[test]
+fn test_label_batch_updates_edited_labels() {
+    let atlas = fixed_advance_atlas("abcd");
+    let mut batch = LabelBatchNode::with_font_atlas(atlas.clone());
+    let label = batched_label(&atlas, "ab", 0.0);
+    batch.add_child(label.clone()).unwrap();
+    assert_eq!(batch.get_texture_atlas().get_total_quads(), 2);
+    let x_before = batch.get_texture_atlas().get_quad(0).unwrap().get_tl().get_position().0;
+
+    label.borrow_mut().set_string("abcd");
+    label.borrow_mut().get_node_mut().set_position(Vec2::new(50.0, 0.0));
+    let mut renderer = crate::renderer::Renderer::new();
+    batch.render(&mut renderer);
+    assert_eq!(batch.get_texture_atlas().get_total_quads(), 4);
+    let first = batch.get_texture_atlas().get_quad(0).unwrap();
+    assert_eq!(first.get_tl().get_position().0, x_before + 50.0);
+}
+
+#[test]
+fn test_label_batch_rejects_glyphs_on_other_pages() {
+    let mut atlas = FontAtlas::new("paged", 10.0);
+    for (page, ch) in ['a', 'b'].iter().enumerate() {
+        let mut def = FontLetterDefinition::new();
+        def.letter_char = *ch;
+        def.width = 8.0;
+        def.height = 10.0;
+        def.x_advance = 10.0;
+        def.texture_page = page as i32;
+        def.valid = true;
+        atlas.add_letter_definition(*ch, def);
+    }
+    let atlas = crate::base::RefPtr::new(atlas);
+    let mut batch = LabelBatchNode::with_font_atlas(atlas.clone());
+
+    assert!(batch.add_child(batched_label(&atlas, "ab", 0.0)).is_err());
+    let label = batched_label(&atlas, "aa", 0.0);
+    batch.add_child(label.clone()).unwrap();
+
+    label.borrow_mut().set_string("ab");
+    let mut renderer = crate::renderer::Renderer::new();
+    batch.render(&mut renderer);
+    assert_eq!(batch.get_texture_atlas().get_total_quads(), 1);
+}
+
+#[test]
+fn test_label_batch_rejects_other_atlas() {
+    let atlas = fixed_advance_atlas("abc");
+    let mut batch = LabelBatchNode::with_font_atlas(atlas.clone());
+
+    let other = fixed_advance_atlas("abc");
+    assert!(batch.add_child(batched_label(&other, "abc", 0.0)).is_err());
+    let mut plain = Label::new();
+    plain.set_string("abc");
+    assert!(batch.add_child(Rc::new(RefCell::new(plain))).is_err());
+    assert!(batch.get_children().is_empty());
+    assert_eq!(batch.get_texture_atlas().get_total_quads(), 0);
+}
+
 // ============================================================================
 // Performance/Stress Tests (示例)
 // ============================================================================
@@ -19,6 +19,7 @@ pub mod input;
 pub mod animation;
 pub mod shader;
 pub mod transition;
+pub mod pathfinding;
 
 pub use base::{Director, Scene, Node, Color3B, Color4B, Color4F, Size, Rect};
 pub use scene::{Layer, LayerColor};
@@ -2,7 +2,7 @@ use cocos2d_rust::{Director, Scene, Sprite, Color3B};
 
 fn main() {
     // Initialize the director
-    let mut director = Director::get_instance();
+    let director = Director::get_instance();
 
     // Create a scene
     let mut scene = Scene::new();
@@ -20,7 +20,7 @@ fn main() {
     scene.add_child(sprite);
 
     // Run the scene
-    director.run_scene(scene);
+    director.borrow_mut().run_scene(scene);
 
     println!("cocos2d-rust game engine initialized successfully!");
     println!("This is a Rust port of the cocos2d-x game engine.");
@@ -4,6 +4,8 @@ pub mod vec4;
 pub mod geometry;
 pub mod quaternion;
 pub mod mat4;
+pub mod random;
+pub mod ray;
 
 pub use vec2::Vec2;
 pub use vec3::Vec3;
@@ -11,3 +13,5 @@ pub use vec4::Vec4;
 pub use geometry::{Size, Rect};
 pub use quaternion::Quaternion;
 pub use mat4::Mat4;
+pub use random::Rng;
+pub use ray::Ray;
@@ -0,0 +1,114 @@
+use std::f32::consts::PI;
+use crate::math::Vec2;
+
+/// Small seedable pseudo random generator (xorshift64*).
+///
+/// Not suitable for cryptography; it exists so particles and gameplay code
+/// can replay the same sequence from a fixed seed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Rng {
+    state: u64,
+}
+
+impl Rng {
+    /// Creates a generator whose sequence is fully determined by `seed`
+    pub fn from_seed(seed: u64) -> Rng {
+        // Scramble the seed with splitmix64 so that nearby seeds diverge and
+        // a zero seed does not lock xorshift into zero
+        let mut z = seed.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^= z >> 31;
+        Rng {
+            state: if z == 0 { 0x9E37_79B9_7F4A_7C15 } else { z },
+        }
+    }
+
+    /// Creates a generator from a random seed
+    pub fn from_entropy() -> Rng {
+        Rng::from_seed(rand::random::<u64>())
+    }
+
+    /// Gets the next 64 random bits
+    pub fn next_u64(&mut self) -> u64 {
+        let mut x = self.state;
+        x ^= x >> 12;
+        x ^= x << 25;
+        x ^= x >> 27;
+        self.state = x;
+        x.wrapping_mul(0x2545_F491_4F6C_DD1D)
+    }
+
+    /// Gets the next 32 random bits
+    pub fn next_u32(&mut self) -> u32 {
+        (self.next_u64() >> 32) as u32
+    }
+
+    /// Gets a float in `[0, 1)`
+    pub fn next_f32(&mut self) -> f32 {
+        (self.next_u64() >> 40) as f32 / (1u64 << 24) as f32
+    }
+
+    /// Gets a float in `[min, max)`
+    pub fn next_f32_in(&mut self, min: f32, max: f32) -> f32 {
+        let value = min + (max - min) * self.next_f32();
+        // Rounding can land exactly on max for wide ranges
+        if value >= max && max > min {
+            min.max(max - (max - min) * f32::EPSILON)
+        } else {
+            value
+        }
+    }
+
+    /// Gets a random boolean
+    pub fn next_bool(&mut self) -> bool {
+        self.next_u64() >> 63 == 1
+    }
+
+    /// Gets a point uniformly distributed inside a circle around the origin
+    pub fn next_vec2_in_circle(&mut self, radius: f32) -> Vec2 {
+        let angle = self.next_f32() * 2.0 * PI;
+        let distance = radius * self.next_f32().sqrt();
+        Vec2::new(angle.cos() * distance, angle.sin() * distance)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_same_seed_same_sequence() {
+        let mut a = Rng::from_seed(42);
+        let mut b = Rng::from_seed(42);
+        for _ in 0..100 {
+            assert_eq!(a.next_u64(), b.next_u64());
+        }
+        assert_eq!(a.next_f32_in(-1.0, 1.0), b.next_f32_in(-1.0, 1.0));
+        assert_eq!(a.next_bool(), b.next_bool());
+
+        let mut c = Rng::from_seed(43);
+        assert_ne!(Rng::from_seed(42).next_u64(), c.next_u64());
+        assert_ne!(Rng::from_seed(0).next_u64(), 0);
+    }
+
+    #[test]
+    fn test_next_f32_in_bounds() {
+        let mut rng = Rng::from_seed(7);
+        for _ in 0..10_000 {
+            let value = rng.next_f32_in(-3.0, 5.0);
+            assert!((-3.0..5.0).contains(&value));
+        }
+        assert_eq!(rng.next_f32_in(2.0, 2.0), 2.0);
+    }
+
+    #[test]
+    fn test_next_vec2_in_circle() {
+        let mut rng = Rng::from_seed(9);
+        let trues = (0..1000).filter(|_| rng.next_bool()).count();
+        assert!(trues > 400 && trues < 600);
+        for _ in 0..1000 {
+            assert!(rng.next_vec2_in_circle(10.0).length() <= 10.0 + 1e-4);
+        }
+    }
+}
@@ -0,0 +1,109 @@
+use crate::math::Vec3;
+
+/// Half-line used for picking, starting at `origin` and running along `dir`.
+///
+/// Intersections return the parametric distance `t`, so the hit point is
+/// `ray.point_at(t)`; `t` is in units of `dir`'s length.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Ray {
+    pub origin: Vec3,
+    pub dir: Vec3,
+}
+
+impl Ray {
+    pub fn new(origin: Vec3, dir: Vec3) -> Self {
+        Ray { origin, dir }
+    }
+
+    /// Gets the point at parametric distance `t` along the ray
+    pub fn point_at(&self, t: f32) -> Vec3 {
+        self.origin + self.dir * t
+    }
+
+    /// Intersects an axis-aligned box using the slab method. A ray starting
+    /// inside the box hits it at `t = 0`.
+    pub fn intersect_aabb(&self, min: Vec3, max: Vec3) -> Option<f32> {
+        let origin = [self.origin.x, self.origin.y, self.origin.z];
+        let dir = [self.dir.x, self.dir.y, self.dir.z];
+        let min = [min.x, min.y, min.z];
+        let max = [max.x, max.y, max.z];
+
+        let mut t_near = f32::NEG_INFINITY;
+        let mut t_far = f32::INFINITY;
+        for axis in 0..3 {
+            if dir[axis] == 0.0 {
+                // Parallel to this slab: it must already be between the planes
+                if origin[axis] < min[axis] || origin[axis] > max[axis] {
+                    return None;
+                }
+                continue;
+            }
+            let t1 = (min[axis] - origin[axis]) / dir[axis];
+            let t2 = (max[axis] - origin[axis]) / dir[axis];
+            t_near = t_near.max(t1.min(t2));
+            t_far = t_far.min(t1.max(t2));
+        }
+
+        if t_near > t_far || t_far < 0.0 {
+            None
+        } else {
+            Some(t_near.max(0.0))
+        }
+    }
+
+    /// Intersects the plane of points `p` with `normal.dot(p) + d = 0`.
+    /// Rays parallel to the plane or pointing away from it miss.
+    pub fn intersect_plane(&self, normal: Vec3, d: f32) -> Option<f32> {
+        let denom = normal.dot(&self.dir);
+        if denom.abs() < f32::EPSILON {
+            return None;
+        }
+        let t = -(normal.dot(&self.origin) + d) / denom;
+        if t >= 0.0 {
+            Some(t)
+        } else {
+            None
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn down_z() -> Ray {
+        Ray::new(Vec3::new(0.0, 0.0, 10.0), Vec3::new(0.0, 0.0, -1.0))
+    }
+
+    #[test]
+    fn test_ray_hits_box_at_origin() {
+        let t = down_z().intersect_aabb(Vec3::new(-1.0, -1.0, -1.0), Vec3::new(1.0, 1.0, 1.0));
+        assert_eq!(t, Some(9.0));
+        assert_eq!(down_z().point_at(9.0), Vec3::new(0.0, 0.0, 1.0));
+
+        let inside = Ray::new(Vec3::ZERO, Vec3::new(0.0, 0.0, -1.0));
+        assert_eq!(inside.intersect_aabb(Vec3::new(-1.0, -1.0, -1.0), Vec3::ONE), Some(0.0));
+    }
+
+    #[test]
+    fn test_ray_misses_box() {
+        let ray = down_z();
+        assert_eq!(ray.intersect_aabb(Vec3::new(5.0, -1.0, -1.0), Vec3::new(7.0, 1.0, 1.0)), None);
+        // Box behind the ray
+        assert_eq!(ray.intersect_aabb(Vec3::new(-1.0, -1.0, 20.0), Vec3::new(1.0, 1.0, 22.0)), None);
+
+        let diagonal = Ray::new(Vec3::new(-5.0, 0.0, 10.0), Vec3::new(1.0, 0.0, -1.0));
+        assert_eq!(diagonal.intersect_aabb(Vec3::new(-1.0, -1.0, -1.0), Vec3::ONE), None);
+    }
+
+    #[test]
+    fn test_ray_hits_plane() {
+        let ray = down_z();
+        assert_eq!(ray.intersect_plane(Vec3::UNIT_Z, 0.0), Some(10.0));
+        // Plane z = 4
+        assert_eq!(ray.intersect_plane(Vec3::UNIT_Z, -4.0), Some(6.0));
+        // Plane z = 12 is behind the ray
+        assert_eq!(ray.intersect_plane(Vec3::UNIT_Z, -12.0), None);
+        assert_eq!(ray.intersect_plane(Vec3::UNIT_X, 0.0), None);
+    }
+}
@@ -116,6 +116,10 @@ impl Vec3 {
         v
     }
 
+    pub fn normalized(&self) -> Self {
+        self.get_normalized()
+    }
+
     pub fn scale(&mut self, scalar: f32) {
         self.x *= scalar;
         self.y *= scalar;
@@ -219,3 +223,73 @@ impl DivAssign<f32> for Vec3 {
         self.z /= scalar;
     }
 }
+
+impl Mul<Vec3> for Vec3 {
+    type Output = Vec3;
+    fn mul(self, other: Vec3) -> Vec3 {
+        Vec3 { x: self.x * other.x, y: self.y * other.y, z: self.z * other.z }
+    }
+}
+
+impl MulAssign<Vec3> for Vec3 {
+    fn mul_assign(&mut self, other: Vec3) {
+        self.x *= other.x;
+        self.y *= other.y;
+        self.z *= other.z;
+    }
+}
+
+impl Mul<Vec3> for f32 {
+    type Output = Vec3;
+    fn mul(self, v: Vec3) -> Vec3 {
+        v * self
+    }
+}
+
+impl Div<Vec3> for Vec3 {
+    type Output = Vec3;
+    fn div(self, other: Vec3) -> Vec3 {
+        Vec3 { x: self.x / other.x, y: self.y / other.y, z: self.z / other.z }
+    }
+}
+
+impl DivAssign<Vec3> for Vec3 {
+    fn div_assign(&mut self, other: Vec3) {
+        self.x /= other.x;
+        self.y /= other.y;
+        self.z /= other.z;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cross_of_basis_vectors() {
+        assert_eq!(Vec3::UNIT_X.cross(&Vec3::UNIT_Y), Vec3::UNIT_Z);
+        assert_eq!(Vec3::UNIT_Y.cross(&Vec3::UNIT_Z), Vec3::UNIT_X);
+        assert_eq!(Vec3::UNIT_Z.cross(&Vec3::UNIT_X), Vec3::UNIT_Y);
+        assert_eq!(Vec3::UNIT_Y.cross(&Vec3::UNIT_X), -Vec3::UNIT_Z);
+    }
+
+    #[test]
+    fn test_normalized_and_lerp() {
+        let v = Vec3::new(3.0, -4.0, 12.0);
+        assert!((v.normalized().length() - 1.0).abs() < 1e-6);
+        assert_eq!(Vec3::ZERO.normalized(), Vec3::ZERO);
+
+        let mid = Vec3::new(0.0, 2.0, -4.0).lerp(&Vec3::new(2.0, 4.0, 4.0), 0.5);
+        assert_eq!(mid, Vec3::new(1.0, 3.0, 0.0));
+    }
+
+    #[test]
+    fn test_component_wise_ops() {
+        let a = Vec3::new(2.0, 3.0, 4.0);
+        let b = Vec3::new(4.0, 6.0, 8.0);
+        assert_eq!(a * b, Vec3::new(8.0, 18.0, 32.0));
+        assert_eq!(b / a, Vec3::new(2.0, 2.0, 2.0));
+        assert_eq!(2.0 * a, b);
+        assert_eq!(b / 2.0, a);
+    }
+}
@@ -1,5 +1,6 @@
 use std::ops::{Add, AddAssign, Div, DivAssign, Mul, MulAssign, Neg, Sub, SubAssign};
 use std::f32;
+use crate::math::Vec3;
 
 #[derive(Copy, Clone, Debug, PartialEq)]
 pub struct Vec4 {
@@ -106,6 +107,10 @@ impl Vec4 {
         v
     }
 
+    pub fn normalized(&self) -> Self {
+        self.get_normalized()
+    }
+
     pub fn scale(&mut self, scalar: f32) {
         self.x *= scalar;
         self.y *= scalar;
@@ -126,6 +131,18 @@ impl Vec4 {
         self.z -= v.z;
         self.w -= v.w;
     }
+
+    pub fn lerp(&self, target: &Vec4, alpha: f32) -> Vec4 {
+        *self * (1.0 - alpha) + *target * alpha
+    }
+
+    pub fn xyz(&self) -> Vec3 {
+        Vec3::new(self.x, self.y, self.z)
+    }
+
+    pub fn from_vec3(v: &Vec3, w: f32) -> Self {
+        Vec4 { x: v.x, y: v.y, z: v.z, w }
+    }
 }
 
 // Operator Overloads
@@ -200,3 +217,64 @@ impl DivAssign<f32> for Vec4 {
         self.w /= scalar;
     }
 }
+
+impl Mul<Vec4> for Vec4 {
+    type Output = Vec4;
+    fn mul(self, other: Vec4) -> Vec4 {
+        Vec4 { x: self.x * other.x, y: self.y * other.y, z: self.z * other.z, w: self.w * other.w }
+    }
+}
+
+impl MulAssign<Vec4> for Vec4 {
+    fn mul_assign(&mut self, other: Vec4) {
+        self.x *= other.x;
+        self.y *= other.y;
+        self.z *= other.z;
+        self.w *= other.w;
+    }
+}
+
+impl Mul<Vec4> for f32 {
+    type Output = Vec4;
+    fn mul(self, v: Vec4) -> Vec4 {
+        v * self
+    }
+}
+
+impl Div<Vec4> for Vec4 {
+    type Output = Vec4;
+    fn div(self, other: Vec4) -> Vec4 {
+        Vec4 { x: self.x / other.x, y: self.y / other.y, z: self.z / other.z, w: self.w / other.w }
+    }
+}
+
+impl DivAssign<Vec4> for Vec4 {
+    fn div_assign(&mut self, other: Vec4) {
+        self.x /= other.x;
+        self.y /= other.y;
+        self.z /= other.z;
+        self.w /= other.w;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_xyz_drops_w() {
+        let v = Vec4::new(1.0, 2.0, 3.0, 4.0);
+        assert_eq!(v.xyz(), Vec3::new(1.0, 2.0, 3.0));
+        assert_eq!(Vec4::from_vec3(&v.xyz(), 1.0), Vec4::new(1.0, 2.0, 3.0, 1.0));
+    }
+
+    #[test]
+    fn test_normalized_lerp_and_component_wise_ops() {
+        let v = Vec4::new(1.0, 2.0, 2.0, 4.0);
+        assert!((v.normalized().length() - 1.0).abs() < 1e-6);
+        assert_eq!(Vec4::ZERO.lerp(&v, 0.5), Vec4::new(0.5, 1.0, 1.0, 2.0));
+        assert_eq!(v * v, Vec4::new(1.0, 4.0, 4.0, 16.0));
+        assert_eq!((v * v) / v, v);
+        assert_eq!(2.0 * v, v * 2.0);
+    }
+}
@@ -1,3 +1,3 @@
 pub mod particle_system;
 
-pub use particle_system::{ParticleSystem, Particle};
+pub use particle_system::{ParticleSystem, Particle, ParticleEmitterConfig, BlendType, EmitterType};
@@ -1,5 +1,8 @@
-use crate::math::{Vec2, Vec3, Vec4};
+use crate::math::{Rng, Vec2, Vec3, Vec4};
 use crate::base::types::Color4F;
+use crate::renderer::{BlendState, Quad, Triangles};
+use crate::renderer::command::Vertex;
+use crate::sprite::BlendFunc;
 use std::f32::consts::PI;
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -65,6 +68,26 @@ impl Particle {
         self.life = self.max_life;
     }
 
+    pub fn get_position(&self) -> Vec3 {
+        self.position
+    }
+
+    pub fn get_velocity(&self) -> Vec3 {
+        self.velocity
+    }
+
+    pub fn get_size(&self) -> f32 {
+        self.size
+    }
+
+    pub fn get_life(&self) -> f32 {
+        self.life
+    }
+
+    pub fn get_max_life(&self) -> f32 {
+        self.max_life
+    }
+
     pub fn update(&mut self, delta: f32) {
         self.velocity += self.acceleration * delta;
         self.position += self.velocity * delta;
@@ -165,13 +188,13 @@ impl Default for ParticleEmitterConfig {
 pub struct ParticleSystem {
     config: ParticleEmitterConfig,
     particles: Vec<Particle>,
-    emission_count: u32,
+    emit_counter: f32,
     elapsed: f32,
     duration: f32,
     is_active: bool,
     is_visible: bool,
-    auto_remove: bool,
     texture: Option<()>,
+    rng: Rng,
 }
 
 impl ParticleSystem {
@@ -179,13 +202,13 @@ impl ParticleSystem {
         ParticleSystem {
             config: ParticleEmitterConfig::default(),
             particles: Vec::new(),
-            emission_count: 0,
+            emit_counter: 0.0,
             elapsed: 0.0,
             duration: -1.0,
             is_active: false,
             is_visible: true,
-            auto_remove: false,
             texture: None,
+            rng: Rng::from_entropy(),
         }
     }
 
@@ -206,10 +229,23 @@ impl ParticleSystem {
         self.texture = Some(texture);
     }
 
+    pub fn get_config(&self) -> &ParticleEmitterConfig {
+        &self.config
+    }
+
+    pub fn get_config_mut(&mut self) -> &mut ParticleEmitterConfig {
+        &mut self.config
+    }
+
+    /// Reseeds the emission randomness so the same seed replays the same particles
+    pub fn set_random_seed(&mut self, seed: u64) {
+        self.rng = Rng::from_seed(seed);
+    }
+
     pub fn start(&mut self) {
         self.is_active = true;
         self.elapsed = 0.0;
-        self.emission_count = 0;
+        self.emit_counter = 0.0;
     }
 
     pub fn stop(&mut self) {
@@ -219,20 +255,56 @@ impl ParticleSystem {
     pub fn reset(&mut self) {
         self.particles.clear();
         self.elapsed = 0.0;
-        self.emission_count = 0;
+        self.emit_counter = 0.0;
     }
 
-    pub fn update(&mut self, delta: f32) {
-        if !self.is_active {
-            return;
-        }
+    /// Sets how long the system emits after `start`. `None` emits until
+    /// stopped; live particles always finish their lifetime.
+    pub fn set_duration(&mut self, duration: Option<f32>) {
+        self.duration = duration.unwrap_or(-1.0);
+    }
 
-        self.elapsed += delta;
+    pub fn get_duration(&self) -> Option<f32> {
+        if self.duration < 0.0 {
+            None
+        } else {
+            Some(self.duration)
+        }
+    }
 
-        while self.emission_count < self.config.total_particles
-            && (self.duration < 0.0 || self.elapsed < self.duration) {
+    /// Emits up to `count` particles immediately, without exceeding
+    /// `total_particles` live particles. Returns the number emitted.
+    pub fn burst(&mut self, count: u32) -> u32 {
+        let free = self.config.total_particles.saturating_sub(self.particles.len() as u32);
+        let count = count.min(free);
+        for _ in 0..count {
             self.emit_particle();
-            self.emission_count += 1;
+        }
+        count
+    }
+
+    /// Emits `emission_rate` particles per second while active and advances
+    /// every live particle, including after emission has stopped
+    pub fn update(&mut self, delta: f32) {
+        if self.is_active {
+            let emit_time = if self.duration < 0.0 {
+                delta
+            } else {
+                delta.min((self.duration - self.elapsed).max(0.0))
+            };
+            self.elapsed += delta;
+
+            if (self.particles.len() as u32) < self.config.total_particles {
+                self.emit_counter += emit_time * self.config.emission_rate;
+            }
+            while self.emit_counter >= 1.0 && (self.particles.len() as u32) < self.config.total_particles {
+                self.emit_particle();
+                self.emit_counter -= 1.0;
+            }
+
+            if self.duration >= 0.0 && self.elapsed >= self.duration {
+                self.stop();
+            }
         }
 
         for particle in &mut self.particles {
@@ -240,25 +312,21 @@ impl ParticleSystem {
         }
 
         self.particles.retain(|p| p.life > 0.0);
-
-        if self.auto_remove && self.particles.is_empty() && self.emission_count >= self.config.total_particles {
-            self.is_active = false;
-        }
     }
 
     fn emit_particle(&mut self) {
         let mut particle = Particle::new();
-        particle.max_life = self.config.life + self.config.life_var * (rand::random::<f32>() - 0.5);
+        particle.max_life = self.config.life + self.config.life_var * self.rng.next_f32_in(-0.5, 0.5);
         particle.life = particle.max_life;
-        particle.start_size = self.config.start_size + self.config.start_size_var * (rand::random::<f32>() - 0.5);
-        particle.end_size = self.config.end_size + self.config.end_size_var * (rand::random::<f32>() - 0.5);
+        particle.start_size = self.config.start_size + self.config.start_size_var * self.rng.next_f32_in(-0.5, 0.5);
+        particle.end_size = self.config.end_size + self.config.end_size_var * self.rng.next_f32_in(-0.5, 0.5);
         particle.size = particle.start_size;
 
         // Calculate initial velocity based on emitter type
         match self.config.emitter_type {
             EmitterType::GRAVITY => {
-                let angle = (self.config.angle + self.config.angle_var * (rand::random::<f32>() - 0.5)) * PI / 180.0;
-                let speed = self.config.speed + self.config.speed_var * (rand::random::<f32>() - 0.5);
+                let angle = (self.config.angle + self.config.angle_var * self.rng.next_f32_in(-0.5, 0.5)) * PI / 180.0;
+                let speed = self.config.speed + self.config.speed_var * self.rng.next_f32_in(-0.5, 0.5);
                 particle.velocity.x = angle.cos() * speed;
                 particle.velocity.y = angle.sin() * speed;
                 particle.velocity.z = 0.0;
@@ -266,7 +334,7 @@ impl ParticleSystem {
             }
             EmitterType::RADIUS => {
                 particle.position.x = self.config.start_radius;
-                particle.velocity.z = (rand::random::<f32>() - 0.5) * self.config.rotate_per_second * PI / 180.0;
+                particle.velocity.z = self.rng.next_f32_in(-0.5, 0.5) * self.config.rotate_per_second * PI / 180.0;
             }
         }
 
@@ -275,6 +343,81 @@ impl ParticleSystem {
         self.particles.push(particle);
     }
 
+    /// Gets the blend factors for the configured blend type. SUBTRACT uses
+    /// additive factors; the subtraction comes from the blend equation set
+    /// by `get_blend_state` and carried by the built quads.
+    pub fn get_blend_func(&self) -> BlendFunc {
+        match self.config.blend_type {
+            BlendType::ADD | BlendType::SUBTRACT => BlendFunc::ADDITIVE,
+            BlendType::SCREEN => BlendFunc::SCREEN,
+        }
+    }
+
+    /// Gets the pipeline blend state for the configured blend type
+    pub fn get_blend_state(&self) -> BlendState {
+        let func = self.get_blend_func();
+        let mut state = BlendState::new();
+        state.set_enabled(true);
+        state.set_blend_func(func.src, func.dst);
+        if self.config.blend_type == BlendType::SUBTRACT {
+            state.set_blend_equation(BlendState::FUNC_REVERSE_SUBTRACT);
+        }
+        state
+    }
+
+    /// Builds one quad per live particle, centered on the particle, sized by
+    /// its size, rotated by its rotation in degrees (clockwise) and tinted
+    /// with its color, using the system's blend function and equation
+    pub fn build_quads(&self) -> Vec<Quad> {
+        let func = self.get_blend_func();
+        let equation = self.get_blend_state().get_rgb_op();
+        self.particles
+            .iter()
+            .filter(|particle| particle.life > 0.0)
+            .map(|particle| {
+                let half = particle.size * 0.5;
+                let (sin, cos) = (-particle.rotation * PI / 180.0).sin_cos();
+                let center = particle.position;
+                let corner = |x: f32, y: f32, u: f32, v: f32| Vertex {
+                    position: [center.x + x * cos - y * sin, center.y + x * sin + y * cos, center.z],
+                    tex_coord: [u, v],
+                    color: particle.color,
+                };
+
+                let mut quad = Quad::new();
+                quad.tl = corner(-half, half, 0.0, 0.0);
+                quad.tr = corner(half, half, 1.0, 0.0);
+                quad.bl = corner(-half, -half, 0.0, 1.0);
+                quad.br = corner(half, -half, 1.0, 1.0);
+                quad.blend_func = (func.src, func.dst);
+                quad.blend_equation = equation;
+                quad
+            })
+            .collect()
+    }
+
+    /// Packs the quads from `build_quads` into one vertex and index buffer so
+    /// every particle is drawn by a single batched `Triangles` command
+    pub fn build_triangles(&self) -> Triangles {
+        let quads = self.build_quads();
+        let mut triangles = Triangles::new();
+        triangles.vertices.reserve(quads.len() * 4);
+        triangles.indices.reserve(quads.len() * 6);
+        for (i, quad) in quads.iter().enumerate() {
+            let base = (i * 4) as u32;
+            triangles.vertices.extend_from_slice(&[quad.tl, quad.bl, quad.tr, quad.br]);
+            triangles.indices.extend_from_slice(&[base, base + 1, base + 2, base + 3, base + 2, base + 1]);
+        }
+        let func = self.get_blend_func();
+        triangles.blend_func = (func.src, func.dst);
+        triangles.blend_equation = self.get_blend_state().get_rgb_op();
+        triangles
+    }
+
+    pub fn get_particles(&self) -> &[Particle] {
+        &self.particles
+    }
+
     pub fn get_particle_count(&self) -> u32 {
         self.particles.len() as u32
     }
@@ -283,10 +426,16 @@ impl ParticleSystem {
         self.config.total_particles
     }
 
+    /// Checks if the system is still emitting
     pub fn is_active(&self) -> bool {
         self.is_active
     }
 
+    /// Checks if emission has stopped and every particle has expired
+    pub fn is_finished(&self) -> bool {
+        !self.is_active && self.particles.is_empty()
+    }
+
     pub fn is_visible(&self) -> bool {
         self.is_visible
     }
@@ -295,3 +444,180 @@ impl ParticleSystem {
         self.is_visible = visible;
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn emitted(seed: u64) -> Vec<(f32, f32, Vec3)> {
+        let mut system = ParticleSystem::new();
+        let config = system.get_config_mut();
+        config.total_particles = 10;
+        config.life_var = 0.5;
+        config.start_size_var = 4.0;
+        config.angle_var = 30.0;
+        config.speed_var = 20.0;
+        system.set_random_seed(seed);
+        system.burst(10);
+        system
+            .get_particles()
+            .iter()
+            .map(|p| (p.get_max_life(), p.get_size(), p.get_velocity()))
+            .collect()
+    }
+
+    #[test]
+    fn test_fixed_seed_reproduces_emission() {
+        let first = emitted(1234);
+        assert!(!first.is_empty());
+        assert_eq!(first, emitted(1234));
+        assert_ne!(first, emitted(4321));
+    }
+
+    fn system_with_blend(blend_type: BlendType) -> ParticleSystem {
+        let mut system = ParticleSystem::new();
+        system.get_config_mut().blend_type = blend_type;
+        system
+    }
+
+    #[test]
+    fn test_blend_type_maps_to_blend_func() {
+        let add = system_with_blend(BlendType::ADD);
+        assert_eq!(add.get_blend_func(), BlendFunc::ADDITIVE);
+        assert_eq!(add.get_blend_state().get_rgb_op(), BlendState::FUNC_ADD);
+
+        let screen = system_with_blend(BlendType::SCREEN);
+        assert_eq!(screen.get_blend_func(), BlendFunc::SCREEN);
+        let state = screen.get_blend_state();
+        assert!(state.is_enabled());
+        assert_eq!((state.get_src_rgb(), state.get_dst_rgb()), (BlendFunc::SCREEN.src, BlendFunc::SCREEN.dst));
+        assert_eq!(state.get_alpha_op(), BlendState::FUNC_ADD);
+    }
+
+    #[test]
+    fn test_subtract_uses_reverse_subtract_equation() {
+        let system = system_with_blend(BlendType::SUBTRACT);
+        let state = system.get_blend_state();
+        assert_eq!((state.get_src_rgb(), state.get_dst_rgb()), (BlendFunc::ADDITIVE.src, BlendFunc::ADDITIVE.dst));
+        assert_eq!(state.get_rgb_op(), BlendState::FUNC_REVERSE_SUBTRACT);
+        assert_eq!(state.get_alpha_op(), BlendState::FUNC_REVERSE_SUBTRACT);
+
+        let mut system = system;
+        system.get_config_mut().total_particles = 2;
+        system.set_random_seed(3);
+        system.burst(2);
+        assert!(system.build_quads().iter().all(|quad| quad.blend_equation == BlendState::FUNC_REVERSE_SUBTRACT));
+        assert_eq!(system.build_triangles().blend_equation, BlendState::FUNC_REVERSE_SUBTRACT);
+    }
+
+    #[test]
+    fn test_quads_use_blend_func() {
+        let mut system = system_with_blend(BlendType::SCREEN);
+        system.get_config_mut().total_particles = 3;
+        system.set_random_seed(5);
+        system.burst(3);
+
+        let quads = system.build_quads();
+        assert_eq!(quads.len(), 3);
+        assert!(quads.iter().all(|quad| quad.blend_func == (BlendFunc::SCREEN.src, BlendFunc::SCREEN.dst)));
+        let size = system.get_particles()[0].get_size();
+        assert_eq!(quads[0].tr.position[0] - quads[0].tl.position[0], size);
+    }
+
+    #[test]
+    fn test_build_quads_one_per_live_particle() {
+        let mut system = ParticleSystem::new();
+        system.get_config_mut().total_particles = 5;
+        system.set_random_seed(11);
+        system.burst(5);
+        assert_eq!(system.build_quads().len(), 5);
+
+        system.particles[1].life = 0.0;
+        system.particles[3].life = -0.5;
+        assert_eq!(system.build_quads().len(), 3);
+
+        let triangles = system.build_triangles();
+        assert_eq!(triangles.get_vertex_count(), 12);
+        assert_eq!(triangles.get_index_count(), 18);
+        assert_eq!(&triangles.indices[6..12], &[4, 5, 6, 7, 6, 5]);
+    }
+
+    #[test]
+    fn test_build_triangles_indexes_past_u16() {
+        let mut system = ParticleSystem::new();
+        system.get_config_mut().total_particles = 16385;
+        system.set_random_seed(1);
+        system.burst(16385);
+
+        let triangles = system.build_triangles();
+        assert_eq!(triangles.get_vertex_count(), 16385 * 4);
+        assert_eq!(&triangles.indices[16384 * 6..], &[65536, 65537, 65538, 65539, 65538, 65537]);
+    }
+
+    #[test]
+    fn test_build_quads_applies_rotation_size_and_color() {
+        let mut system = ParticleSystem::new();
+        system.get_config_mut().total_particles = 1;
+        system.burst(1);
+        let particle = &mut system.particles[0];
+        particle.position = Vec3::new(10.0, 20.0, 0.0);
+        particle.size = 4.0;
+        particle.color = Color4F::RED;
+
+        let quad = &system.build_quads()[0];
+        assert_eq!(quad.tl.position, [8.0, 22.0, 0.0]);
+        assert_eq!(quad.br.position, [12.0, 18.0, 0.0]);
+        assert_eq!(quad.tl.color, Color4F::RED);
+
+        // Rotating 90 degrees clockwise moves the top-left corner to the top-right
+        system.particles[0].rotation = 90.0;
+        let quad = &system.build_quads()[0];
+        assert!((quad.tl.position[0] - 12.0).abs() < 1e-5);
+        assert!((quad.tl.position[1] - 22.0).abs() < 1e-5);
+        assert!((quad.br.position[0] - 8.0).abs() < 1e-5);
+        assert!((quad.br.position[1] - 18.0).abs() < 1e-5);
+    }
+
+    #[test]
+    fn test_duration_stops_emission_but_keeps_live_particles() {
+        let mut system = ParticleSystem::new();
+        let config = system.get_config_mut();
+        config.emission_rate = 100.0;
+        config.total_particles = 1000;
+        config.life = 1.0;
+        system.set_duration(Some(0.2));
+        system.set_random_seed(3);
+        system.start();
+
+        system.update(0.1);
+        system.update(0.1);
+        let spawned = system.get_particle_count();
+        assert!((19..=20).contains(&spawned));
+        assert!(!system.is_active());
+
+        system.update(0.5);
+        assert_eq!(system.get_particle_count(), spawned);
+        assert!(!system.is_finished());
+
+        system.update(0.5);
+        assert_eq!(system.get_particle_count(), 0);
+        assert!(system.is_finished());
+    }
+
+    #[test]
+    fn test_burst_is_capped_at_total_particles() {
+        let mut system = ParticleSystem::new();
+        system.get_config_mut().total_particles = 30;
+        system.set_random_seed(8);
+
+        assert_eq!(system.burst(50), 30);
+        assert_eq!(system.get_particle_count(), 30);
+        assert_eq!(system.burst(5), 0);
+        assert!(!system.is_active());
+        assert!(!system.is_finished());
+
+        system.get_config_mut().total_particles = 100;
+        assert_eq!(system.burst(50), 50);
+        assert_eq!(system.get_particle_count(), 80);
+    }
+}
@@ -0,0 +1,229 @@
+use std::cmp::Ordering;
+use std::collections::{BinaryHeap, HashMap};
+
+/// Neighbourhood used when expanding a grid cell
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Connectivity {
+    /// Up, down, left and right
+    Four,
+    /// The four sides plus diagonals
+    Eight,
+}
+
+/// A grid of cells that can be searched with `astar`
+pub trait Grid {
+    /// Checks if a cell can be entered; cells outside the grid must return false
+    fn is_walkable(&self, x: i32, y: i32) -> bool;
+
+    /// Gets the cost of entering a cell. Costs should be at least 1 for the
+    /// returned path to be the cheapest one.
+    fn cost(&self, x: i32, y: i32) -> f32 {
+        let _ = (x, y);
+        1.0
+    }
+
+    /// Gets the neighbourhood used when expanding cells
+    fn connectivity(&self) -> Connectivity {
+        Connectivity::Four
+    }
+}
+
+const SIDES: [(i32, i32); 4] = [(1, 0), (-1, 0), (0, 1), (0, -1)];
+const DIAGONALS: [(i32, i32); 4] = [(1, 1), (1, -1), (-1, 1), (-1, -1)];
+
+/// Open-set entry ordered so the `BinaryHeap` pops the lowest estimate first
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct OpenNode {
+    estimate: f32,
+    cell: (i32, i32),
+}
+
+impl Eq for OpenNode {}
+
+impl Ord for OpenNode {
+    fn cmp(&self, other: &Self) -> Ordering {
+        other
+            .estimate
+            .partial_cmp(&self.estimate)
+            .unwrap_or(Ordering::Equal)
+            .then_with(|| self.cell.cmp(&other.cell))
+    }
+}
+
+impl PartialOrd for OpenNode {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+/// Lower bound of the cost between two cells for the given connectivity
+fn heuristic(from: (i32, i32), to: (i32, i32), connectivity: Connectivity) -> f32 {
+    let dx = (from.0 - to.0).abs() as f32;
+    let dy = (from.1 - to.1).abs() as f32;
+    match connectivity {
+        Connectivity::Four => dx + dy,
+        Connectivity::Eight => dx.max(dy) + (std::f32::consts::SQRT_2 - 1.0) * dx.min(dy),
+    }
+}
+
+/// Finds the cheapest path from `start` to `goal` with A*.
+///
+/// The path includes both ends, so `start == goal` yields a single cell.
+/// Diagonal steps cost `sqrt(2)` times the entered cell's cost and may not
+/// cut past a blocked corner. Returns None when the goal is unreachable.
+pub fn astar(grid: &impl Grid, start: (i32, i32), goal: (i32, i32)) -> Option<Vec<(i32, i32)>> {
+    if !grid.is_walkable(start.0, start.1) || !grid.is_walkable(goal.0, goal.1) {
+        return None;
+    }
+
+    let connectivity = grid.connectivity();
+    let mut open = BinaryHeap::new();
+    let mut came_from: HashMap<(i32, i32), (i32, i32)> = HashMap::new();
+    let mut best_cost: HashMap<(i32, i32), f32> = HashMap::new();
+
+    best_cost.insert(start, 0.0);
+    open.push(OpenNode {
+        estimate: heuristic(start, goal, connectivity),
+        cell: start,
+    });
+
+    while let Some(OpenNode { estimate, cell }) = open.pop() {
+        if cell == goal {
+            let mut path = vec![goal];
+            let mut current = goal;
+            while let Some(&previous) = came_from.get(&current) {
+                path.push(previous);
+                current = previous;
+            }
+            path.reverse();
+            return Some(path);
+        }
+
+        let cost_so_far = best_cost[&cell];
+        if estimate > cost_so_far + heuristic(cell, goal, connectivity) {
+            // Stale entry superseded by a cheaper route
+            continue;
+        }
+
+        let diagonals: &[(i32, i32)] = match connectivity {
+            Connectivity::Four => &[],
+            Connectivity::Eight => &DIAGONALS,
+        };
+        for &(dx, dy) in SIDES.iter().chain(diagonals) {
+            let next = (cell.0 + dx, cell.1 + dy);
+            if !grid.is_walkable(next.0, next.1) {
+                continue;
+            }
+            let diagonal = dx != 0 && dy != 0;
+            if diagonal && !(grid.is_walkable(cell.0 + dx, cell.1) && grid.is_walkable(cell.0, cell.1 + dy)) {
+                continue;
+            }
+
+            let step = if diagonal { std::f32::consts::SQRT_2 } else { 1.0 };
+            let cost = cost_so_far + grid.cost(next.0, next.1) * step;
+            if best_cost.get(&next).map_or(true, |&known| cost < known) {
+                best_cost.insert(next, cost);
+                came_from.insert(next, cell);
+                open.push(OpenNode {
+                    estimate: cost + heuristic(next, goal, connectivity),
+                    cell: next,
+                });
+            }
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Grid parsed from rows of `.` (floor) and `#` (wall)
+    struct TestGrid {
+        rows: Vec<Vec<bool>>,
+        connectivity: Connectivity,
+    }
+
+    impl TestGrid {
+        fn parse(rows: &[&str], connectivity: Connectivity) -> TestGrid {
+            TestGrid {
+                rows: rows.iter().map(|row| row.chars().map(|c| c == '.').collect()).collect(),
+                connectivity,
+            }
+        }
+    }
+
+    impl Grid for TestGrid {
+        fn is_walkable(&self, x: i32, y: i32) -> bool {
+            if x < 0 || y < 0 {
+                return false;
+            }
+            self.rows
+                .get(y as usize)
+                .and_then(|row| row.get(x as usize))
+                .copied()
+                .unwrap_or(false)
+        }
+
+        fn connectivity(&self) -> Connectivity {
+            self.connectivity
+        }
+    }
+
+    const WALLED: [&str; 4] = [
+        ".....",
+        ".###.",
+        "...#.",
+        ".....",
+    ];
+
+    #[test]
+    fn test_path_routes_around_wall() {
+        let grid = TestGrid::parse(&WALLED, Connectivity::Four);
+        let path = astar(&grid, (0, 2), (4, 2)).unwrap();
+
+        assert_eq!(path.first(), Some(&(0, 2)));
+        assert_eq!(path.last(), Some(&(4, 2)));
+        assert_eq!(path.len(), 7);
+        assert!(path.iter().all(|&(x, y)| grid.is_walkable(x, y)));
+        for pair in path.windows(2) {
+            let (a, b) = (pair[0], pair[1]);
+            assert_eq!((a.0 - b.0).abs() + (a.1 - b.1).abs(), 1);
+        }
+    }
+
+    #[test]
+    fn test_eight_connectivity_takes_diagonals() {
+        let grid = TestGrid::parse(&WALLED, Connectivity::Eight);
+        let path = astar(&grid, (0, 2), (4, 2)).unwrap();
+
+        // One diagonal shortcut; the wall corners at (3, 2) cannot be cut
+        assert_eq!(path.len(), 6);
+        let diagonal_steps = path
+            .windows(2)
+            .filter(|pair| pair[0].0 != pair[1].0 && pair[0].1 != pair[1].1)
+            .inspect(|pair| {
+                assert!(grid.is_walkable(pair[1].0, pair[0].1));
+                assert!(grid.is_walkable(pair[0].0, pair[1].1));
+            })
+            .count();
+        assert_eq!(diagonal_steps, 1);
+    }
+
+    #[test]
+    fn test_start_equals_goal() {
+        let grid = TestGrid::parse(&WALLED, Connectivity::Four);
+        assert_eq!(astar(&grid, (2, 0), (2, 0)), Some(vec![(2, 0)]));
+    }
+
+    #[test]
+    fn test_walled_off_goal_is_unreachable() {
+        let grid = TestGrid::parse(&[
+            "..#..",
+            "..#..",
+            "..#..",
+        ], Connectivity::Eight);
+        assert_eq!(astar(&grid, (0, 1), (4, 1)), None);
+        assert_eq!(astar(&grid, (0, 1), (2, 1)), None);
+    }
+}
@@ -1,3 +1,6 @@
+use crate::audio::AudioEngine;
+use crate::base::{Director, Size};
+
 /// Platform types
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum Platform {
@@ -62,11 +65,52 @@ pub trait ApplicationDelegate {
     fn application_did_become_active(&mut self);
 }
 
+/// The part of the director driven by application lifecycle events.
+///
+/// Implemented by `Director`; tests can drive `Application` with a double.
+pub trait LifecycleDirector {
+    /// Pauses the game loop and audio, remembering whether the game loop
+    /// was running
+    fn pause(&mut self);
+
+    /// Resumes the audio, and the game loop if `pause` stopped it
+    fn resume(&mut self);
+
+    /// Applies a new window size and recomputes the projection
+    fn resize(&mut self, size: Size);
+}
+
+impl LifecycleDirector for Director {
+    fn pause(&mut self) {
+        self.pause_for_background();
+        AudioEngine::pause_all();
+    }
+
+    fn resume(&mut self) {
+        self.resume_from_background();
+        AudioEngine::resume_all();
+    }
+
+    fn resize(&mut self, size: Size) {
+        self.set_frame_size(size);
+    }
+}
+
+/// Callback run on a lifecycle event
+pub type LifecycleCallback = Box<dyn FnMut()>;
+
+/// Callback run with the new window size after a resize
+pub type ResizeCallback = Box<dyn FnMut(Size)>;
+
 /// Application manages the main application lifecycle
 pub struct Application {
     delegate: Option<Box<dyn ApplicationDelegate>>,
     running: bool,
     paused: bool,
+    window_size: Size,
+    on_enter_background: Option<LifecycleCallback>,
+    on_enter_foreground: Option<LifecycleCallback>,
+    on_window_resize: Option<ResizeCallback>,
 }
 
 impl Application {
@@ -76,6 +120,10 @@ impl Application {
             delegate: None,
             running: false,
             paused: false,
+            window_size: Size::new(960.0, 640.0),
+            on_enter_background: None,
+            on_enter_foreground: None,
+            on_window_resize: None,
         }
     }
 
@@ -126,6 +174,73 @@ impl Application {
         self.paused = false;
     }
 
+    /// Checks if the application is paused
+    pub fn is_paused(&self) -> bool {
+        self.paused
+    }
+
+    /// Sets the callback run when the application enters the background
+    pub fn set_on_enter_background(&mut self, callback: LifecycleCallback) {
+        self.on_enter_background = Some(callback);
+    }
+
+    /// Sets the callback run when the application returns to the foreground
+    pub fn set_on_enter_foreground(&mut self, callback: LifecycleCallback) {
+        self.on_enter_foreground = Some(callback);
+    }
+
+    /// Sets the callback run after the window was resized
+    pub fn set_on_window_resize(&mut self, callback: ResizeCallback) {
+        self.on_window_resize = Some(callback);
+    }
+
+    /// Gets the window size in pixels
+    pub fn get_window_size(&self) -> Size {
+        self.window_size
+    }
+
+    /// Handles the OS moving the application to the background: pauses the
+    /// director, then notifies the delegate and the callback
+    pub fn on_enter_background(&mut self, director: &mut dyn LifecycleDirector) {
+        if self.paused {
+            return;
+        }
+        self.pause();
+        director.pause();
+        if let Some(delegate) = self.delegate.as_mut() {
+            delegate.application_did_enter_background();
+        }
+        if let Some(callback) = self.on_enter_background.as_mut() {
+            callback();
+        }
+    }
+
+    /// Handles the OS bringing the application back: resumes the director,
+    /// then notifies the delegate and the callback
+    pub fn on_enter_foreground(&mut self, director: &mut dyn LifecycleDirector) {
+        if !self.paused {
+            return;
+        }
+        self.resume();
+        director.resume();
+        if let Some(delegate) = self.delegate.as_mut() {
+            delegate.application_will_enter_foreground();
+        }
+        if let Some(callback) = self.on_enter_foreground.as_mut() {
+            callback();
+        }
+    }
+
+    /// Handles a window resize: stores the size, lets the director recompute
+    /// its projection, then notifies the callback
+    pub fn on_window_resize(&mut self, size: Size, director: &mut dyn LifecycleDirector) {
+        self.window_size = size;
+        director.resize(size);
+        if let Some(callback) = self.on_window_resize.as_mut() {
+            callback(size);
+        }
+    }
+
     /// Gets the application name
     pub fn get_application_name() -> String {
         "cocos2d-rust".to_string()
@@ -163,3 +278,115 @@ impl Application {
         "./Resources".to_string()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    #[derive(Default)]
+    struct MockDirector {
+        paused: bool,
+        paused_by_lifecycle: bool,
+        audio_paused: bool,
+        projection_sizes: Vec<Size>,
+    }
+
+    impl LifecycleDirector for MockDirector {
+        fn pause(&mut self) {
+            self.paused_by_lifecycle = !self.paused;
+            self.paused = true;
+            self.audio_paused = true;
+        }
+
+        fn resume(&mut self) {
+            if self.paused_by_lifecycle {
+                self.paused_by_lifecycle = false;
+                self.paused = false;
+            }
+            self.audio_paused = false;
+        }
+
+        fn resize(&mut self, size: Size) {
+            self.projection_sizes.push(size);
+        }
+    }
+
+    #[test]
+    fn test_background_and_foreground_toggle_pause() {
+        let events = Rc::new(RefCell::new(Vec::new()));
+        let mut application = Application::new();
+        let log = Rc::clone(&events);
+        application.set_on_enter_background(Box::new(move || log.borrow_mut().push("background")));
+        let log = Rc::clone(&events);
+        application.set_on_enter_foreground(Box::new(move || log.borrow_mut().push("foreground")));
+
+        let mut director = MockDirector::default();
+        application.on_enter_background(&mut director);
+        assert!(director.paused);
+        assert!(application.is_paused());
+        application.on_enter_background(&mut director);
+
+        application.on_enter_foreground(&mut director);
+        assert!(!director.paused);
+        assert!(!application.is_paused());
+        assert_eq!(*events.borrow(), vec!["background", "foreground"]);
+    }
+
+    #[test]
+    fn test_foreground_keeps_user_pause() {
+        let mut application = Application::new();
+        let mut director = MockDirector {
+            paused: true,
+            ..MockDirector::default()
+        };
+
+        application.on_enter_background(&mut director);
+        assert!(director.paused);
+        assert!(director.audio_paused);
+        application.on_enter_foreground(&mut director);
+        assert!(director.paused);
+        assert!(!director.audio_paused);
+    }
+
+    #[test]
+    fn test_director_stays_paused_by_user_after_foreground() {
+        let mut director = Director::new();
+        director.pause();
+        LifecycleDirector::pause(&mut director);
+        LifecycleDirector::resume(&mut director);
+        assert!(director.is_paused());
+
+        director.resume();
+        LifecycleDirector::pause(&mut director);
+        assert!(director.is_paused());
+        LifecycleDirector::resume(&mut director);
+        assert!(!director.is_paused());
+    }
+
+    #[test]
+    fn test_resize_updates_size_and_projection() {
+        let resized = Rc::new(RefCell::new(None));
+        let mut application = Application::new();
+        let last = Rc::clone(&resized);
+        application.set_on_window_resize(Box::new(move |size| *last.borrow_mut() = Some(size)));
+
+        let mut director = MockDirector::default();
+        application.on_window_resize(Size::new(1280.0, 720.0), &mut director);
+        assert_eq!(application.get_window_size(), Size::new(1280.0, 720.0));
+        assert_eq!(director.projection_sizes, vec![Size::new(1280.0, 720.0)]);
+        assert_eq!(*resized.borrow(), Some(Size::new(1280.0, 720.0)));
+    }
+
+    #[test]
+    fn test_director_resize_recomputes_projection() {
+        let mut director = Director::new();
+        LifecycleDirector::resize(&mut director, Size::new(1280.0, 720.0));
+        assert_eq!(director.get_frame_size(), Size::new(1280.0, 720.0));
+        assert_eq!(
+            director.get_projection_2d(),
+            crate::math::Mat4::create_orthographic_off_center(0.0, 1280.0, 0.0, 720.0, -1024.0, 1024.0)
+        );
+    }
+}
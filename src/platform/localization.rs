@@ -0,0 +1,134 @@
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::rc::Rc;
+use crate::platform::FileUtils;
+
+/// Localization holds per-language string tables and translates keys into
+/// the current language
+#[derive(Debug)]
+pub struct Localization {
+    tables: HashMap<String, HashMap<String, String>>,
+    language: String,
+}
+
+impl Localization {
+    /// Creates an empty localization set to English
+    pub fn new() -> Localization {
+        Localization {
+            tables: HashMap::new(),
+            language: String::from("en"),
+        }
+    }
+
+    /// Gets the localization of the current thread.
+    ///
+    /// Every call returns a handle to the same tables; borrow it mutably
+    /// only for the duration of a call.
+    pub fn get_instance() -> Rc<RefCell<Localization>> {
+        thread_local! {
+            static LOCALIZATION: Rc<RefCell<Localization>> = Rc::new(RefCell::new(Localization::new()));
+        }
+        LOCALIZATION.with(Rc::clone)
+    }
+
+    /// Loads a language's table from a JSON object of key/string pairs,
+    /// merging it into any table already loaded for that language
+    pub fn load_language_from_json(&mut self, language: &str, json: &str) -> bool {
+        let entries: HashMap<String, String> = match serde_json::from_str(json) {
+            Ok(entries) => entries,
+            Err(_) => return false,
+        };
+        self.tables.entry(language.to_string()).or_default().extend(entries);
+        true
+    }
+
+    /// Loads a language's table from a JSON file, resolved through the
+    /// `FileUtils` search paths
+    pub fn load_language_from_file(&mut self, language: &str, filename: &str) -> bool {
+        let file_utils = FileUtils::get_instance();
+        let path = file_utils
+            .get_full_path(filename)
+            .unwrap_or_else(|| PathBuf::from(filename));
+        match file_utils.get_string_from_file(&path.to_string_lossy()) {
+            Some(json) => self.load_language_from_json(language, &json),
+            None => false,
+        }
+    }
+
+    /// Sets the current language code
+    pub fn set_language(&mut self, language: &str) {
+        self.language = language.to_string();
+    }
+
+    /// Gets the current language code
+    pub fn get_language(&self) -> &str {
+        &self.language
+    }
+
+    /// Checks if a table is loaded for a language
+    pub fn has_language(&self, language: &str) -> bool {
+        self.tables.contains_key(language)
+    }
+
+    /// Translates a key into the current language. Missing keys return the
+    /// key itself so untranslated text stays visible.
+    pub fn tr<'a>(&'a self, key: &'a str) -> &'a str {
+        self.tables
+            .get(&self.language)
+            .and_then(|table| table.get(key))
+            .map_or(key, |text| text.as_str())
+    }
+}
+
+impl Default for Localization {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn two_languages() -> Localization {
+        let mut localization = Localization::new();
+        assert!(localization.load_language_from_json("en", r#"{"start": "Start", "quit": "Quit"}"#));
+        assert!(localization.load_language_from_json("fr", r#"{"start": "Commencer", "quit": "Quitter"}"#));
+        localization
+    }
+
+    #[test]
+    fn test_switching_languages() {
+        let mut localization = two_languages();
+        assert_eq!(localization.tr("start"), "Start");
+
+        localization.set_language("fr");
+        assert_eq!(localization.get_language(), "fr");
+        assert_eq!(localization.tr("start"), "Commencer");
+        assert_eq!(localization.tr("quit"), "Quitter");
+    }
+
+    #[test]
+    fn test_missing_key_echoes_itself() {
+        let mut localization = two_languages();
+        assert_eq!(localization.tr("options"), "options");
+
+        localization.set_language("de");
+        assert!(!localization.has_language("de"));
+        assert_eq!(localization.tr("start"), "start");
+    }
+
+    #[test]
+    fn test_invalid_json_is_rejected() {
+        let mut localization = Localization::new();
+        assert!(!localization.load_language_from_json("en", "[1, 2]"));
+        assert!(!localization.has_language("en"));
+    }
+
+    #[test]
+    fn test_instance_is_shared() {
+        Localization::get_instance().borrow_mut().set_language("instance-test");
+        assert_eq!(Localization::get_instance().borrow().get_language(), "instance-test");
+    }
+}
@@ -2,7 +2,11 @@
 pub mod file_utils;
 pub mod application;
 pub mod types;
+pub mod localization;
+pub mod user_default;
 
 pub use file_utils::FileUtils;
-pub use application::Application;
+pub use application::{Application, LifecycleDirector, LifecycleCallback, ResizeCallback};
 pub use types::{Platform, KeyboardState};
+pub use localization::Localization;
+pub use user_default::UserDefault;
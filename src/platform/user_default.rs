@@ -0,0 +1,174 @@
+use std::cell::RefCell;
+use std::path::{Path, PathBuf};
+use std::rc::Rc;
+use serde_json::{Map, Value};
+use crate::platform::FileUtils;
+
+/// File name of the shared store inside the writable path
+const USER_DEFAULT_FILE: &str = "UserDefault.json";
+
+/// UserDefault is a persistent key-value store for small game data such as
+/// settings and high scores.
+///
+/// Values live in memory until `flush` writes them to a JSON file.
+#[derive(Debug)]
+pub struct UserDefault {
+    path: PathBuf,
+    values: Map<String, Value>,
+}
+
+impl UserDefault {
+    /// Creates a store backed by `path`, loading any values already saved there
+    pub fn with_file(path: impl Into<PathBuf>) -> UserDefault {
+        let path = path.into();
+        let values = FileUtils::get_instance()
+            .get_string_from_file(&path.to_string_lossy())
+            .and_then(|json| serde_json::from_str(&json).ok())
+            .unwrap_or_default();
+        UserDefault { path, values }
+    }
+
+    /// Gets the store of the current thread, stored in the `FileUtils`
+    /// writable path.
+    ///
+    /// Every call returns a handle to the same store; borrow it mutably only
+    /// for the duration of a call.
+    pub fn get_instance() -> Rc<RefCell<UserDefault>> {
+        thread_local! {
+            static USER_DEFAULT: Rc<RefCell<UserDefault>> = {
+                let path = FileUtils::get_instance().get_writable_path().join(USER_DEFAULT_FILE);
+                Rc::new(RefCell::new(UserDefault::with_file(path)))
+            };
+        }
+        USER_DEFAULT.with(Rc::clone)
+    }
+
+    /// Gets the backing file path
+    pub fn get_file_path(&self) -> &Path {
+        &self.path
+    }
+
+    pub fn set_int(&mut self, key: &str, value: i32) {
+        self.values.insert(key.to_string(), Value::from(value));
+    }
+
+    pub fn get_int(&self, key: &str) -> i32 {
+        self.get_int_with_default(key, 0)
+    }
+
+    pub fn get_int_with_default(&self, key: &str, default: i32) -> i32 {
+        self.values
+            .get(key)
+            .and_then(Value::as_i64)
+            .map_or(default, |value| value as i32)
+    }
+
+    pub fn set_float(&mut self, key: &str, value: f32) {
+        self.values.insert(key.to_string(), Value::from(value as f64));
+    }
+
+    pub fn get_float(&self, key: &str) -> f32 {
+        self.get_float_with_default(key, 0.0)
+    }
+
+    pub fn get_float_with_default(&self, key: &str, default: f32) -> f32 {
+        self.values
+            .get(key)
+            .and_then(Value::as_f64)
+            .map_or(default, |value| value as f32)
+    }
+
+    pub fn set_bool(&mut self, key: &str, value: bool) {
+        self.values.insert(key.to_string(), Value::from(value));
+    }
+
+    pub fn get_bool(&self, key: &str) -> bool {
+        self.get_bool_with_default(key, false)
+    }
+
+    pub fn get_bool_with_default(&self, key: &str, default: bool) -> bool {
+        self.values.get(key).and_then(Value::as_bool).unwrap_or(default)
+    }
+
+    pub fn set_string(&mut self, key: &str, value: &str) {
+        self.values.insert(key.to_string(), Value::from(value));
+    }
+
+    pub fn get_string(&self, key: &str) -> String {
+        self.get_string_with_default(key, "")
+    }
+
+    pub fn get_string_with_default(&self, key: &str, default: &str) -> String {
+        self.values
+            .get(key)
+            .and_then(Value::as_str)
+            .unwrap_or(default)
+            .to_string()
+    }
+
+    /// Checks if a value is stored for a key
+    pub fn has_key(&self, key: &str) -> bool {
+        self.values.contains_key(key)
+    }
+
+    /// Removes the value stored for a key
+    pub fn delete_value_for_key(&mut self, key: &str) {
+        self.values.remove(key);
+    }
+
+    /// Writes every value to the backing file. Returns false if the file
+    /// could not be written.
+    pub fn flush(&self) -> bool {
+        let json = match serde_json::to_string_pretty(&self.values) {
+            Ok(json) => json,
+            Err(_) => return false,
+        };
+        FileUtils::get_instance().write_string_to_file(&json, &self.path.to_string_lossy())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    fn temp_file(name: &str) -> PathBuf {
+        let path = std::env::temp_dir().join(format!("cocos2d-rust-{}-{}.json", name, std::process::id()));
+        let _ = fs::remove_file(&path);
+        path
+    }
+
+    #[test]
+    fn test_flushed_values_are_read_back() {
+        let path = temp_file("user-default");
+        let mut store = UserDefault::with_file(&path);
+        store.set_int("level", 7);
+        store.set_float("volume", 0.5);
+        store.set_bool("muted", true);
+        store.set_string("name", "player one");
+        assert!(store.flush());
+
+        let reloaded = UserDefault::with_file(&path);
+        assert_eq!(reloaded.get_int("level"), 7);
+        assert_eq!(reloaded.get_float("volume"), 0.5);
+        assert!(reloaded.get_bool("muted"));
+        assert_eq!(reloaded.get_string("name"), "player one");
+        let _ = fs::remove_file(&path);
+    }
+
+    #[test]
+    fn test_missing_keys_return_defaults() {
+        let path = temp_file("user-default-empty");
+        let mut store = UserDefault::with_file(&path);
+        assert_eq!(store.get_int_with_default("score", 42), 42);
+        assert_eq!(store.get_float_with_default("speed", 1.5), 1.5);
+        assert!(store.get_bool_with_default("tutorial", true));
+        assert_eq!(store.get_string_with_default("name", "guest"), "guest");
+        assert_eq!(store.get_int("score"), 0);
+
+        store.set_string("score", "high");
+        assert_eq!(store.get_int_with_default("score", 42), 42);
+        store.delete_value_for_key("score");
+        assert!(!store.has_key("score"));
+    }
+}
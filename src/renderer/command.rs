@@ -1,7 +1,7 @@
 use crate::math::Mat4;
 use crate::base::{Ref, RefPtr};
 use crate::base::types::Color4F;
-use super::{Renderer, Texture2D};
+use super::{BlendState, Renderer, Texture2D};
 
 pub trait RenderCommand {
     fn get_command_type(&self) -> CommandType;
@@ -23,8 +23,11 @@ pub enum CommandType {
 #[derive(Debug, Clone)]
 pub struct Triangles {
     pub vertices: Vec<Vertex>,
-    pub indices: Vec<u16>,
+    /// 32-bit so batches of more than 16384 quads index every vertex
+    pub indices: Vec<u32>,
     pub blend_func: (u32, u32),
+    /// Blend equation, one of the `BlendState::FUNC_*` values
+    pub blend_equation: u32,
     pub texture: Option<RefPtr<Texture2D>>,
     pub model_matrix: Mat4,
 }
@@ -35,6 +38,7 @@ impl Triangles {
             vertices: Vec::new(),
             indices: Vec::new(),
             blend_func: (770, 771),
+            blend_equation: BlendState::FUNC_ADD,
             texture: None,
             model_matrix: Mat4::IDENTITY,
         }
@@ -56,6 +60,8 @@ pub struct Quad {
     pub bl: Vertex,
     pub br: Vertex,
     pub blend_func: (u32, u32),
+    /// Blend equation, one of the `BlendState::FUNC_*` values
+    pub blend_equation: u32,
     pub texture: Option<RefPtr<Texture2D>>,
     pub model_matrix: Mat4,
 }
@@ -68,6 +74,7 @@ impl Quad {
             bl: Vertex::default(),
             br: Vertex::default(),
             blend_func: (770, 771),
+            blend_equation: BlendState::FUNC_ADD,
             texture: None,
             model_matrix: Mat4::IDENTITY,
         }
@@ -117,6 +124,43 @@ impl Vertex {
     }
 }
 
+/// Draws a quad through the renderer's batch; consecutive quads with the
+/// same texture, blend function and blend equation share one draw call
+#[derive(Debug, Clone)]
+pub struct QuadCommand {
+    command_type: CommandType,
+    global_order: f32,
+    quad: Quad,
+}
+
+impl QuadCommand {
+    pub fn new(global_order: f32, quad: Quad) -> QuadCommand {
+        QuadCommand {
+            command_type: CommandType::Quad,
+            global_order,
+            quad,
+        }
+    }
+
+    pub fn get_quad(&self) -> &Quad {
+        &self.quad
+    }
+}
+
+impl RenderCommand for QuadCommand {
+    fn get_command_type(&self) -> CommandType {
+        self.command_type
+    }
+
+    fn get_global_order(&self) -> f32 {
+        self.global_order
+    }
+
+    fn execute(&self, renderer: &mut Renderer) {
+        renderer.batch_quad(&self.quad);
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct MeshCommand {
     command_type: CommandType,
@@ -226,7 +270,9 @@ impl RenderCommand for CallbackCommand {
     }
 }
 
-#[derive(Debug, Clone)]
+/// Runs user code, e.g. raw GL calls, at its place in the sorted command
+/// order. The pending batch is flushed first so earlier quads are drawn
+/// before the callback.
 pub struct CustomCommand {
     command_type: CommandType,
     global_order: f32,
@@ -234,6 +280,16 @@ pub struct CustomCommand {
     callback: Box<dyn Fn(&mut Renderer)>,
 }
 
+impl std::fmt::Debug for CustomCommand {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("CustomCommand")
+            .field("command_type", &self.command_type)
+            .field("global_order", &self.global_order)
+            .field("depth", &self.depth)
+            .finish()
+    }
+}
+
 impl CustomCommand {
     pub fn new() -> CustomCommand {
         CustomCommand {
@@ -251,6 +307,10 @@ impl CustomCommand {
     pub fn set_depth(&mut self, depth: f32) {
         self.depth = depth;
     }
+
+    pub fn set_global_order(&mut self, global_order: f32) {
+        self.global_order = global_order;
+    }
 }
 
 impl RenderCommand for CustomCommand {
@@ -263,6 +323,7 @@ impl RenderCommand for CustomCommand {
     }
 
     fn execute(&self, renderer: &mut Renderer) {
+        renderer.flush();
         (self.callback)(renderer);
     }
 }
@@ -1,12 +1,15 @@
 use std::collections::HashMap;
+use serde::Deserialize;
 use crate::base::{Ref, RefPtr};
 use crate::base::types::Color4F;
-use crate::math::{Vec4, Mat4};
+use crate::math::{Vec2, Vec3, Vec4, Mat4};
+use crate::shader::ShaderCache;
 
 #[derive(Debug, Clone)]
 pub struct Material {
     name: String,
-    technique: Option<RefPtr<Technique>>,
+    /// Name of the active technique in `techniques`
+    technique: Option<String>,
     techniques: HashMap<String, RefPtr<Technique>>,
     state: MaterialState,
 }
@@ -30,6 +33,55 @@ impl Material {
         }
     }
 
+    /// Loads a JSON material description, resolving pass programs through
+    /// the shared `ShaderCache`
+    pub fn from_file(path: &str) -> Result<Material, String> {
+        let json = std::fs::read_to_string(path)
+            .map_err(|e| format!("Failed to read material file '{}': {}", path, e))?;
+        Material::from_json(&json, &ShaderCache::shared().borrow())
+    }
+
+    /// Builds a material from a JSON description:
+    ///
+    /// ```json
+    /// { "name": "sprite", "default_technique": "normal",
+    ///   "techniques": [{ "name": "normal", "passes": [{
+    ///       "program": "position_texture_color",
+    ///       "render_state": { "blend": true, "depth_write": false },
+    ///       "uniforms": { "u_alpha": 1.0, "u_tint": [1.0, 1.0, 1.0, 1.0] } }] }] }
+    /// ```
+    ///
+    /// The default technique falls back to the first one listed.
+    pub fn from_json(json: &str, shader_cache: &ShaderCache) -> Result<Material, String> {
+        let data: MaterialData = serde_json::from_str(json)
+            .map_err(|e| format!("Failed to parse material: {}", e))?;
+
+        let mut material = Material::with_name(&data.name);
+        for technique_data in &data.techniques {
+            let mut technique = Technique::with_name(&technique_data.name);
+            for pass_data in &technique_data.passes {
+                technique.add_pass(RefPtr::new(pass_data.to_pass(shader_cache)?));
+            }
+
+            let technique = RefPtr::new(technique);
+            let is_default = match &data.default_technique {
+                Some(name) => *name == technique_data.name,
+                None => material.technique.is_none(),
+            };
+            if is_default {
+                material.set_technique(technique.clone());
+            }
+            material.add_technique(&technique_data.name, technique);
+        }
+
+        if let Some(name) = &data.default_technique {
+            if material.technique.is_none() {
+                return Err(format!("Default technique '{}' not found", name));
+            }
+        }
+        Ok(material)
+    }
+
     pub fn set_name(&mut self, name: &str) {
         self.name = name.to_string();
     }
@@ -38,12 +90,16 @@ impl Material {
         &self.name
     }
 
+    /// Makes `technique` the active one, adding it under its name
     pub fn set_technique(&mut self, technique: RefPtr<Technique>) {
-        self.technique = Some(technique);
+        let name = technique.get_name().to_string();
+        self.techniques.insert(name.clone(), technique);
+        self.technique = Some(name);
     }
 
+    /// Gets the active technique
     pub fn get_technique(&self) -> Option<&RefPtr<Technique>> {
-        self.technique.as_ref()
+        self.techniques.get(self.technique.as_ref()?)
     }
 
     pub fn add_technique(&mut self, name: &str, technique: RefPtr<Technique>) {
@@ -82,6 +138,49 @@ impl Material {
     pub fn set_cull_mode(&mut self, mode: u32) {
         self.state.cull_mode = mode;
     }
+
+    /// Clones the material with its own copies of every technique and pass,
+    /// so uniform overrides on the clone never reach the source
+    pub fn clone_with_overrides(&self) -> Material {
+        let techniques = self
+            .techniques
+            .iter()
+            .map(|(name, technique)| (name.clone(), RefPtr::new(technique.clone_deep())))
+            .collect();
+        Material {
+            name: self.name.clone(),
+            technique: self.technique.clone(),
+            techniques,
+            state: self.state.clone(),
+        }
+    }
+
+    /// Sets a uniform on the first pass of the active technique. A technique
+    /// shared with other holders, strong or weak, is copied first so they
+    /// are unaffected. Returns false when there is no active pass.
+    pub fn set_uniform(&mut self, name: &str, value: UniformValue) -> bool {
+        let technique = match self.technique.as_ref().and_then(|active| self.techniques.get_mut(active)) {
+            Some(technique) => technique,
+            None => return false,
+        };
+        if RefPtr::get_mut(technique).is_none() {
+            *technique = RefPtr::new(technique.clone_deep());
+        }
+        let pass = match technique.borrow_mut().passes.first_mut() {
+            Some(pass) => pass,
+            None => return false,
+        };
+        if RefPtr::get_mut(pass).is_none() {
+            *pass = RefPtr::new(pass.borrow().clone());
+        }
+        pass.borrow_mut().set_uniform(name, value);
+        true
+    }
+
+    /// Gets a uniform from the first pass of the active technique
+    pub fn get_uniform(&self, name: &str) -> Option<&UniformValue> {
+        self.get_technique()?.passes.first()?.get_uniform(name)
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -160,6 +259,15 @@ impl Technique {
     pub fn get_render_state(&self, index: u32) -> Option<&RenderState> {
         self.render_states.get(index as usize)
     }
+
+    /// Clones the technique along with its own copy of every pass
+    pub fn clone_deep(&self) -> Technique {
+        Technique {
+            name: self.name.clone(),
+            passes: self.passes.iter().map(|pass| RefPtr::new(pass.borrow().clone())).collect(),
+            render_states: self.render_states.clone(),
+        }
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -262,20 +370,55 @@ impl RenderState {
     }
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq)]
 pub enum UniformValue {
     Float(f32),
-    Vec2(Vec4),
-    Vec3(Vec4),
+    Vec2(Vec2),
+    Vec3(Vec3),
     Vec4(Vec4),
     Mat4(Mat4),
     Int(i32),
-    IVec2(Vec4),
-    IVec3(Vec4),
-    IVec4(Vec4),
+    IVec2([i32; 2]),
+    IVec3([i32; 3]),
+    IVec4([i32; 4]),
     Sampler(i32),
 }
 
+impl UniformValue {
+    /// Gets the uniform type matching this value
+    pub fn gl_type(&self) -> UniformType {
+        match self {
+            UniformValue::Float(_) => UniformType::Float,
+            UniformValue::Vec2(_) => UniformType::Vec2,
+            UniformValue::Vec3(_) => UniformType::Vec3,
+            UniformValue::Vec4(_) => UniformType::Vec4,
+            UniformValue::Mat4(_) => UniformType::Mat4,
+            UniformValue::Int(_) => UniformType::Int,
+            UniformValue::IVec2(_) => UniformType::IVec2,
+            UniformValue::IVec3(_) => UniformType::IVec3,
+            UniformValue::IVec4(_) => UniformType::IVec4,
+            UniformValue::Sampler(_) => UniformType::Sampler2D,
+        }
+    }
+
+    /// Packs the value's components, in order, as native-endian bytes ready
+    /// for upload
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let floats: Vec<f32> = match self {
+            UniformValue::Float(v) => vec![*v],
+            UniformValue::Vec2(v) => vec![v.x, v.y],
+            UniformValue::Vec3(v) => vec![v.x, v.y, v.z],
+            UniformValue::Vec4(v) => vec![v.x, v.y, v.z, v.w],
+            UniformValue::Mat4(m) => m.m.to_vec(),
+            UniformValue::Int(v) | UniformValue::Sampler(v) => return v.to_ne_bytes().to_vec(),
+            UniformValue::IVec2(v) => return v.iter().flat_map(|i| i.to_ne_bytes()).collect(),
+            UniformValue::IVec3(v) => return v.iter().flat_map(|i| i.to_ne_bytes()).collect(),
+            UniformValue::IVec4(v) => return v.iter().flat_map(|i| i.to_ne_bytes()).collect(),
+        };
+        floats.iter().flat_map(|f| f.to_ne_bytes()).collect()
+    }
+}
+
 #[derive(Debug)]
 pub struct Program {
     name: String,
@@ -383,3 +526,243 @@ impl UniformType {
         }
     }
 }
+
+#[derive(Debug, Deserialize)]
+struct MaterialData {
+    #[serde(default)]
+    name: String,
+    #[serde(default)]
+    default_technique: Option<String>,
+    #[serde(default)]
+    techniques: Vec<TechniqueData>,
+}
+
+#[derive(Debug, Deserialize)]
+struct TechniqueData {
+    #[serde(default)]
+    name: String,
+    #[serde(default)]
+    passes: Vec<PassData>,
+}
+
+#[derive(Debug, Deserialize)]
+struct PassData {
+    #[serde(default)]
+    name: String,
+    #[serde(default)]
+    program: Option<String>,
+    #[serde(default)]
+    render_state: RenderStateData,
+    #[serde(default)]
+    uniforms: HashMap<String, UniformData>,
+}
+
+impl PassData {
+    fn to_pass(&self, shader_cache: &ShaderCache) -> Result<Pass, String> {
+        let mut pass = Pass::new();
+        pass.set_name(&self.name);
+
+        if let Some(name) = &self.program {
+            let shader = shader_cache
+                .get_program(name)
+                .ok_or_else(|| format!("Shader program '{}' not found", name))?;
+            let shader = shader.borrow();
+            let mut program = Program::with_name(name);
+            program.set_vertex_shader(shader.vertex_source());
+            program.set_fragment_shader(shader.fragment_source());
+            pass.set_program(RefPtr::new(program));
+        }
+
+        self.render_state.apply(pass.get_render_state_mut());
+        for (name, value) in &self.uniforms {
+            pass.set_uniform(name, value.to_uniform_value(name)?);
+        }
+        Ok(pass)
+    }
+}
+
+/// Render state overrides; unset fields keep the `RenderState` defaults
+#[derive(Debug, Default, Deserialize)]
+struct RenderStateData {
+    depth_write: Option<bool>,
+    depth_test: Option<bool>,
+    depth_func: Option<u32>,
+    blend: Option<bool>,
+    blend_src: Option<u32>,
+    blend_dst: Option<u32>,
+    cull_mode: Option<u32>,
+    alpha_test: Option<bool>,
+    alpha_test_value: Option<f32>,
+}
+
+impl RenderStateData {
+    fn apply(&self, state: &mut RenderState) {
+        state.depth_write = self.depth_write.unwrap_or(state.depth_write);
+        state.depth_test = self.depth_test.unwrap_or(state.depth_test);
+        state.depth_func = self.depth_func.unwrap_or(state.depth_func);
+        state.blend = self.blend.unwrap_or(state.blend);
+        state.blend_src = self.blend_src.unwrap_or(state.blend_src);
+        state.blend_dst = self.blend_dst.unwrap_or(state.blend_dst);
+        state.cull_mode = self.cull_mode.unwrap_or(state.cull_mode);
+        state.alpha_test = self.alpha_test.unwrap_or(state.alpha_test);
+        state.alpha_test_value = self.alpha_test_value.unwrap_or(state.alpha_test_value);
+    }
+}
+
+/// A uniform default: a scalar, or a 2/3/4/16 component array
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+enum UniformData {
+    Float(f32),
+    Array(Vec<f32>),
+}
+
+impl UniformData {
+    fn to_uniform_value(&self, name: &str) -> Result<UniformValue, String> {
+        match self {
+            UniformData::Float(v) => Ok(UniformValue::Float(*v)),
+            UniformData::Array(v) => match v.len() {
+                2 => Ok(UniformValue::Vec2(Vec2::new(v[0], v[1]))),
+                3 => Ok(UniformValue::Vec3(Vec3::new(v[0], v[1], v[2]))),
+                4 => Ok(UniformValue::Vec4(Vec4::new(v[0], v[1], v[2], v[3]))),
+                16 => {
+                    let mut m = [0.0; 16];
+                    m.copy_from_slice(v);
+                    Ok(UniformValue::Mat4(Mat4 { m }))
+                }
+                n => Err(format!("Uniform '{}' has unsupported component count {}", name, n)),
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_uniform_vec3_to_bytes() {
+        let value = UniformValue::Vec3(Vec3::new(1.0, 2.0, 3.0));
+        let bytes = value.to_bytes();
+
+        assert_eq!(bytes.len(), 12);
+        assert_eq!(&bytes[0..4], &1.0f32.to_ne_bytes());
+        assert_eq!(&bytes[4..8], &2.0f32.to_ne_bytes());
+        assert_eq!(&bytes[8..12], &3.0f32.to_ne_bytes());
+        assert_eq!(bytes.len() as u32, value.gl_type().get_size());
+    }
+
+    #[test]
+    fn test_uniform_gl_type() {
+        assert_eq!(UniformValue::Vec3(Vec3::new(0.0, 0.0, 0.0)).gl_type(), UniformType::Vec3);
+        assert_eq!(UniformValue::Vec2(Vec2::new(0.0, 0.0)).gl_type(), UniformType::Vec2);
+        assert_eq!(UniformValue::Sampler(0).gl_type(), UniformType::Sampler2D);
+        assert_eq!(UniformValue::Mat4(Mat4::IDENTITY).to_bytes().len(), 64);
+        assert_eq!(UniformValue::IVec3([1, 2, 3]).to_bytes().len(), 12);
+    }
+
+    fn tinted_material() -> Material {
+        let mut pass = Pass::new();
+        pass.set_uniform("u_tint", UniformValue::Float(1.0));
+        let mut technique = Technique::with_name("normal");
+        technique.add_pass(RefPtr::new(pass));
+
+        let technique = RefPtr::new(technique);
+        let mut material = Material::with_name("sprite");
+        material.add_technique("normal", technique.clone());
+        material.set_technique(technique);
+        material
+    }
+
+    #[test]
+    fn test_clone_with_overrides_is_independent() {
+        let original = tinted_material();
+        let mut clone = original.clone_with_overrides();
+
+        assert!(clone.set_uniform("u_tint", UniformValue::Float(0.25)));
+        assert_eq!(clone.get_uniform("u_tint"), Some(&UniformValue::Float(0.25)));
+        assert_eq!(original.get_uniform("u_tint"), Some(&UniformValue::Float(1.0)));
+
+        let named = original.get_technique_by_name("normal").unwrap();
+        assert_eq!(named.get_passes()[0].get_uniform("u_tint"), Some(&UniformValue::Float(1.0)));
+        let named = clone.get_technique_by_name("normal").unwrap();
+        assert_eq!(named.get_passes()[0].get_uniform("u_tint"), Some(&UniformValue::Float(0.25)));
+        assert!(RefPtr::ptr_eq(named, clone.get_technique().unwrap()));
+    }
+
+    #[test]
+    fn test_set_uniform_copies_shared_technique() {
+        let mut material = tinted_material();
+        let shared = material.get_technique().unwrap().clone();
+
+        assert!(material.set_uniform("u_tint", UniformValue::Float(0.5)));
+        assert_eq!(material.get_uniform("u_tint"), Some(&UniformValue::Float(0.5)));
+        assert_eq!(shared.get_passes()[0].get_uniform("u_tint"), Some(&UniformValue::Float(1.0)));
+
+        assert!(!Material::new().set_uniform("u_tint", UniformValue::Float(0.5)));
+    }
+
+    #[test]
+    fn test_set_uniform_copies_weakly_held_technique_and_pass() {
+        let mut material = tinted_material();
+        let technique = material.get_technique().unwrap().downgrade();
+        let pass = material.get_technique().unwrap().get_passes()[0].downgrade();
+
+        assert!(material.set_uniform("u_tint", UniformValue::Float(0.5)));
+        assert_eq!(material.get_uniform("u_tint"), Some(&UniformValue::Float(0.5)));
+        assert!(technique.upgrade().is_none());
+        assert!(pass.upgrade().is_none());
+    }
+
+    const SPRITE_MATERIAL: &str = r#"{
+        "name": "sprite",
+        "default_technique": "outline",
+        "techniques": [
+            { "name": "normal", "passes": [
+                { "program": "sprite_shader",
+                  "render_state": { "blend": true, "depth_write": false },
+                  "uniforms": { "u_alpha": 0.75, "u_tint": [1.0, 0.5, 0.25, 1.0] } }
+            ] },
+            { "name": "outline", "passes": [
+                { "program": "sprite_shader", "uniforms": { "u_width": 2.0 } },
+                { "program": "sprite_shader" }
+            ] }
+        ]
+    }"#;
+
+    fn sprite_shader_cache() -> ShaderCache {
+        let mut cache = ShaderCache::new();
+        cache.load_program_from_source("sprite_shader", "void main() {}", "void main() {}").unwrap();
+        cache
+    }
+
+    #[test]
+    fn test_material_from_json() {
+        let material = Material::from_json(SPRITE_MATERIAL, &sprite_shader_cache()).unwrap();
+        assert_eq!(material.get_name(), "sprite");
+
+        let normal = material.get_technique_by_name("normal").unwrap();
+        assert_eq!(normal.get_pass_count(), 1);
+        let pass = &normal.get_passes()[0];
+        assert_eq!(pass.get_uniform("u_alpha"), Some(&UniformValue::Float(0.75)));
+        assert_eq!(pass.get_uniform("u_tint"), Some(&UniformValue::Vec4(Vec4::new(1.0, 0.5, 0.25, 1.0))));
+        assert!(pass.get_render_state().blend);
+        assert!(!pass.get_render_state().depth_write);
+        assert!(pass.get_render_state().depth_test);
+        assert_eq!(pass.get_program().unwrap().get_name(), "sprite_shader");
+
+        let active = material.get_technique().unwrap();
+        assert_eq!(active.get_name(), "outline");
+        assert_eq!(active.get_pass_count(), 2);
+        assert_eq!(material.get_uniform("u_width"), Some(&UniformValue::Float(2.0)));
+    }
+
+    #[test]
+    fn test_material_from_json_errors() {
+        let cache = ShaderCache::new();
+        assert!(Material::from_json(SPRITE_MATERIAL, &cache).is_err());
+        assert!(Material::from_json("{ not json", &cache).is_err());
+        assert!(Material::from_json(r#"{ "default_technique": "missing" }"#, &cache).is_err());
+        assert!(Material::from_file("does/not/exist.material").is_err());
+    }
+}
@@ -5,9 +5,9 @@ pub mod pipeline;
 pub mod texture;
 pub mod render_texture;
 
-pub use renderer::Renderer;
-pub use command::{RenderCommand, CommandType, Triangles, Quad};
-pub use material::{Material, Technique, Pass};
+pub use renderer::{QuadRenderer, Renderer};
+pub use command::{RenderCommand, CommandType, Triangles, Quad, QuadCommand, CustomCommand};
+pub use material::{Material, Technique, Pass, UniformValue, UniformType};
 pub use pipeline::{PipelineState, BlendState, DepthStencilState, RasterizerState};
-pub use texture::{Texture, Texture2D, TextureAtlas, Sampler, PixelFormat, TextureType};
+pub use texture::{Texture, Texture2D, TextureRegionUpdate, TextureAtlas, TextureQuad, TexturedVertex, DynamicAtlas, Sampler, TextureFilter, TextureWrap, PixelFormat, TextureType};
 pub use render_texture::RenderTexture;
@@ -1,4 +1,5 @@
 use crate::base::types::Color4F;
+use crate::renderer::renderer::ScissorRect;
 
 #[derive(Debug, Clone)]
 pub struct PipelineState {
@@ -115,6 +116,11 @@ pub struct BlendState {
 }
 
 impl BlendState {
+    /// Blend equation adding source and destination
+    pub const FUNC_ADD: u32 = 0x8006;
+    /// Blend equation subtracting the source from the destination
+    pub const FUNC_REVERSE_SUBTRACT: u32 = 0x800B;
+
     pub fn new() -> BlendState {
         BlendState {
             enabled: false,
@@ -122,8 +128,8 @@ impl BlendState {
             dst_rgb: 771,
             src_alpha: 770,
             dst_alpha: 771,
-            rgb_op: 32774,
-            alpha_op: 32774,
+            rgb_op: BlendState::FUNC_ADD,
+            alpha_op: BlendState::FUNC_ADD,
             write_mask: ColorWriteMask::ALL,
         }
     }
@@ -182,6 +188,24 @@ impl BlendState {
         self.dst_alpha = dst_alpha;
     }
 
+    pub fn get_rgb_op(&self) -> u32 {
+        self.rgb_op
+    }
+
+    pub fn get_alpha_op(&self) -> u32 {
+        self.alpha_op
+    }
+
+    pub fn set_blend_equation(&mut self, op: u32) {
+        self.rgb_op = op;
+        self.alpha_op = op;
+    }
+
+    pub fn set_blend_equation_separate(&mut self, rgb_op: u32, alpha_op: u32) {
+        self.rgb_op = rgb_op;
+        self.alpha_op = alpha_op;
+    }
+
     pub fn get_write_mask(&self) -> ColorWriteMask {
         self.write_mask
     }
@@ -395,6 +419,7 @@ pub struct RasterizerState {
     slope_scaled_depth_bias: f32,
     depth_clip_enabled: bool,
     scissor_test_enabled: bool,
+    scissor_rect: ScissorRect,
     multisample_antialiasing_enabled: bool,
     line_width: f32,
 }
@@ -408,6 +433,7 @@ impl RasterizerState {
             slope_scaled_depth_bias: 0.0,
             depth_clip_enabled: true,
             scissor_test_enabled: false,
+            scissor_rect: ScissorRect::new(0, 0, 0, 0),
             multisample_antialiasing_enabled: true,
             line_width: 1.0,
         }
@@ -437,6 +463,14 @@ impl RasterizerState {
         self.scissor_test_enabled = enabled;
     }
 
+    pub fn get_scissor_rect(&self) -> ScissorRect {
+        self.scissor_rect
+    }
+
+    pub fn set_scissor_rect(&mut self, rect: ScissorRect) {
+        self.scissor_rect = rect;
+    }
+
     pub fn is_multisample_antialiasing_enabled(&self) -> bool {
         self.multisample_antialiasing_enabled
     }
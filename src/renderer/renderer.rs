@@ -1,9 +1,20 @@
 use crate::base::{Ref, RefPtr};
-use crate::base::types::Color4F;
+use crate::base::types::{Color4F, Rect};
 use crate::math::Mat4;
 use crate::renderer::command::{RenderCommand, CommandType, Triangles, Quad, MeshCommand, GroupCommand};
+use crate::renderer::texture::Texture2D;
 use crate::renderer::material::Material;
 use crate::renderer::pipeline::PipelineState;
+use crate::renderer::texture::TextureAtlas;
+use crate::renderer::pipeline::PrimitiveType;
+use crate::backend::{BuiltinUniforms, GraphicsDevice, RenderState};
+use crate::backend::device::ShaderProgram;
+
+/// Receives the quads of a scene's render walk
+pub trait QuadRenderer {
+    fn set_view_projection_matrix(&mut self, view_projection: Mat4);
+    fn draw_quad(&mut self, quad: &Quad, material: RefPtr<Material>);
+}
 
 pub struct Renderer {
     commands: Vec<Box<dyn RenderCommand>>,
@@ -13,6 +24,12 @@ pub struct Renderer {
     is_recording: bool,
     frustum_culled: bool,
     view_projection: Mat4,
+    draw_calls: u32,
+    batched_quads: usize,
+    batch_key: (usize, (u32, u32), u32),
+    device: Option<GraphicsDevice>,
+    program: Option<ShaderProgram>,
+    time: f32,
 }
 
 impl Renderer {
@@ -25,6 +42,12 @@ impl Renderer {
             is_recording: false,
             frustum_culled: false,
             view_projection: Mat4::IDENTITY,
+            draw_calls: 0,
+            batched_quads: 0,
+            batch_key: (0, (0, 0), 0),
+            device: None,
+            program: None,
+            time: 0.0,
         }
     }
 
@@ -36,6 +59,8 @@ impl Renderer {
     pub fn start_frame(&mut self) {
         self.commands.clear();
         self.is_recording = true;
+        self.draw_calls = 0;
+        self.batched_quads = 0;
     }
 
     pub fn end_frame(&mut self) {
@@ -65,9 +90,12 @@ impl Renderer {
         });
 
         // Execute all commands
-        for command in &self.command_queue {
+        let commands = std::mem::take(&mut self.command_queue);
+        for command in &commands {
             command.execute(self);
         }
+        self.command_queue = commands;
+        self.flush();
 
         self.end_frame();
     }
@@ -86,12 +114,44 @@ impl Renderer {
         self.view_projection
     }
 
+    /// Sets the device that batches are submitted to
+    pub fn set_device(&mut self, device: GraphicsDevice) {
+        self.device = Some(device);
+    }
+
+    pub fn get_device_mut(&mut self) -> Option<&mut GraphicsDevice> {
+        self.device.as_mut()
+    }
+
+    /// Sets the program batches are drawn with; its built-in uniforms are
+    /// bound before every draw call
+    pub fn set_program(&mut self, program: ShaderProgram) {
+        self.program = Some(program);
+    }
+
+    /// Sets the time bound to the built-in time uniform, usually the
+    /// director's total time
+    pub fn set_time(&mut self, time: f32) {
+        self.time = time;
+    }
+
+    /// Issues a draw call for `quads` quads. Quads are already in world
+    /// space, so the view-projection alone forms the MVP matrix.
+    fn submit_quads(&mut self, quads: usize) {
+        self.draw_calls += 1;
+        if let (Some(device), Some(program)) = (self.device.as_mut(), self.program.as_ref()) {
+            let uniforms = BuiltinUniforms::new(&self.view_projection, &Mat4::IDENTITY, self.time);
+            device.draw_with_program(program, &uniforms, PrimitiveType::TRIANGLES, 0, (quads * 6) as u32);
+        }
+    }
+
     pub fn draw_triangles(&mut self, triangles: &Triangles, material: RefPtr<Material>) {
         self.current_material = Some(material);
     }
 
     pub fn draw_quad(&mut self, quad: &Quad, material: RefPtr<Material>) {
         self.current_material = Some(material);
+        self.batch_quad(quad);
     }
 
     pub fn draw_mesh(&mut self, mesh: &MeshCommand) {
@@ -100,6 +160,50 @@ impl Renderer {
     pub fn draw_group(&mut self, group: &GroupCommand) {
     }
 
+    /// Adds a quad to the current batch, flushing the batch first when the
+    /// quad's texture, blend function or blend equation differs from it
+    pub fn batch_quad(&mut self, quad: &Quad) {
+        let texture = quad.texture.as_ref().map_or(0, |texture| &**texture as *const Texture2D as usize);
+        let key = (texture, quad.blend_func, quad.blend_equation);
+        if self.batched_quads > 0 && key != self.batch_key {
+            self.flush();
+        }
+        self.batch_key = key;
+        self.batched_quads += 1;
+    }
+
+    /// Submits the batched quads as one draw call with the batch's blend
+    /// function and equation
+    pub fn flush(&mut self) {
+        if self.batched_quads > 0 {
+            let (_, (src, dst), equation) = self.batch_key;
+            if let Some(device) = self.device.as_mut() {
+                device.set_state(RenderState::BlendFunc(src, dst));
+                device.set_state(RenderState::BlendEquation(equation));
+            }
+            self.submit_quads(self.batched_quads);
+            self.batched_quads = 0;
+        }
+    }
+
+    /// Number of quads waiting in the current batch
+    pub fn get_batched_quad_count(&self) -> usize {
+        self.batched_quads
+    }
+
+    /// Draws every quad of a texture atlas with a single draw call
+    pub fn draw_texture_atlas(&mut self, atlas: &TextureAtlas) {
+        self.flush();
+        if atlas.get_total_quads() > 0 {
+            self.submit_quads(atlas.get_total_quads() as usize);
+        }
+    }
+
+    /// Number of draw calls issued since the frame started
+    pub fn get_draw_calls(&self) -> u32 {
+        self.draw_calls
+    }
+
     pub fn set_pipeline(&mut self, pipeline: RefPtr<PipelineState>) {
         self.current_pipeline = Some(pipeline);
     }
@@ -146,6 +250,16 @@ impl Renderer {
     }
 }
 
+impl QuadRenderer for Renderer {
+    fn set_view_projection_matrix(&mut self, view_projection: Mat4) {
+        Renderer::set_view_projection_matrix(self, view_projection);
+    }
+
+    fn draw_quad(&mut self, quad: &Quad, material: RefPtr<Material>) {
+        Renderer::draw_quad(self, quad, material);
+    }
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum CullMode {
     None,
@@ -159,7 +273,7 @@ impl Default for CullMode {
     }
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub struct ScissorRect {
     x: i32,
     y: i32,
@@ -172,6 +286,21 @@ impl ScissorRect {
         ScissorRect { x, y, width, height }
     }
 
+    /// Converts a screen-space rectangle to whole pixels, rounding outward so
+    /// partially covered pixels stay visible.
+    pub fn from_rect(rect: &Rect) -> ScissorRect {
+        let min_x = rect.get_min_x().floor();
+        let min_y = rect.get_min_y().floor();
+        let max_x = rect.get_max_x().ceil();
+        let max_y = rect.get_max_y().ceil();
+        ScissorRect {
+            x: min_x as i32,
+            y: min_y as i32,
+            width: (max_x - min_x).max(0.0) as u32,
+            height: (max_y - min_y).max(0.0) as u32,
+        }
+    }
+
     pub fn get_x(&self) -> i32 {
         self.x
     }
@@ -231,3 +360,103 @@ impl ViewPort {
         self.scale = scale;
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::Cell;
+    use std::rc::Rc;
+    use crate::renderer::command::{CustomCommand, QuadCommand};
+
+    fn queue_quads(renderer: &mut Renderer, orders: &[f32]) {
+        for &order in orders {
+            renderer.push_command(Box::new(QuadCommand::new(order, Quad::new())));
+        }
+    }
+
+    #[test]
+    fn test_quads_with_same_state_share_draw_call() {
+        let mut renderer = Renderer::new();
+        queue_quads(&mut renderer, &[0.0, 2.0]);
+        renderer.render();
+        assert_eq!(renderer.get_draw_calls(), 1);
+    }
+
+    #[test]
+    fn test_flush_draws_with_builtin_uniforms() {
+        use crate::backend::{BackendCall, MockBackend};
+        use crate::renderer::UniformValue;
+        use crate::shader::BuiltInShaders;
+
+        let backend = MockBackend::new();
+        let log = backend.get_log();
+        let mut device = GraphicsDevice::new();
+        device.init(Box::new(backend));
+        let program = device
+            .create_shader(BuiltInShaders::POSITION_TEXTURE_COLOR_VERT, BuiltInShaders::POSITION_TEXTURE_COLOR_FRAG)
+            .unwrap();
+        let location = program.get_uniform_location(crate::backend::UNIFORM_MVP_MATRIX).unwrap();
+        let id = program.get_id();
+
+        let mut renderer = Renderer::new();
+        renderer.set_device(device);
+        renderer.set_program(program);
+        let view_projection = Mat4::create_orthographic_off_center(0.0, 960.0, 0.0, 640.0, -1024.0, 1024.0);
+        renderer.set_view_projection_matrix(view_projection);
+        queue_quads(&mut renderer, &[0.0, 1.0]);
+        renderer.render();
+
+        let log = log.borrow();
+        assert!(log.contains(&BackendCall::SetUniform { program: id, location, value: UniformValue::Mat4(view_projection) }));
+        assert_eq!(log.last(), Some(&BackendCall::Draw { primitive: PrimitiveType::TRIANGLES, first: 0, count: 12 }));
+    }
+
+    #[test]
+    fn test_blend_equation_breaks_batch_and_reaches_backend() {
+        use crate::backend::{BackendCall, MockBackend};
+
+        let backend = MockBackend::new();
+        let log = backend.get_log();
+        let mut device = GraphicsDevice::new();
+        device.init(Box::new(backend));
+        let mut renderer = Renderer::new();
+        renderer.set_device(device);
+
+        let mut subtract = Quad::new();
+        subtract.blend_equation = crate::renderer::BlendState::FUNC_REVERSE_SUBTRACT;
+        renderer.push_command(Box::new(QuadCommand::new(0.0, Quad::new())));
+        renderer.push_command(Box::new(QuadCommand::new(1.0, subtract)));
+        renderer.render();
+
+        assert_eq!(renderer.get_draw_calls(), 2);
+        let equations: Vec<u32> = log
+            .borrow()
+            .iter()
+            .filter_map(|call| match call {
+                BackendCall::SetState(RenderState::BlendEquation(equation)) => Some(*equation),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(equations, vec![crate::renderer::BlendState::FUNC_ADD, crate::renderer::BlendState::FUNC_REVERSE_SUBTRACT]);
+    }
+
+    #[test]
+    fn test_custom_command_breaks_batch_in_order() {
+        let mut renderer = Renderer::new();
+        let draw_calls_seen = Rc::new(Cell::new(None));
+        let mut custom = CustomCommand::new();
+        custom.set_global_order(1.0);
+        let seen = Rc::clone(&draw_calls_seen);
+        custom.init(move |renderer| seen.set(Some(renderer.get_draw_calls())));
+
+        queue_quads(&mut renderer, &[2.0]);
+        renderer.push_command(Box::new(custom));
+        queue_quads(&mut renderer, &[0.0]);
+        renderer.render();
+
+        // The quad at order 0 was flushed before the callback ran
+        assert_eq!(draw_calls_seen.get(), Some(1));
+        assert_eq!(renderer.get_draw_calls(), 2);
+        assert_eq!(renderer.get_batched_quad_count(), 0);
+    }
+}
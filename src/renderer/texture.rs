@@ -1,5 +1,7 @@
 use std::collections::HashMap;
 use crate::base::{Ref, RefPtr};
+use crate::base::types::Rect;
+use std::ops::Range;
 
 pub trait Texture {
     fn get_name(&self) -> u32;
@@ -126,6 +128,16 @@ pub enum TextureWrap {
     MIRRORED_REPEAT,
 }
 
+/// Pixels waiting to be uploaded into a region of a texture
+#[derive(Debug, Clone, PartialEq)]
+pub struct TextureRegionUpdate {
+    pub x: u32,
+    pub y: u32,
+    pub width: u32,
+    pub height: u32,
+    pub data: Vec<u8>,
+}
+
 #[derive(Debug)]
 pub struct Texture2D {
     name: u32,
@@ -135,6 +147,8 @@ pub struct Texture2D {
     texture_type: TextureType,
     bits_per_pixel: u32,
     has_mipmaps: bool,
+    pending_updates: Vec<TextureRegionUpdate>,
+    sampler: Sampler,
 }
 
 impl Texture2D {
@@ -147,6 +161,8 @@ impl Texture2D {
             texture_type: TextureType::Texture2D,
             bits_per_pixel: 32,
             has_mipmaps: false,
+            pending_updates: Vec::new(),
+            sampler: Sampler::new(),
         }
     }
 
@@ -179,6 +195,74 @@ impl Texture2D {
         self.height = height;
         self.pixel_format = pixel_format;
         self.bits_per_pixel = pixel_format.get_bytes_per_pixel() * 8;
+        self.pending_updates.clear();
+    }
+
+    /// Replaces the pixels of a sub-rectangle of the texture.
+    ///
+    /// `data` holds tightly packed rows in the texture's pixel format. The
+    /// region is queued until `GraphicsDevice::upload_texture_updates`
+    /// sends it to the backend.
+    pub fn update_sub_image(&mut self, x: u32, y: u32, width: u32, height: u32, data: &[u8]) -> Result<(), String> {
+        let fits = |offset: u32, size: u32, limit: u32| offset.checked_add(size).map_or(false, |end| end <= limit);
+        if !fits(x, width, self.width) || !fits(y, height, self.height) {
+            return Err(format!(
+                "Region {}x{} at ({}, {}) exceeds texture size {}x{}",
+                width, height, x, y, self.width, self.height
+            ));
+        }
+        let bytes_per_pixel = self.pixel_format.get_bytes_per_pixel() as usize;
+        if bytes_per_pixel == 0 {
+            return Err(format!("Pixel format {:?} does not support sub-image updates", self.pixel_format));
+        }
+        let expected = width as usize * height as usize * bytes_per_pixel;
+        if data.len() != expected {
+            return Err(format!("Expected {} bytes for a {}x{} region, got {}", expected, width, height, data.len()));
+        }
+
+        self.pending_updates.push(TextureRegionUpdate { x, y, width, height, data: data.to_vec() });
+        Ok(())
+    }
+
+    /// Gets the regions queued by `update_sub_image` that have not been
+    /// uploaded yet
+    pub fn get_pending_updates(&self) -> &[TextureRegionUpdate] {
+        &self.pending_updates
+    }
+
+    /// Removes and returns the queued regions, oldest first
+    pub fn take_pending_updates(&mut self) -> Vec<TextureRegionUpdate> {
+        std::mem::take(&mut self.pending_updates)
+    }
+
+    /// Sets the filtering and wrapping used when sampling the texture.
+    ///
+    /// The state is applied when `GraphicsDevice::bind_texture` binds the
+    /// texture.
+    pub fn set_tex_parameters(&mut self, min_filter: TextureFilter, mag_filter: TextureFilter, wrap_s: TextureWrap, wrap_t: TextureWrap) {
+        self.sampler.set_min_filter(min_filter);
+        self.sampler.set_mag_filter(mag_filter);
+        self.sampler.set_wrap_s(wrap_s);
+        self.sampler.set_wrap_t(wrap_t);
+    }
+
+    /// Uses nearest filtering, for crisp pixel art. Keeps the wrap modes.
+    pub fn set_aliased(&mut self) {
+        let min_filter = if self.has_mipmaps { TextureFilter::NEAREST_MIPMAP_NEAREST } else { TextureFilter::NEAREST };
+        self.sampler.set_min_filter(min_filter);
+        self.sampler.set_mag_filter(TextureFilter::NEAREST);
+    }
+
+    /// Uses linear filtering, the default. Keeps the wrap modes.
+    pub fn set_antialiased(&mut self) {
+        let min_filter = if self.has_mipmaps { TextureFilter::LINEAR_MIPMAP_NEAREST } else { TextureFilter::LINEAR };
+        self.sampler.set_min_filter(min_filter);
+        self.sampler.set_mag_filter(TextureFilter::LINEAR);
+    }
+
+    /// Gets the sampling state applied when the texture is bound
+    pub fn get_sampler(&self) -> &Sampler {
+        &self.sampler
     }
 }
 
@@ -220,10 +304,11 @@ pub struct TextureAtlas {
     texture: Option<RefPtr<Texture2D>>,
     capacity: u32,
     quads: Vec<TextureQuad>,
-    indices: Vec<u16>,
+    indices: Vec<u32>,
+    dirty: Option<Range<u32>>,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq)]
 pub struct TextureQuad {
     tl: TexturedVertex,
     tr: TexturedVertex,
@@ -231,7 +316,7 @@ pub struct TextureQuad {
     br: TexturedVertex,
 }
 
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq)]
 pub struct TexturedVertex {
     x: f32,
     y: f32,
@@ -247,33 +332,93 @@ impl TextureAtlas {
             capacity: 0,
             quads: Vec::new(),
             indices: Vec::new(),
+            dirty: None,
         }
     }
 
+    pub fn with_capacity(texture: RefPtr<Texture2D>, capacity: u32) -> TextureAtlas {
+        let mut atlas = TextureAtlas::new();
+        atlas.init(texture, capacity);
+        atlas
+    }
+
     pub fn init(&mut self, texture: RefPtr<Texture2D>, capacity: u32) {
         self.texture = Some(texture);
         self.capacity = capacity;
-        self.quads.resize(capacity as usize, TextureQuad::new());
+        self.quads.clear();
+        self.quads.reserve(capacity as usize);
+        self.setup_indices();
+        self.dirty = None;
+    }
+
+    /// Changes the capacity, dropping any quads past the new capacity.
+    pub fn resize_capacity(&mut self, capacity: u32) {
+        let total = self.get_total_quads();
+        self.capacity = capacity;
+        if total > capacity {
+            self.quads.truncate(capacity as usize);
+            self.mark_dirty(capacity..total);
+        }
+        self.setup_indices();
+    }
+
+    /// Replaces the quad at `index`, extending the quad count if `index` is
+    /// past the current end.
+    pub fn update_quad(&mut self, index: u32, quad: &TextureQuad) {
+        if index >= self.capacity {
+            return;
+        }
+        let i = index as usize;
+        if i >= self.quads.len() {
+            let start = self.quads.len() as u32;
+            self.quads.resize(i + 1, TextureQuad::new());
+            self.mark_dirty(start..index + 1);
+        } else {
+            self.mark_dirty(index..index + 1);
+        }
+        self.quads[i] = quad.clone();
     }
 
-    pub fn update_quad(&mut self, quad: TextureQuad, index: u32) {
-        if index < self.capacity {
-            self.quads[index as usize] = quad;
+    /// Inserts `quad` at `index`, shifting every following quad up by one.
+    pub fn insert_quad(&mut self, quad: &TextureQuad, index: u32) {
+        let total = self.get_total_quads();
+        if index > total || total >= self.capacity {
+            return;
         }
+        self.quads.insert(index as usize, quad.clone());
+        self.mark_dirty(index..total + 1);
     }
 
-    pub fn insert_quad(&mut self, quad: TextureQuad, index: u32) {
-        if index < self.quads.len() as u32 {
-            self.quads.insert(index as usize, quad);
+    /// Removes the quad at `index`, shifting every following quad down by one.
+    pub fn remove_quad(&mut self, index: u32) {
+        let total = self.get_total_quads();
+        if index >= total {
+            return;
         }
+        self.quads.remove(index as usize);
+        self.mark_dirty(index..total);
     }
 
-    pub fn remove_quad_at(&mut self, index: u32) {
-        if index < self.quads.len() as u32 {
-            self.quads.remove(index as usize);
+    pub fn remove_all_quads(&mut self) {
+        let total = self.get_total_quads();
+        self.quads.clear();
+        if total > 0 {
+            self.mark_dirty(0..total);
         }
     }
 
+    pub fn get_quad(&self, index: u32) -> Option<&TextureQuad> {
+        self.quads.get(index as usize)
+    }
+
+    pub fn get_quads(&self) -> &[TextureQuad] {
+        &self.quads
+    }
+
+    pub fn get_indices(&self) -> &[u32] {
+        &self.indices
+    }
+
     pub fn get_total_quads(&self) -> u32 {
         self.quads.len() as u32
     }
@@ -285,6 +430,41 @@ impl TextureAtlas {
     pub fn get_texture(&self) -> Option<&RefPtr<Texture2D>> {
         self.texture.as_ref()
     }
+
+    /// Sets the texture the quads sample, keeping the quads
+    pub fn set_texture(&mut self, texture: RefPtr<Texture2D>) {
+        self.texture = Some(texture);
+    }
+
+    pub fn is_dirty(&self) -> bool {
+        self.dirty.is_some()
+    }
+
+    /// Quad indices modified since the last upload.
+    pub fn get_dirty_range(&self) -> Option<Range<u32>> {
+        self.dirty.clone()
+    }
+
+    /// Called by the renderer once the dirty quads have been uploaded.
+    pub fn clear_dirty(&mut self) {
+        self.dirty = None;
+    }
+
+    fn mark_dirty(&mut self, range: Range<u32>) {
+        self.dirty = Some(match self.dirty.take() {
+            Some(dirty) => dirty.start.min(range.start)..dirty.end.max(range.end),
+            None => range,
+        });
+    }
+
+    fn setup_indices(&mut self) {
+        self.indices.clear();
+        self.indices.reserve(self.capacity as usize * 6);
+        for i in 0..self.capacity {
+            let base = i * 4;
+            self.indices.extend_from_slice(&[base, base + 1, base + 2, base + 3, base + 2, base + 1]);
+        }
+    }
 }
 
 impl TextureQuad {
@@ -296,9 +476,174 @@ impl TextureQuad {
             br: TexturedVertex { x: 0.0, y: 0.0, z: 0.0, u: 1.0, v: 1.0 },
         }
     }
+
+    pub fn with_vertices(tl: TexturedVertex, tr: TexturedVertex, bl: TexturedVertex, br: TexturedVertex) -> TextureQuad {
+        TextureQuad { tl, tr, bl, br }
+    }
+
+    pub fn get_tl(&self) -> &TexturedVertex {
+        &self.tl
+    }
+
+    pub fn get_tr(&self) -> &TexturedVertex {
+        &self.tr
+    }
+
+    pub fn get_bl(&self) -> &TexturedVertex {
+        &self.bl
+    }
+
+    pub fn get_br(&self) -> &TexturedVertex {
+        &self.br
+    }
+
+    /// Returns a copy of the quad moved by (dx, dy)
+    pub fn translated(&self, dx: f32, dy: f32) -> TextureQuad {
+        let shift = |v: &TexturedVertex| TexturedVertex { x: v.x + dx, y: v.y + dy, ..*v };
+        TextureQuad {
+            tl: shift(&self.tl),
+            tr: shift(&self.tr),
+            bl: shift(&self.bl),
+            br: shift(&self.br),
+        }
+    }
 }
 
+impl TexturedVertex {
+    pub fn new(x: f32, y: f32, z: f32, u: f32, v: f32) -> TexturedVertex {
+        TexturedVertex { x, y, z, u, v }
+    }
+
+    pub fn get_position(&self) -> (f32, f32, f32) {
+        (self.x, self.y, self.z)
+    }
+
+    pub fn get_tex_coord(&self) -> (f32, f32) {
+        (self.u, self.v)
+    }
+}
+
+/// Runtime rectangle packer for glyph and sprite frame atlases.
+///
+/// Uses a skyline with the bottom-left heuristic: each insert is placed at the
+/// position that keeps the resulting top edge lowest, ties broken by the
+/// leftmost position.
 #[derive(Debug, Clone)]
+pub struct DynamicAtlas {
+    width: u32,
+    height: u32,
+    skyline: Vec<SkylineNode>,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct SkylineNode {
+    x: u32,
+    y: u32,
+    width: u32,
+}
+
+impl DynamicAtlas {
+    pub fn new(width: u32, height: u32) -> DynamicAtlas {
+        DynamicAtlas {
+            width,
+            height,
+            skyline: vec![SkylineNode { x: 0, y: 0, width }],
+        }
+    }
+
+    pub fn get_width(&self) -> u32 {
+        self.width
+    }
+
+    pub fn get_height(&self) -> u32 {
+        self.height
+    }
+
+    /// Reserves a `width` x `height` region, returning `None` when it does not fit.
+    pub fn insert(&mut self, width: u32, height: u32) -> Option<Rect> {
+        if width == 0 || height == 0 {
+            return None;
+        }
+
+        let mut best: Option<(usize, u32, u32)> = None;
+        for index in 0..self.skyline.len() {
+            if let Some(y) = self.fit(index, width, height) {
+                let x = self.skyline[index].x;
+                let better = match best {
+                    Some((_, best_x, best_y)) => y < best_y || (y == best_y && x < best_x),
+                    None => true,
+                };
+                if better {
+                    best = Some((index, x, y));
+                }
+            }
+        }
+
+        let (index, x, y) = best?;
+        self.add_level(index, x, y + height, width);
+        Some(Rect::new(x as f32, y as f32, width as f32, height as f32))
+    }
+
+    /// Forgets all placed rectangles.
+    pub fn reset(&mut self) {
+        self.skyline.clear();
+        self.skyline.push(SkylineNode { x: 0, y: 0, width: self.width });
+    }
+
+    fn fit(&self, index: usize, width: u32, height: u32) -> Option<u32> {
+        let x = self.skyline[index].x;
+        if x + width > self.width {
+            return None;
+        }
+
+        let mut y = 0;
+        let mut remaining = width as i64;
+        let mut i = index;
+        while remaining > 0 {
+            let node = self.skyline.get(i)?;
+            y = y.max(node.y);
+            if y + height > self.height {
+                return None;
+            }
+            remaining -= node.width as i64;
+            i += 1;
+        }
+        Some(y)
+    }
+
+    fn add_level(&mut self, index: usize, x: u32, y: u32, width: u32) {
+        self.skyline.insert(index, SkylineNode { x, y, width });
+
+        let right = x + width;
+        let i = index + 1;
+        while i < self.skyline.len() {
+            let node = self.skyline[i];
+            if node.x >= right {
+                break;
+            }
+            let shrink = right - node.x;
+            if shrink >= node.width {
+                self.skyline.remove(i);
+            } else {
+                self.skyline[i].x += shrink;
+                self.skyline[i].width -= shrink;
+                break;
+            }
+        }
+
+        let mut i = 0;
+        while i + 1 < self.skyline.len() {
+            if self.skyline[i].y == self.skyline[i + 1].y {
+                self.skyline[i].width += self.skyline[i + 1].width;
+                self.skyline.remove(i + 1);
+            } else {
+                i += 1;
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
 pub struct Sampler {
     min_filter: TextureFilter,
     mag_filter: TextureFilter,
@@ -335,6 +680,22 @@ impl Sampler {
     pub fn set_wrap_t(&mut self, wrap: TextureWrap) {
         self.wrap_t = wrap;
     }
+
+    pub fn get_min_filter(&self) -> TextureFilter {
+        self.min_filter
+    }
+
+    pub fn get_mag_filter(&self) -> TextureFilter {
+        self.mag_filter
+    }
+
+    pub fn get_wrap_s(&self) -> TextureWrap {
+        self.wrap_s
+    }
+
+    pub fn get_wrap_t(&self) -> TextureWrap {
+        self.wrap_t
+    }
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -348,3 +709,182 @@ pub enum CompareFunc {
     GEQUAL,
     ALWAYS,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn quad_at(x: f32) -> TextureQuad {
+        let mut quad = TextureQuad::new();
+        quad.tl.x = x;
+        quad
+    }
+
+    #[test]
+    fn test_texture_filtering_presets() {
+        let mut texture = Texture2D::new();
+        texture.set_aliased();
+        assert_eq!(texture.get_sampler().get_min_filter(), TextureFilter::NEAREST);
+        assert_eq!(texture.get_sampler().get_mag_filter(), TextureFilter::NEAREST);
+
+        texture.set_antialiased();
+        assert_eq!(texture.get_sampler().get_min_filter(), TextureFilter::LINEAR);
+        assert_eq!(texture.get_sampler().get_mag_filter(), TextureFilter::LINEAR);
+    }
+
+    #[test]
+    fn test_texture_wrap_modes_round_trip() {
+        let mut texture = Texture2D::new();
+        texture.set_tex_parameters(TextureFilter::LINEAR, TextureFilter::NEAREST, TextureWrap::REPEAT, TextureWrap::MIRRORED_REPEAT);
+        texture.set_aliased();
+        let sampler = texture.get_sampler();
+        assert_eq!(sampler.get_mag_filter(), TextureFilter::NEAREST);
+        assert_eq!(sampler.get_wrap_s(), TextureWrap::REPEAT);
+        assert_eq!(sampler.get_wrap_t(), TextureWrap::MIRRORED_REPEAT);
+    }
+
+    fn test_atlas(capacity: u32) -> TextureAtlas {
+        TextureAtlas::with_capacity(RefPtr::new(Texture2D::new()), capacity)
+    }
+
+    #[test]
+    fn test_texture_atlas_with_capacity() {
+        let atlas = test_atlas(8);
+        assert_eq!(atlas.get_capacity(), 8);
+        assert_eq!(atlas.get_total_quads(), 0);
+        assert_eq!(atlas.get_indices().len(), 48);
+        assert!(!atlas.is_dirty());
+    }
+
+    #[test]
+    fn test_texture_atlas_indices_past_u16() {
+        let atlas = test_atlas(20000);
+        let last = &atlas.get_indices()[19999 * 6..];
+        assert_eq!(last, &[79996, 79997, 79998, 79999, 79998, 79997]);
+    }
+
+    #[test]
+    fn test_texture_atlas_insert_shifts_quads() {
+        let mut atlas = test_atlas(8);
+        for i in 0..4 {
+            atlas.update_quad(i, &quad_at(i as f32));
+        }
+        atlas.clear_dirty();
+
+        atlas.insert_quad(&quad_at(10.0), 1);
+        assert_eq!(atlas.get_total_quads(), 5);
+        assert_eq!(atlas.get_quad(1), Some(&quad_at(10.0)));
+        assert_eq!(atlas.get_quad(2), Some(&quad_at(1.0)));
+        assert_eq!(atlas.get_quad(4), Some(&quad_at(3.0)));
+        assert_eq!(atlas.get_dirty_range(), Some(1..5));
+    }
+
+    #[test]
+    fn test_texture_atlas_remove_shrinks() {
+        let mut atlas = test_atlas(8);
+        for i in 0..4 {
+            atlas.update_quad(i, &quad_at(i as f32));
+        }
+        atlas.clear_dirty();
+
+        atlas.remove_quad(2);
+        assert_eq!(atlas.get_total_quads(), 3);
+        assert_eq!(atlas.get_quad(2), Some(&quad_at(3.0)));
+        assert_eq!(atlas.get_dirty_range(), Some(2..4));
+
+        atlas.remove_quad(10);
+        assert_eq!(atlas.get_total_quads(), 3);
+    }
+
+    #[test]
+    fn test_texture_atlas_dirty_range_merges() {
+        let mut atlas = test_atlas(8);
+        for i in 0..6 {
+            atlas.update_quad(i, &quad_at(i as f32));
+        }
+        atlas.clear_dirty();
+
+        atlas.update_quad(4, &quad_at(40.0));
+        assert_eq!(atlas.get_dirty_range(), Some(4..5));
+        atlas.update_quad(1, &quad_at(10.0));
+        assert_eq!(atlas.get_dirty_range(), Some(1..5));
+    }
+
+    #[test]
+    fn test_texture_atlas_respects_capacity() {
+        let mut atlas = test_atlas(2);
+        atlas.insert_quad(&quad_at(0.0), 0);
+        atlas.insert_quad(&quad_at(1.0), 1);
+        atlas.insert_quad(&quad_at(2.0), 2);
+        atlas.update_quad(5, &quad_at(5.0));
+        assert_eq!(atlas.get_total_quads(), 2);
+    }
+
+    #[test]
+    fn test_dynamic_atlas_no_overlap() {
+        let mut atlas = DynamicAtlas::new(128, 128);
+        let sizes = [(32, 16), (40, 40), (16, 64), (50, 10), (30, 30), (64, 20), (10, 10)];
+
+        let mut placed: Vec<Rect> = Vec::new();
+        for &(w, h) in &sizes {
+            let rect = atlas.insert(w, h).expect("rect should fit");
+            assert_eq!(rect.size.width, w as f32);
+            assert_eq!(rect.size.height, h as f32);
+            assert!(rect.get_min_x() >= 0.0 && rect.get_min_y() >= 0.0);
+            assert!(rect.get_max_x() <= 128.0 && rect.get_max_y() <= 128.0);
+            for other in &placed {
+                assert!(!rect.intersects_rect(other), "{:?} overlaps {:?}", rect, other);
+            }
+            placed.push(rect);
+        }
+    }
+
+    fn texture_8x8() -> Texture2D {
+        let mut texture = Texture2D::new();
+        texture.update(&[], 8, 8, PixelFormat::RGBA8888);
+        texture
+    }
+
+    #[test]
+    fn test_update_sub_image_queues_region() {
+        let mut texture = texture_8x8();
+        assert!(texture.update_sub_image(6, 6, 2, 2, &[7; 16]).is_ok());
+        assert_eq!(
+            texture.get_pending_updates(),
+            &[TextureRegionUpdate { x: 6, y: 6, width: 2, height: 2, data: vec![7; 16] }]
+        );
+        assert_eq!(texture.take_pending_updates().len(), 1);
+        assert!(texture.get_pending_updates().is_empty());
+    }
+
+    #[test]
+    fn test_update_sub_image_rejects_bad_region() {
+        let mut texture = texture_8x8();
+        assert!(texture.update_sub_image(7, 0, 2, 1, &[0; 8]).is_err());
+        assert!(texture.update_sub_image(0, 8, 1, 1, &[0; 4]).is_err());
+        assert!(texture.update_sub_image(u32::MAX, 0, 2, 1, &[0; 8]).is_err());
+        assert!(texture.update_sub_image(0, 0, 2, 2, &[0; 15]).is_err());
+        assert!(texture.get_pending_updates().is_empty());
+    }
+
+    #[test]
+    fn test_dynamic_atlas_oversized_insert() {
+        let mut atlas = DynamicAtlas::new(64, 64);
+        assert!(atlas.insert(65, 10).is_none());
+        assert!(atlas.insert(10, 65).is_none());
+        assert!(atlas.insert(64, 64).is_some());
+    }
+
+    #[test]
+    fn test_dynamic_atlas_full_and_reset() {
+        let mut atlas = DynamicAtlas::new(64, 64);
+        for _ in 0..4 {
+            assert!(atlas.insert(32, 32).is_some());
+        }
+        assert!(atlas.insert(1, 1).is_none());
+
+        atlas.reset();
+        let rect = atlas.insert(64, 64).unwrap();
+        assert_eq!(rect, Rect::new(0.0, 0.0, 64.0, 64.0));
+    }
+}
@@ -0,0 +1,200 @@
+use crate::base::Node;
+use crate::base::types::Rect;
+use crate::math::{Mat4, Vec3};
+use crate::renderer::RasterizerState;
+use crate::renderer::renderer::ScissorRect;
+
+/// Stack of active scissor rectangles in screen space.
+///
+/// Each pushed rectangle is intersected with the one below it so nested
+/// clipping nodes never draw outside their ancestors.
+#[derive(Debug, Default)]
+pub struct ScissorStack {
+    rects: Vec<Rect>,
+}
+
+impl ScissorStack {
+    pub fn new() -> ScissorStack {
+        ScissorStack { rects: Vec::new() }
+    }
+
+    /// Pushes `rect`, clipped against the current scissor, and applies it.
+    pub fn push(&mut self, rect: Rect, rasterizer: &mut RasterizerState) -> Rect {
+        let clipped = match self.rects.last() {
+            Some(parent) => parent.intersect_rect(&rect),
+            None => rect,
+        };
+        self.rects.push(clipped);
+        rasterizer.set_scissor_test_enabled(true);
+        rasterizer.set_scissor_rect(ScissorRect::from_rect(&clipped));
+        clipped
+    }
+
+    /// Pops the innermost scissor and restores the parent one, disabling the
+    /// scissor test once the stack is empty.
+    pub fn pop(&mut self, rasterizer: &mut RasterizerState) {
+        self.rects.pop();
+        match self.rects.last() {
+            Some(parent) => rasterizer.set_scissor_rect(ScissorRect::from_rect(parent)),
+            None => rasterizer.set_scissor_test_enabled(false),
+        }
+    }
+
+    pub fn current(&self) -> Option<Rect> {
+        self.rects.last().copied()
+    }
+
+    pub fn depth(&self) -> usize {
+        self.rects.len()
+    }
+}
+
+/// A node that clips its children to a rectangle using the scissor test.
+#[derive(Debug)]
+pub struct ClippingRectangleNode {
+    node: Node,
+    clipping_region: Rect,
+    clipping_enabled: bool,
+}
+
+impl ClippingRectangleNode {
+    pub fn new() -> ClippingRectangleNode {
+        ClippingRectangleNode {
+            node: Node::new(),
+            clipping_region: Rect::ZERO,
+            clipping_enabled: true,
+        }
+    }
+
+    pub fn create(clipping_region: Rect) -> ClippingRectangleNode {
+        let mut node = ClippingRectangleNode::new();
+        node.set_clipping_region(clipping_region);
+        node
+    }
+
+    pub fn get_node(&self) -> &Node {
+        &self.node
+    }
+
+    pub fn get_node_mut(&mut self) -> &mut Node {
+        &mut self.node
+    }
+
+    /// Gets the clipping region in node space
+    pub fn get_clipping_region(&self) -> Rect {
+        self.clipping_region
+    }
+
+    /// Sets the clipping region in node space
+    pub fn set_clipping_region(&mut self, clipping_region: Rect) {
+        self.clipping_region = clipping_region;
+    }
+
+    pub fn is_clipping_enabled(&self) -> bool {
+        self.clipping_enabled
+    }
+
+    pub fn set_clipping_enabled(&mut self, enabled: bool) {
+        self.clipping_enabled = enabled;
+    }
+
+    /// Computes the screen-space bounding rectangle of the clipping region.
+    pub fn get_scissor_rect(&self, node_to_world: &Mat4) -> Rect {
+        let region = &self.clipping_region;
+        let corners = [
+            Vec3::new(region.get_min_x(), region.get_min_y(), 0.0),
+            Vec3::new(region.get_max_x(), region.get_min_y(), 0.0),
+            Vec3::new(region.get_min_x(), region.get_max_y(), 0.0),
+            Vec3::new(region.get_max_x(), region.get_max_y(), 0.0),
+        ];
+
+        let mut min_x = f32::MAX;
+        let mut min_y = f32::MAX;
+        let mut max_x = f32::MIN;
+        let mut max_y = f32::MIN;
+        for corner in &corners {
+            let p = node_to_world.transform_point(corner);
+            min_x = min_x.min(p.x);
+            min_y = min_y.min(p.y);
+            max_x = max_x.max(p.x);
+            max_y = max_y.max(p.y);
+        }
+        Rect::new(min_x, min_y, max_x - min_x, max_y - min_y)
+    }
+
+    /// Enables the scissor for this node's subtree.
+    pub fn before_visit(&self, node_to_world: &Mat4, stack: &mut ScissorStack, rasterizer: &mut RasterizerState) {
+        if self.clipping_enabled {
+            stack.push(self.get_scissor_rect(node_to_world), rasterizer);
+        }
+    }
+
+    /// Restores the scissor that was active before `before_visit`.
+    pub fn after_visit(&self, stack: &mut ScissorStack, rasterizer: &mut RasterizerState) {
+        if self.clipping_enabled {
+            stack.pop(rasterizer);
+        }
+    }
+
+    /// Renders the subtree with clipping applied, restoring state afterward.
+    pub fn visit<F>(&self, node_to_world: &Mat4, stack: &mut ScissorStack, rasterizer: &mut RasterizerState, render_children: F)
+    where
+        F: FnOnce(&mut ScissorStack, &mut RasterizerState),
+    {
+        if !self.node.is_visible() {
+            return;
+        }
+        self.before_visit(node_to_world, stack, rasterizer);
+        render_children(stack, rasterizer);
+        self.after_visit(stack, rasterizer);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn translate_scale(tx: f32, ty: f32, sx: f32, sy: f32) -> Mat4 {
+        Mat4::create_translation(&Vec3::new(tx, ty, 0.0)) * Mat4::create_scale(&Vec3::new(sx, sy, 1.0))
+    }
+
+    #[test]
+    fn test_scissor_rect_translated_scaled() {
+        let clip = ClippingRectangleNode::create(Rect::new(0.0, 0.0, 100.0, 50.0));
+        let rect = clip.get_scissor_rect(&translate_scale(20.0, 30.0, 2.0, 0.5));
+        assert_eq!(rect, Rect::new(20.0, 30.0, 200.0, 25.0));
+    }
+
+    #[test]
+    fn test_nested_scissor_intersects() {
+        let outer = ClippingRectangleNode::create(Rect::new(0.0, 0.0, 100.0, 100.0));
+        let inner = ClippingRectangleNode::create(Rect::new(0.0, 0.0, 100.0, 100.0));
+        let mut stack = ScissorStack::new();
+        let mut rasterizer = RasterizerState::new();
+
+        outer.visit(&Mat4::IDENTITY, &mut stack, &mut rasterizer, |stack, rasterizer| {
+            inner.visit(&translate_scale(50.0, 25.0, 1.0, 1.0), stack, rasterizer, |stack, rasterizer| {
+                assert_eq!(stack.current(), Some(Rect::new(50.0, 25.0, 50.0, 75.0)));
+                assert_eq!(rasterizer.get_scissor_rect(), ScissorRect::new(50, 25, 50, 75));
+            });
+            assert_eq!(stack.current(), Some(Rect::new(0.0, 0.0, 100.0, 100.0)));
+            assert_eq!(rasterizer.get_scissor_rect(), ScissorRect::new(0, 0, 100, 100));
+        });
+
+        assert_eq!(stack.depth(), 0);
+        assert!(!rasterizer.is_scissor_test_enabled());
+    }
+
+    #[test]
+    fn test_clipping_disabled_leaves_scissor_untouched() {
+        let mut clip = ClippingRectangleNode::create(Rect::new(0.0, 0.0, 10.0, 10.0));
+        clip.set_clipping_enabled(false);
+        let mut stack = ScissorStack::new();
+        let mut rasterizer = RasterizerState::new();
+
+        clip.visit(&Mat4::IDENTITY, &mut stack, &mut rasterizer, |stack, rasterizer| {
+            assert_eq!(stack.depth(), 0);
+            assert!(!rasterizer.is_scissor_test_enabled());
+        });
+    }
+}
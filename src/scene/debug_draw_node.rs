@@ -0,0 +1,181 @@
+use crate::base::{Node, NodeRef};
+use crate::base::camera_2d::bounding_rect;
+use crate::base::types::Color4F;
+use crate::math::{Mat4, Vec2, Vec3};
+use super::draw_node::DrawNode;
+
+/// Half the length of each arm of an anchor point cross
+const ANCHOR_MARKER_SIZE: f32 = 4.0;
+
+const BOUNDS_COLOR: Color4F = Color4F::GREEN;
+const ANCHOR_COLOR: Color4F = Color4F::RED;
+const NAME_COLOR: Color4F = Color4F::WHITE;
+
+/// Overlay that outlines a node tree for visual debugging.
+///
+/// `update` redraws the overlay from a root node: the bounding rectangle of
+/// every visible node, a cross at its anchor point and, when enabled, its
+/// name. Invisible nodes are skipped together with their subtree. The
+/// overlay is drawn in the root's parent space.
+#[derive(Debug)]
+pub struct DebugDrawNode {
+    draw_node: DrawNode,
+    show_bounds: bool,
+    show_anchors: bool,
+    show_names: bool,
+}
+
+impl DebugDrawNode {
+    pub fn new() -> DebugDrawNode {
+        DebugDrawNode {
+            draw_node: DrawNode::new(),
+            show_bounds: true,
+            show_anchors: true,
+            show_names: false,
+        }
+    }
+
+    pub fn get_draw_node(&self) -> &DrawNode {
+        &self.draw_node
+    }
+
+    pub fn set_show_bounds(&mut self, show_bounds: bool) {
+        self.show_bounds = show_bounds;
+    }
+
+    pub fn is_show_bounds(&self) -> bool {
+        self.show_bounds
+    }
+
+    pub fn set_show_anchors(&mut self, show_anchors: bool) {
+        self.show_anchors = show_anchors;
+    }
+
+    pub fn is_show_anchors(&self) -> bool {
+        self.show_anchors
+    }
+
+    /// Sets whether named nodes are labeled at the top-left of their bounds
+    pub fn set_show_names(&mut self, show_names: bool) {
+        self.show_names = show_names;
+    }
+
+    pub fn is_show_names(&self) -> bool {
+        self.show_names
+    }
+
+    /// Redraws the overlay for `root` and its subtree
+    pub fn update(&mut self, root: &Node) {
+        self.draw_node.clear();
+        let mut stack: Vec<(NodeRef, Mat4)> = Vec::new();
+        self.draw_subtree(root, &Mat4::IDENTITY, &mut stack);
+        while let Some((child, parent_transform)) = stack.pop() {
+            self.draw_subtree(&child.borrow(), &parent_transform, &mut stack);
+        }
+    }
+
+    /// Draws a visible node and pushes its children for `update`
+    fn draw_subtree(&mut self, node: &Node, parent_transform: &Mat4, stack: &mut Vec<(NodeRef, Mat4)>) {
+        if !node.is_visible() {
+            return;
+        }
+        let transform = *parent_transform * *node.get_local_transform();
+        self.draw(node, parent_transform, &transform);
+        for child in node.get_children().iter().rev() {
+            stack.push((child.clone(), transform));
+        }
+    }
+
+    fn draw(&mut self, node: &Node, parent_transform: &Mat4, transform: &Mat4) {
+        let size = node.get_content_size();
+        let corners = [Vec2::ZERO, Vec2::new(size.x, 0.0), Vec2::new(0.0, size.y), size]
+            .map(|corner| to_2d(transform.transform_point(&Vec3::new(corner.x, corner.y, 0.0))));
+        let bounds = bounding_rect(&corners);
+
+        if self.show_bounds {
+            self.draw_node.draw_rect(
+                Vec2::new(bounds.get_min_x(), bounds.get_min_y()),
+                Vec2::new(bounds.get_max_x(), bounds.get_max_y()),
+                BOUNDS_COLOR,
+            );
+        }
+        if self.show_anchors {
+            let position = node.get_position();
+            let anchor = to_2d(parent_transform.transform_point(&Vec3::new(position.x, position.y, 0.0)));
+            let horizontal = Vec2::new(ANCHOR_MARKER_SIZE, 0.0);
+            let vertical = Vec2::new(0.0, ANCHOR_MARKER_SIZE);
+            self.draw_node.draw_line(anchor - horizontal, anchor + horizontal, ANCHOR_COLOR);
+            self.draw_node.draw_line(anchor - vertical, anchor + vertical, ANCHOR_COLOR);
+        }
+        if self.show_names && !node.get_name().is_empty() {
+            self.draw_node.draw_string(Vec2::new(bounds.get_min_x(), bounds.get_max_y()), node.get_name(), NAME_COLOR);
+        }
+    }
+}
+
+impl Default for DebugDrawNode {
+    fn default() -> Self {
+        DebugDrawNode::new()
+    }
+}
+
+fn to_2d(point: Vec3) -> Vec2 {
+    Vec2::new(point.x, point.y)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::scene::draw_node::DrawPrimitive;
+
+    fn two_node_tree() -> Node {
+        let mut root = Node::new();
+        root.set_name("root".to_string());
+        root.set_content_size(Vec2::new(200.0, 100.0));
+        root.set_position(Vec2::new(100.0, 50.0));
+
+        let mut child = Node::new();
+        child.set_name("child".to_string());
+        child.set_content_size(Vec2::new(20.0, 10.0));
+        child.set_position(Vec2::new(50.0, 50.0));
+        root.add_child(child.into_ref());
+        root
+    }
+
+    fn count(overlay: &DebugDrawNode, matches: fn(&DrawPrimitive) -> bool) -> usize {
+        overlay.get_draw_node().get_primitives().iter().filter(|primitive| matches(primitive)).count()
+    }
+
+    #[test]
+    fn test_bounds_and_anchors_per_node() {
+        let mut overlay = DebugDrawNode::new();
+        overlay.update(&two_node_tree());
+
+        assert_eq!(count(&overlay, |p| matches!(p, DrawPrimitive::Rect { .. })), 2);
+        assert_eq!(count(&overlay, |p| matches!(p, DrawPrimitive::Line { .. })), 4);
+        assert_eq!(
+            overlay.get_draw_node().get_primitives()[3],
+            DrawPrimitive::Rect { origin: Vec2::new(40.0, 45.0), destination: Vec2::new(60.0, 55.0), color: BOUNDS_COLOR }
+        );
+        assert_eq!(
+            overlay.get_draw_node().get_primitives()[4],
+            DrawPrimitive::Line { from: Vec2::new(46.0, 50.0), to: Vec2::new(54.0, 50.0), color: ANCHOR_COLOR }
+        );
+    }
+
+    #[test]
+    fn test_toggles_select_primitives() {
+        let mut tree = two_node_tree();
+        let mut overlay = DebugDrawNode::new();
+        overlay.set_show_anchors(false);
+        overlay.set_show_names(true);
+        overlay.update(&tree);
+        assert_eq!(count(&overlay, |p| matches!(p, DrawPrimitive::Rect { .. })), 2);
+        assert_eq!(count(&overlay, |p| matches!(p, DrawPrimitive::Line { .. })), 0);
+        assert_eq!(count(&overlay, |p| matches!(p, DrawPrimitive::Text { .. })), 2);
+
+        tree.set_visible(false);
+        overlay.update(&tree);
+        assert!(overlay.get_draw_node().get_primitives().is_empty());
+    }
+}
@@ -0,0 +1,77 @@
+use crate::base::Node;
+use crate::base::types::Color4F;
+use crate::math::Vec2;
+
+/// A shape recorded by a `DrawNode`, in the node's space
+#[derive(Debug, Clone, PartialEq)]
+pub enum DrawPrimitive {
+    Line { from: Vec2, to: Vec2, color: Color4F },
+    /// Axis-aligned rectangle outline between two opposite corners
+    Rect { origin: Vec2, destination: Vec2, color: Color4F },
+    Poly { points: Vec<Vec2>, closed: bool, color: Color4F },
+    Dot { position: Vec2, radius: f32, color: Color4F },
+    Text { position: Vec2, text: String, color: Color4F },
+}
+
+/// A node that draws simple shapes, mainly for debugging and prototyping.
+///
+/// Primitives accumulate until `clear` is called, so a node redrawn every
+/// frame should be cleared first.
+#[derive(Debug)]
+pub struct DrawNode {
+    node: Node,
+    primitives: Vec<DrawPrimitive>,
+}
+
+impl DrawNode {
+    pub fn new() -> DrawNode {
+        DrawNode {
+            node: Node::new(),
+            primitives: Vec::new(),
+        }
+    }
+
+    pub fn get_node(&self) -> &Node {
+        &self.node
+    }
+
+    pub fn get_node_mut(&mut self) -> &mut Node {
+        &mut self.node
+    }
+
+    pub fn draw_line(&mut self, from: Vec2, to: Vec2, color: Color4F) {
+        self.primitives.push(DrawPrimitive::Line { from, to, color });
+    }
+
+    pub fn draw_rect(&mut self, origin: Vec2, destination: Vec2, color: Color4F) {
+        self.primitives.push(DrawPrimitive::Rect { origin, destination, color });
+    }
+
+    pub fn draw_poly(&mut self, points: Vec<Vec2>, closed: bool, color: Color4F) {
+        self.primitives.push(DrawPrimitive::Poly { points, closed, color });
+    }
+
+    pub fn draw_dot(&mut self, position: Vec2, radius: f32, color: Color4F) {
+        self.primitives.push(DrawPrimitive::Dot { position, radius, color });
+    }
+
+    pub fn draw_string(&mut self, position: Vec2, text: &str, color: Color4F) {
+        self.primitives.push(DrawPrimitive::Text { position, text: text.to_string(), color });
+    }
+
+    /// Gets the recorded primitives in drawing order
+    pub fn get_primitives(&self) -> &[DrawPrimitive] {
+        &self.primitives
+    }
+
+    /// Removes every recorded primitive
+    pub fn clear(&mut self) {
+        self.primitives.clear();
+    }
+}
+
+impl Default for DrawNode {
+    fn default() -> Self {
+        DrawNode::new()
+    }
+}
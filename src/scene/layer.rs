@@ -1,4 +1,4 @@
-use crate::base::{Director, Scene, Node, Color3B, Color4F, Ref, RefPtr};
+use crate::base::{Director, Scene, Node, NodeRef, Color3B, Color4F, Ref};
 use crate::math::Vec2;
 use crate::sprite::Sprite;
 use crate::action::{Action, FiniteTimeAction};
@@ -31,7 +31,7 @@ impl Layer {
         &mut self.node
     }
 
-    pub fn add_child(&mut self, child: RefPtr<Node>) {
+    pub fn add_child(&mut self, child: NodeRef) {
         self.node.add_child(child);
     }
 
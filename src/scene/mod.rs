@@ -1,2 +1,8 @@
 pub mod layer;
+pub mod clipping_rectangle_node;
+pub mod draw_node;
+pub mod debug_draw_node;
 pub use layer::{Layer, LayerColor};
+pub use clipping_rectangle_node::{ClippingRectangleNode, ScissorStack};
+pub use draw_node::{DrawNode, DrawPrimitive};
+pub use debug_draw_node::DebugDrawNode;
@@ -156,6 +156,38 @@ impl BuiltInShaders {
         }
     "#;
 
+    /// Gray Scale Color 着色器（灰度 + 顶点颜色，用于精灵）
+    pub const GRAY_SCALE_COLOR_FRAG: &'static str = r#"
+        #version 330 core
+        in vec2 vTexCoord;
+        in vec4 vColor;
+        out vec4 FragColor;
+        
+        uniform sampler2D uTexture;
+        
+        void main() {
+            vec4 color = texture(uTexture, vTexCoord) * vColor;
+            float gray = dot(color.rgb, vec3(0.299, 0.587, 0.114));
+            FragColor = vec4(gray, gray, gray, color.a);
+        }
+    "#;
+
+    /// Color Matrix 着色器（自定义 4x4 颜色变换）
+    pub const COLOR_MATRIX_FRAG: &'static str = r#"
+        #version 330 core
+        in vec2 vTexCoord;
+        in vec4 vColor;
+        out vec4 FragColor;
+        
+        uniform sampler2D uTexture;
+        uniform mat4 uColorMatrix;
+        
+        void main() {
+            vec4 color = texture(uTexture, vTexCoord) * vColor;
+            FragColor = clamp(uColorMatrix * color, 0.0, 1.0);
+        }
+    "#;
+
     /// Sepia 着色器（褐色效果）
     pub const SEPIA_FRAG: &'static str = r#"
         #version 330 core
@@ -209,6 +241,8 @@ impl BuiltInShaders {
             "position_texture_alpha_test",
             "label",
             "gray_scale",
+            "gray_scale_color",
+            "color_matrix",
             "sepia",
             "blur",
         ]
@@ -241,6 +275,14 @@ impl BuiltInShaders {
                 Self::POSITION_TEXTURE_VERT,
                 Self::GRAY_SCALE_FRAG,
             )),
+            "gray_scale_color" => Some((
+                Self::POSITION_TEXTURE_COLOR_VERT,
+                Self::GRAY_SCALE_COLOR_FRAG,
+            )),
+            "color_matrix" => Some((
+                Self::POSITION_TEXTURE_COLOR_VERT,
+                Self::COLOR_MATRIX_FRAG,
+            )),
             "sepia" => Some((
                 Self::POSITION_TEXTURE_VERT,
                 Self::SEPIA_FRAG,
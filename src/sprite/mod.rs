@@ -1,6 +1,29 @@
-use crate::base::{Node, Ref, RefPtr};
-use crate::base::types::{Color3B, Rect, Size};
-use crate::math::Vec2;
+pub mod motion_streak;
+pub mod sprite_batch_node;
+
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use crate::animation::{SpriteFrame, SpriteFrameCache};
+use crate::base::{ColorProtocol, Node, Ref, RefPtr};
+use crate::base::types::{Color3B, Color4B, Color4F, Rect, Size};
+use crate::math::{Mat4, Vec2, Vec3};
+use crate::renderer::{Material, Pass, Quad, Technique, UniformValue};
+use crate::renderer::command::Vertex;
+use crate::renderer::material::Program;
+use crate::shader::BuiltInShaders;
+
+pub use motion_streak::MotionStreak;
+pub use sprite_batch_node::SpriteBatchNode;
+
+/// Built-in program of a plain sprite
+pub const SPRITE_PROGRAM: &str = "position_texture_color";
+/// Built-in program of a grayscale sprite
+pub const SPRITE_GRAY_SCALE_PROGRAM: &str = "gray_scale_color";
+/// Built-in program of a sprite with a color matrix
+pub const SPRITE_COLOR_MATRIX_PROGRAM: &str = "color_matrix";
+/// Uniform holding a sprite's color matrix
+pub const COLOR_MATRIX_UNIFORM: &str = "uColorMatrix";
 
 /// Sprite is a 2D image that can be rendered
 #[derive(Debug)]
@@ -13,6 +36,12 @@ pub struct Sprite {
     flipped_y: bool,
     blend_func: BlendFunc,
     rect: Rect,
+    rect_rotated: bool,
+    sprite_frame: Option<Rc<RefCell<SpriteFrame>>>,
+    hit_polygon: Option<Vec<Vec2>>,
+    grayscale: bool,
+    color_matrix: Option<[f32; 16]>,
+    material: RefPtr<Material>,
 }
 
 impl Sprite {
@@ -27,6 +56,12 @@ impl Sprite {
             flipped_y: false,
             blend_func: BlendFunc::ALPHA_PREMULTIPLIED,
             rect: Rect::ZERO,
+            rect_rotated: false,
+            sprite_frame: None,
+            hit_polygon: None,
+            grayscale: false,
+            color_matrix: None,
+            material: RefPtr::new(Sprite::build_material(SPRITE_PROGRAM, None)),
         }
     }
 
@@ -41,6 +76,12 @@ impl Sprite {
             flipped_y: false,
             blend_func: BlendFunc::ALPHA_PREMULTIPLIED,
             rect: Rect::ZERO,
+            rect_rotated: false,
+            sprite_frame: None,
+            hit_polygon: None,
+            grayscale: false,
+            color_matrix: None,
+            material: RefPtr::new(Sprite::build_material(SPRITE_PROGRAM, None)),
         };
         sprite.rect = Rect::new(0.0, 0.0, 0.0, 0.0);
         sprite
@@ -48,7 +89,7 @@ impl Sprite {
 
     /// Creates a sprite with a file path
     pub fn with_file(file_path: &str) -> Option<Sprite> {
-        let texture = TextureCache::get_instance().add_image(file_path)?;
+        let texture = TextureCache::get_instance().borrow_mut().add_image(file_path)?;
         Some(Sprite::with_texture(texture))
     }
 
@@ -109,6 +150,187 @@ impl Sprite {
         self.rect = rect;
     }
 
+    /// Checks if the texture rect is stored rotated 90 degrees clockwise in
+    /// the texture, as packed by atlas tools
+    pub fn is_texture_rect_rotated(&self) -> bool {
+        self.rect_rotated
+    }
+
+    /// Gets the sprite frame being displayed
+    pub fn get_sprite_frame(&self) -> Option<&Rc<RefCell<SpriteFrame>>> {
+        self.sprite_frame.as_ref()
+    }
+
+    /// Displays a sprite frame: takes its texture rect, rotation flag and
+    /// untrimmed size. The texture is only replaced when the frame comes from
+    /// a different texture than the previous frame.
+    pub fn set_sprite_frame(&mut self, frame: Rc<RefCell<SpriteFrame>>) {
+        {
+            let new_frame = frame.borrow();
+            let frame_rect = new_frame.rect();
+            self.rect = Rect::new(frame_rect.origin.x, frame_rect.origin.y, frame_rect.width(), frame_rect.height());
+            self.rect_rotated = new_frame.is_rotated();
+
+            let (width, height) = match new_frame.original_size() {
+                (width, height) if width > 0.0 && height > 0.0 => (width, height),
+                _ => (new_frame.width(), new_frame.height()),
+            };
+            self.node.set_content_size(Vec2::new(width, height));
+
+            if let Some(texture) = new_frame.texture() {
+                let same_texture = self
+                    .sprite_frame
+                    .as_ref()
+                    .and_then(|old| old.borrow().texture())
+                    .map_or(false, |old| Rc::ptr_eq(&old, &texture));
+                if !same_texture || self.texture.is_none() {
+                    self.texture = Some(RefPtr::new(Texture2D::from_renderer_texture(&texture.borrow())));
+                }
+            }
+        }
+        self.sprite_frame = Some(frame);
+    }
+
+    /// Displays the frame registered under `name` in the shared
+    /// `SpriteFrameCache`. Returns false when there is no such frame.
+    pub fn set_display_frame_named(&mut self, name: &str) -> bool {
+        let frame = SpriteFrameCache::shared().borrow().get_frame(name);
+        match frame {
+            Some(frame) => {
+                self.set_sprite_frame(frame);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Sets a polygon, in node space, used instead of the texture rect for
+    /// hit testing irregular sprites
+    pub fn set_hit_polygon(&mut self, points: Vec<Vec2>) {
+        self.hit_polygon = Some(points);
+    }
+
+    /// Gets the hit polygon
+    pub fn get_hit_polygon(&self) -> Option<&Vec<Vec2>> {
+        self.hit_polygon.as_ref()
+    }
+
+    /// Removes the hit polygon, falling back to the texture rect
+    pub fn clear_hit_polygon(&mut self) {
+        self.hit_polygon = None;
+    }
+
+    /// Checks if a point hits the sprite, testing against the hit polygon
+    /// when one is set and the texture rect otherwise.
+    ///
+    /// `point` is in the space of the node holding the sprite, which is
+    /// world space for sprites added straight to a scene. Use
+    /// `contains_point_with` for a world point and a sprite held deeper.
+    pub fn contains_point(&self, point: Vec2) -> bool {
+        self.contains_point_with(point, self.node.get_local_transform())
+    }
+
+    /// Checks if a point hits the sprite placed by `transform`, e.g. a world
+    /// point and the holder's `get_node_to_world_transform` combined with
+    /// the sprite's local transform
+    pub fn contains_point_with(&self, world_point: Vec2, transform: &Mat4) -> bool {
+        let to_world = |point: &Vec2| {
+            let world = transform.transform_point(&Vec3::new(point.x, point.y, 0.0));
+            Vec2::new(world.x, world.y)
+        };
+
+        let polygon: Vec<Vec2> = match &self.hit_polygon {
+            Some(points) => points.iter().map(to_world).collect(),
+            None => {
+                let (width, height) = self.get_displayed_rect_size();
+                [Vec2::ZERO, Vec2::new(width, 0.0), Vec2::new(width, height), Vec2::new(0.0, height)]
+                    .iter()
+                    .map(to_world)
+                    .collect()
+            }
+        };
+        polygon_contains_point(&polygon, world_point)
+    }
+
+    /// Checks if a point hits an opaque part of the sprite.
+    ///
+    /// After the shape test of `contains_point`, the texture pixel under the
+    /// point must have an alpha above `alpha_threshold`. Textures without
+    /// pixel data fall back to the shape test alone. `point` is in the same
+    /// space as for `contains_point`.
+    pub fn contains_point_pixel_perfect(&self, point: Vec2, alpha_threshold: u8) -> bool {
+        self.contains_point_pixel_perfect_with(point, self.node.get_local_transform(), alpha_threshold)
+    }
+
+    /// Checks if a point hits an opaque part of the sprite placed by
+    /// `transform`, as for `contains_point_with`
+    pub fn contains_point_pixel_perfect_with(&self, world_point: Vec2, transform: &Mat4, alpha_threshold: u8) -> bool {
+        if !self.contains_point_with(world_point, transform) {
+            return false;
+        }
+        let texture = match self.texture.as_ref().filter(|texture| texture.get_pixel_data().is_some()) {
+            Some(texture) => texture,
+            None => return true,
+        };
+        let local = match Sprite::to_local(world_point, transform) {
+            Some(local) => local,
+            None => return false,
+        };
+
+        let (width, height) = self.get_displayed_rect_size();
+        let mut x = local.x.clamp(0.0, width);
+        let mut y = local.y.clamp(0.0, height);
+        if self.flipped_x {
+            x = width - x;
+        }
+        if self.flipped_y {
+            y = height - y;
+        }
+        // Texture rows run from the top; a rotated rect is stored turned 90
+        // degrees clockwise, as in `compute_quad`
+        let (texel_x, texel_y) = if self.rect_rotated {
+            (self.rect.origin.x + y, self.rect.origin.y + x)
+        } else {
+            (self.rect.origin.x + x, self.rect.origin.y + height - y)
+        };
+        let texel_x = (texel_x.max(0.0) as u32).min(texture.get_width().saturating_sub(1));
+        let texel_y = (texel_y.max(0.0) as u32).min(texture.get_height().saturating_sub(1));
+        texture
+            .get_alpha_at(texel_x, texel_y)
+            .map_or(false, |alpha| alpha > alpha_threshold)
+    }
+
+    /// Maps a point through the inverse of a 2D `transform`, or `None` when
+    /// the transform collapses the sprite
+    fn to_local(world_point: Vec2, transform: &Mat4) -> Option<Vec2> {
+        let m = &transform.m;
+        let (a, b, c, d) = (m[0], m[1], m[4], m[5]);
+        let determinant = a * d - b * c;
+        if determinant.abs() < f32::EPSILON {
+            return None;
+        }
+        let x = world_point.x - m[12];
+        let y = world_point.y - m[13];
+        Some(Vec2::new((d * x - c * y) / determinant, (a * y - b * x) / determinant))
+    }
+
+    /// Gets the bounding rectangle of the sprite's content size moved by its
+    /// transform
+    pub fn get_bounding_box(&self) -> Rect {
+        self.get_bounding_box_with(self.node.get_local_transform())
+    }
+
+    /// Gets the bounding rectangle of the sprite's content size moved by
+    /// `transform`, e.g. its node-to-world transform
+    pub fn get_bounding_box_with(&self, transform: &Mat4) -> Rect {
+        let size = self.node.get_content_size();
+        let corners = [Vec2::ZERO, Vec2::new(size.x, 0.0), Vec2::new(0.0, size.y), size].map(|corner| {
+            let point = transform.transform_point(&Vec3::new(corner.x, corner.y, 0.0));
+            Vec2::new(point.x, point.y)
+        });
+        crate::base::camera_2d::bounding_rect(&corners)
+    }
+
     /// Checks if the sprite is flipped on X axis
     pub fn is_flipped_x(&self) -> bool {
         self.flipped_x
@@ -129,6 +351,160 @@ impl Sprite {
         self.flipped_y = flipped_y;
     }
 
+    /// Draws the sprite desaturated. Grayscale takes precedence over a
+    /// color matrix while enabled.
+    pub fn set_grayscale(&mut self, grayscale: bool) {
+        self.grayscale = grayscale;
+        self.update_material();
+    }
+
+    /// Checks if the sprite is drawn desaturated
+    pub fn is_grayscale(&self) -> bool {
+        self.grayscale
+    }
+
+    /// Sets a column-major 4x4 matrix applied to the sprite's RGBA color
+    pub fn set_color_matrix(&mut self, matrix: [f32; 16]) {
+        self.color_matrix = Some(matrix);
+        self.update_material();
+    }
+
+    /// Gets the color matrix
+    pub fn get_color_matrix(&self) -> Option<&[f32; 16]> {
+        self.color_matrix.as_ref()
+    }
+
+    /// Removes the color matrix
+    pub fn clear_color_matrix(&mut self) {
+        self.color_matrix = None;
+        self.update_material();
+    }
+
+    /// Gets the material the sprite is drawn with. The handle stays the same
+    /// until the shader effect changes.
+    pub fn get_material(&self) -> &RefPtr<Material> {
+        &self.material
+    }
+
+    /// Gets the name of the built-in program the sprite is drawn with
+    pub fn get_program_name(&self) -> &str {
+        if self.grayscale {
+            SPRITE_GRAY_SCALE_PROGRAM
+        } else if self.color_matrix.is_some() {
+            SPRITE_COLOR_MATRIX_PROGRAM
+        } else {
+            SPRITE_PROGRAM
+        }
+    }
+
+    /// Rebuilds the material for the current shader effect
+    fn update_material(&mut self) {
+        let matrix = if self.grayscale { None } else { self.color_matrix };
+        self.material = RefPtr::new(Sprite::build_material(self.get_program_name(), matrix));
+    }
+
+    /// Builds a single-pass material running a built-in program
+    fn build_material(program_name: &str, color_matrix: Option<[f32; 16]>) -> Material {
+        let mut program = Program::with_name(program_name);
+        if let Some((vertex_source, fragment_source)) = BuiltInShaders::get_shader_source(program_name) {
+            program.set_vertex_shader(vertex_source);
+            program.set_fragment_shader(fragment_source);
+        }
+        let mut pass = Pass::new();
+        pass.set_program(RefPtr::new(program));
+        pass.get_render_state_mut().blend = true;
+        if let Some(matrix) = color_matrix {
+            pass.set_uniform(COLOR_MATRIX_UNIFORM, UniformValue::Mat4(Mat4::from_array(&matrix)));
+        }
+
+        let mut technique = Technique::with_name("normal");
+        technique.add_pass(RefPtr::new(pass));
+        let mut material = Material::with_name("sprite");
+        material.set_technique(RefPtr::new(technique));
+        material
+    }
+
+    /// Builds the sprite's quad: the texture rect's corners transformed by
+    /// the node's local transform, with texture coordinates normalized to
+    /// the texture size and swapped according to the flip flags
+    pub fn compute_quad(&self) -> Quad {
+        self.compute_quad_with(self.node.get_local_transform(), self.color, self.opacity)
+    }
+
+    /// Builds the sprite's quad like `compute_quad`, with the corners moved
+    /// by `transform` and the vertices tinted with `color` and `opacity`
+    /// instead of the sprite's own
+    pub fn compute_quad_with(&self, transform: &Mat4, color: Color3B, opacity: u8) -> Quad {
+        let (tex_width, tex_height) = self
+            .texture
+            .as_ref()
+            .map(|texture| (texture.get_width() as f32, texture.get_height() as f32))
+            .filter(|(w, h)| *w > 0.0 && *h > 0.0)
+            .unwrap_or((1.0, 1.0));
+        let rect = self.rect;
+        let (width, height) = self.get_displayed_rect_size();
+
+        let mut quad = Quad::new();
+        if self.rect_rotated {
+            // The rect is stored rotated 90 degrees clockwise, so the
+            // displayed width runs down the texture and the height across
+            let mut left = rect.origin.x / tex_width;
+            let mut right = (rect.origin.x + height) / tex_width;
+            let mut top = rect.origin.y / tex_height;
+            let mut bottom = (rect.origin.y + width) / tex_height;
+            if self.flipped_x {
+                std::mem::swap(&mut top, &mut bottom);
+            }
+            if self.flipped_y {
+                std::mem::swap(&mut left, &mut right);
+            }
+            quad.bl.tex_coord = [left, top];
+            quad.br.tex_coord = [left, bottom];
+            quad.tl.tex_coord = [right, top];
+            quad.tr.tex_coord = [right, bottom];
+        } else {
+            let mut left = rect.origin.x / tex_width;
+            let mut right = (rect.origin.x + width) / tex_width;
+            let mut top = rect.origin.y / tex_height;
+            let mut bottom = (rect.origin.y + height) / tex_height;
+            if self.flipped_x {
+                std::mem::swap(&mut left, &mut right);
+            }
+            if self.flipped_y {
+                std::mem::swap(&mut top, &mut bottom);
+            }
+            quad.bl.tex_coord = [left, bottom];
+            quad.br.tex_coord = [right, bottom];
+            quad.tl.tex_coord = [left, top];
+            quad.tr.tex_coord = [right, top];
+        }
+
+        let color = Color4F::from_color4b(Color4B::from_color3b(color, opacity));
+        let corners = [
+            (&mut quad.bl, 0.0, 0.0),
+            (&mut quad.br, width, 0.0),
+            (&mut quad.tl, 0.0, height),
+            (&mut quad.tr, width, height),
+        ];
+        for (vertex, x, y) in corners {
+            let position = transform.transform_point(&Vec3::new(x, y, 0.0));
+            vertex.position = [position.x, position.y, position.z];
+            vertex.color = color;
+        }
+        quad.blend_func = (self.blend_func.src, self.blend_func.dst);
+        quad
+    }
+
+    /// Gets the size of the texture rect as displayed, swapping the stored
+    /// width and height of a rotated rect
+    fn get_displayed_rect_size(&self) -> (f32, f32) {
+        if self.rect_rotated {
+            (self.rect.size.height, self.rect.size.width)
+        } else {
+            (self.rect.size.width, self.rect.size.height)
+        }
+    }
+
     /// Gets the node
     pub fn get_node(&self) -> &Node {
         &self.node
@@ -140,6 +516,43 @@ impl Sprite {
     }
 }
 
+/// Even-odd ray casting test of a point against a closed polygon
+fn polygon_contains_point(polygon: &[Vec2], point: Vec2) -> bool {
+    if polygon.len() < 3 {
+        return false;
+    }
+    let mut inside = false;
+    let mut j = polygon.len() - 1;
+    for i in 0..polygon.len() {
+        let (a, b) = (polygon[i], polygon[j]);
+        if (a.y > point.y) != (b.y > point.y)
+            && point.x < (b.x - a.x) * (point.y - a.y) / (b.y - a.y) + a.x
+        {
+            inside = !inside;
+        }
+        j = i;
+    }
+    inside
+}
+
+impl ColorProtocol for Sprite {
+    fn get_color(&self) -> Color3B {
+        self.color
+    }
+
+    fn set_color(&mut self, color: Color3B) {
+        self.color = color;
+    }
+
+    fn get_opacity(&self) -> u8 {
+        self.opacity
+    }
+
+    fn set_opacity(&mut self, opacity: u8) {
+        self.opacity = opacity;
+    }
+}
+
 /// Blend function for rendering
 #[derive(Debug, Clone, Copy, PartialEq)]
 pub struct BlendFunc {
@@ -147,15 +560,62 @@ pub struct BlendFunc {
     pub dst: u32,
 }
 
+const GL_ZERO: u32 = 0;
+const GL_ONE: u32 = 1;
+const GL_ONE_MINUS_SRC_COLOR: u32 = 0x0301;
+const GL_SRC_ALPHA: u32 = 0x0302;
+const GL_ONE_MINUS_SRC_ALPHA: u32 = 0x0303;
+const GL_DST_COLOR: u32 = 0x0306;
+
 impl BlendFunc {
-    pub const DISABLE: BlendFunc = BlendFunc { src: 0, dst: 0 };
-    pub const ALPHA_NON_PREMULTIPLIED: BlendFunc = BlendFunc { src: 770, dst: 771 };
-    pub const ALPHA_PREMULTIPLIED: BlendFunc = BlendFunc { src: 1, dst: 771 };
-    pub const ADDITIVE: BlendFunc = BlendFunc { src: 1, dst: 1 };
+    pub const DISABLE: BlendFunc = BlendFunc { src: GL_ZERO, dst: GL_ZERO };
+    pub const ALPHA_NON_PREMULTIPLIED: BlendFunc = BlendFunc { src: GL_SRC_ALPHA, dst: GL_ONE_MINUS_SRC_ALPHA };
+    pub const ALPHA_PREMULTIPLIED: BlendFunc = BlendFunc { src: GL_ONE, dst: GL_ONE_MINUS_SRC_ALPHA };
+    pub const ADDITIVE: BlendFunc = BlendFunc { src: GL_ONE, dst: GL_ONE };
+    pub const ALPHA: BlendFunc = BlendFunc::ALPHA_NON_PREMULTIPLIED;
+    pub const MULTIPLY: BlendFunc = BlendFunc { src: GL_DST_COLOR, dst: GL_ONE_MINUS_SRC_ALPHA };
+    pub const SCREEN: BlendFunc = BlendFunc { src: GL_ONE, dst: GL_ONE_MINUS_SRC_COLOR };
+
+    /// Preset names, in lookup order; `to_name` reports the first match
+    const PRESETS: [(&'static str, BlendFunc); 7] = [
+        ("disable", BlendFunc::DISABLE),
+        ("alpha", BlendFunc::ALPHA),
+        ("alpha_non_premultiplied", BlendFunc::ALPHA_NON_PREMULTIPLIED),
+        ("alpha_premultiplied", BlendFunc::ALPHA_PREMULTIPLIED),
+        ("additive", BlendFunc::ADDITIVE),
+        ("multiply", BlendFunc::MULTIPLY),
+        ("screen", BlendFunc::SCREEN),
+    ];
 
     pub fn new(src: u32, dst: u32) -> Self {
         BlendFunc { src, dst }
     }
+
+    /// Looks up a preset by name, ignoring case
+    pub fn from_name(name: &str) -> Option<BlendFunc> {
+        BlendFunc::PRESETS
+            .iter()
+            .find(|(preset, _)| preset.eq_ignore_ascii_case(name))
+            .map(|(_, func)| *func)
+    }
+
+    /// Gets the preset name of this function, if it matches one
+    pub fn to_name(&self) -> Option<&'static str> {
+        BlendFunc::PRESETS
+            .iter()
+            .find(|(_, func)| func == self)
+            .map(|(name, _)| *name)
+    }
+
+    /// Turns blending off
+    pub fn disable(&mut self) {
+        *self = BlendFunc::DISABLE;
+    }
+
+    /// Checks if blending is off
+    pub fn is_disabled(&self) -> bool {
+        *self == BlendFunc::DISABLE
+    }
 }
 
 /// Texture2D represents an OpenGL texture
@@ -165,6 +625,7 @@ pub struct Texture2D {
     width: u32,
     height: u32,
     path: String,
+    pixels: Option<Vec<u8>>,
 }
 
 impl Texture2D {
@@ -175,6 +636,29 @@ impl Texture2D {
             width,
             height,
             path: String::new(),
+            pixels: None,
+        }
+    }
+
+    /// Creates a texture referring to the same GPU texture as a renderer texture
+    pub fn from_renderer_texture(texture: &crate::renderer::Texture2D) -> Texture2D {
+        Texture2D {
+            name: texture.get_name(),
+            width: texture.get_width(),
+            height: texture.get_height(),
+            path: String::new(),
+            pixels: None,
+        }
+    }
+
+    /// Creates a new texture loaded from the given path
+    pub fn with_path(width: u32, height: u32, path: &str) -> Texture2D {
+        Texture2D {
+            name: 0,
+            width,
+            height,
+            path: path.to_string(),
+            pixels: None,
         }
     }
 
@@ -197,6 +681,36 @@ impl Texture2D {
     pub fn get_path(&self) -> &str {
         &self.path
     }
+
+    /// Keeps a CPU copy of the texture's RGBA8 pixels, rows from the top,
+    /// for hit testing. Fails if `data` does not hold exactly one pixel per
+    /// texel.
+    pub fn set_pixel_data(&mut self, data: Vec<u8>) -> Result<(), String> {
+        let expected = self.width as usize * self.height as usize * 4;
+        if data.len() != expected {
+            return Err(format!(
+                "Expected {} bytes of RGBA pixels for a {}x{} texture, got {}",
+                expected, self.width, self.height, data.len()
+            ));
+        }
+        self.pixels = Some(data);
+        Ok(())
+    }
+
+    /// Gets the CPU copy of the pixels, if one was kept
+    pub fn get_pixel_data(&self) -> Option<&[u8]> {
+        self.pixels.as_deref()
+    }
+
+    /// Gets the alpha of the pixel at `x`, `y` from the top-left, if pixel
+    /// data is kept and the pixel is inside the texture
+    pub fn get_alpha_at(&self, x: u32, y: u32) -> Option<u8> {
+        if x >= self.width || y >= self.height {
+            return None;
+        }
+        let index = (y as usize * self.width as usize + x as usize) * 4 + 3;
+        self.pixels.as_ref().map(|pixels| pixels[index])
+    }
 }
 
 /// TextureCache manages all textures
@@ -206,15 +720,13 @@ pub struct TextureCache {
 }
 
 impl TextureCache {
-    /// Gets the singleton instance
-    pub fn get_instance() -> &'static mut TextureCache {
-        static mut TEXTURE_CACHE: Option<TextureCache> = None;
-        unsafe {
-            if TEXTURE_CACHE.is_none() {
-                TEXTURE_CACHE = Some(TextureCache::new());
-            }
-            TEXTURE_CACHE.as_mut().unwrap()
+    /// Gets the texture cache of the current thread. Every call returns a
+    /// handle to the same cache.
+    pub fn get_instance() -> Rc<RefCell<TextureCache>> {
+        thread_local! {
+            static TEXTURE_CACHE: Rc<RefCell<TextureCache>> = Rc::new(RefCell::new(TextureCache::new()));
         }
+        TEXTURE_CACHE.with(Rc::clone)
     }
 
     /// Creates a new texture cache
@@ -231,7 +743,7 @@ impl TextureCache {
         }
 
         // In a real implementation, this would load the texture from file
-        let texture = Ref::new(Texture2D::new(0, 0));
+        let texture = RefPtr::new(Texture2D::with_path(0, 0, path));
         self.textures.insert(path.to_string(), texture.clone());
         Some(texture)
     }
@@ -256,3 +768,264 @@ impl TextureCache {
         self.textures.clear();
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn frame(name: &str, rect: crate::math::Rect, rotated: bool, texture: &Rc<RefCell<crate::renderer::Texture2D>>) -> Rc<RefCell<SpriteFrame>> {
+        let size = if rotated { (rect.height(), rect.width()) } else { (rect.width(), rect.height()) };
+        Rc::new(RefCell::new(SpriteFrame::with_details(name, texture.clone(), rect, rotated, size, (0.0, 0.0))))
+    }
+
+    #[test]
+    fn test_set_sprite_frame_updates_rect() {
+        let texture = Rc::new(RefCell::new(crate::renderer::Texture2D::new()));
+        let mut sprite = Sprite::new();
+        sprite.set_sprite_frame(frame("walk_0", crate::math::Rect::new(32.0, 0.0, 16.0, 24.0), false, &texture));
+
+        assert_eq!(sprite.get_texture_rect(), Rect::new(32.0, 0.0, 16.0, 24.0));
+        assert!(!sprite.is_texture_rect_rotated());
+        assert_eq!(sprite.get_node().get_content_size(), Vec2::new(16.0, 24.0));
+        assert!(sprite.get_texture().is_some());
+        assert_eq!(sprite.get_sprite_frame().unwrap().borrow().name(), "walk_0");
+    }
+
+    #[test]
+    fn test_set_rotated_sprite_frame() {
+        let texture = Rc::new(RefCell::new(crate::renderer::Texture2D::new()));
+        let mut sprite = Sprite::new();
+        sprite.set_sprite_frame(frame("packed", crate::math::Rect::new(0.0, 0.0, 24.0, 16.0), true, &texture));
+
+        assert!(sprite.is_texture_rect_rotated());
+        assert_eq!(sprite.get_texture_rect(), Rect::new(0.0, 0.0, 24.0, 16.0));
+        assert_eq!(sprite.get_node().get_content_size(), Vec2::new(16.0, 24.0));
+
+        sprite.set_sprite_frame(frame("upright", crate::math::Rect::new(24.0, 0.0, 8.0, 8.0), false, &texture));
+        assert!(!sprite.is_texture_rect_rotated());
+    }
+
+    #[test]
+    fn test_blend_func_from_name() {
+        assert_eq!(BlendFunc::from_name("additive"), Some(BlendFunc::ADDITIVE));
+        assert_eq!(BlendFunc::from_name("Multiply"), Some(BlendFunc::MULTIPLY));
+        assert_eq!(BlendFunc::from_name("overlay"), None);
+        assert_eq!(BlendFunc::new(770, 1).to_name(), None);
+    }
+
+    #[test]
+    fn test_blend_func_name_round_trip() {
+        for func in [BlendFunc::ALPHA, BlendFunc::ALPHA_PREMULTIPLIED, BlendFunc::ADDITIVE, BlendFunc::MULTIPLY, BlendFunc::SCREEN] {
+            let name = func.to_name().unwrap();
+            assert_eq!(BlendFunc::from_name(name), Some(func));
+        }
+        assert_eq!(BlendFunc::ALPHA_NON_PREMULTIPLIED.to_name(), Some("alpha"));
+
+        let mut func = BlendFunc::ADDITIVE;
+        assert!(!func.is_disabled());
+        func.disable();
+        assert!(func.is_disabled());
+        assert_eq!(func.to_name(), Some("disable"));
+    }
+
+    fn translated_sprite() -> Sprite {
+        let texture = RefPtr::new(Texture2D::new(64, 64));
+        let mut sprite = Sprite::with_texture_rect(texture, Rect::new(0.0, 0.0, 32.0, 32.0));
+        let node = sprite.get_node_mut();
+        node.set_content_size(Vec2::new(32.0, 32.0));
+        node.set_anchor_point(Vec2::ZERO);
+        node.set_position(Vec2::new(100.0, 50.0));
+        sprite
+    }
+
+    #[test]
+    fn test_contains_point_falls_back_to_rect() {
+        let sprite = translated_sprite();
+        assert!(sprite.contains_point(Vec2::new(130.0, 80.0)));
+        assert!(sprite.contains_point(Vec2::new(101.0, 51.0)));
+        assert!(!sprite.contains_point(Vec2::new(10.0, 10.0)));
+        assert!(!sprite.contains_point(Vec2::new(140.0, 60.0)));
+    }
+
+    #[test]
+    fn test_contains_point_with_world_transform() {
+        let root = crate::base::Node::create();
+        root.borrow_mut().set_position(Vec2::new(0.0, 100.0));
+        let holder = crate::base::Node::create();
+        holder.borrow_mut().set_position(Vec2::new(200.0, 0.0));
+        root.borrow_mut().add_child(std::rc::Rc::clone(&holder));
+
+        let sprite = translated_sprite();
+        let transform = holder.borrow().get_node_to_world_transform() * *sprite.get_node().get_local_transform();
+        assert!(sprite.contains_point_with(Vec2::new(330.0, 180.0), &transform));
+        assert!(!sprite.contains_point_with(Vec2::new(130.0, 80.0), &transform));
+        assert!(sprite.contains_point(Vec2::new(130.0, 80.0)));
+        assert!(sprite.contains_point_pixel_perfect_with(Vec2::new(330.0, 180.0), &transform, 0));
+    }
+
+    #[test]
+    fn test_contains_point_uses_hit_polygon() {
+        let mut sprite = translated_sprite();
+        sprite.set_hit_polygon(vec![Vec2::new(0.0, 0.0), Vec2::new(32.0, 0.0), Vec2::new(0.0, 32.0)]);
+
+        assert!(sprite.contains_point(Vec2::new(105.0, 55.0)));
+        assert!(!sprite.contains_point(Vec2::new(130.0, 80.0)));
+
+        sprite.clear_hit_polygon();
+        assert!(sprite.contains_point(Vec2::new(130.0, 80.0)));
+    }
+
+    /// 32x32 texture, transparent on the left half and opaque on the right
+    fn half_opaque_texture() -> RefPtr<Texture2D> {
+        let mut texture = Texture2D::new(32, 32);
+        let pixels = (0..32 * 32)
+            .flat_map(|i| {
+                let alpha = if i % 32 < 16 { 0 } else { 255 };
+                [255, 255, 255, alpha]
+            })
+            .collect();
+        texture.set_pixel_data(pixels).unwrap();
+        RefPtr::new(texture)
+    }
+
+    #[test]
+    fn test_contains_point_pixel_perfect() {
+        let mut sprite = translated_sprite();
+        assert!(sprite.contains_point_pixel_perfect(Vec2::new(105.0, 60.0), 0));
+
+        sprite.set_texture(half_opaque_texture());
+        assert!(!sprite.contains_point_pixel_perfect(Vec2::new(105.0, 60.0), 0));
+        assert!(sprite.contains_point_pixel_perfect(Vec2::new(125.0, 60.0), 0));
+        assert!(!sprite.contains_point_pixel_perfect(Vec2::new(125.0, 60.0), 255));
+        assert!(!sprite.contains_point_pixel_perfect(Vec2::new(140.0, 60.0), 0));
+
+        sprite.set_flipped_x(true);
+        assert!(sprite.contains_point_pixel_perfect(Vec2::new(105.0, 60.0), 0));
+        assert!(!sprite.contains_point_pixel_perfect(Vec2::new(125.0, 60.0), 0));
+    }
+
+    #[test]
+    fn test_set_pixel_data_checks_length() {
+        let mut texture = Texture2D::new(2, 2);
+        assert!(texture.set_pixel_data(vec![0; 15]).is_err());
+        assert!(texture.get_pixel_data().is_none());
+        assert_eq!(texture.get_alpha_at(1, 1), None);
+
+        texture.set_pixel_data((0..16).collect()).unwrap();
+        assert_eq!(texture.get_alpha_at(1, 1), Some(15));
+        assert_eq!(texture.get_alpha_at(2, 0), None);
+    }
+
+    fn quad_sprite() -> Sprite {
+        let mut sprite = translated_sprite();
+        sprite.set_texture_rect(Rect::new(16.0, 0.0, 32.0, 16.0));
+        sprite
+    }
+
+    fn uvs(quad: &Quad) -> [[f32; 2]; 4] {
+        [quad.bl.tex_coord, quad.br.tex_coord, quad.tl.tex_coord, quad.tr.tex_coord]
+    }
+
+    #[test]
+    fn test_compute_quad_maps_rect_corners() {
+        let quad = quad_sprite().compute_quad();
+        assert_eq!(uvs(&quad), [[0.25, 0.25], [0.75, 0.25], [0.25, 0.0], [0.75, 0.0]]);
+        assert_eq!(quad.bl.position, [100.0, 50.0, 0.0]);
+        assert_eq!(quad.tr.position, [132.0, 66.0, 0.0]);
+        assert_eq!(quad.blend_func, (BlendFunc::ALPHA_PREMULTIPLIED.src, BlendFunc::ALPHA_PREMULTIPLIED.dst));
+    }
+
+    #[test]
+    fn test_compute_quad_rotates_frame_uvs() {
+        let mut sprite = translated_sprite();
+        let mut packed = SpriteFrame::new("packed");
+        packed.set_rect(crate::math::Rect::new(16.0, 0.0, 32.0, 16.0));
+        packed.set_rotated(true);
+        sprite.set_sprite_frame(Rc::new(RefCell::new(packed)));
+        assert_eq!(sprite.get_node().get_content_size(), Vec2::new(16.0, 32.0));
+
+        // The atlas rect spans u 0.25..0.75 and v 0..0.25; turned back 90
+        // degrees its left edge runs along the top of the atlas rect
+        let quad = sprite.compute_quad();
+        assert_eq!(uvs(&quad), [[0.25, 0.0], [0.25, 0.25], [0.75, 0.0], [0.75, 0.25]]);
+        assert_eq!(quad.bl.position, [100.0, 50.0, 0.0]);
+        assert_eq!(quad.tr.position, [116.0, 82.0, 0.0]);
+        assert!(sprite.contains_point(Vec2::new(110.0, 80.0)));
+        assert!(!sprite.contains_point(Vec2::new(125.0, 60.0)));
+    }
+
+    #[test]
+    fn test_compute_quad_flipped_x_swaps_left_and_right() {
+        let mut sprite = quad_sprite();
+        sprite.set_flipped_x(true);
+        let quad = sprite.compute_quad();
+        assert_eq!(uvs(&quad), [[0.75, 0.25], [0.25, 0.25], [0.75, 0.0], [0.25, 0.0]]);
+        assert_eq!(quad.bl.position, [100.0, 50.0, 0.0]);
+    }
+
+    #[test]
+    fn test_compute_quad_flipped_both_swaps_all_corners() {
+        let mut sprite = quad_sprite();
+        sprite.set_flipped_x(true);
+        sprite.set_flipped_y(true);
+        let quad = sprite.compute_quad();
+        assert_eq!(uvs(&quad), [[0.75, 0.0], [0.25, 0.0], [0.75, 0.25], [0.25, 0.25]]);
+        assert_eq!(quad.br.position, [132.0, 50.0, 0.0]);
+        assert_eq!(quad.tl.position, [100.0, 66.0, 0.0]);
+    }
+
+    fn program_name(sprite: &Sprite) -> String {
+        let technique = sprite.get_material().get_technique().unwrap();
+        technique.get_passes()[0].get_program().unwrap().get_name().to_string()
+    }
+
+    #[test]
+    fn test_grayscale_selects_program() {
+        let mut sprite = Sprite::new();
+        assert_eq!(program_name(&sprite), SPRITE_PROGRAM);
+
+        sprite.set_grayscale(true);
+        assert!(sprite.is_grayscale());
+        assert_eq!(sprite.get_program_name(), SPRITE_GRAY_SCALE_PROGRAM);
+        assert_eq!(program_name(&sprite), SPRITE_GRAY_SCALE_PROGRAM);
+        assert!(crate::shader::BuiltInShaders::get_shader_source(SPRITE_GRAY_SCALE_PROGRAM).is_some());
+
+        sprite.set_grayscale(false);
+        assert_eq!(program_name(&sprite), SPRITE_PROGRAM);
+    }
+
+    #[test]
+    fn test_color_matrix_is_exposed_as_uniform() {
+        let mut sprite = Sprite::new();
+        let mut matrix = Mat4::IDENTITY.m;
+        matrix[0] = 0.5;
+        sprite.set_color_matrix(matrix);
+
+        assert_eq!(sprite.get_color_matrix(), Some(&matrix));
+        assert_eq!(program_name(&sprite), SPRITE_COLOR_MATRIX_PROGRAM);
+        assert_eq!(
+            sprite.get_material().get_uniform(COLOR_MATRIX_UNIFORM),
+            Some(&UniformValue::Mat4(Mat4::from_array(&matrix)))
+        );
+
+        sprite.set_grayscale(true);
+        assert!(sprite.get_material().get_uniform(COLOR_MATRIX_UNIFORM).is_none());
+        sprite.set_grayscale(false);
+        sprite.clear_color_matrix();
+        assert_eq!(program_name(&sprite), SPRITE_PROGRAM);
+        assert!(sprite.get_material().get_uniform(COLOR_MATRIX_UNIFORM).is_none());
+    }
+
+    #[test]
+    fn test_get_instance_shares_texture_cache() {
+        let first = TextureCache::get_instance();
+        let second = TextureCache::get_instance();
+        assert!(Rc::ptr_eq(&first, &second));
+
+        let texture = RefPtr::new(Texture2D::with_path(4, 4, "shared_cache_test.png"));
+        first.borrow_mut().add_texture("shared_cache_test", texture.clone());
+        assert!(RefPtr::ptr_eq(second.borrow().get_texture("shared_cache_test").unwrap(), &texture));
+        second.borrow_mut().remove_texture("shared_cache_test");
+        assert!(first.borrow().get_texture("shared_cache_test").is_none());
+    }
+}
@@ -0,0 +1,243 @@
+use crate::base::{Node, RefPtr};
+use crate::base::types::{Color3B, Color4B};
+use crate::math::Vec2;
+use crate::sprite::Texture2D;
+
+/// A point recorded along the streak path
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct StreakPoint {
+    position: Vec2,
+    age: f32,
+}
+
+/// MotionStreak leaves a fading trail behind its moving node.
+///
+/// Each update records the node's position and rebuilds a triangle strip
+/// along the recorded path. Older points narrow and fade out until they
+/// expire after `fade_time` seconds.
+#[derive(Debug)]
+pub struct MotionStreak {
+    node: Node,
+    texture: Option<RefPtr<Texture2D>>,
+    fade_time: f32,
+    min_seg: f32,
+    stroke: f32,
+    color: Color3B,
+    points: Vec<StreakPoint>,
+    vertices: Vec<Vec2>,
+    colors: Vec<Color4B>,
+}
+
+impl MotionStreak {
+    /// Creates a streak with the given fade time, minimum segment length,
+    /// stroke width and color
+    pub fn new(fade_time: f32, min_seg: f32, stroke: f32, color: Color3B) -> MotionStreak {
+        MotionStreak {
+            node: Node::new(),
+            texture: None,
+            fade_time,
+            min_seg,
+            stroke,
+            color,
+            points: Vec::new(),
+            vertices: Vec::new(),
+            colors: Vec::new(),
+        }
+    }
+
+    /// Creates a textured streak
+    pub fn with_texture(fade_time: f32, min_seg: f32, stroke: f32, color: Color3B, texture: RefPtr<Texture2D>) -> MotionStreak {
+        let mut streak = MotionStreak::new(fade_time, min_seg, stroke, color);
+        streak.texture = Some(texture);
+        streak
+    }
+
+    /// Gets the texture
+    pub fn get_texture(&self) -> Option<&RefPtr<Texture2D>> {
+        self.texture.as_ref()
+    }
+
+    /// Sets the texture
+    pub fn set_texture(&mut self, texture: RefPtr<Texture2D>) {
+        self.texture = Some(texture);
+    }
+
+    /// Gets the time in seconds a point takes to fade out
+    pub fn get_fade_time(&self) -> f32 {
+        self.fade_time
+    }
+
+    /// Sets the fade time
+    pub fn set_fade_time(&mut self, fade_time: f32) {
+        self.fade_time = fade_time;
+    }
+
+    /// Gets the minimum distance between recorded points
+    pub fn get_min_seg(&self) -> f32 {
+        self.min_seg
+    }
+
+    /// Sets the minimum distance between recorded points
+    pub fn set_min_seg(&mut self, min_seg: f32) {
+        self.min_seg = min_seg;
+    }
+
+    /// Gets the stroke width
+    pub fn get_stroke(&self) -> f32 {
+        self.stroke
+    }
+
+    /// Sets the stroke width
+    pub fn set_stroke(&mut self, stroke: f32) {
+        self.stroke = stroke;
+    }
+
+    /// Gets the color
+    pub fn get_color(&self) -> Color3B {
+        self.color
+    }
+
+    /// Sets the color
+    pub fn set_color(&mut self, color: Color3B) {
+        self.color = color;
+    }
+
+    /// Gets the number of recorded points
+    pub fn get_point_count(&self) -> usize {
+        self.points.len()
+    }
+
+    /// Gets the strip vertices, two per recorded point
+    pub fn get_vertices(&self) -> &[Vec2] {
+        &self.vertices
+    }
+
+    /// Gets the per-vertex colors, fading with point age
+    pub fn get_colors(&self) -> &[Color4B] {
+        &self.colors
+    }
+
+    /// Removes every recorded point
+    pub fn reset(&mut self) {
+        self.points.clear();
+        self.vertices.clear();
+        self.colors.clear();
+    }
+
+    /// Ages the recorded points, drops expired ones, records the node's
+    /// current position and rebuilds the strip
+    pub fn update(&mut self, delta: f32) {
+        for point in &mut self.points {
+            point.age += delta;
+        }
+        let fade_time = self.fade_time;
+        self.points.retain(|point| point.age < fade_time);
+
+        let position = *self.node.get_position();
+        let far_enough = match self.points.last() {
+            Some(last) => last.position.distance(&position) >= self.min_seg,
+            None => true,
+        };
+        if far_enough {
+            self.points.push(StreakPoint { position, age: 0.0 });
+        }
+
+        self.build_strip();
+    }
+
+    /// Rebuilds the triangle strip from the recorded points
+    fn build_strip(&mut self) {
+        self.vertices.clear();
+        self.colors.clear();
+        if self.points.len() < 2 {
+            return;
+        }
+
+        let last = self.points.len() - 1;
+        for (i, point) in self.points.iter().enumerate() {
+            let (prev, next) = (self.points[i.saturating_sub(1)], self.points[(i + 1).min(last)]);
+            let direction = (next.position - prev.position).get_normalized();
+            let life = if self.fade_time > 0.0 {
+                (1.0 - point.age / self.fade_time).clamp(0.0, 1.0)
+            } else {
+                1.0
+            };
+
+            let offset = direction.get_perp() * (self.stroke * 0.5 * life);
+            self.vertices.push(point.position + offset);
+            self.vertices.push(point.position - offset);
+
+            let color = Color4B::from_color3b(self.color, (life * 255.0) as u8);
+            self.colors.push(color);
+            self.colors.push(color);
+        }
+    }
+
+    /// Gets the node
+    pub fn get_node(&self) -> &Node {
+        &self.node
+    }
+
+    /// Gets mutable node
+    pub fn get_node_mut(&mut self) -> &mut Node {
+        &mut self.node
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn streak() -> MotionStreak {
+        MotionStreak::new(1.0, 5.0, 10.0, Color3B::WHITE)
+    }
+
+    fn move_to(streak: &mut MotionStreak, x: f32, y: f32, delta: f32) {
+        streak.get_node_mut().set_position(Vec2::new(x, y));
+        streak.update(delta);
+    }
+
+    #[test]
+    fn test_strip_grows_with_points() {
+        let mut streak = streak();
+        move_to(&mut streak, 0.0, 0.0, 0.1);
+        assert_eq!(streak.get_vertices().len(), 0);
+
+        move_to(&mut streak, 10.0, 0.0, 0.1);
+        move_to(&mut streak, 20.0, 0.0, 0.1);
+        assert_eq!(streak.get_point_count(), 3);
+        assert_eq!(streak.get_vertices().len(), 6);
+        assert_eq!(streak.get_colors().len(), 6);
+
+        // The newest point spans the full stroke, perpendicular to the path
+        let newest = &streak.get_vertices()[4..];
+        assert_eq!(newest[0], Vec2::new(20.0, 5.0));
+        assert_eq!(newest[1], Vec2::new(20.0, -5.0));
+        assert_eq!(streak.get_colors()[5].a, 255);
+        assert!(streak.get_colors()[0].a < 255);
+    }
+
+    #[test]
+    fn test_points_expire_after_fade_time() {
+        let mut streak = streak();
+        move_to(&mut streak, 0.0, 0.0, 0.0);
+        move_to(&mut streak, 10.0, 0.0, 0.6);
+        move_to(&mut streak, 20.0, 0.0, 0.6);
+
+        assert_eq!(streak.get_point_count(), 2);
+        assert!(streak.get_vertices()[1].fuzzy_equals(&Vec2::new(10.0, -2.0), 1e-4));
+    }
+
+    #[test]
+    fn test_close_points_are_dropped() {
+        let mut streak = streak();
+        move_to(&mut streak, 0.0, 0.0, 0.1);
+        move_to(&mut streak, 3.0, 0.0, 0.1);
+        move_to(&mut streak, 4.0, 3.0, 0.1);
+        assert_eq!(streak.get_point_count(), 2);
+
+        streak.reset();
+        assert_eq!(streak.get_point_count(), 0);
+        assert!(streak.get_vertices().is_empty());
+    }
+}
@@ -0,0 +1,209 @@
+use crate::base::{Node, RefPtr};
+use crate::math::Vec3;
+use crate::renderer::{Renderer, TextureAtlas, TextureQuad, TexturedVertex};
+use crate::sprite::{Sprite, Texture2D};
+
+const DEFAULT_CAPACITY: u32 = 29;
+
+/// SpriteBatchNode draws all of its child sprites with a single draw call.
+///
+/// Every child must share the batch's texture; their quads are written into
+/// one `TextureAtlas` that is submitted to the renderer as a whole.
+#[derive(Debug)]
+pub struct SpriteBatchNode {
+    node: Node,
+    texture: RefPtr<Texture2D>,
+    atlas: TextureAtlas,
+    children: Vec<RefPtr<Sprite>>,
+}
+
+impl SpriteBatchNode {
+    /// Creates a batch node for the given texture
+    pub fn with_texture(texture: RefPtr<Texture2D>) -> SpriteBatchNode {
+        SpriteBatchNode::with_texture_capacity(texture, DEFAULT_CAPACITY)
+    }
+
+    /// Creates a batch node for the given texture with room for `capacity` sprites
+    pub fn with_texture_capacity(texture: RefPtr<Texture2D>, capacity: u32) -> SpriteBatchNode {
+        let mut atlas = TextureAtlas::new();
+        atlas.resize_capacity(capacity);
+        SpriteBatchNode {
+            node: Node::new(),
+            texture,
+            atlas,
+            children: Vec::new(),
+        }
+    }
+
+    /// Creates a batch node for the texture at the given file path
+    pub fn with_file(file_path: &str) -> Option<SpriteBatchNode> {
+        let texture = crate::sprite::TextureCache::get_instance().borrow_mut().add_image(file_path)?;
+        Some(SpriteBatchNode::with_texture(texture))
+    }
+
+    /// Gets the texture shared by all children
+    pub fn get_texture(&self) -> &RefPtr<Texture2D> {
+        &self.texture
+    }
+
+    /// Gets the texture atlas
+    pub fn get_texture_atlas(&self) -> &TextureAtlas {
+        &self.atlas
+    }
+
+    /// Gets the child sprites
+    pub fn get_children(&self) -> &Vec<RefPtr<Sprite>> {
+        &self.children
+    }
+
+    /// Adds a child sprite, which must use the batch's texture
+    pub fn add_child(&mut self, sprite: RefPtr<Sprite>) -> Result<(), String> {
+        match sprite.get_texture() {
+            Some(texture) if RefPtr::ptr_eq(texture, &self.texture) => {}
+            _ => {
+                return Err(format!(
+                    "sprite texture does not match batch texture '{}'",
+                    self.texture.get_path()
+                ))
+            }
+        }
+
+        let index = self.children.len() as u32;
+        if index >= self.atlas.get_capacity() {
+            let capacity = (self.atlas.get_capacity() * 4 / 3).max(index + 1);
+            self.atlas.resize_capacity(capacity);
+        }
+        self.atlas.update_quad(index, &self.quad_for(&sprite));
+        self.children.push(sprite);
+        Ok(())
+    }
+
+    /// Removes a child sprite
+    pub fn remove_child(&mut self, sprite: &RefPtr<Sprite>) {
+        if let Some(index) = self.children.iter().position(|c| RefPtr::ptr_eq(c, sprite)) {
+            self.children.remove(index);
+            self.atlas.remove_quad(index as u32);
+        }
+    }
+
+    /// Removes all child sprites
+    pub fn remove_all_children(&mut self) {
+        self.children.clear();
+        self.atlas.remove_all_quads();
+    }
+
+    /// Refreshes every quad and submits the atlas as a single draw
+    pub fn render(&mut self, renderer: &mut Renderer) {
+        if !self.node.is_visible() {
+            return;
+        }
+        for (index, sprite) in self.children.iter().enumerate() {
+            let quad = self.quad_for(sprite);
+            if self.atlas.get_quad(index as u32) != Some(&quad) {
+                self.atlas.update_quad(index as u32, &quad);
+            }
+        }
+        renderer.draw_texture_atlas(&self.atlas);
+        self.atlas.clear_dirty();
+    }
+
+    /// Gets the node
+    pub fn get_node(&self) -> &Node {
+        &self.node
+    }
+
+    /// Gets mutable node
+    pub fn get_node_mut(&mut self) -> &mut Node {
+        &mut self.node
+    }
+
+    /// Builds the quad for a sprite from its transform and texture rect
+    fn quad_for(&self, sprite: &Sprite) -> TextureQuad {
+        let rect = sprite.get_texture_rect();
+        let transform = sprite.get_node().get_local_transform();
+        let corner = |x: f32, y: f32| transform.transform_point(&Vec3::new(x, y, 0.0));
+
+        let tex_width = self.texture.get_width() as f32;
+        let tex_height = self.texture.get_height() as f32;
+        let (left, right, top, bottom) = if tex_width > 0.0 && tex_height > 0.0 {
+            (
+                rect.get_min_x() / tex_width,
+                rect.get_max_x() / tex_width,
+                rect.get_min_y() / tex_height,
+                rect.get_max_y() / tex_height,
+            )
+        } else {
+            (0.0, 1.0, 0.0, 1.0)
+        };
+
+        let (w, h) = (rect.size.width, rect.size.height);
+        let tl = corner(0.0, h);
+        let tr = corner(w, h);
+        let bl = corner(0.0, 0.0);
+        let br = corner(w, 0.0);
+        TextureQuad::with_vertices(
+            TexturedVertex::new(tl.x, tl.y, tl.z, left, top),
+            TexturedVertex::new(tr.x, tr.y, tr.z, right, top),
+            TexturedVertex::new(bl.x, bl.y, bl.z, left, bottom),
+            TexturedVertex::new(br.x, br.y, br.z, right, bottom),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::base::types::Rect;
+    use crate::math::Vec2;
+
+    fn batch_texture() -> RefPtr<Texture2D> {
+        RefPtr::new(Texture2D::with_path(64, 64, "batch.png"))
+    }
+
+    fn sprite_at(texture: &RefPtr<Texture2D>, x: f32, y: f32) -> RefPtr<Sprite> {
+        let mut sprite = Sprite::with_texture_rect(texture.clone(), Rect::new(0.0, 0.0, 32.0, 16.0));
+        sprite.get_node_mut().set_position(Vec2::new(x, y));
+        RefPtr::new(sprite)
+    }
+
+    #[test]
+    fn test_add_matching_sprites() {
+        let texture = batch_texture();
+        let mut batch = SpriteBatchNode::with_texture_capacity(texture.clone(), 2);
+        for i in 0..3 {
+            batch.add_child(sprite_at(&texture, i as f32 * 10.0, 0.0)).unwrap();
+        }
+
+        assert_eq!(batch.get_children().len(), 3);
+        assert_eq!(batch.get_texture_atlas().get_total_quads(), 3);
+
+        let quad = batch.get_texture_atlas().get_quad(2).unwrap();
+        assert_eq!(quad.get_bl().get_position(), (20.0, 0.0, 0.0));
+        assert_eq!(quad.get_tr().get_position(), (52.0, 16.0, 0.0));
+        assert_eq!(quad.get_tr().get_tex_coord(), (0.5, 0.0));
+    }
+
+    #[test]
+    fn test_add_mismatched_sprite_fails() {
+        let mut batch = SpriteBatchNode::with_texture(batch_texture());
+        let other = RefPtr::new(Texture2D::with_path(64, 64, "other.png"));
+
+        assert!(batch.add_child(sprite_at(&other, 0.0, 0.0)).is_err());
+        assert!(batch.add_child(RefPtr::new(Sprite::new())).is_err());
+        assert_eq!(batch.get_texture_atlas().get_total_quads(), 0);
+    }
+
+    #[test]
+    fn test_render_issues_single_draw() {
+        let texture = batch_texture();
+        let mut batch = SpriteBatchNode::with_texture(texture.clone());
+        for i in 0..5 {
+            batch.add_child(sprite_at(&texture, i as f32, 0.0)).unwrap();
+        }
+
+        let mut renderer = Renderer::new();
+        batch.render(&mut renderer);
+        assert_eq!(renderer.get_draw_calls(), 1);
+        assert!(!batch.get_texture_atlas().is_dirty());
+    }
+}
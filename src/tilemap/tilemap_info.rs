@@ -71,7 +71,7 @@ impl TileSet {
     }
 }
 
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq)]
 pub struct Rect {
     x: f32,
     y: f32,
@@ -93,6 +93,7 @@ impl TileMapLayer {
 pub struct TileMap {
     map_info: Option<TileMapInfo>,
     layers: Vec<RefPtr<TileMapLayer>>,
+    collision_layer: Option<String>,
 }
 
 impl TileMap {
@@ -100,6 +101,7 @@ impl TileMap {
         TileMap {
             map_info: None,
             layers: Vec::new(),
+            collision_layer: None,
         }
     }
 
@@ -132,6 +134,51 @@ impl TileMap {
         self.layers.iter().find(|l| l.borrow().get_layer_name() == name)
     }
 
+    /// Designates the layer whose non-empty tiles are solid
+    pub fn set_collision_layer(&mut self, name: &str) {
+        self.collision_layer = Some(name.to_string());
+    }
+
+    pub fn get_collision_layer(&self) -> Option<&str> {
+        self.collision_layer.as_deref()
+    }
+
+    /// Builds world-space rectangles covering the solid tiles (gid > 0) of
+    /// the collision layer. Horizontally adjacent tiles in a row are merged
+    /// into one rectangle. Row 0 is the top of the map and world y grows
+    /// upward from the map's bottom edge.
+    pub fn get_collision_rects(&self) -> Vec<Rect> {
+        let layer = match self.collision_layer.as_deref().and_then(|name| self.get_layer_by_name(name)) {
+            Some(layer) => layer,
+            None => return Vec::new(),
+        };
+        let (width, height) = (layer.get_map_width(), layer.get_map_height());
+        let (tile_width, tile_height) = (layer.get_tile_width(), layer.get_tile_height());
+
+        let mut rects = Vec::new();
+        for row in 0..height {
+            let y = (height - 1 - row) as f32 * tile_height;
+            let mut column = 0;
+            while column < width {
+                if layer.get_tile_gid(column, row) == 0 {
+                    column += 1;
+                    continue;
+                }
+                let start = column;
+                while column < width && layer.get_tile_gid(column, row) > 0 {
+                    column += 1;
+                }
+                rects.push(Rect::new(
+                    start as f32 * tile_width,
+                    y,
+                    (column - start) as f32 * tile_width,
+                    tile_height,
+                ));
+            }
+        }
+        rects
+    }
+
     pub fn get_property(&self, key: &str) -> Option<&str> {
         self.map_info.as_ref().and_then(|m| m.get_property(key))
     }
@@ -140,3 +187,55 @@ impl TileMap {
         None
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::math::Vec2;
+
+    /// 4x3 map of 16x16 tiles with an L-shaped solid region:
+    ///
+    /// ```text
+    /// X . . .
+    /// X . . .
+    /// X X X .
+    /// ```
+    fn l_shaped_map() -> TileMap {
+        let mut info = LayerInfo::new("walls", 4, 3);
+        for (x, y) in [(0, 0), (0, 1), (0, 2), (1, 2), (2, 2)] {
+            info.set_tile(x, y, 1);
+        }
+        let mut tileset = TileSet::new();
+        tileset.set_tile_size(Vec2::new(16.0, 16.0));
+
+        let mut map = TileMap::new();
+        map.add_layer(RefPtr::new(TileMapLayer::create_with_layer_info(info, tileset)));
+        map.add_layer(RefPtr::new(TileMapLayer::create_with_layer_info(LayerInfo::new("floor", 4, 3), TileSet::new())));
+        map
+    }
+
+    #[test]
+    fn test_collision_rects_merge_rows() {
+        let mut map = l_shaped_map();
+        assert!(map.get_collision_rects().is_empty());
+
+        map.set_collision_layer("walls");
+        let rects = map.get_collision_rects();
+        assert_eq!(rects.len(), 3);
+        assert_eq!(rects[0], Rect::new(0.0, 32.0, 16.0, 16.0));
+        assert_eq!(rects[2], Rect::new(0.0, 0.0, 48.0, 16.0));
+
+        let area: f32 = rects.iter().map(|r| r.get_width() * r.get_height()).sum();
+        assert_eq!(area, 5.0 * 16.0 * 16.0);
+    }
+
+    #[test]
+    fn test_empty_collision_layer() {
+        let mut map = l_shaped_map();
+        map.set_collision_layer("floor");
+        assert!(map.get_collision_rects().is_empty());
+
+        map.set_collision_layer("missing");
+        assert!(map.get_collision_rects().is_empty());
+    }
+}
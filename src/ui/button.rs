@@ -188,6 +188,15 @@ impl Button {
         self.state = ButtonState::Normal;
     }
 
+    /// 处理鼠标移动，悬停时高亮（触摸中不改变状态）
+    pub fn on_mouse_move(&mut self, point: Vec2) -> bool {
+        let hovered = self.widget.on_mouse_move(point);
+        if self.interactable && !self.is_touching {
+            self.state = if hovered { ButtonState::Highlighted } else { ButtonState::Normal };
+        }
+        hovered
+    }
+
     /// 检查点是否在按钮范围内
     fn contains_point(&self, point: Vec2) -> bool {
         let pos = self.widget.get_position();
@@ -0,0 +1,255 @@
+use crate::input::KeyboardEvent;
+use std::cell::{Cell, RefCell};
+use std::fmt;
+use std::rc::Rc;
+
+/// 焦点管理器分配的控件 ID
+pub type WidgetId = usize;
+
+/// 可获得键盘焦点的控件
+pub trait Focusable {
+    /// 焦点改变时调用
+    fn on_focus_changed(&mut self, focused: bool);
+
+    /// 处理键盘事件，返回是否已消费
+    fn on_keyboard_event(&mut self, event: &KeyboardEvent) -> bool;
+
+    /// 是否可以获得焦点（用于 Tab 切换时跳过）
+    fn is_focusable(&self) -> bool {
+        true
+    }
+
+    /// 注册到焦点管理器时调用，控件可保存句柄以便主动请求焦点
+    fn set_focus_handle(&mut self, _handle: FocusHandle) {}
+}
+
+/// 控件请求焦点的句柄
+#[derive(Debug, Clone)]
+pub struct FocusHandle {
+    id: WidgetId,
+    request: Rc<Cell<Option<WidgetId>>>,
+}
+
+impl FocusHandle {
+    /// 获取控件 ID
+    pub fn id(&self) -> WidgetId {
+        self.id
+    }
+
+    /// 请求焦点，焦点管理器在下次处理事件时切换
+    pub fn request_focus(&self) {
+        self.request.set(Some(self.id));
+    }
+}
+
+/// 焦点管理器
+///
+/// 按注册顺序维护 Tab 顺序，同一时刻只有一个控件拥有焦点，
+/// 键盘事件只会分发给拥有焦点的控件。
+pub struct FocusManager {
+    /// 注册的控件（按 Tab 顺序）
+    widgets: Vec<(WidgetId, Rc<RefCell<dyn Focusable>>)>,
+    /// 当前拥有焦点的控件
+    focused: Option<WidgetId>,
+    /// 下一个控件 ID
+    next_id: WidgetId,
+    /// 控件发出的焦点请求
+    request: Rc<Cell<Option<WidgetId>>>,
+}
+
+impl FocusManager {
+    /// 创建新的焦点管理器
+    pub fn new() -> Self {
+        Self {
+            widgets: Vec::new(),
+            focused: None,
+            next_id: 1,
+            request: Rc::new(Cell::new(None)),
+        }
+    }
+
+    /// 注册控件，返回其 ID
+    pub fn register(&mut self, widget: Rc<RefCell<dyn Focusable>>) -> WidgetId {
+        let id = self.next_id;
+        self.next_id += 1;
+        widget.borrow_mut().set_focus_handle(FocusHandle {
+            id,
+            request: Rc::clone(&self.request),
+        });
+        self.widgets.push((id, widget));
+        id
+    }
+
+    /// 注销控件，若其拥有焦点则清除焦点
+    pub fn unregister(&mut self, id: WidgetId) {
+        if self.focused == Some(id) {
+            self.clear_focus();
+        }
+        self.widgets.retain(|(widget_id, _)| *widget_id != id);
+    }
+
+    /// 获取当前拥有焦点的控件 ID
+    pub fn get_focused(&self) -> Option<WidgetId> {
+        self.focused
+    }
+
+    /// 将焦点设置到指定控件，控件不存在或不可获得焦点时返回 false
+    pub fn set_focus(&mut self, id: WidgetId) -> bool {
+        let widget = match self.find(id) {
+            Some(widget) if widget.borrow().is_focusable() => widget,
+            _ => return false,
+        };
+        if self.focused == Some(id) {
+            return true;
+        }
+
+        self.clear_focus();
+        self.focused = Some(id);
+        widget.borrow_mut().on_focus_changed(true);
+        // 控件获得焦点时自身发出的请求无需再次处理
+        self.request.set(None);
+        true
+    }
+
+    /// 清除焦点
+    pub fn clear_focus(&mut self) {
+        if let Some(widget) = self.focused.take().and_then(|id| self.find(id)) {
+            widget.borrow_mut().on_focus_changed(false);
+        }
+        self.request.set(None);
+    }
+
+    /// 按 Tab 顺序将焦点移到下一个控件
+    pub fn focus_next(&mut self) -> Option<WidgetId> {
+        self.move_focus(1)
+    }
+
+    /// 按 Tab 顺序将焦点移到上一个控件
+    pub fn focus_previous(&mut self) -> Option<WidgetId> {
+        self.move_focus(-1)
+    }
+
+    /// 将键盘事件分发给拥有焦点的控件，返回是否已消费
+    pub fn dispatch_keyboard_event(&mut self, event: &KeyboardEvent) -> bool {
+        self.process_focus_request();
+        match self.focused.and_then(|id| self.find(id)) {
+            Some(widget) => widget.borrow_mut().on_keyboard_event(event),
+            None => false,
+        }
+    }
+
+    /// 处理控件主动发出的焦点请求
+    pub fn process_focus_request(&mut self) {
+        if let Some(id) = self.request.take() {
+            self.set_focus(id);
+        }
+    }
+
+    /// 查找控件
+    fn find(&self, id: WidgetId) -> Option<Rc<RefCell<dyn Focusable>>> {
+        self.widgets
+            .iter()
+            .find(|(widget_id, _)| *widget_id == id)
+            .map(|(_, widget)| Rc::clone(widget))
+    }
+
+    /// 沿 Tab 顺序移动焦点，跳过不可获得焦点的控件
+    fn move_focus(&mut self, step: isize) -> Option<WidgetId> {
+        let count = self.widgets.len() as isize;
+        if count == 0 {
+            return None;
+        }
+        let current = self
+            .focused
+            .and_then(|id| self.widgets.iter().position(|(widget_id, _)| *widget_id == id))
+            .map(|index| index as isize)
+            .unwrap_or(if step > 0 { -1 } else { count });
+
+        for offset in 1..=count {
+            let index = (current + step * offset).rem_euclid(count) as usize;
+            let (id, widget) = &self.widgets[index];
+            if widget.borrow().is_focusable() {
+                let id = *id;
+                self.set_focus(id);
+                return Some(id);
+            }
+        }
+        None
+    }
+}
+
+impl Default for FocusManager {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl fmt::Debug for FocusManager {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("FocusManager")
+            .field("widgets", &self.widgets.iter().map(|(id, _)| *id).collect::<Vec<_>>())
+            .field("focused", &self.focused)
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::input::{KeyCode, KeyEventType};
+    use crate::ui::TextField;
+
+    fn key(ch: char) -> KeyboardEvent {
+        KeyboardEvent::new(KeyCode::A, KeyEventType::Pressed).with_character(ch)
+    }
+
+    fn two_fields() -> (FocusManager, Rc<RefCell<TextField>>, Rc<RefCell<TextField>>, WidgetId, WidgetId) {
+        let mut manager = FocusManager::new();
+        let first = Rc::new(RefCell::new(TextField::new()));
+        let second = Rc::new(RefCell::new(TextField::new()));
+        let first_id = manager.register(first.clone());
+        let second_id = manager.register(second.clone());
+        (manager, first, second, first_id, second_id)
+    }
+
+    #[test]
+    fn test_keystrokes_reach_only_focused_field() {
+        let (mut manager, first, second, first_id, second_id) = two_fields();
+
+        assert!(manager.set_focus(first_id));
+        manager.dispatch_keyboard_event(&key('h'));
+        manager.dispatch_keyboard_event(&key('i'));
+        assert_eq!(first.borrow().text(), "hi");
+        assert_eq!(second.borrow().text(), "");
+
+        assert_eq!(manager.focus_next(), Some(second_id));
+        assert!(!first.borrow().is_editing());
+        assert!(second.borrow().is_editing());
+        manager.dispatch_keyboard_event(&key('x'));
+        assert_eq!(first.borrow().text(), "hi");
+        assert_eq!(second.borrow().text(), "x");
+
+        assert_eq!(manager.focus_next(), Some(first_id));
+        assert_eq!(manager.focus_previous(), Some(second_id));
+    }
+
+    #[test]
+    fn test_begin_editing_requests_focus() {
+        let (mut manager, first, second, first_id, second_id) = two_fields();
+        manager.set_focus(first_id);
+
+        second.borrow_mut().begin_editing();
+        manager.dispatch_keyboard_event(&key('z'));
+        assert_eq!(manager.get_focused(), Some(second_id));
+        assert!(!first.borrow().is_editing());
+        assert_eq!(second.borrow().text(), "z");
+    }
+
+    #[test]
+    fn test_focus_skips_non_editable_field() {
+        let (mut manager, _first, second, first_id, _) = two_fields();
+        second.borrow_mut().set_editable(false);
+        manager.set_focus(first_id);
+        assert_eq!(manager.focus_next(), Some(first_id));
+    }
+}
@@ -1,5 +1,6 @@
-use super::widget::{Widget, LayoutParameter, WidgetSizeType};
+use super::widget::{Widget, LayoutParameter};
 use crate::base::RefPtr;
+use crate::math::Vec2;
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum LayoutType {
@@ -58,7 +59,25 @@ impl Layout {
         &self.children
     }
 
+    pub fn get_widget(&self) -> &Widget {
+        &self.widget
+    }
+
+    pub fn get_widget_mut(&mut self) -> &mut Widget {
+        &mut self.widget
+    }
+
+    /// Arranges the children, resizing percent-sized ones to match the
+    /// layout's current size
     pub fn request_layout(&mut self) {
+        self.resolve_children(self.widget.get_size());
+    }
+
+    /// Resizes the percent-sized children against `parent_size`
+    fn resolve_children(&self, parent_size: Vec2) {
+        for child in &self.children {
+            child.apply_parent_size(parent_size);
+        }
     }
 }
 
@@ -119,6 +138,20 @@ impl LinearLayout {
         self.padding_top = padding;
         self.padding_bottom = padding;
     }
+
+    pub fn get_layout(&self) -> &Layout {
+        &self.layout
+    }
+
+    pub fn get_layout_mut(&mut self) -> &mut Layout {
+        &mut self.layout
+    }
+
+    /// Arranges the children, resizing percent-sized ones against the
+    /// layout's size
+    pub fn request_layout(&mut self) {
+        self.layout.request_layout();
+    }
 }
 
 #[derive(Debug)]
@@ -187,6 +220,20 @@ impl RelativeLayout {
     pub fn get_align(&self) -> RelativeAlign {
         self.relative_align
     }
+
+    pub fn get_layout(&self) -> &Layout {
+        &self.layout
+    }
+
+    pub fn get_layout_mut(&mut self) -> &mut Layout {
+        &mut self.layout
+    }
+
+    /// Arranges the children, resizing percent-sized ones against the
+    /// layout's size
+    pub fn request_layout(&mut self) {
+        self.layout.request_layout();
+    }
 }
 
 #[derive(Debug)]
@@ -258,4 +305,86 @@ impl GridLayout {
     pub fn get_start_axis(&self) -> AxisDirection {
         self.start_axis
     }
+
+    pub fn get_layout(&self) -> &Layout {
+        &self.layout
+    }
+
+    pub fn get_layout_mut(&mut self) -> &mut Layout {
+        &mut self.layout
+    }
+
+    /// Arranges the children, resizing percent-sized ones against the cell
+    /// they occupy
+    pub fn request_layout(&mut self) {
+        let (width, height) = self.cell_size;
+        self.layout.resolve_children(Vec2::new(width, height));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ui::widget::WidgetSizeType;
+
+    fn half_size_widget() -> RefPtr<Widget> {
+        let mut widget = Widget::new();
+        widget.set_size_type(WidgetSizeType::PERCENT);
+        widget.set_size_percent(Vec2::new(0.5, 0.5));
+        RefPtr::new(widget)
+    }
+
+    #[test]
+    fn test_request_layout_resolves_percent_children() {
+        let mut layout = Layout::new();
+        layout.get_widget_mut().set_size(Vec2::new(200.0, 100.0));
+
+        let mut percent = Widget::new();
+        percent.set_size_type(WidgetSizeType::PERCENT);
+        percent.set_size_percent(Vec2::new(0.5, 0.25));
+        layout.add_child(RefPtr::new(percent));
+
+        let mut absolute = Widget::new();
+        absolute.set_size(Vec2::new(10.0, 10.0));
+        layout.add_child(RefPtr::new(absolute));
+
+        layout.request_layout();
+        assert_eq!(layout.get_children()[0].get_size(), Vec2::new(100.0, 25.0));
+        assert_eq!(layout.get_children()[1].get_size(), Vec2::new(10.0, 10.0));
+    }
+
+    #[test]
+    fn test_request_layout_resizes_shared_children() {
+        let mut layout = Layout::new();
+        layout.get_widget_mut().set_size(Vec2::new(200.0, 100.0));
+
+        let shared = half_size_widget();
+        layout.add_child(shared.clone());
+
+        layout.request_layout();
+        assert_eq!(shared.get_size(), Vec2::new(100.0, 50.0));
+    }
+
+    #[test]
+    fn test_linear_layout_resolves_percent_children() {
+        let mut linear = LinearLayout::new();
+        linear.get_layout_mut().get_widget_mut().set_size(Vec2::new(200.0, 100.0));
+        let child = half_size_widget();
+        linear.get_layout_mut().add_child(child.clone());
+
+        linear.request_layout();
+        assert_eq!(child.get_size(), Vec2::new(100.0, 50.0));
+    }
+
+    #[test]
+    fn test_grid_layout_resolves_percent_children_against_cells() {
+        let mut grid = GridLayout::new();
+        grid.get_layout_mut().get_widget_mut().set_size(Vec2::new(400.0, 400.0));
+        grid.set_cell_size(80.0, 60.0);
+        let child = half_size_widget();
+        grid.get_layout_mut().add_child(child.clone());
+
+        grid.request_layout();
+        assert_eq!(child.get_size(), Vec2::new(40.0, 30.0));
+    }
 }
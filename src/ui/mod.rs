@@ -5,11 +5,13 @@ pub mod textfield;
 pub mod slider;
 pub mod scroll;
 pub mod rich_text;
+pub mod focus;
 
-pub use widget::Widget;
+pub use widget::{Widget, find_widget_at};
 pub use layouts::{Layout, LinearLayout, RelativeLayout, GridLayout};
 pub use button::Button;
 pub use textfield::TextField;
 pub use slider::Slider;
-pub use scroll::{ScrollView, ListView, PageView, ScrollDirection, ListViewGravity};
+pub use scroll::{ScrollView, ListView, PageView, TableView, ScrollDirection, ListViewGravity};
 pub use rich_text::{RichText, RichElement, RichElementType};
+pub use focus::{FocusManager, Focusable, FocusHandle, WidgetId};
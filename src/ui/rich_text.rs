@@ -1,7 +1,7 @@
-use crate::base::Node;
+use crate::base::{Node, RefPtr};
 use crate::base::types::Color3B;
 use crate::math::Vec2;
-use crate::label::Label;
+use crate::label::FontAtlas;
 use crate::sprite::Sprite;
 use std::collections::HashMap;
 
@@ -14,7 +14,7 @@ pub enum RichElementType {
 }
 
 /// 富文本元素
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct RichElement {
     element_type: RichElementType,
     tag: String,
@@ -103,6 +103,7 @@ pub struct RichText {
     font_name: String,
     font_size: f32,
     font_color: Color3B,
+    font_atlases: HashMap<String, RefPtr<FontAtlas>>,
     
     // 链接配置
     anchor_text_bold: bool,
@@ -130,6 +131,7 @@ impl RichText {
             font_name: String::from("Arial"),
             font_size: 12.0,
             font_color: Color3B::WHITE,
+            font_atlases: HashMap::new(),
             
             anchor_text_bold: false,
             anchor_text_italic: false,
@@ -206,6 +208,17 @@ impl RichText {
         self.font_color
     }
     
+    /// 设置字体图集，使用该字体的文本元素按图集的字形度量排版
+    pub fn set_font_atlas(&mut self, font_name: &str, font_atlas: RefPtr<FontAtlas>) {
+        self.font_atlases.insert(font_name.to_string(), font_atlas);
+        self.format_text();
+    }
+    
+    /// 获取字体图集
+    pub fn get_font_atlas(&self, font_name: &str) -> Option<&RefPtr<FontAtlas>> {
+        self.font_atlases.get(font_name)
+    }
+    
     /// 设置水平间距
     pub fn set_horizontal_space(&mut self, space: f32) {
         self.horizontal_space = space;
@@ -316,59 +329,61 @@ impl RichText {
     }
     
     /// 格式化文本布局
+    ///
+    /// 文本按单词换行，单词尽量保持完整，只有超过最大宽度的单词才会被
+    /// 逐字符截断。每段连续排在同一行的文本生成一个渲染节点。
     fn format_text(&mut self) {
-        // 清除旧的渲染节点
-        self.element_nodes.clear();
-        
-        if self.elements.is_empty() {
-            return;
-        }
-        
-        let mut current_x = 0.0;
-        let mut current_y = 0.0;
-        let mut line_height = 0.0;
-        
+        let mut nodes = Vec::new();
+        let mut layout = LineLayout::new(self.max_width, self.vertical_space);
         for element in &self.elements {
             match element.element_type {
                 RichElementType::TEXT => {
-                    // 创建文本标签
-                    let mut label = Label::create_with_ttf(
-                        &element.text,
-                        &element.font_name,
-                        element.font_size,
-                    );
-                    label.set_text_color(element.color);
+                    let line_height = self.text_line_height(element);
+                    let mut run = String::new();
+                    let mut run_x = layout.x;
                     
-                    let size = label.get_content_size();
-                    
-                    // 检查是否需要换行
-                    if self.max_width > 0.0 && current_x + size.x > self.max_width {
-                        current_x = 0.0;
-                        current_y -= line_height + self.vertical_space;
-                        line_height = 0.0;
+                    for word in split_words(&element.text) {
+                        let word_width = self.measure_text(element, word.trim_end());
+                        if !layout.fits(word_width) {
+                            self.push_text_node(&mut nodes, element, &mut run, run_x, &layout, line_height);
+                            layout.new_line();
+                            run_x = layout.x;
+                        }
+                        
+                        if layout.max_width > 0.0 && word_width > layout.max_width {
+                            // 单词超过整行宽度，逐字符截断
+                            for ch in word.chars() {
+                                let char_width = self.measure_text(element, ch.encode_utf8(&mut [0; 4]));
+                                if !ch.is_whitespace() && !layout.fits(char_width) {
+                                    self.push_text_node(&mut nodes, element, &mut run, run_x, &layout, line_height);
+                                    layout.new_line();
+                                    run_x = layout.x;
+                                }
+                                run.push(ch);
+                                layout.advance(char_width, line_height);
+                            }
+                        } else {
+                            run.push_str(word);
+                            layout.advance(self.measure_text(element, word), line_height);
+                        }
                     }
                     
-                    // 设置位置
-                    label.get_node_mut().set_position(Vec2::new(current_x, current_y));
-                    
-                    current_x += size.x + self.horizontal_space;
-                    line_height = line_height.max(size.y);
+                    self.push_text_node(&mut nodes, element, &mut run, run_x, &layout, line_height);
+                    layout.x += self.horizontal_space;
                 }
                 
                 RichElementType::IMAGE => {
-                    // 创建图片精灵
-                    // let sprite = Sprite::create(&element.image_file);
-                    // let size = Vec2::new(element.width, element.height);
-                    
-                    // 检查是否需要换行
-                    if self.max_width > 0.0 && current_x + element.width > self.max_width {
-                        current_x = 0.0;
-                        current_y -= line_height + self.vertical_space;
-                        line_height = 0.0;
+                    if !layout.fits(element.width) {
+                        layout.new_line();
                     }
                     
-                    current_x += element.width + self.horizontal_space;
-                    line_height = line_height.max(element.height);
+                    let mut node = Node::new();
+                    node.set_position(Vec2::new(layout.x, layout.y));
+                    node.set_content_size(Vec2::new(element.width, element.height));
+                    nodes.push(RichElementNode { node, element: element.clone() });
+                    
+                    layout.advance(element.width, element.height);
+                    layout.x += self.horizontal_space;
                 }
                 
                 RichElementType::CUSTOM_NODE => {
@@ -376,6 +391,38 @@ impl RichText {
                 }
             }
         }
+        self.element_nodes = nodes;
+    }
+    
+    /// 为一段已排好的文本创建渲染节点，并清空这段文本
+    fn push_text_node(&self, nodes: &mut Vec<RichElementNode>, element: &RichElement, run: &mut String, x: f32, layout: &LineLayout, line_height: f32) {
+        if run.is_empty() {
+            return;
+        }
+        let mut text_element = element.clone();
+        text_element.text = std::mem::take(run);
+        
+        let width = self.measure_text(element, &text_element.text);
+        let mut node = Node::new();
+        node.set_position(Vec2::new(x, layout.y));
+        node.set_content_size(Vec2::new(width, line_height));
+        nodes.push(RichElementNode { node, element: text_element });
+    }
+    
+    /// 用字体图集测量文本宽度；没有图集时按半个字号估算每个字符
+    fn measure_text(&self, element: &RichElement, text: &str) -> f32 {
+        match self.font_atlases.get(&element.font_name) {
+            Some(atlas) => atlas.measure_string(text).x,
+            None => text.chars().count() as f32 * element.font_size * 0.5,
+        }
+    }
+    
+    /// 获取文本元素的行高
+    fn text_line_height(&self, element: &RichElement) -> f32 {
+        match self.font_atlases.get(&element.font_name) {
+            Some(atlas) if atlas.get_common_line_height() > 0.0 => atlas.get_common_line_height(),
+            _ => element.font_size,
+        }
     }
     
     /// 处理 URL 点击
@@ -396,6 +443,63 @@ impl RichText {
     }
 }
 
+/// 排版时的当前行状态
+struct LineLayout {
+    max_width: f32,
+    vertical_space: f32,
+    x: f32,
+    y: f32,
+    line_height: f32,
+}
+
+impl LineLayout {
+    fn new(max_width: f32, vertical_space: f32) -> Self {
+        LineLayout {
+            max_width,
+            vertical_space,
+            x: 0.0,
+            y: 0.0,
+            line_height: 0.0,
+        }
+    }
+    
+    /// 检查宽度为 width 的内容能否放在当前行；空行总能放下
+    fn fits(&self, width: f32) -> bool {
+        self.max_width <= 0.0 || self.x <= 0.0 || self.x + width <= self.max_width
+    }
+    
+    fn advance(&mut self, width: f32, height: f32) {
+        self.x += width;
+        self.line_height = self.line_height.max(height);
+    }
+    
+    fn new_line(&mut self) {
+        self.x = 0.0;
+        self.y -= self.line_height + self.vertical_space;
+        self.line_height = 0.0;
+    }
+}
+
+/// 把文本切分成单词，每个单词带上其后的空白
+fn split_words(text: &str) -> Vec<&str> {
+    let mut words = Vec::new();
+    let mut start = 0;
+    let mut in_space = false;
+    for (index, ch) in text.char_indices() {
+        if ch.is_whitespace() {
+            in_space = true;
+        } else if in_space {
+            words.push(&text[start..index]);
+            start = index;
+            in_space = false;
+        }
+    }
+    if start < text.len() {
+        words.push(&text[start..]);
+    }
+    words
+}
+
 impl Default for RichText {
     fn default() -> Self {
         Self::new()
@@ -467,4 +571,81 @@ mod tests {
         rich_text.set_max_width(400.0);
         assert_eq!(rich_text.get_max_width(), 400.0);
     }
+    
+    /// 每个字符宽 10、行高 12 的字体图集
+    fn fixed_atlas() -> RefPtr<FontAtlas> {
+        let mut atlas = FontAtlas::new("fixed", 10.0);
+        atlas.set_common_line_height(12.0);
+        for ch in "abcdefghijklmnopqrstuvwxyz ".chars() {
+            let mut def = crate::label::FontLetterDefinition::new();
+            def.letter_char = ch;
+            def.x_advance = 10.0;
+            def.valid = true;
+            atlas.add_letter_definition(ch, def);
+        }
+        RefPtr::new(atlas)
+    }
+    
+    fn text(text: &str) -> RichElement {
+        RichElement::create_text("text", Color3B::WHITE, 255, text, "fixed", 10.0)
+    }
+    
+    fn node_layout(rich_text: &RichText) -> Vec<(String, Vec2)> {
+        rich_text.element_nodes.iter()
+            .map(|node| (node.element.text.clone(), *node.node.get_position()))
+            .collect()
+    }
+    
+    #[test]
+    fn test_split_words_keeps_trailing_space() {
+        assert_eq!(split_words("ab  cd e"), vec!["ab  ", "cd ", "e"]);
+        assert_eq!(split_words(" ab"), vec![" ", "ab"]);
+        assert!(split_words("").is_empty());
+    }
+    
+    #[test]
+    fn test_wraps_small_elements_on_word_boundary() {
+        let mut rich_text = RichText::new();
+        rich_text.set_font_atlas("fixed", fixed_atlas());
+        rich_text.set_vertical_space(3.0);
+        rich_text.set_max_width(100.0);
+        rich_text.push_back_element(text("one two "));
+        rich_text.push_back_element(text("three "));
+        rich_text.push_back_element(text("four"));
+        
+        assert_eq!(node_layout(&rich_text), vec![
+            ("one two ".to_string(), Vec2::new(0.0, 0.0)),
+            ("three ".to_string(), Vec2::new(0.0, -15.0)),
+            ("four".to_string(), Vec2::new(60.0, -15.0)),
+        ]);
+    }
+    
+    #[test]
+    fn test_word_wraps_inside_element() {
+        let mut rich_text = RichText::new();
+        rich_text.set_font_atlas("fixed", fixed_atlas());
+        rich_text.set_max_width(100.0);
+        rich_text.push_back_element(text("abcd efghij kl"));
+        
+        assert_eq!(node_layout(&rich_text), vec![
+            ("abcd ".to_string(), Vec2::new(0.0, 0.0)),
+            ("efghij kl".to_string(), Vec2::new(0.0, -12.0)),
+        ]);
+        assert_eq!(rich_text.element_nodes[1].node.get_content_size(), Vec2::new(90.0, 12.0));
+    }
+    
+    #[test]
+    fn test_oversized_word_is_hard_broken() {
+        let mut rich_text = RichText::new();
+        rich_text.set_font_atlas("fixed", fixed_atlas());
+        rich_text.set_max_width(50.0);
+        rich_text.push_back_element(text("ab abcdefghijkl"));
+        
+        assert_eq!(node_layout(&rich_text), vec![
+            ("ab ".to_string(), Vec2::new(0.0, 0.0)),
+            ("abcde".to_string(), Vec2::new(0.0, -12.0)),
+            ("fghij".to_string(), Vec2::new(0.0, -24.0)),
+            ("kl".to_string(), Vec2::new(0.0, -36.0)),
+        ]);
+    }
 }
@@ -1,4 +1,6 @@
-use crate::base::Node;
+use std::fmt;
+use std::ops::Range;
+use crate::base::{Color3B, Node};
 use crate::math::Vec2;
 use crate::ui::Widget;
 use super::scroll_view::{ScrollView, ScrollDirection};
@@ -24,6 +26,15 @@ pub enum ListViewEventType {
 /// 列表项回调类型
 pub type ListItemCallback = Box<dyn FnMut(&ListView, usize, ListViewEventType)>;
 
+/// 列表项工厂类型，按逻辑索引创建列表项
+///
+/// 第二个参数为回收池中已重置的列表项（若有），工厂应为新索引重新绑定并返回它，
+/// 为 `None` 时才需要新建列表项。列表项按值持有，不是 `NodeRef`，因此无法运行动作。
+pub type ListItemFactory = Box<dyn FnMut(usize, Option<Node>) -> Node>;
+
+/// 回收模式下视口外额外保留的列表项数量
+const DEFAULT_RECYCLE_BUFFER: usize = 2;
+
 /// ListView 列表视图组件
 /// 
 /// 基于 ScrollView，提供列表项管理功能：
@@ -31,7 +42,7 @@ pub type ListItemCallback = Box<dyn FnMut(&ListView, usize, ListViewEventType)>;
 /// - 列表项自动排列
 /// - 列表项选择回调
 /// - 动态添加/删除列表项
-#[derive(Debug)]
+/// - 大数据量的列表项回收（只实例化视口附近的列表项）
 pub struct ListView {
     scroll_view: ScrollView,
     items: Vec<Node>,
@@ -39,6 +50,31 @@ pub struct ListView {
     item_spacing: f32,
     selected_index: Option<usize>,
     event_callback: Option<ListItemCallback>,
+    
+    // 列表项回收
+    data_source: Option<ListItemFactory>,
+    data_count: usize,
+    item_extent: f32,
+    recycle_buffer: usize,
+    visible_items: Vec<(usize, Node)>,
+    recycled_items: Vec<Node>,
+}
+
+impl fmt::Debug for ListView {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ListView")
+            .field("scroll_view", &self.scroll_view)
+            .field("items", &self.items)
+            .field("item_gravity", &self.item_gravity)
+            .field("item_spacing", &self.item_spacing)
+            .field("selected_index", &self.selected_index)
+            .field("data_count", &self.data_count)
+            .field("item_extent", &self.item_extent)
+            .field("recycle_buffer", &self.recycle_buffer)
+            .field("visible_items", &self.visible_items)
+            .field("recycled_items", &self.recycled_items)
+            .finish()
+    }
 }
 
 impl ListView {
@@ -51,6 +87,12 @@ impl ListView {
             item_spacing: 0.0,
             selected_index: None,
             event_callback: None,
+            data_source: None,
+            data_count: 0,
+            item_extent: 0.0,
+            recycle_buffer: DEFAULT_RECYCLE_BUFFER,
+            visible_items: Vec::new(),
+            recycled_items: Vec::new(),
         }
     }
     
@@ -194,6 +236,121 @@ impl ListView {
         self.scroll_to_item(index, 0.0, false);
     }
     
+    /// 设置数据源，启用列表项回收模式
+    ///
+    /// 列表包含 `count` 个逻辑项，但只有视口内及前后 `recycle_buffer` 个项会通过
+    /// `factory` 实例化。所有列表项沿滚动方向的尺寸以第一项为准。
+    pub fn set_data_source(&mut self, count: usize, mut factory: ListItemFactory) {
+        self.items.clear();
+        self.visible_items.clear();
+        self.recycled_items.clear();
+        self.selected_index = None;
+        self.data_count = count;
+        self.item_extent = 0.0;
+        
+        if count > 0 {
+            let first = factory(0, None);
+            let size = first.get_content_size();
+            self.item_extent = match self.scroll_view.get_direction() {
+                ScrollDirection::HORIZONTAL => size.x,
+                _ => size.y,
+            };
+            self.visible_items.push((0, first));
+        }
+        self.data_source = Some(factory);
+        
+        let total = self.get_item_stride() * count as f32 - if count > 0 { self.item_spacing } else { 0.0 };
+        let view_size = self.scroll_view.get_content_size();
+        match self.scroll_view.get_direction() {
+            ScrollDirection::HORIZONTAL => {
+                self.scroll_view.set_inner_container_size(Vec2::new(total, view_size.y));
+            }
+            _ => {
+                self.scroll_view.set_inner_container_size(Vec2::new(view_size.x, total));
+            }
+        }
+        self.update_visible_range();
+    }
+    
+    /// 检查是否处于列表项回收模式
+    pub fn is_recycling_enabled(&self) -> bool {
+        self.data_source.is_some()
+    }
+    
+    /// 获取数据源的逻辑项数量
+    pub fn get_data_count(&self) -> usize {
+        self.data_count
+    }
+    
+    /// 设置视口外额外保留的列表项数量
+    pub fn set_recycle_buffer(&mut self, buffer: usize) {
+        self.recycle_buffer = buffer;
+        self.update_visible_range();
+    }
+    
+    /// 获取视口外额外保留的列表项数量
+    pub fn get_recycle_buffer(&self) -> usize {
+        self.recycle_buffer
+    }
+    
+    /// 获取当前应实例化的逻辑项范围
+    pub fn get_visible_range(&self) -> Range<usize> {
+        let stride = self.get_item_stride();
+        if self.data_count == 0 || stride <= 0.0 {
+            return 0..0;
+        }
+        
+        let inner_position = self.scroll_view.get_inner_container_position();
+        let view_size = self.scroll_view.get_content_size();
+        let (offset, viewport) = match self.scroll_view.get_direction() {
+            ScrollDirection::HORIZONTAL => (-inner_position.x, view_size.x),
+            _ => (-inner_position.y, view_size.y),
+        };
+        
+        let first = (offset.max(0.0) / stride).floor() as usize;
+        let last = ((offset.max(0.0) + viewport) / stride).ceil() as usize;
+        let start = first.saturating_sub(self.recycle_buffer).min(self.data_count);
+        let end = (last + self.recycle_buffer).min(self.data_count);
+        start..end
+    }
+    
+    /// 根据滚动位置更新已实例化的列表项
+    ///
+    /// 离开范围的列表项进入回收池，仍在范围内的列表项保留不变，新进入范围的
+    /// 索引优先复用回收池中的列表项。
+    pub fn update_visible_range(&mut self) {
+        if self.data_source.is_none() {
+            return;
+        }
+        
+        let range = self.get_visible_range();
+        if let Some(factory) = self.data_source.as_mut() {
+            recycle_items(&mut self.visible_items, &mut self.recycled_items, range, factory);
+        }
+        
+        let stride = self.get_item_stride();
+        let extent = self.item_extent;
+        let direction = self.scroll_view.get_direction();
+        for (index, item) in self.visible_items.iter_mut() {
+            let along = *index as f32 * stride + extent / 2.0;
+            let position = match direction {
+                ScrollDirection::HORIZONTAL => Vec2::new(along, item.get_position().y),
+                _ => Vec2::new(item.get_position().x, -along),
+            };
+            item.set_position(position);
+        }
+    }
+    
+    /// 获取已实例化的列表项及其逻辑索引
+    pub fn get_visible_items(&self) -> &[(usize, Node)] {
+        &self.visible_items
+    }
+    
+    /// 获取列表项沿滚动方向的步长
+    fn get_item_stride(&self) -> f32 {
+        self.item_extent + self.item_spacing
+    }
+    
     /// 刷新列表视图布局
     fn refresh_view(&mut self) {
         let direction = self.scroll_view.get_direction();
@@ -288,6 +445,7 @@ impl ListView {
     /// 更新列表视图
     pub fn update(&mut self, dt: f32) {
         self.scroll_view.update(dt);
+        self.update_visible_range();
     }
     
     /// 获取底层 ScrollView
@@ -301,6 +459,45 @@ impl ListView {
     }
 }
 
+/// 回收已实例化的列表项
+///
+/// 离开 `range` 的列表项重置后放入 `pool`，仍在范围内的列表项保留不变。新进入
+/// 范围的索引调用 `factory`，并传入回收池中的一个列表项供其重新绑定。结果按逻辑
+/// 索引排序。
+pub(super) fn recycle_items(
+    items: &mut Vec<(usize, Node)>,
+    pool: &mut Vec<Node>,
+    range: Range<usize>,
+    factory: &mut dyn FnMut(usize, Option<Node>) -> Node,
+) {
+    let (kept, released): (Vec<_>, Vec<_>) = items.drain(..).partition(|(index, _)| range.contains(index));
+    *items = kept;
+    for (_, mut item) in released {
+        reset_recycled_item(&mut item);
+        pool.push(item);
+    }
+    
+    for index in range {
+        if !items.iter().any(|(i, _)| *i == index) {
+            let item = factory(index, pool.pop());
+            items.push((index, item));
+        }
+    }
+    items.sort_by_key(|(index, _)| *index);
+}
+
+/// 重置回收的列表项：恢复变换、可见性和颜色
+///
+/// 按值持有的列表项无法作为动作目标，因此没有需要停止的动作。
+pub(super) fn reset_recycled_item(item: &mut Node) {
+    item.set_position(Vec2::ZERO);
+    item.set_rotation(0.0);
+    item.set_scale(1.0);
+    item.set_visible(true);
+    item.set_color(Color3B::WHITE);
+    item.set_opacity(255);
+}
+
 impl Default for ListView {
     fn default() -> Self {
         Self::new()
@@ -348,4 +545,99 @@ mod tests {
         list_view.set_gravity(ListViewGravity::CENTER_HORIZONTAL);
         assert_eq!(list_view.get_gravity(), ListViewGravity::CENTER_HORIZONTAL);
     }
+    
+    fn recycled_list(count: usize, created: &std::rc::Rc<std::cell::Cell<usize>>) -> ListView {
+        let mut list_view = ListView::new();
+        list_view.get_scroll_view_mut().set_content_size(Vec2::new(100.0, 200.0));
+        let created = created.clone();
+        list_view.set_data_source(count, Box::new(move |_, recycled| {
+            recycled.unwrap_or_else(|| {
+                created.set(created.get() + 1);
+                let mut item = Node::new();
+                item.set_content_size(Vec2::new(100.0, 50.0));
+                item
+            })
+        }));
+        list_view
+    }
+    
+    #[test]
+    fn test_recycling_materializes_bounded_items() {
+        let created = std::rc::Rc::new(std::cell::Cell::new(0));
+        let mut list_view = recycled_list(1000, &created);
+        
+        assert_eq!(list_view.get_data_count(), 1000);
+        assert_eq!(list_view.get_scroll_view().get_inner_container_size(), Vec2::new(100.0, 50000.0));
+        assert_eq!(list_view.get_visible_range(), 0..6);
+        assert_eq!(list_view.get_visible_items().len(), 6);
+        
+        for step in 1..200 {
+            let y = -(step as f32) * 123.0;
+            list_view.get_scroll_view_mut().set_inner_container_position(Vec2::new(0.0, y));
+            list_view.update_visible_range();
+            assert!(list_view.get_visible_items().len() <= 9);
+        }
+    }
+    
+    #[test]
+    fn test_recycling_positions_and_reuses_items() {
+        let created = std::rc::Rc::new(std::cell::Cell::new(0));
+        let mut list_view = recycled_list(1000, &created);
+        
+        list_view.get_scroll_view_mut().set_inner_container_position(Vec2::new(0.0, -25000.0));
+        list_view.update_visible_range();
+        assert_eq!(list_view.get_visible_range(), 498..506);
+        let (index, item) = &list_view.get_visible_items()[2];
+        assert_eq!(*index, 500);
+        assert_eq!(item.get_position().y, -25025.0);
+        
+        let before = created.get();
+        list_view.get_scroll_view_mut().set_inner_container_position(Vec2::new(0.0, -25050.0));
+        list_view.update_visible_range();
+        assert_eq!(list_view.get_visible_range(), 499..507);
+        assert_eq!(created.get(), before);
+    }
+    
+    #[test]
+    fn test_recycling_hands_back_reset_items() {
+        let mut list_view = ListView::new();
+        list_view.get_scroll_view_mut().set_content_size(Vec2::new(100.0, 200.0));
+        let reused = std::rc::Rc::new(std::cell::RefCell::new(Vec::new()));
+        let log = reused.clone();
+        let mut next_tag = 0;
+        list_view.set_data_source(1000, Box::new(move |_, recycled| match recycled {
+            Some(item) => {
+                log.borrow_mut().push((item.get_tag(), *item.get_position()));
+                item
+            }
+            None => {
+                let mut item = Node::new();
+                item.set_content_size(Vec2::new(100.0, 50.0));
+                item.set_tag(next_tag);
+                next_tag += 1;
+                item
+            }
+        }));
+        let released_tag = list_view.get_visible_items()[0].1.get_tag();
+        
+        // 第 0 项离开范围，第 6..9 项进入，其中一项复用第 0 项
+        list_view.get_scroll_view_mut().set_inner_container_position(Vec2::new(0.0, -150.0));
+        list_view.update_visible_range();
+        assert_eq!(list_view.get_visible_range(), 1..9);
+        assert_eq!(*reused.borrow(), vec![(released_tag, Vec2::ZERO)]);
+        let (index, item) = &list_view.get_visible_items()[5];
+        assert_eq!(*index, 6);
+        assert_eq!(item.get_tag(), released_tag);
+        assert_eq!(item.get_position().y, -325.0);
+    }
+    
+    #[test]
+    fn test_recycling_clamps_at_end() {
+        let created = std::rc::Rc::new(std::cell::Cell::new(0));
+        let mut list_view = recycled_list(1000, &created);
+        
+        list_view.get_scroll_view_mut().jump_to_bottom();
+        list_view.update_visible_range();
+        assert_eq!(list_view.get_visible_range(), 994..1000);
+    }
 }
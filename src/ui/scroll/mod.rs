@@ -1,7 +1,9 @@
 pub mod scroll_view;
 pub mod list_view;
 pub mod page_view;
+pub mod table_view;
 
 pub use scroll_view::{ScrollView, ScrollDirection, ScrollViewEventType};
 pub use list_view::{ListView, ListViewGravity, ListViewEventType};
 pub use page_view::{PageView, PageViewEventType};
+pub use table_view::{TableView, TableCellSizeSource, TableCellSource};
@@ -1,4 +1,5 @@
-use crate::base::Node;
+use crate::base::{Node, Rect};
+use crate::input::{MouseEvent, MouseEventType, Touch, TouchId};
 use crate::math::Vec2;
 use crate::ui::Widget;
 use std::time::Duration;
@@ -27,6 +28,9 @@ pub enum ScrollViewEventType {
     BOUNCE_RIGHT,
 }
 
+/// 滚动条从停止滚动到完全隐藏的时间（秒）
+const SCROLL_BAR_FADE_DURATION: f32 = 1.0;
+
 /// 滚动视图回调类型
 pub type ScrollEventCallback = Box<dyn FnMut(&ScrollView, ScrollViewEventType)>;
 
@@ -46,6 +50,7 @@ pub struct ScrollView {
     content_size: Vec2,
     inner_size: Vec2,
     inner_position: Vec2,
+    content_padding: Vec2,
     
     // 滚动配置
     bounce_enabled: bool,
@@ -53,23 +58,32 @@ pub struct ScrollView {
     scroll_bar_enabled: bool,
     scroll_bar_auto_hide: bool,
     scroll_bar_opacity: f32,
+    scroll_bar_width: f32,
+    scroll_bar_current_opacity: f32,
+    scroll_bar_idle_time: f32,
     
     // 滚动状态
     is_scrolling: bool,
     is_auto_scrolling: bool,
     auto_scroll_duration: Duration,
     auto_scroll_elapsed: Duration,
+    auto_scroll_start_position: Vec2,
+    auto_scroll_target_position: Vec2,
     
     // 触摸和拖拽
     touch_began_position: Vec2,
     touch_moved_position: Vec2,
     touch_ended_position: Vec2,
     touch_move_distance: Vec2,
+    captured_touch: Option<TouchId>,
     
     // 惯性滚动
     inertia_scroll_velocity: Vec2,
     inertia_scroll_friction: f32,
     
+    // 鼠标滚轮
+    mouse_scroll_speed: f32,
+    
     // 边界反弹
     bounce_duration: Duration,
     bounce_back_distance: f32,
@@ -88,26 +102,35 @@ impl ScrollView {
             content_size: Vec2::ZERO,
             inner_size: Vec2::ZERO,
             inner_position: Vec2::ZERO,
+            content_padding: Vec2::ZERO,
             
             bounce_enabled: true,
             inertia_scroll_enabled: true,
             scroll_bar_enabled: true,
             scroll_bar_auto_hide: true,
             scroll_bar_opacity: 0.4,
+            scroll_bar_width: 4.0,
+            scroll_bar_current_opacity: 0.0,
+            scroll_bar_idle_time: SCROLL_BAR_FADE_DURATION,
             
             is_scrolling: false,
             is_auto_scrolling: false,
             auto_scroll_duration: Duration::from_millis(300),
             auto_scroll_elapsed: Duration::ZERO,
+            auto_scroll_start_position: Vec2::ZERO,
+            auto_scroll_target_position: Vec2::ZERO,
             
             touch_began_position: Vec2::ZERO,
             touch_moved_position: Vec2::ZERO,
             touch_ended_position: Vec2::ZERO,
             touch_move_distance: Vec2::ZERO,
+            captured_touch: None,
             
             inertia_scroll_velocity: Vec2::ZERO,
             inertia_scroll_friction: 0.95,
             
+            mouse_scroll_speed: 20.0,
+            
             bounce_duration: Duration::from_millis(200),
             bounce_back_distance: 100.0,
             
@@ -132,6 +155,19 @@ impl ScrollView {
         self.direction
     }
     
+    /// 设置视图大小
+    pub fn set_content_size(&mut self, size: Vec2) {
+        self.content_size = size;
+        self.widget.set_size(size);
+        self.limit_inner_position();
+        self.update_inner_container();
+    }
+    
+    /// 获取视图大小
+    pub fn get_content_size(&self) -> Vec2 {
+        self.content_size
+    }
+    
     /// 设置内容大小
     pub fn set_inner_container_size(&mut self, size: Vec2) {
         self.inner_size = size;
@@ -143,6 +179,40 @@ impl ScrollView {
         self.inner_size
     }
     
+    /// 设置内容边距，自动计算内容大小时加在子节点边界的右侧和上方
+    pub fn set_content_padding(&mut self, padding: Vec2) {
+        self.content_padding = padding;
+    }
+    
+    /// 获取内容边距
+    pub fn get_content_padding(&self) -> Vec2 {
+        self.content_padding
+    }
+    
+    /// 根据内容容器中子节点的边界更新内容大小
+    ///
+    /// 内容从容器原点开始排列，内容大小为子节点最远边缘加上内容边距。
+    /// 不可见的子节点不参与计算，结果不会小于视图大小。
+    pub fn update_inner_size_from_content(&mut self) {
+        let mut extent = Vec2::ZERO;
+        for child in self.inner_container.get_children() {
+            let child = child.borrow();
+            if !child.is_visible() {
+                continue;
+            }
+            let size = child.get_content_size();
+            let anchor = child.get_anchor_point();
+            let position = *child.get_position();
+            extent.x = extent.x.max(position.x + size.x * (1.0 - anchor.x));
+            extent.y = extent.y.max(position.y + size.y * (1.0 - anchor.y));
+        }
+        
+        let size = extent + self.content_padding;
+        self.inner_size = Vec2::new(size.x.max(self.content_size.x), size.y.max(self.content_size.y));
+        self.limit_inner_position();
+        self.update_inner_container();
+    }
+    
     /// 设置内容位置
     pub fn set_inner_container_position(&mut self, position: Vec2) {
         self.inner_position = position;
@@ -190,11 +260,86 @@ impl ScrollView {
         self.scroll_bar_auto_hide = enabled;
     }
     
+    /// 检查滚动条是否自动隐藏
+    pub fn is_scroll_bar_auto_hide_enabled(&self) -> bool {
+        self.scroll_bar_auto_hide
+    }
+    
     /// 设置滚动条透明度
     pub fn set_scroll_bar_opacity(&mut self, opacity: f32) {
         self.scroll_bar_opacity = opacity.clamp(0.0, 1.0);
     }
     
+    /// 获取滚动条透明度
+    pub fn get_scroll_bar_opacity(&self) -> f32 {
+        self.scroll_bar_opacity
+    }
+    
+    /// 设置滚动条宽度
+    pub fn set_scroll_bar_width(&mut self, width: f32) {
+        self.scroll_bar_width = width.max(0.0);
+    }
+    
+    /// 获取滚动条宽度
+    pub fn get_scroll_bar_width(&self) -> f32 {
+        self.scroll_bar_width
+    }
+    
+    /// 获取滚动条当前透明度
+    ///
+    /// 开启自动隐藏时，滚动后透明度升至 `scroll_bar_opacity`，停止滚动后在约 1 秒内降为 0。
+    pub fn get_scroll_bar_current_opacity(&self) -> f32 {
+        if !self.scroll_bar_enabled {
+            0.0
+        } else if self.scroll_bar_auto_hide {
+            self.scroll_bar_current_opacity
+        } else {
+            self.scroll_bar_opacity
+        }
+    }
+    
+    /// 获取垂直滚动条滑块区域（视图坐标，原点在左下角）
+    ///
+    /// 滑块长度与可视高度和内容高度之比成正比，内容不超出视图时返回 `None`。
+    pub fn get_vertical_scroll_bar_rect(&self) -> Option<Rect> {
+        if !self.scroll_bar_enabled
+            || !matches!(self.direction, ScrollDirection::VERTICAL | ScrollDirection::BOTH)
+        {
+            return None;
+        }
+        let (length, progress) = self.scroll_bar_thumb(self.content_size.y, self.inner_size.y, -self.inner_position.y)?;
+        let travel = self.content_size.y - length;
+        Some(Rect::new(
+            self.content_size.x - self.scroll_bar_width,
+            travel - progress * travel,
+            self.scroll_bar_width,
+            length,
+        ))
+    }
+    
+    /// 获取水平滚动条滑块区域（视图坐标，原点在左下角）
+    pub fn get_horizontal_scroll_bar_rect(&self) -> Option<Rect> {
+        if !self.scroll_bar_enabled
+            || !matches!(self.direction, ScrollDirection::HORIZONTAL | ScrollDirection::BOTH)
+        {
+            return None;
+        }
+        let (length, progress) = self.scroll_bar_thumb(self.content_size.x, self.inner_size.x, -self.inner_position.x)?;
+        let travel = self.content_size.x - length;
+        Some(Rect::new(progress * travel, 0.0, length, self.scroll_bar_width))
+    }
+    
+    /// 计算滑块长度和滚动进度（0 为起始端，1 为末端）
+    fn scroll_bar_thumb(&self, view: f32, content: f32, offset: f32) -> Option<(f32, f32)> {
+        if view <= 0.0 || content <= view {
+            return None;
+        }
+        let min_length = (self.scroll_bar_width * 2.0).min(view);
+        let length = (view * view / content).max(min_length);
+        let progress = (offset / (content - view)).clamp(0.0, 1.0);
+        Some((length, progress))
+    }
+    
     /// 滚动到顶部
     pub fn scroll_to_top(&mut self, time_in_sec: f32, attenuated: bool) {
         self.start_auto_scroll(Vec2::new(self.inner_position.x, 0.0), time_in_sec);
@@ -247,6 +392,37 @@ impl ScrollView {
         self.start_auto_scroll(dest, time_in_sec);
     }
     
+    /// 以最小的滚动距离使内容容器中的子节点完全进入视图
+    ///
+    /// 子节点已完全可见时不滚动；子节点比视图大时对齐其起始边缘。
+    pub fn scroll_to_node(&mut self, child: &Node, time: f32) {
+        let size = child.get_content_size();
+        let anchor = child.get_anchor_point();
+        let origin = *child.get_position() - Vec2::new(size.x * anchor.x, size.y * anchor.y);
+        
+        // 单个方向上使 [start, start + length] 落入视图所需的内容位置
+        let reveal = |position: f32, start: f32, length: f32, view: f32| -> f32 {
+            if start + position < 0.0 || length > view {
+                -start
+            } else if start + length + position > view {
+                view - start - length
+            } else {
+                position
+            }
+        };
+        
+        let mut dest = self.inner_position;
+        if matches!(self.direction, ScrollDirection::HORIZONTAL | ScrollDirection::BOTH) {
+            dest.x = reveal(dest.x, origin.x, size.x, self.content_size.x);
+        }
+        if matches!(self.direction, ScrollDirection::VERTICAL | ScrollDirection::BOTH) {
+            dest.y = reveal(dest.y, origin.y, size.y, self.content_size.y);
+        }
+        if dest != self.inner_position {
+            self.start_auto_scroll(dest, time);
+        }
+    }
+    
     /// 跳转到顶部（无动画）
     pub fn jump_to_top(&mut self) {
         self.inner_position.y = 0.0;
@@ -271,6 +447,151 @@ impl ScrollView {
         self.update_inner_container();
     }
     
+    /// 设置惯性滚动摩擦系数（每次更新后速度的保留比例），限制在 0.8..0.999 之间
+    pub fn set_inertia_friction(&mut self, friction: f32) {
+        self.inertia_scroll_friction = friction.clamp(0.8, 0.999);
+    }
+    
+    /// 获取惯性滚动摩擦系数
+    pub fn get_inertia_friction(&self) -> f32 {
+        self.inertia_scroll_friction
+    }
+    
+    /// 以指定速度开始惯性滚动，不允许滚动的方向上的分量会被忽略
+    pub fn start_inertia_scroll(&mut self, velocity: Vec2) {
+        self.inertia_scroll_velocity = match self.direction {
+            ScrollDirection::VERTICAL => Vec2::new(0.0, velocity.y),
+            ScrollDirection::HORIZONTAL => Vec2::new(velocity.x, 0.0),
+            ScrollDirection::BOTH => velocity,
+            ScrollDirection::NONE => Vec2::ZERO,
+        };
+    }
+    
+    /// 立即停止惯性滚动和自动滚动
+    pub fn stop_scrolling(&mut self) {
+        self.inertia_scroll_velocity = Vec2::ZERO;
+        self.is_auto_scrolling = false;
+        self.auto_scroll_elapsed = Duration::ZERO;
+    }
+    
+    /// 检查是否正在拖拽或惯性滚动
+    pub fn is_scrolling(&self) -> bool {
+        self.is_scrolling || self.inertia_scroll_velocity != Vec2::ZERO
+    }
+    
+    /// 设置鼠标滚轮速度（每格滚动的像素数）
+    pub fn set_mouse_scroll_speed(&mut self, speed: f32) {
+        self.mouse_scroll_speed = speed;
+    }
+    
+    /// 获取鼠标滚轮速度
+    pub fn get_mouse_scroll_speed(&self) -> f32 {
+        self.mouse_scroll_speed
+    }
+    
+    /// 处理鼠标滚轮滚动
+    ///
+    /// `delta` 为滚轮增量，向下滚动为负值。内容沿允许的方向移动并限制在边界内。
+    pub fn on_mouse_scroll(&mut self, delta: Vec2) {
+        let offset = delta * self.mouse_scroll_speed;
+        let previous = self.inner_position;
+        
+        match self.direction {
+            ScrollDirection::VERTICAL => self.inner_position.y += offset.y,
+            ScrollDirection::HORIZONTAL => self.inner_position.x += offset.x,
+            ScrollDirection::BOTH => self.inner_position += offset,
+            ScrollDirection::NONE => return,
+        }
+        self.limit_inner_position();
+        self.update_inner_container();
+        
+        if self.inner_position != previous {
+            self.trigger_event(ScrollViewEventType::SCROLLING);
+        }
+    }
+    
+    /// 处理鼠标事件，返回是否已消费
+    ///
+    /// 仅垂直滚动时按住 Shift，水平滚轮增量会映射为垂直滚动。
+    pub fn on_mouse_event(&mut self, event: &MouseEvent) -> bool {
+        if event.event_type != MouseEventType::Scroll {
+            return false;
+        }
+        
+        let mut delta = event.scroll_delta;
+        if event.shift && self.direction == ScrollDirection::VERTICAL {
+            delta = Vec2::new(0.0, delta.y + delta.x);
+        }
+        self.on_mouse_scroll(delta);
+        true
+    }
+    
+    /// 获取正在拖拽的触摸 ID
+    pub fn get_captured_touch(&self) -> Option<TouchId> {
+        self.captured_touch
+    }
+    
+    /// 处理触摸开始，返回是否开始拖拽
+    ///
+    /// 拖拽只跟随开始拖拽的那个触摸，其他触摸在它结束前都会被忽略。
+    pub fn on_touch_began(&mut self, touch: &Touch) -> bool {
+        if self.captured_touch.is_some()
+            || self.direction == ScrollDirection::NONE
+            || !self.widget.hit_test(touch.location()) {
+            return false;
+        }
+        
+        self.stop_scrolling();
+        self.captured_touch = Some(touch.id());
+        self.is_scrolling = true;
+        self.touch_began_position = touch.location();
+        self.touch_moved_position = touch.location();
+        self.touch_move_distance = Vec2::ZERO;
+        true
+    }
+    
+    /// 处理触摸移动，内容沿允许的方向跟随拖拽
+    pub fn on_touch_moved(&mut self, touch: &Touch) {
+        if self.captured_touch != Some(touch.id()) {
+            return;
+        }
+        
+        let delta = touch.location() - self.touch_moved_position;
+        self.touch_moved_position = touch.location();
+        self.touch_move_distance = delta;
+        
+        let previous = self.inner_position;
+        match self.direction {
+            ScrollDirection::VERTICAL => self.inner_position.y += delta.y,
+            ScrollDirection::HORIZONTAL => self.inner_position.x += delta.x,
+            ScrollDirection::BOTH => self.inner_position += delta,
+            ScrollDirection::NONE => {}
+        }
+        self.limit_inner_position();
+        self.update_inner_container();
+        
+        if self.inner_position != previous {
+            self.trigger_event(ScrollViewEventType::SCROLLING);
+        }
+    }
+    
+    /// 处理触摸结束，只有开始拖拽的触摸才能结束拖拽
+    pub fn on_touch_ended(&mut self, touch: &Touch) {
+        if self.captured_touch != Some(touch.id()) {
+            return;
+        }
+        
+        self.captured_touch = None;
+        self.is_scrolling = false;
+        self.touch_ended_position = touch.location();
+        self.trigger_event(ScrollViewEventType::SCROLL_ENDED);
+    }
+    
+    /// 处理触摸取消
+    pub fn on_touch_cancelled(&mut self, touch: &Touch) {
+        self.on_touch_ended(touch);
+    }
+    
     /// 设置事件回调
     pub fn set_event_callback(&mut self, callback: ScrollEventCallback) {
         self.event_callback = Some(callback);
@@ -305,14 +626,22 @@ impl ScrollView {
     /// 开始自动滚动
     fn start_auto_scroll(&mut self, dest: Vec2, time_in_sec: f32) {
         self.is_auto_scrolling = true;
-        self.auto_scroll_duration = Duration::from_secs_f32(time_in_sec);
+        self.auto_scroll_duration = Duration::from_secs_f32(time_in_sec.max(0.0));
         self.auto_scroll_elapsed = Duration::ZERO;
+        self.auto_scroll_start_position = self.inner_position;
+        self.auto_scroll_target_position = dest;
     }
     
     /// 触发滚动事件
     fn trigger_event(&mut self, event_type: ScrollViewEventType) {
-        if let Some(ref mut callback) = self.event_callback {
+        if event_type == ScrollViewEventType::SCROLLING {
+            // 滚动时显示滚动条并重新开始淡出计时
+            self.scroll_bar_current_opacity = self.scroll_bar_opacity;
+            self.scroll_bar_idle_time = 0.0;
+        }
+        if let Some(mut callback) = self.event_callback.take() {
             callback(self, event_type);
+            self.event_callback = Some(callback);
         }
     }
     
@@ -321,7 +650,17 @@ impl ScrollView {
         // 更新自动滚动
         if self.is_auto_scrolling {
             self.auto_scroll_elapsed += Duration::from_secs_f32(dt);
-            if self.auto_scroll_elapsed >= self.auto_scroll_duration {
+            let finished = self.auto_scroll_elapsed >= self.auto_scroll_duration;
+            let progress = if finished {
+                1.0
+            } else {
+                self.auto_scroll_elapsed.as_secs_f32() / self.auto_scroll_duration.as_secs_f32()
+            };
+            let start = self.auto_scroll_start_position;
+            self.inner_position = start + (self.auto_scroll_target_position - start) * progress;
+            self.limit_inner_position();
+            self.update_inner_container();
+            if finished {
                 self.is_auto_scrolling = false;
                 self.trigger_event(ScrollViewEventType::SCROLL_ENDED);
             }
@@ -340,6 +679,13 @@ impl ScrollView {
                 self.inertia_scroll_velocity = Vec2::ZERO;
             }
         }
+        
+        // 停止滚动后淡出滚动条
+        if self.scroll_bar_auto_hide && self.scroll_bar_current_opacity > 0.0 {
+            self.scroll_bar_idle_time += dt;
+            let remaining = 1.0 - self.scroll_bar_idle_time / SCROLL_BAR_FADE_DURATION;
+            self.scroll_bar_current_opacity = (self.scroll_bar_opacity * remaining).max(0.0);
+        }
     }
     
     /// 获取 Widget
@@ -372,6 +718,7 @@ impl Default for ScrollView {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::base::NodeRef;
     
     #[test]
     fn test_scroll_view_creation() {
@@ -412,4 +759,225 @@ mod tests {
         scroll_view.set_scroll_bar_opacity(0.5);
         assert_eq!(scroll_view.scroll_bar_opacity, 0.5);
     }
+    
+    fn vertical_scroll_view() -> ScrollView {
+        let mut scroll_view = ScrollView::create(ScrollDirection::VERTICAL);
+        scroll_view.set_content_size(Vec2::new(100.0, 100.0));
+        scroll_view.set_inner_container_size(Vec2::new(100.0, 300.0));
+        scroll_view
+    }
+    
+    #[test]
+    fn test_mouse_scroll_moves_content_until_bottom() {
+        let mut scroll_view = vertical_scroll_view();
+        
+        scroll_view.on_mouse_scroll(Vec2::new(0.0, -3.0));
+        assert_eq!(scroll_view.get_inner_container_position(), Vec2::new(0.0, -60.0));
+        assert_eq!(*scroll_view.get_inner_container().get_position(), Vec2::new(0.0, -60.0));
+        
+        for _ in 0..20 {
+            scroll_view.on_mouse_scroll(Vec2::new(0.0, -3.0));
+        }
+        assert_eq!(scroll_view.get_inner_container_position(), Vec2::new(0.0, -200.0));
+    }
+    
+    #[test]
+    fn test_mouse_scroll_ignores_disallowed_axis() {
+        let mut scroll_view = vertical_scroll_view();
+        scroll_view.on_mouse_scroll(Vec2::new(-5.0, 0.0));
+        assert_eq!(scroll_view.get_inner_container_position(), Vec2::ZERO);
+    }
+    
+    #[test]
+    fn test_shift_maps_horizontal_wheel_to_vertical() {
+        let mut scroll_view = vertical_scroll_view();
+        let mut event = MouseEvent::scroll(Vec2::ZERO, Vec2::new(-1.0, 0.0));
+        
+        assert!(scroll_view.on_mouse_event(&event));
+        assert_eq!(scroll_view.get_inner_container_position(), Vec2::ZERO);
+        
+        event.shift = true;
+        assert!(scroll_view.on_mouse_event(&event));
+        assert_eq!(scroll_view.get_inner_container_position(), Vec2::new(0.0, -20.0));
+        
+        let click = MouseEvent::move_event(Vec2::ZERO, Vec2::ZERO);
+        assert!(!scroll_view.on_mouse_event(&click));
+    }
+    
+    fn horizontal_scroll_view() -> ScrollView {
+        let mut scroll_view = ScrollView::create(ScrollDirection::HORIZONTAL);
+        scroll_view.set_content_size(Vec2::new(100.0, 100.0));
+        scroll_view.set_inner_container_size(Vec2::new(10000.0, 100.0));
+        scroll_view
+    }
+    
+    #[test]
+    fn test_inertia_friction_is_clamped() {
+        let mut scroll_view = ScrollView::new();
+        scroll_view.set_inertia_friction(0.5);
+        assert_eq!(scroll_view.get_inertia_friction(), 0.8);
+        scroll_view.set_inertia_friction(1.5);
+        assert_eq!(scroll_view.get_inertia_friction(), 0.999);
+    }
+    
+    #[test]
+    fn test_lower_friction_decays_faster() {
+        let mut slow = horizontal_scroll_view();
+        let mut fast = horizontal_scroll_view();
+        slow.set_inertia_friction(0.8);
+        fast.set_inertia_friction(0.99);
+        slow.start_inertia_scroll(Vec2::new(-1000.0, 0.0));
+        fast.start_inertia_scroll(Vec2::new(-1000.0, 0.0));
+        
+        for _ in 0..10 {
+            slow.update(0.016);
+            fast.update(0.016);
+        }
+        let slow_distance = -slow.get_inner_container_position().x;
+        let fast_distance = -fast.get_inner_container_position().x;
+        assert!(slow_distance > 0.0);
+        assert!(slow_distance < fast_distance);
+        assert!(slow.inertia_scroll_velocity.length() < fast.inertia_scroll_velocity.length());
+    }
+    
+    #[test]
+    fn test_stop_scrolling_halts_inertia() {
+        let mut scroll_view = horizontal_scroll_view();
+        scroll_view.start_inertia_scroll(Vec2::new(-1000.0, 0.0));
+        scroll_view.update(0.016);
+        assert!(scroll_view.is_scrolling());
+        
+        scroll_view.stop_scrolling();
+        assert!(!scroll_view.is_scrolling());
+        let position = scroll_view.get_inner_container_position();
+        scroll_view.update(0.016);
+        assert_eq!(scroll_view.get_inner_container_position(), position);
+    }
+
+    #[test]
+    fn test_scroll_bar_thumb_shrinks_as_content_grows() {
+        let mut scroll_view = vertical_scroll_view();
+        let short = scroll_view.get_vertical_scroll_bar_rect().unwrap();
+        assert!((short.size.height - 100.0 / 3.0).abs() < 1e-4);
+        assert_eq!(short.origin.y, 100.0 - short.size.height);
+        assert!(scroll_view.get_horizontal_scroll_bar_rect().is_none());
+        
+        scroll_view.set_inner_container_size(Vec2::new(100.0, 1000.0));
+        let long = scroll_view.get_vertical_scroll_bar_rect().unwrap();
+        assert!(long.size.height < short.size.height);
+        
+        scroll_view.jump_to_bottom();
+        let bottom = scroll_view.get_vertical_scroll_bar_rect().unwrap();
+        assert!(bottom.origin.y.abs() < 1e-4);
+        
+        scroll_view.set_inner_container_size(Vec2::new(100.0, 80.0));
+        assert!(scroll_view.get_vertical_scroll_bar_rect().is_none());
+    }
+    
+    #[test]
+    fn test_scroll_bar_fades_after_scrolling() {
+        let mut scroll_view = vertical_scroll_view();
+        assert_eq!(scroll_view.get_scroll_bar_current_opacity(), 0.0);
+        
+        scroll_view.on_mouse_scroll(Vec2::new(0.0, -1.0));
+        assert_eq!(scroll_view.get_scroll_bar_current_opacity(), 0.4);
+        
+        scroll_view.update(0.5);
+        let half = scroll_view.get_scroll_bar_current_opacity();
+        assert!(half > 0.0 && half < 0.4);
+        
+        for _ in 0..10 {
+            scroll_view.update(0.1);
+        }
+        assert_eq!(scroll_view.get_scroll_bar_current_opacity(), 0.0);
+    }
+    
+    fn child_node(position: Vec2, size: Vec2, anchor: Vec2) -> NodeRef {
+        let mut node = Node::new();
+        node.set_position(position);
+        node.set_content_size(size);
+        node.set_anchor_point(anchor);
+        node.into_ref()
+    }
+    
+    #[test]
+    fn test_inner_size_from_content() {
+        let mut scroll_view = ScrollView::create(ScrollDirection::BOTH);
+        scroll_view.set_content_size(Vec2::new(50.0, 50.0));
+        scroll_view.set_content_padding(Vec2::new(5.0, 10.0));
+        
+        let container = scroll_view.get_inner_container_mut();
+        container.add_child(child_node(Vec2::new(10.0, 20.0), Vec2::new(50.0, 40.0), Vec2::ZERO));
+        container.add_child(child_node(Vec2::new(80.0, 150.0), Vec2::new(30.0, 60.0), Vec2::new(0.5, 0.5)));
+        
+        scroll_view.update_inner_size_from_content();
+        assert_eq!(scroll_view.get_inner_container_size(), Vec2::new(100.0, 190.0));
+    }
+    
+    #[test]
+    fn test_inner_size_from_content_not_smaller_than_view() {
+        let mut scroll_view = ScrollView::create(ScrollDirection::VERTICAL);
+        scroll_view.set_content_size(Vec2::new(100.0, 100.0));
+        scroll_view.get_inner_container_mut()
+            .add_child(child_node(Vec2::new(0.0, 0.0), Vec2::new(20.0, 20.0), Vec2::ZERO));
+        
+        scroll_view.update_inner_size_from_content();
+        assert_eq!(scroll_view.get_inner_container_size(), Vec2::new(100.0, 100.0));
+    }
+    
+    #[test]
+    fn test_drag_follows_only_captured_touch() {
+        let mut scroll_view = vertical_scroll_view();
+        scroll_view.get_widget_mut().set_anchor_point(Vec2::ZERO);
+        scroll_view.set_inner_container_position(Vec2::new(0.0, -200.0));
+        
+        assert!(scroll_view.on_touch_began(&Touch::new(1, Vec2::new(50.0, 50.0))));
+        assert!(!scroll_view.on_touch_began(&Touch::new(2, Vec2::new(60.0, 60.0))));
+        
+        scroll_view.on_touch_moved(&Touch::new(2, Vec2::new(60.0, 90.0)));
+        assert_eq!(scroll_view.get_inner_container_position(), Vec2::new(0.0, -200.0));
+        scroll_view.on_touch_ended(&Touch::new(2, Vec2::new(60.0, 90.0)));
+        assert_eq!(scroll_view.get_captured_touch(), Some(1));
+        
+        scroll_view.on_touch_moved(&Touch::new(1, Vec2::new(55.0, 80.0)));
+        assert_eq!(scroll_view.get_inner_container_position(), Vec2::new(0.0, -170.0));
+        scroll_view.on_touch_ended(&Touch::new(1, Vec2::new(55.0, 80.0)));
+        assert_eq!(scroll_view.get_captured_touch(), None);
+        assert!(!scroll_view.is_scrolling());
+        
+        scroll_view.on_touch_moved(&Touch::new(1, Vec2::new(55.0, 10.0)));
+        assert_eq!(scroll_view.get_inner_container_position(), Vec2::new(0.0, -170.0));
+    }
+    
+    #[test]
+    fn test_scroll_to_node_moves_just_enough() {
+        let mut scroll_view = vertical_scroll_view();
+        scroll_view.get_inner_container_mut().add_child(child_node(Vec2::new(0.0, 150.0), Vec2::new(100.0, 40.0), Vec2::ZERO));
+        let child = scroll_view.get_inner_container().get_children()[0].clone();
+        
+        scroll_view.scroll_to_node(&child.borrow(), 0.5);
+        scroll_view.update(0.25);
+        assert_eq!(scroll_view.get_inner_container_position(), Vec2::new(0.0, -45.0));
+        scroll_view.update(0.25);
+        // 子节点的远端边缘刚好落在视图边缘
+        assert_eq!(scroll_view.get_inner_container_position(), Vec2::new(0.0, -90.0));
+        assert_eq!(*scroll_view.get_inner_container().get_position(), Vec2::new(0.0, -90.0));
+        
+        // 回到起始边缘之前的子节点
+        let first = child_node(Vec2::new(0.0, 20.0), Vec2::new(100.0, 30.0), Vec2::ZERO);
+        scroll_view.scroll_to_node(&first.borrow(), 0.0);
+        scroll_view.update(0.0);
+        assert_eq!(scroll_view.get_inner_container_position(), Vec2::new(0.0, -20.0));
+    }
+    
+    #[test]
+    fn test_scroll_to_visible_node_does_not_move() {
+        let mut scroll_view = vertical_scroll_view();
+        scroll_view.set_inner_container_position(Vec2::new(0.0, -50.0));
+        let child = child_node(Vec2::new(0.0, 80.0), Vec2::new(100.0, 40.0), Vec2::ZERO);
+        
+        scroll_view.scroll_to_node(&child.borrow(), 0.3);
+        scroll_view.update(0.3);
+        assert_eq!(scroll_view.get_inner_container_position(), Vec2::new(0.0, -50.0));
+    }
 }
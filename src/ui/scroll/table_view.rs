@@ -0,0 +1,311 @@
+use std::fmt;
+use std::ops::Range;
+use crate::base::Node;
+use crate::math::Vec2;
+use super::list_view::{recycle_items, reset_recycled_item};
+use super::scroll_view::{ScrollView, ScrollDirection};
+
+/// 单元格尺寸回调类型，按逻辑索引返回单元格尺寸
+pub type TableCellSizeSource = Box<dyn FnMut(usize) -> Vec2>;
+
+/// 单元格工厂类型，按逻辑索引创建单元格
+///
+/// 第二个参数为回收池中已重置的单元格（若有），工厂应为新索引重新绑定并返回它。
+pub type TableCellSource = Box<dyn FnMut(usize, Option<Node>) -> Node>;
+
+/// 视口外额外保留的单元格数量
+const DEFAULT_CELL_BUFFER: usize = 2;
+
+/// TableView 表格视图组件
+///
+/// 基于 ScrollView 的数据驱动列表：
+/// - 通过 `set_number_of_cells`、`cell_size_for_index`、`cell_for_index` 提供数据
+/// - 支持不同尺寸的单元格
+/// - 滚动时回收单元格，只实例化视口内及前后 `cell_buffer` 个单元格
+pub struct TableView {
+    scroll_view: ScrollView,
+    cell_count: usize,
+    size_source: Option<TableCellSizeSource>,
+    cell_source: Option<TableCellSource>,
+    /// 每个单元格沿滚动方向的起始偏移，末尾为内容总长度
+    cell_offsets: Vec<f32>,
+    cell_buffer: usize,
+    visible_cells: Vec<(usize, Node)>,
+    recycled_cells: Vec<Node>,
+}
+
+impl fmt::Debug for TableView {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("TableView")
+            .field("scroll_view", &self.scroll_view)
+            .field("cell_count", &self.cell_count)
+            .field("cell_offsets", &self.cell_offsets)
+            .field("cell_buffer", &self.cell_buffer)
+            .field("visible_cells", &self.visible_cells)
+            .field("recycled_cells", &self.recycled_cells)
+            .finish()
+    }
+}
+
+impl TableView {
+    /// 创建新的表格视图
+    pub fn new() -> Self {
+        TableView {
+            scroll_view: ScrollView::create(ScrollDirection::VERTICAL),
+            cell_count: 0,
+            size_source: None,
+            cell_source: None,
+            cell_offsets: vec![0.0],
+            cell_buffer: DEFAULT_CELL_BUFFER,
+            visible_cells: Vec::new(),
+            recycled_cells: Vec::new(),
+        }
+    }
+
+    /// 创建带方向的表格视图
+    pub fn create(direction: ScrollDirection) -> Self {
+        let mut table_view = TableView::new();
+        table_view.scroll_view.set_direction(direction);
+        table_view
+    }
+
+    /// 设置单元格数量并重新加载数据
+    pub fn set_number_of_cells(&mut self, count: usize) {
+        self.cell_count = count;
+        self.reload_data();
+    }
+
+    /// 获取单元格数量
+    pub fn get_number_of_cells(&self) -> usize {
+        self.cell_count
+    }
+
+    /// 设置单元格尺寸回调并重新加载数据
+    pub fn cell_size_for_index(&mut self, source: TableCellSizeSource) {
+        self.size_source = Some(source);
+        self.reload_data();
+    }
+
+    /// 设置单元格工厂并重新加载数据
+    pub fn cell_for_index(&mut self, source: TableCellSource) {
+        self.cell_source = Some(source);
+        self.recycled_cells.clear();
+        self.reload_data();
+    }
+
+    /// 重新加载数据
+    ///
+    /// 重新查询所有单元格尺寸，更新内容大小，并将已实例化的单元格放回回收池。
+    /// 未设置尺寸回调时单元格尺寸为零。
+    pub fn reload_data(&mut self) {
+        for (_, mut cell) in self.visible_cells.drain(..) {
+            reset_recycled_item(&mut cell);
+            self.recycled_cells.push(cell);
+        }
+
+        let direction = self.scroll_view.get_direction();
+        self.cell_offsets.clear();
+        self.cell_offsets.push(0.0);
+        let mut total = 0.0;
+        for index in 0..self.cell_count {
+            let size = self.size_source.as_mut().map_or(Vec2::ZERO, |source| source(index));
+            total += match direction {
+                ScrollDirection::HORIZONTAL => size.x,
+                _ => size.y,
+            };
+            self.cell_offsets.push(total);
+        }
+
+        let view_size = self.scroll_view.get_content_size();
+        match direction {
+            ScrollDirection::HORIZONTAL => {
+                self.scroll_view.set_inner_container_size(Vec2::new(total, view_size.y));
+            }
+            _ => {
+                self.scroll_view.set_inner_container_size(Vec2::new(view_size.x, total));
+            }
+        }
+        self.update_visible_range();
+    }
+
+    /// 获取所有单元格沿滚动方向的总长度
+    pub fn get_total_extent(&self) -> f32 {
+        self.cell_offsets[self.cell_count]
+    }
+
+    /// 获取单元格沿滚动方向的起始偏移
+    pub fn get_cell_offset(&self, index: usize) -> Option<f32> {
+        if index < self.cell_count {
+            Some(self.cell_offsets[index])
+        } else {
+            None
+        }
+    }
+
+    /// 设置视口外额外保留的单元格数量
+    pub fn set_cell_buffer(&mut self, buffer: usize) {
+        self.cell_buffer = buffer;
+        self.update_visible_range();
+    }
+
+    /// 获取视口外额外保留的单元格数量
+    pub fn get_cell_buffer(&self) -> usize {
+        self.cell_buffer
+    }
+
+    /// 获取当前应实例化的单元格范围
+    pub fn get_visible_range(&self) -> Range<usize> {
+        if self.cell_count == 0 {
+            return 0..0;
+        }
+
+        let inner_position = self.scroll_view.get_inner_container_position();
+        let view_size = self.scroll_view.get_content_size();
+        let (offset, viewport) = match self.scroll_view.get_direction() {
+            ScrollDirection::HORIZONTAL => (-inner_position.x, view_size.x),
+            _ => (-inner_position.y, view_size.y),
+        };
+        let offset = offset.max(0.0);
+
+        // 第一个结束位置超过视口起点的单元格，到最后一个起始位置在视口终点之前的单元格
+        let first = self.cell_offsets[1..].partition_point(|end| *end <= offset);
+        let last = self.cell_offsets[..self.cell_count].partition_point(|start| *start < offset + viewport);
+        let start = first.saturating_sub(self.cell_buffer).min(self.cell_count);
+        let end = (last.max(first + 1) + self.cell_buffer).min(self.cell_count);
+        start..end
+    }
+
+    /// 根据滚动位置更新已实例化的单元格
+    pub fn update_visible_range(&mut self) {
+        let range = self.get_visible_range();
+        match self.cell_source.as_mut() {
+            Some(source) => recycle_items(&mut self.visible_cells, &mut self.recycled_cells, range, source),
+            None => return,
+        }
+
+        let direction = self.scroll_view.get_direction();
+        for (index, cell) in self.visible_cells.iter_mut() {
+            let start = self.cell_offsets[*index];
+            let along = (start + self.cell_offsets[*index + 1]) / 2.0;
+            let position = match direction {
+                ScrollDirection::HORIZONTAL => Vec2::new(along, cell.get_position().y),
+                _ => Vec2::new(cell.get_position().x, -along),
+            };
+            cell.set_position(position);
+        }
+    }
+
+    /// 获取已实例化的单元格及其逻辑索引
+    pub fn get_visible_cells(&self) -> &[(usize, Node)] {
+        &self.visible_cells
+    }
+
+    /// 更新表格视图
+    pub fn update(&mut self, dt: f32) {
+        self.scroll_view.update(dt);
+        self.update_visible_range();
+    }
+
+    /// 获取底层 ScrollView
+    pub fn get_scroll_view(&self) -> &ScrollView {
+        &self.scroll_view
+    }
+
+    /// 获取可变底层 ScrollView
+    pub fn get_scroll_view_mut(&mut self) -> &mut ScrollView {
+        &mut self.scroll_view
+    }
+}
+
+impl Default for TableView {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::Cell;
+    use std::rc::Rc;
+
+    /// 高度在 20、40、60 之间循环的单元格
+    fn cell_height(index: usize) -> f32 {
+        20.0 + (index % 3) as f32 * 20.0
+    }
+
+    fn variable_table(count: usize, created: &Rc<Cell<usize>>) -> TableView {
+        let mut table_view = TableView::new();
+        table_view.get_scroll_view_mut().set_content_size(Vec2::new(100.0, 200.0));
+        table_view.set_number_of_cells(count);
+        table_view.cell_size_for_index(Box::new(|index| Vec2::new(100.0, cell_height(index))));
+        let created = created.clone();
+        table_view.cell_for_index(Box::new(move |index, recycled| {
+            let mut cell = recycled.unwrap_or_else(|| {
+                created.set(created.get() + 1);
+                Node::new()
+            });
+            cell.set_content_size(Vec2::new(100.0, cell_height(index)));
+            cell
+        }));
+        table_view
+    }
+
+    #[test]
+    fn test_total_height_is_sum_of_cell_heights() {
+        let created = Rc::new(Cell::new(0));
+        let table_view = variable_table(500, &created);
+
+        let expected: f32 = (0..500).map(cell_height).sum();
+        assert_eq!(table_view.get_total_extent(), expected);
+        assert_eq!(table_view.get_scroll_view().get_inner_container_size(), Vec2::new(100.0, expected));
+        assert_eq!(table_view.get_cell_offset(3), Some(120.0));
+        assert_eq!(table_view.get_cell_offset(500), None);
+    }
+
+    #[test]
+    fn test_only_visible_cells_are_instantiated() {
+        let created = Rc::new(Cell::new(0));
+        let mut table_view = variable_table(500, &created);
+
+        // 视口高 200：单元格 0..6 覆盖 0..240
+        assert_eq!(table_view.get_visible_range(), 0..8);
+        assert_eq!(table_view.get_visible_cells().len(), 8);
+
+        for step in 1..150 {
+            let y = -(step as f32) * 97.0;
+            table_view.get_scroll_view_mut().set_inner_container_position(Vec2::new(0.0, y));
+            table_view.update_visible_range();
+            let range = table_view.get_visible_range();
+            // 最小单元格高 20，视口最多跨 11 个单元格，另加前后缓冲
+            assert!(table_view.get_visible_cells().len() <= 11 + 2 * DEFAULT_CELL_BUFFER);
+            for (index, cell) in table_view.get_visible_cells() {
+                assert!(range.contains(index));
+                let top = table_view.get_cell_offset(*index).unwrap();
+                assert_eq!(cell.get_position().y, -(top + cell_height(*index) / 2.0));
+            }
+        }
+        assert!(created.get() < 500);
+    }
+
+    #[test]
+    fn test_scrolling_reuses_cells_in_range() {
+        let created = Rc::new(Cell::new(0));
+        let mut table_view = variable_table(500, &created);
+
+        // 每 3 个单元格总高 120
+        table_view.get_scroll_view_mut().set_inner_container_position(Vec2::new(0.0, -1200.0));
+        table_view.update_visible_range();
+        assert_eq!(table_view.get_visible_range(), 28..38);
+
+        let before = created.get();
+        table_view.get_scroll_view_mut().set_inner_container_position(Vec2::new(0.0, -1220.0));
+        table_view.update_visible_range();
+        assert_eq!(table_view.get_visible_range(), 29..38);
+        assert_eq!(created.get(), before);
+
+        table_view.get_scroll_view_mut().jump_to_bottom();
+        table_view.update_visible_range();
+        assert_eq!(table_view.get_visible_range().end, 500);
+    }
+}
@@ -1,6 +1,6 @@
 use crate::base::{Ref, RefPtr};
 use crate::ui::Widget;
-use crate::input::Touch;
+use crate::input::{Touch, TouchId};
 use crate::math::Vec2;
 use std::rc::Rc;
 use std::cell::RefCell;
@@ -33,8 +33,8 @@ pub struct Slider {
     interactable: bool,
     /// 是否整数模式
     whole_numbers: bool,
-    /// 是否正在拖动
-    is_dragging: bool,
+    /// 正在拖动滑块的触摸 ID
+    dragging_touch: Option<TouchId>,
     /// 滑块大小（0.0-1.0，相对于轨道）
     handle_size: f32,
     /// 轨道颜色
@@ -58,7 +58,7 @@ impl Slider {
             direction: SliderDirection::Horizontal,
             interactable: true,
             whole_numbers: false,
-            is_dragging: false,
+            dragging_touch: None,
             handle_size: 0.2,
             track_color: [0.8, 0.8, 0.8, 1.0],
             fill_color: [0.2, 0.6, 1.0, 1.0],
@@ -127,7 +127,7 @@ impl Slider {
     pub fn set_interactable(&mut self, interactable: bool) {
         self.interactable = interactable;
         if !interactable {
-            self.is_dragging = false;
+            self.dragging_touch = None;
         }
     }
 
@@ -225,9 +225,21 @@ impl Slider {
         point.y <= pos.y + half_size.y
     }
 
+    /// 是否正在拖动
+    pub fn is_dragging(&self) -> bool {
+        self.dragging_touch.is_some()
+    }
+
+    /// 获取正在拖动滑块的触摸 ID
+    pub fn dragging_touch(&self) -> Option<TouchId> {
+        self.dragging_touch
+    }
+
     /// 处理触摸开始
+    ///
+    /// 拖动只跟随开始拖动的那个触摸，其他触摸在它结束前都会被忽略。
     pub fn on_touch_began(&mut self, touch: &Touch) -> bool {
-        if !self.interactable {
+        if !self.interactable || self.dragging_touch.is_some() {
             return false;
         }
 
@@ -235,7 +247,7 @@ impl Slider {
             return false;
         }
 
-        self.is_dragging = true;
+        self.dragging_touch = Some(touch.id());
         let new_value = self.value_from_position(touch.location());
         self.set_value(new_value);
         true
@@ -243,7 +255,7 @@ impl Slider {
 
     /// 处理触摸移动
     pub fn on_touch_moved(&mut self, touch: &Touch) {
-        if !self.is_dragging {
+        if self.dragging_touch != Some(touch.id()) {
             return;
         }
 
@@ -252,13 +264,15 @@ impl Slider {
     }
 
     /// 处理触摸结束
-    pub fn on_touch_ended(&mut self, _touch: &Touch) {
-        self.is_dragging = false;
+    pub fn on_touch_ended(&mut self, touch: &Touch) {
+        if self.dragging_touch == Some(touch.id()) {
+            self.dragging_touch = None;
+        }
     }
 
     /// 处理触摸取消
-    pub fn on_touch_cancelled(&mut self, _touch: &Touch) {
-        self.is_dragging = false;
+    pub fn on_touch_cancelled(&mut self, touch: &Touch) {
+        self.on_touch_ended(touch);
     }
 
     /// 设置值改变回调
@@ -417,4 +431,29 @@ mod tests {
         slider.set_handle_size(-0.1);
         assert_eq!(slider.handle_size(), 0.01);
     }
+
+    #[test]
+    fn test_slider_follows_only_dragging_touch() {
+        let mut slider = Slider::new();
+        slider.set_range(0.0, 100.0);
+        slider.widget_mut().set_size(Vec2::new(200.0, 20.0));
+
+        assert!(slider.on_touch_began(&Touch::new(1, Vec2::new(-50.0, 0.0))));
+        assert_eq!(slider.dragging_touch(), Some(1));
+        assert_eq!(slider.value(), 25.0);
+        assert!(!slider.on_touch_began(&Touch::new(2, Vec2::new(50.0, 0.0))));
+
+        slider.on_touch_moved(&Touch::new(2, Vec2::new(80.0, 0.0)));
+        assert_eq!(slider.value(), 25.0);
+        slider.on_touch_ended(&Touch::new(2, Vec2::new(80.0, 0.0)));
+        assert!(slider.is_dragging());
+
+        slider.on_touch_moved(&Touch::new(1, Vec2::new(0.0, 0.0)));
+        assert_eq!(slider.value(), 50.0);
+        slider.on_touch_ended(&Touch::new(1, Vec2::new(0.0, 0.0)));
+        assert!(!slider.is_dragging());
+
+        slider.on_touch_moved(&Touch::new(1, Vec2::new(100.0, 0.0)));
+        assert_eq!(slider.value(), 50.0);
+    }
 }
@@ -1,5 +1,6 @@
 use crate::base::{Ref, RefPtr};
 use crate::ui::Widget;
+use crate::ui::focus::{FocusHandle, Focusable};
 use crate::input::{Touch, KeyCode, KeyboardEvent, KeyEventType};
 use crate::math::Vec2;
 use std::rc::Rc;
@@ -67,6 +68,8 @@ pub struct TextField {
     on_editing_began: Option<Box<dyn FnMut(&TextField)>>,
     /// 编辑结束回调
     on_editing_ended: Option<Box<dyn FnMut(&TextField)>>,
+    /// 焦点管理器句柄
+    focus_handle: Option<FocusHandle>,
 }
 
 impl TextField {
@@ -91,6 +94,7 @@ impl TextField {
             on_text_changed: None,
             on_editing_began: None,
             on_editing_ended: None,
+            focus_handle: None,
         }
     }
 
@@ -110,8 +114,9 @@ impl TextField {
         self.clear_selection();
 
         // 触发回调
-        if let Some(ref mut callback) = self.on_text_changed {
+        if let Some(mut callback) = self.on_text_changed.take() {
             callback(self, &text_to_set);
+            self.on_text_changed = Some(callback);
         }
     }
 
@@ -194,16 +199,20 @@ impl TextField {
         &mut self.widget
     }
 
-    /// 开始编辑
+    /// 开始编辑，注册到焦点管理器时同时请求焦点
     pub fn begin_editing(&mut self) {
         if !self.editable || self.is_editing {
             return;
         }
 
         self.is_editing = true;
+        if let Some(handle) = &self.focus_handle {
+            handle.request_focus();
+        }
         
-        if let Some(ref mut callback) = self.on_editing_began {
+        if let Some(mut callback) = self.on_editing_began.take() {
             callback(self);
+            self.on_editing_began = Some(callback);
         }
     }
 
@@ -216,8 +225,9 @@ impl TextField {
         self.is_editing = false;
         self.clear_selection();
         
-        if let Some(ref mut callback) = self.on_editing_ended {
+        if let Some(mut callback) = self.on_editing_ended.take() {
             callback(self);
+            self.on_editing_ended = Some(callback);
         }
     }
 
@@ -248,9 +258,10 @@ impl TextField {
         self.cursor_position += text_to_insert.len();
 
         // 触发回调
-        if let Some(ref mut callback) = self.on_text_changed {
+        if let Some(mut callback) = self.on_text_changed.take() {
             let text_clone = self.text.clone();
             callback(self, &text_clone);
+            self.on_text_changed = Some(callback);
         }
     }
 
@@ -266,9 +277,10 @@ impl TextField {
             self.text.remove(self.cursor_position - 1);
             self.cursor_position -= 1;
 
-            if let Some(ref mut callback) = self.on_text_changed {
+            if let Some(mut callback) = self.on_text_changed.take() {
                 let text_clone = self.text.clone();
                 callback(self, &text_clone);
+                self.on_text_changed = Some(callback);
             }
         }
     }
@@ -284,9 +296,10 @@ impl TextField {
         } else if self.cursor_position < self.text.len() {
             self.text.remove(self.cursor_position);
 
-            if let Some(ref mut callback) = self.on_text_changed {
+            if let Some(mut callback) = self.on_text_changed.take() {
                 let text_clone = self.text.clone();
                 callback(self, &text_clone);
+                self.on_text_changed = Some(callback);
             }
         }
     }
@@ -342,7 +355,7 @@ impl TextField {
             self.cursor_position = start;
             self.clear_selection();
 
-            if let Some(callback) = self.on_text_changed.take() {
+            if let Some(mut callback) = self.on_text_changed.take() {
                 let text_clone = self.text.clone();
                 callback(&*self, &text_clone);
                 self.on_text_changed = Some(callback);
@@ -449,6 +462,30 @@ impl TextField {
     }
 }
 
+impl Focusable for TextField {
+    fn on_focus_changed(&mut self, focused: bool) {
+        if focused {
+            self.begin_editing();
+        } else {
+            self.end_editing();
+        }
+    }
+
+    fn on_keyboard_event(&mut self, event: &KeyboardEvent) -> bool {
+        let handled = self.is_editing;
+        TextField::on_keyboard_event(self, event);
+        handled
+    }
+
+    fn is_focusable(&self) -> bool {
+        self.editable && self.widget.is_enabled()
+    }
+
+    fn set_focus_handle(&mut self, handle: FocusHandle) {
+        self.focus_handle = Some(handle);
+    }
+}
+
 impl Default for TextField {
     fn default() -> Self {
         Self::new()
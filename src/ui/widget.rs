@@ -1,6 +1,10 @@
+use std::cell::Cell;
+use std::fmt;
 use crate::base::{Ref, RefPtr};
-use crate::base::types::Color3B;
+use crate::base::types::{Color3B, Rect};
+use crate::animation::SpriteFrameCache;
 use crate::math::Vec2;
+use crate::sprite::Sprite;
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum TouchEventType {
@@ -30,12 +34,18 @@ pub enum WidgetTextureType {
     FILLED,
 }
 
-#[derive(Debug)]
+/// Called when the mouse cursor enters or leaves a widget
+pub type WidgetHoverCallback = Box<dyn FnMut()>;
+
 pub struct Widget {
     name: String,
     tag: i32,
     position: Vec2,
-    size: Vec2,
+    /// In a cell so that layouts can resolve PERCENT sizes through shared
+    /// handles
+    size: Cell<Vec2>,
+    size_type: WidgetSizeType,
+    size_percent: Vec2,
     anchor_point: Vec2,
     color: Color3B,
     opacity: u8,
@@ -48,6 +58,35 @@ pub struct Widget {
     layout_parameter: Option<LayoutParameter>,
     parent: Option<RefPtr<Widget>>,
     children: Vec<RefPtr<Widget>>,
+    hovered: bool,
+    on_hover_enter: Option<WidgetHoverCallback>,
+    on_hover_exit: Option<WidgetHoverCallback>,
+}
+
+impl fmt::Debug for Widget {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Widget")
+            .field("name", &self.name)
+            .field("tag", &self.tag)
+            .field("position", &self.position)
+            .field("size", &self.size.get())
+            .field("size_type", &self.size_type)
+            .field("size_percent", &self.size_percent)
+            .field("anchor_point", &self.anchor_point)
+            .field("color", &self.color)
+            .field("opacity", &self.opacity)
+            .field("enabled", &self.enabled)
+            .field("bright", &self.bright)
+            .field("bright_style", &self.bright_style)
+            .field("touch_pass_through", &self.touch_pass_through)
+            .field("pass_through_lb", &self.pass_through_lb)
+            .field("pass_through_rb", &self.pass_through_rb)
+            .field("layout_parameter", &self.layout_parameter)
+            .field("parent", &self.parent)
+            .field("children", &self.children)
+            .field("hovered", &self.hovered)
+            .finish()
+    }
 }
 
 impl Widget {
@@ -56,7 +95,9 @@ impl Widget {
             name: String::new(),
             tag: 0,
             position: Vec2::ZERO,
-            size: Vec2::new(100.0, 100.0),
+            size: Cell::new(Vec2::new(100.0, 100.0)),
+            size_type: WidgetSizeType::ABSOLUTE,
+            size_percent: Vec2::ZERO,
             anchor_point: Vec2::new(0.5, 0.5),
             color: Color3B::WHITE,
             opacity: 255,
@@ -69,6 +110,9 @@ impl Widget {
             layout_parameter: None,
             parent: None,
             children: Vec::new(),
+            hovered: false,
+            on_hover_enter: None,
+            on_hover_exit: None,
         }
     }
 
@@ -97,11 +141,45 @@ impl Widget {
     }
 
     pub fn set_size(&mut self, size: Vec2) {
-        self.size = size;
+        self.size.set(size);
     }
 
     pub fn get_size(&self) -> Vec2 {
-        self.size
+        self.size.get()
+    }
+
+    pub fn set_size_type(&mut self, size_type: WidgetSizeType) {
+        self.size_type = size_type;
+    }
+
+    pub fn get_size_type(&self) -> WidgetSizeType {
+        self.size_type
+    }
+
+    /// Sets the size as a fraction of the parent size, used when the size
+    /// type is PERCENT. (1, 1) fills the parent.
+    pub fn set_size_percent(&mut self, percent: Vec2) {
+        self.size_percent = percent;
+    }
+
+    pub fn get_size_percent(&self) -> Vec2 {
+        self.size_percent
+    }
+
+    /// Resolves the widget size against its parent's size
+    pub fn resolve_size(&self, parent_size: Vec2) -> Vec2 {
+        match self.size_type {
+            WidgetSizeType::ABSOLUTE => self.size.get(),
+            WidgetSizeType::PERCENT => Vec2::new(parent_size.x * self.size_percent.x, parent_size.y * self.size_percent.y),
+        }
+    }
+
+    /// Resizes a PERCENT widget to its share of `parent_size`. Layouts call
+    /// this while arranging their children, which may be shared.
+    pub fn apply_parent_size(&self, parent_size: Vec2) {
+        if self.size_type == WidgetSizeType::PERCENT {
+            self.size.set(self.resolve_size(parent_size));
+        }
     }
 
     pub fn set_anchor_point(&mut self, anchor_point: Vec2) {
@@ -136,6 +214,16 @@ impl Widget {
         self.enabled
     }
 
+    /// Sets whether touches fall through the widget to what lies beneath.
+    /// Its children can still be hit.
+    pub fn set_touch_pass_through(&mut self, pass_through: bool) {
+        self.touch_pass_through = pass_through;
+    }
+
+    pub fn is_touch_pass_through(&self) -> bool {
+        self.touch_pass_through
+    }
+
     pub fn set_bright(&mut self, bright: bool) {
         self.bright = bright;
     }
@@ -164,6 +252,54 @@ impl Widget {
         &self.children
     }
 
+    /// Gets the bottom-left corner of the widget's rectangle in its parent's
+    /// space, where its children's positions are measured from
+    fn get_origin(&self) -> Vec2 {
+        let size = self.size.get();
+        self.position - Vec2::new(size.x * self.anchor_point.x, size.y * self.anchor_point.y)
+    }
+
+    /// Checks if a point lies inside the widget's rectangle, taking the
+    /// anchor point into account
+    pub fn hit_test(&self, point: Vec2) -> bool {
+        let origin = self.get_origin();
+        let size = self.size.get();
+        point.x >= origin.x
+            && point.x <= origin.x + size.x
+            && point.y >= origin.y
+            && point.y <= origin.y + size.y
+    }
+
+    /// Sets the callback fired when the cursor moves onto the widget
+    pub fn set_on_hover_enter(&mut self, callback: WidgetHoverCallback) {
+        self.on_hover_enter = Some(callback);
+    }
+
+    /// Sets the callback fired when the cursor moves off the widget
+    pub fn set_on_hover_exit(&mut self, callback: WidgetHoverCallback) {
+        self.on_hover_exit = Some(callback);
+    }
+
+    /// Checks if the mouse cursor is over the widget
+    pub fn is_hovered(&self) -> bool {
+        self.hovered
+    }
+
+    /// Tracks the mouse cursor, firing the hover callbacks when it crosses
+    /// the widget's rectangle. Disabled widgets are never hovered. Returns
+    /// whether the widget is hovered.
+    pub fn on_mouse_move(&mut self, point: Vec2) -> bool {
+        let hovered = self.enabled && self.hit_test(point);
+        if hovered != self.hovered {
+            self.hovered = hovered;
+            let callback = if hovered { &mut self.on_hover_enter } else { &mut self.on_hover_exit };
+            if let Some(callback) = callback.as_mut() {
+                callback();
+            }
+        }
+        hovered
+    }
+
     pub fn on_touch_began(&mut self, touch: &Vec2) -> bool {
         false
     }
@@ -178,7 +314,28 @@ impl Widget {
     }
 }
 
-#[derive(Debug)]
+/// Finds the topmost widget under a point, e.g. the drop target of a drag.
+/// `point` is in the space of `root`'s parent; children are tested with it
+/// moved into their parent's space.
+///
+/// Later children are drawn above earlier ones and above their parent, so
+/// they are searched first. Disabled widgets are skipped along with their
+/// subtree; pass-through widgets are skipped but their children are not.
+pub fn find_widget_at(root: &Widget, point: Vec2) -> Option<&Widget> {
+    if !root.is_enabled() {
+        return None;
+    }
+    let local = point - root.get_origin();
+    root.children
+        .iter()
+        .rev()
+        .find_map(|child| find_widget_at(child, local))
+        .or_else(|| (!root.touch_pass_through && root.hit_test(point)).then_some(root))
+}
+
+/// Called when a button is clicked
+pub type ButtonClickCallback = Box<dyn FnMut(&Button)>;
+
 pub struct Button {
     widget: Widget,
     title_text: String,
@@ -186,6 +343,32 @@ pub struct Button {
     normal_image: String,
     pressed_image: String,
     disabled_image: String,
+    textures_are_frames: bool,
+    normal_sprite: Option<Sprite>,
+    pressed_sprite: Option<Sprite>,
+    disabled_sprite: Option<Sprite>,
+    pressed: bool,
+    zoom_on_touch: Option<f32>,
+    on_click: Option<ButtonClickCallback>,
+}
+
+impl fmt::Debug for Button {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Button")
+            .field("widget", &self.widget)
+            .field("title_text", &self.title_text)
+            .field("title_color", &self.title_color)
+            .field("normal_image", &self.normal_image)
+            .field("pressed_image", &self.pressed_image)
+            .field("disabled_image", &self.disabled_image)
+            .field("textures_are_frames", &self.textures_are_frames)
+            .field("normal_sprite", &self.normal_sprite)
+            .field("pressed_sprite", &self.pressed_sprite)
+            .field("disabled_sprite", &self.disabled_sprite)
+            .field("pressed", &self.pressed)
+            .field("zoom_on_touch", &self.zoom_on_touch)
+            .finish()
+    }
 }
 
 impl Button {
@@ -197,7 +380,100 @@ impl Button {
             normal_image: String::new(),
             pressed_image: String::new(),
             disabled_image: String::new(),
+            textures_are_frames: false,
+            normal_sprite: None,
+            pressed_sprite: None,
+            disabled_sprite: None,
+            pressed: false,
+            zoom_on_touch: None,
+            on_click: None,
+        }
+    }
+
+    pub fn get_widget(&self) -> &Widget {
+        &self.widget
+    }
+
+    pub fn get_widget_mut(&mut self) -> &mut Widget {
+        &mut self.widget
+    }
+
+    /// Sets the callback fired when the button is released inside its bounds
+    pub fn set_on_click(&mut self, callback: ButtonClickCallback) {
+        self.on_click = Some(callback);
+    }
+
+    /// Scales the button by `scale` while it is pressed
+    pub fn set_zoom_on_touch(&mut self, scale: f32) {
+        self.zoom_on_touch = Some(scale);
+    }
+
+    pub fn is_pressed(&self) -> bool {
+        self.pressed
+    }
+
+    /// Gets the image for the current state
+    pub fn get_current_image(&self) -> &str {
+        if !self.widget.is_enabled() {
+            &self.disabled_image
+        } else if self.pressed {
+            &self.pressed_image
+        } else {
+            &self.normal_image
+        }
+    }
+
+    /// Gets the sprite for the current state when the textures are sprite
+    /// frames. Falls back to the normal sprite if the state has none.
+    pub fn get_current_sprite(&self) -> Option<&Sprite> {
+        if !self.textures_are_frames {
+            return None;
+        }
+        let sprite = if !self.widget.is_enabled() {
+            self.disabled_sprite.as_ref()
+        } else if self.pressed {
+            self.pressed_sprite.as_ref()
+        } else {
+            None
+        };
+        sprite.or(self.normal_sprite.as_ref())
+    }
+
+    /// Gets the display scale, which differs from 1 only while zoomed on touch
+    pub fn get_current_scale(&self) -> f32 {
+        match self.zoom_on_touch {
+            Some(scale) if self.pressed => scale,
+            _ => 1.0,
+        }
+    }
+
+    /// Presses the button if the touch starts inside it
+    pub fn on_touch_began(&mut self, point: Vec2) -> bool {
+        if !self.widget.is_enabled() || !self.widget.hit_test(point) {
+            return false;
         }
+        self.pressed = true;
+        true
+    }
+
+    /// Releases the button, firing the click callback if released inside
+    pub fn on_touch_ended(&mut self, point: Vec2) {
+        if !self.pressed {
+            return;
+        }
+        self.pressed = false;
+
+        if self.widget.hit_test(point) {
+            if let Some(mut callback) = self.on_click.take() {
+                callback(self);
+                self.on_click = Some(callback);
+            }
+        }
+    }
+
+    /// Releases the button without clicking
+    pub fn on_touch_canceled(&mut self) {
+        self.pressed = false;
     }
 
     pub fn set_title_text(&mut self, text: &str) {
@@ -216,6 +492,55 @@ impl Button {
         self.normal_image = normal.to_string();
         self.pressed_image = pressed.to_string();
         self.disabled_image = disabled.to_string();
+        self.textures_are_frames = false;
+    }
+
+    /// Loads the state images from sprite frames in the shared
+    /// `SpriteFrameCache`. An empty name leaves that state without a sprite;
+    /// a name missing from the cache is an error and loads nothing.
+    pub fn load_textures_from_frames(&mut self, normal: &str, pressed: &str, disabled: &str) -> Result<(), String> {
+        self.load_textures_from_cache(&SpriteFrameCache::shared().borrow(), normal, pressed, disabled)
+    }
+
+    /// Sets whether the image names refer to sprite frames rather than files
+    pub fn set_textures_are_frames(&mut self, textures_are_frames: bool) {
+        self.textures_are_frames = textures_are_frames;
+    }
+
+    pub fn are_textures_frames(&self) -> bool {
+        self.textures_are_frames
+    }
+
+    fn load_textures_from_cache(
+        &mut self,
+        cache: &SpriteFrameCache,
+        normal: &str,
+        pressed: &str,
+        disabled: &str,
+    ) -> Result<(), String> {
+        let sprite_for = |name: &str| -> Result<Option<Sprite>, String> {
+            if name.is_empty() {
+                return Ok(None);
+            }
+            let frame = cache
+                .get_frame(name)
+                .ok_or_else(|| format!("sprite frame '{}' not found", name))?;
+            let mut sprite = Sprite::new();
+            sprite.set_sprite_frame(frame);
+            Ok(Some(sprite))
+        };
+        let normal_sprite = sprite_for(normal)?;
+        let pressed_sprite = sprite_for(pressed)?;
+        let disabled_sprite = sprite_for(disabled)?;
+
+        self.normal_image = normal.to_string();
+        self.pressed_image = pressed.to_string();
+        self.disabled_image = disabled.to_string();
+        self.normal_sprite = normal_sprite;
+        self.pressed_sprite = pressed_sprite;
+        self.disabled_sprite = disabled_sprite;
+        self.textures_are_frames = true;
+        Ok(())
     }
 }
 
@@ -337,7 +662,9 @@ impl Slider {
     }
 }
 
-#[derive(Debug)]
+/// Called with the new state whenever a check box is toggled by touch
+pub type CheckBoxCallback = Box<dyn FnMut(&CheckBox, bool)>;
+
 pub struct CheckBox {
     widget: Widget,
     on_off: bool,
@@ -346,6 +673,21 @@ pub struct CheckBox {
     off_disabled_image: String,
     on_disabled_image: String,
     check_mark_image: String,
+    on_changed: Option<CheckBoxCallback>,
+}
+
+impl fmt::Debug for CheckBox {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("CheckBox")
+            .field("widget", &self.widget)
+            .field("on_off", &self.on_off)
+            .field("off_normal_image", &self.off_normal_image)
+            .field("on_normal_image", &self.on_normal_image)
+            .field("off_disabled_image", &self.off_disabled_image)
+            .field("on_disabled_image", &self.on_disabled_image)
+            .field("check_mark_image", &self.check_mark_image)
+            .finish()
+    }
 }
 
 impl CheckBox {
@@ -358,6 +700,7 @@ impl CheckBox {
             off_disabled_image: String::new(),
             on_disabled_image: String::new(),
             check_mark_image: String::new(),
+            on_changed: None,
         }
     }
 
@@ -369,6 +712,32 @@ impl CheckBox {
         self.on_off
     }
 
+    pub fn get_widget(&self) -> &Widget {
+        &self.widget
+    }
+
+    pub fn get_widget_mut(&mut self) -> &mut Widget {
+        &mut self.widget
+    }
+
+    /// Sets the callback fired when a touch toggles the check box
+    pub fn set_on_changed(&mut self, callback: CheckBoxCallback) {
+        self.on_changed = Some(callback);
+    }
+
+    /// Toggles the check box if the touch is released inside it
+    pub fn on_touch_ended(&mut self, point: Vec2) {
+        if !self.widget.is_enabled() || !self.widget.hit_test(point) {
+            return;
+        }
+
+        self.on_off = !self.on_off;
+        if let Some(mut callback) = self.on_changed.take() {
+            callback(self, self.on_off);
+            self.on_changed = Some(callback);
+        }
+    }
+
     pub fn loadTextures(&mut self, off_normal: &str, on_normal: &str, off_disabled: &str, on_disabled: &str, check_mark: &str) {
         self.off_normal_image = off_normal.to_string();
         self.on_normal_image = on_normal.to_string();
@@ -378,11 +747,21 @@ impl CheckBox {
     }
 }
 
+/// One textured quad of an image view: where it is drawn in widget space and
+/// which part of the texture, in pixels, it samples
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TextureSlice {
+    pub dest: Rect,
+    pub source: Rect,
+}
+
 #[derive(Debug)]
 pub struct ImageView {
     widget: Widget,
     image_texture: String,
+    texture_size: Vec2,
     scale_type: WidgetTextureType,
+    cap_insets: Rect,
 }
 
 impl ImageView {
@@ -390,7 +769,9 @@ impl ImageView {
         ImageView {
             widget: Widget::new(),
             image_texture: String::new(),
+            texture_size: Vec2::ZERO,
             scale_type: WidgetTextureType::PLAIN,
+            cap_insets: Rect::ZERO,
         }
     }
 
@@ -398,9 +779,116 @@ impl ImageView {
         self.image_texture = file.to_string();
     }
 
+    /// Loads a texture and sizes the widget to match it
+    pub fn load_texture_with_size(&mut self, file: &str, size: Vec2) {
+        self.load_texture(file);
+        self.texture_size = size;
+        self.widget.set_size(size);
+    }
+
+    pub fn get_texture_size(&self) -> Vec2 {
+        self.texture_size
+    }
+
     pub fn set_scale_type(&mut self, scale_type: WidgetTextureType) {
         self.scale_type = scale_type;
     }
+
+    pub fn get_scale_type(&self) -> WidgetTextureType {
+        self.scale_type
+    }
+
+    /// Sets the stretchable center region in texture pixels. A zero rect
+    /// splits the texture into thirds.
+    pub fn set_cap_insets(&mut self, cap_insets: Rect) {
+        self.cap_insets = cap_insets;
+    }
+
+    pub fn get_cap_insets(&self) -> Rect {
+        self.cap_insets
+    }
+
+    pub fn get_widget(&self) -> &Widget {
+        &self.widget
+    }
+
+    pub fn get_widget_mut(&mut self) -> &mut Widget {
+        &mut self.widget
+    }
+
+    /// Builds the quads that draw the texture over the widget's size
+    pub fn get_geometry(&self) -> Vec<TextureSlice> {
+        let size = self.widget.get_size();
+        let tex = self.texture_size;
+        if tex.x <= 0.0 || tex.y <= 0.0 || size.x <= 0.0 || size.y <= 0.0 {
+            return Vec::new();
+        }
+
+        match self.scale_type {
+            WidgetTextureType::PLAIN => vec![TextureSlice {
+                dest: Rect::new(0.0, 0.0, size.x, size.y),
+                source: Rect::new(0.0, 0.0, tex.x, tex.y),
+            }],
+            WidgetTextureType::SLICED => self.sliced_geometry(size, tex),
+            WidgetTextureType::FILLED => self.filled_geometry(size, tex),
+        }
+    }
+
+    /// Nine-slice: corners keep their pixel size, edges stretch along one
+    /// axis and the center stretches along both
+    fn sliced_geometry(&self, size: Vec2, tex: Vec2) -> Vec<TextureSlice> {
+        let insets = if self.cap_insets.size.width > 0.0 && self.cap_insets.size.height > 0.0 {
+            self.cap_insets
+        } else {
+            Rect::new(tex.x / 3.0, tex.y / 3.0, tex.x / 3.0, tex.y / 3.0)
+        };
+
+        let src_x = [0.0, insets.get_min_x(), insets.get_max_x(), tex.x];
+        let src_y = [0.0, insets.get_min_y(), insets.get_max_y(), tex.y];
+        let dst_x = Self::slice_edges(src_x, size.x);
+        let dst_y = Self::slice_edges(src_y, size.y);
+
+        let mut slices = Vec::with_capacity(9);
+        for row in 0..3 {
+            for col in 0..3 {
+                let dest = Rect::new(dst_x[col], dst_y[row], dst_x[col + 1] - dst_x[col], dst_y[row + 1] - dst_y[row]);
+                let source = Rect::new(src_x[col], src_y[row], src_x[col + 1] - src_x[col], src_y[row + 1] - src_y[row]);
+                if dest.size.width > 0.0 && dest.size.height > 0.0 && source.size.width > 0.0 && source.size.height > 0.0 {
+                    slices.push(TextureSlice { dest, source });
+                }
+            }
+        }
+        slices
+    }
+
+    /// Maps the four slice edges of one texture axis onto a widget length,
+    /// shrinking the corners proportionally if the widget is too small
+    fn slice_edges(src: [f32; 4], length: f32) -> [f32; 4] {
+        let start = src[1] - src[0];
+        let end = src[3] - src[2];
+        let scale = if start + end > length { length / (start + end) } else { 1.0 };
+        [0.0, start * scale, length - end * scale, length]
+    }
+
+    /// Tiles the texture at its original size, cropping the last row and column
+    fn filled_geometry(&self, size: Vec2, tex: Vec2) -> Vec<TextureSlice> {
+        let mut slices = Vec::new();
+        let mut y = 0.0;
+        while y < size.y {
+            let h = tex.y.min(size.y - y);
+            let mut x = 0.0;
+            while x < size.x {
+                let w = tex.x.min(size.x - x);
+                slices.push(TextureSlice {
+                    dest: Rect::new(x, y, w, h),
+                    source: Rect::new(0.0, 0.0, w, h),
+                });
+                x += tex.x;
+            }
+            y += tex.y;
+        }
+        slices
+    }
 }
 
 #[derive(Debug)]
@@ -505,3 +993,308 @@ impl LayoutParameter {
         self.margin_bottom = bottom;
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    fn check_box_at(x: f32, y: f32) -> CheckBox {
+        let mut check_box = CheckBox::new();
+        check_box.get_widget_mut().set_position(Vec2::new(x, y));
+        check_box.get_widget_mut().set_size(Vec2::new(20.0, 20.0));
+        check_box
+    }
+
+    fn hover_counting_widget(events: &Rc<RefCell<Vec<&'static str>>>) -> Widget {
+        let mut widget = Widget::new();
+        widget.set_position(Vec2::new(50.0, 50.0));
+        widget.set_size(Vec2::new(20.0, 20.0));
+        let enter = Rc::clone(events);
+        widget.set_on_hover_enter(Box::new(move || enter.borrow_mut().push("enter")));
+        let exit = Rc::clone(events);
+        widget.set_on_hover_exit(Box::new(move || exit.borrow_mut().push("exit")));
+        widget
+    }
+
+    fn widget_at(tag: i32, x: f32, y: f32, size: f32) -> Widget {
+        let mut widget = Widget::new();
+        widget.set_tag(tag);
+        widget.set_anchor_point(Vec2::ZERO);
+        widget.set_position(Vec2::new(x, y));
+        widget.set_size(Vec2::new(size, size));
+        widget
+    }
+
+    #[test]
+    fn test_find_widget_at_returns_topmost() {
+        let mut root = widget_at(1, 0.0, 0.0, 200.0);
+        root.add_child(RefPtr::new(widget_at(2, 10.0, 10.0, 50.0)));
+        root.add_child(RefPtr::new(widget_at(3, 40.0, 40.0, 50.0)));
+
+        let tag_at = |root: &Widget, x, y| find_widget_at(root, Vec2::new(x, y)).map(|widget| widget.get_tag());
+        assert_eq!(tag_at(&root, 50.0, 50.0), Some(3));
+        assert_eq!(tag_at(&root, 20.0, 20.0), Some(2));
+        assert_eq!(tag_at(&root, 150.0, 150.0), Some(1));
+        assert_eq!(tag_at(&root, 250.0, 250.0), None);
+
+        root.set_touch_pass_through(true);
+        assert_eq!(tag_at(&root, 150.0, 150.0), None);
+        assert_eq!(tag_at(&root, 20.0, 20.0), Some(2));
+    }
+
+    #[test]
+    fn test_find_widget_at_skips_disabled_subtrees() {
+        let mut root = widget_at(1, 0.0, 0.0, 200.0);
+        let mut panel = widget_at(2, 40.0, 40.0, 50.0);
+        panel.add_child(RefPtr::new(widget_at(3, 50.0, 50.0, 10.0)));
+        panel.set_enabled(false);
+        root.add_child(RefPtr::new(widget_at(4, 10.0, 10.0, 50.0)));
+        root.add_child(RefPtr::new(panel));
+
+        let found = find_widget_at(&root, Vec2::new(55.0, 55.0)).map(|widget| widget.get_tag());
+        assert_eq!(found, Some(4));
+    }
+
+    #[test]
+    fn test_find_widget_at_uses_local_space_of_children() {
+        let mut root = widget_at(1, 100.0, 100.0, 200.0);
+        let mut panel = widget_at(2, 50.0, 50.0, 100.0);
+        panel.add_child(RefPtr::new(widget_at(3, 10.0, 10.0, 20.0)));
+        root.add_child(RefPtr::new(panel));
+
+        let tag_at = |x, y| find_widget_at(&root, Vec2::new(x, y)).map(|widget| widget.get_tag());
+        assert_eq!(tag_at(165.0, 165.0), Some(3));
+        assert_eq!(tag_at(200.0, 200.0), Some(2));
+        assert_eq!(tag_at(115.0, 115.0), Some(1));
+        assert_eq!(tag_at(20.0, 20.0), None);
+    }
+
+    fn add_button_frames(names: &[&str]) {
+        let mut cache = SpriteFrameCache::shared().borrow_mut();
+        for name in names {
+            cache.add_frame(crate::animation::SpriteFrame::new(*name));
+        }
+    }
+
+    fn frame_name(sprite: Option<&Sprite>) -> Option<String> {
+        sprite.and_then(|sprite| sprite.get_sprite_frame()).map(|frame| frame.borrow().name().to_string())
+    }
+
+    #[test]
+    fn test_load_textures_from_frames_resolves_frames() {
+        add_button_frames(&["btn_load_normal.png", "btn_load_pressed.png"]);
+        let mut button = Button::new();
+        button.load_textures_from_frames("btn_load_normal.png", "btn_load_pressed.png", "").unwrap();
+        assert!(button.are_textures_frames());
+        assert_eq!(button.get_current_image(), "btn_load_normal.png");
+        assert_eq!(frame_name(button.get_current_sprite()), Some("btn_load_normal.png".to_string()));
+
+        let error = button.load_textures_from_frames("btn_load_normal.png", "btn_missing.png", "").unwrap_err();
+        assert!(error.contains("btn_missing.png"));
+        assert_eq!(button.get_current_image(), "btn_load_normal.png");
+
+        button.loadTextures("normal.png", "pressed.png", "disabled.png");
+        assert!(!button.are_textures_frames());
+        assert!(button.get_current_sprite().is_none());
+    }
+
+    #[test]
+    fn test_pressing_selects_pressed_frame_sprite() {
+        add_button_frames(&["btn_press_normal.png", "btn_press_pressed.png", "btn_press_disabled.png"]);
+        let mut button = Button::new();
+        button.get_widget_mut().set_position(Vec2::new(50.0, 50.0));
+        button.get_widget_mut().set_size(Vec2::new(20.0, 20.0));
+        button
+            .load_textures_from_frames("btn_press_normal.png", "btn_press_pressed.png", "btn_press_disabled.png")
+            .unwrap();
+
+        assert!(button.on_touch_began(Vec2::new(50.0, 50.0)));
+        assert_eq!(frame_name(button.get_current_sprite()), Some("btn_press_pressed.png".to_string()));
+        button.on_touch_canceled();
+        assert_eq!(frame_name(button.get_current_sprite()), Some("btn_press_normal.png".to_string()));
+
+        button.get_widget_mut().set_enabled(false);
+        assert_eq!(frame_name(button.get_current_sprite()), Some("btn_press_disabled.png".to_string()));
+    }
+
+    #[test]
+    fn test_hover_callbacks_fire_once_per_crossing() {
+        let events = Rc::new(RefCell::new(Vec::new()));
+        let mut widget = hover_counting_widget(&events);
+
+        assert!(!widget.on_mouse_move(Vec2::new(0.0, 0.0)));
+        assert!(widget.on_mouse_move(Vec2::new(45.0, 45.0)));
+        assert!(widget.on_mouse_move(Vec2::new(55.0, 52.0)));
+        assert!(widget.is_hovered());
+        assert!(!widget.on_mouse_move(Vec2::new(100.0, 50.0)));
+        assert!(!widget.on_mouse_move(Vec2::new(120.0, 50.0)));
+        assert!(!widget.is_hovered());
+        assert_eq!(*events.borrow(), vec!["enter", "exit"]);
+    }
+
+    #[test]
+    fn test_disabled_widget_is_not_hovered() {
+        let events = Rc::new(RefCell::new(Vec::new()));
+        let mut widget = hover_counting_widget(&events);
+        widget.set_enabled(false);
+        assert!(!widget.on_mouse_move(Vec2::new(50.0, 50.0)));
+        assert!(!widget.is_hovered());
+        assert!(events.borrow().is_empty());
+    }
+
+    #[test]
+    fn test_check_box_toggles_on_touch_inside() {
+        let mut check_box = check_box_at(50.0, 50.0);
+        let changes = Rc::new(RefCell::new(Vec::new()));
+        let recorded = changes.clone();
+        check_box.set_on_changed(Box::new(move |check_box, on| {
+            assert_eq!(check_box.is_selected(), on);
+            recorded.borrow_mut().push(on);
+        }));
+
+        check_box.on_touch_ended(Vec2::new(55.0, 45.0));
+        assert!(check_box.is_selected());
+        check_box.on_touch_ended(Vec2::new(50.0, 50.0));
+        assert!(!check_box.is_selected());
+        check_box.on_touch_ended(Vec2::new(100.0, 50.0));
+        assert!(!check_box.is_selected());
+
+        assert_eq!(*changes.borrow(), vec![true, false]);
+    }
+
+    fn button_with_counter() -> (Button, Rc<RefCell<u32>>) {
+        let mut button = Button::new();
+        button.get_widget_mut().set_position(Vec2::new(100.0, 100.0));
+        button.get_widget_mut().set_size(Vec2::new(80.0, 40.0));
+        button.loadTextures("normal.png", "pressed.png", "disabled.png");
+
+        let clicks = Rc::new(RefCell::new(0));
+        let counter = clicks.clone();
+        button.set_on_click(Box::new(move |_| *counter.borrow_mut() += 1));
+        (button, clicks)
+    }
+
+    #[test]
+    fn test_button_click_inside() {
+        let (mut button, clicks) = button_with_counter();
+        button.set_zoom_on_touch(1.1);
+        assert_eq!(button.get_current_image(), "normal.png");
+
+        assert!(button.on_touch_began(Vec2::new(110.0, 95.0)));
+        assert!(button.is_pressed());
+        assert_eq!(button.get_current_image(), "pressed.png");
+        assert_eq!(button.get_current_scale(), 1.1);
+
+        button.on_touch_ended(Vec2::new(120.0, 100.0));
+        assert!(!button.is_pressed());
+        assert_eq!(button.get_current_image(), "normal.png");
+        assert_eq!(button.get_current_scale(), 1.0);
+        assert_eq!(*clicks.borrow(), 1);
+    }
+
+    #[test]
+    fn test_button_release_outside_does_not_click() {
+        let (mut button, clicks) = button_with_counter();
+
+        assert!(button.on_touch_began(Vec2::new(100.0, 100.0)));
+        button.on_touch_ended(Vec2::new(300.0, 100.0));
+        assert!(!button.is_pressed());
+        assert_eq!(*clicks.borrow(), 0);
+
+        assert!(!button.on_touch_began(Vec2::new(300.0, 100.0)));
+        button.on_touch_ended(Vec2::new(100.0, 100.0));
+        assert_eq!(*clicks.borrow(), 0);
+    }
+
+    #[test]
+    fn test_disabled_button_shows_disabled_image() {
+        let (mut button, clicks) = button_with_counter();
+        button.get_widget_mut().set_enabled(false);
+
+        assert_eq!(button.get_current_image(), "disabled.png");
+        assert!(!button.on_touch_began(Vec2::new(100.0, 100.0)));
+        button.on_touch_ended(Vec2::new(100.0, 100.0));
+        assert_eq!(*clicks.borrow(), 0);
+    }
+
+    fn image_view(scale_type: WidgetTextureType) -> ImageView {
+        let mut image_view = ImageView::new();
+        image_view.load_texture_with_size("panel.png", Vec2::new(30.0, 30.0));
+        image_view.set_scale_type(scale_type);
+        image_view
+    }
+
+    #[test]
+    fn test_sliced_image_view_keeps_corners() {
+        let mut image_view = image_view(WidgetTextureType::SLICED);
+        image_view.set_cap_insets(Rect::new(5.0, 8.0, 20.0, 14.0));
+        image_view.get_widget_mut().set_size(Vec2::new(200.0, 100.0));
+
+        let slices = image_view.get_geometry();
+        assert_eq!(slices.len(), 9);
+
+        let bottom_left = &slices[0];
+        assert_eq!(bottom_left.dest, Rect::new(0.0, 0.0, 5.0, 8.0));
+        assert_eq!(bottom_left.source, Rect::new(0.0, 0.0, 5.0, 8.0));
+        let top_right = &slices[8];
+        assert_eq!(top_right.dest, Rect::new(195.0, 92.0, 5.0, 8.0));
+        assert_eq!(top_right.source, Rect::new(25.0, 22.0, 5.0, 8.0));
+        let center = &slices[4];
+        assert_eq!(center.dest, Rect::new(5.0, 8.0, 190.0, 84.0));
+        assert_eq!(center.source, Rect::new(5.0, 8.0, 20.0, 14.0));
+    }
+
+    #[test]
+    fn test_plain_image_view_stretches() {
+        let mut image_view = image_view(WidgetTextureType::PLAIN);
+        image_view.get_widget_mut().set_size(Vec2::new(90.0, 60.0));
+
+        let slices = image_view.get_geometry();
+        assert_eq!(slices.len(), 1);
+        assert_eq!(slices[0].dest, Rect::new(0.0, 0.0, 90.0, 60.0));
+        assert_eq!(slices[0].source, Rect::new(0.0, 0.0, 30.0, 30.0));
+    }
+
+    #[test]
+    fn test_filled_image_view_tiles() {
+        let mut image_view = image_view(WidgetTextureType::FILLED);
+        image_view.get_widget_mut().set_size(Vec2::new(70.0, 30.0));
+
+        let slices = image_view.get_geometry();
+        assert_eq!(slices.len(), 3);
+        assert_eq!(slices[2].dest, Rect::new(60.0, 0.0, 10.0, 30.0));
+        assert_eq!(slices[2].source, Rect::new(0.0, 0.0, 10.0, 30.0));
+    }
+
+    #[test]
+    fn test_percent_size_resolves_against_parent() {
+        let mut widget = Widget::new();
+        widget.set_size_type(WidgetSizeType::PERCENT);
+        widget.set_size_percent(Vec2::new(0.5, 0.5));
+        assert_eq!(widget.resolve_size(Vec2::new(200.0, 100.0)), Vec2::new(100.0, 50.0));
+    }
+
+    #[test]
+    fn test_absolute_size_ignores_parent() {
+        let mut widget = Widget::new();
+        widget.set_size(Vec2::new(30.0, 40.0));
+        widget.set_size_percent(Vec2::new(0.5, 0.5));
+        assert_eq!(widget.get_size_type(), WidgetSizeType::ABSOLUTE);
+        assert_eq!(widget.resolve_size(Vec2::new(200.0, 100.0)), Vec2::new(30.0, 40.0));
+    }
+
+    #[test]
+    fn test_disabled_check_box_ignores_touch() {
+        let mut check_box = check_box_at(50.0, 50.0);
+        let fired = Rc::new(RefCell::new(false));
+        let flag = fired.clone();
+        check_box.set_on_changed(Box::new(move |_, _| *flag.borrow_mut() = true));
+        check_box.get_widget_mut().set_enabled(false);
+
+        check_box.on_touch_ended(Vec2::new(50.0, 50.0));
+        assert!(!check_box.is_selected());
+        assert!(!*fired.borrow());
+    }
+}